@@ -3,6 +3,12 @@
 //! The worker checks for pending jobs and process them.
 
 use std::error::Error;
+use std::time::Duration;
+
+use tracing::{event, Level};
+
+use configuration::Config;
+use database::Db;
 
 /// Entry point of the job worker.
 ///
@@ -10,5 +16,40 @@ use std::error::Error;
 /// Result with generic error.
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    dotenvy::dotenv()?;
+
+    tracing_subscriber::fmt()
+        .with_ansi(true)
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_level(true)
+        .with_target(false)
+        .compact()
+        .try_init()?;
+
+    let config = Config::new()?;
+    let (pool, _, _) = database::initialize(&config.database, None, None).await?;
+
+    let mut purge_interval = tokio::time::interval(Duration::from_secs(
+        u64::from(config.users.purge_interval_minutes) * 60,
+    ));
+
+    event!(Level::INFO, "👷 Worker started");
+
+    loop {
+        tokio::select! {
+            _ = purge_interval.tick() => {
+                let db = Db::new(pool.clone()).into_shared();
+
+                if let Err(e) = user::purge_deleted_users(config.clone(), db).await {
+                    event!(Level::ERROR, "Cannot purge the deleted users: {e}");
+                }
+            }
+
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    event!(Level::INFO, "👷 Worker stopped");
+
     Ok(())
 }
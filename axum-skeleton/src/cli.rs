@@ -0,0 +1,142 @@
+//! Command line of the application.
+
+use configuration::Environment;
+
+/// Usage of the command line.
+pub const USAGE: &str = "\
+Usage: axum-skeleton [COMMAND]
+
+Commands:
+  serve                 Start the server (default)
+  migrate up            Apply all the pending migrations
+  migrate down          Revert the last migration applied
+  migrate status        List the migrations and whether they're applied
+  seed --env <name>     Insert the seed data of an environment
+  help                  Print this message";
+
+/// Subcommands of `migrate`.
+#[derive(Debug, PartialEq)]
+pub enum MigrateCommand {
+    /// Apply all the pending migrations.
+    Up,
+
+    /// Revert the last migration applied.
+    Down,
+
+    /// List the migrations.
+    Status,
+}
+
+/// Commands of the application.
+#[derive(Debug)]
+pub enum Command {
+    /// Start the server.
+    Serve,
+
+    /// Manage the migrations.
+    Migrate(MigrateCommand),
+
+    /// Insert the seed data of an environment.
+    Seed(Environment),
+
+    /// Print the usage.
+    Help,
+}
+
+impl Command {
+    /// Parses the command line arguments (without the name of the program).
+    ///
+    /// # Arguments
+    /// * `args` - Arguments of the command line.
+    ///
+    /// # Returns
+    /// The command to be run or an error message.
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+
+        let command = match args.next().as_deref() {
+            None | Some("serve") => Self::Serve,
+
+            Some("migrate") => match args.next().as_deref() {
+                Some("up") => Self::Migrate(MigrateCommand::Up),
+                Some("down") => Self::Migrate(MigrateCommand::Down),
+                Some("status") => Self::Migrate(MigrateCommand::Status),
+                Some(other) => return Err(format!("Unknown migrate subcommand: {other}")),
+                None => return Err("Missing migrate subcommand (up, down or status)".to_string()),
+            },
+
+            Some("seed") => match (args.next().as_deref(), args.next()) {
+                (Some("--env"), Some(name)) => {
+                    let environment = Environment::try_from(name).map_err(|e| e.to_string())?;
+
+                    // Seed data are fake accounts, they must never reach the production
+                    if let Environment::Production = environment {
+                        return Err("Production must not be seeded".to_string());
+                    }
+
+                    Self::Seed(environment)
+                }
+
+                _ => return Err("Missing environment (seed --env <name>)".to_string()),
+            },
+
+            Some("help" | "-h" | "--help") => Self::Help,
+
+            Some(other) => return Err(format!("Unknown command: {other}")),
+        };
+
+        match args.next() {
+            Some(extra) => Err(format!("Unexpected argument: {extra}")),
+            None => Ok(command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(parse(&[]), Ok(Command::Serve)));
+        assert!(matches!(parse(&["serve"]), Ok(Command::Serve)));
+        assert!(matches!(parse(&["--help"]), Ok(Command::Help)));
+
+        assert!(matches!(
+            parse(&["migrate", "up"]),
+            Ok(Command::Migrate(MigrateCommand::Up))
+        ));
+        assert!(matches!(
+            parse(&["migrate", "down"]),
+            Ok(Command::Migrate(MigrateCommand::Down))
+        ));
+        assert!(matches!(
+            parse(&["migrate", "status"]),
+            Ok(Command::Migrate(MigrateCommand::Status))
+        ));
+
+        assert!(matches!(
+            parse(&["seed", "--env", "Testing"]),
+            Ok(Command::Seed(Environment::Testing))
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["unknown"]).is_err());
+        assert!(parse(&["migrate"]).is_err());
+        assert!(parse(&["migrate", "sideways"]).is_err());
+        assert!(parse(&["migrate", "up", "now"]).is_err());
+        assert!(parse(&["seed"]).is_err());
+        assert!(parse(&["seed", "--env"]).is_err());
+        assert!(parse(&["seed", "--env", "unknown"]).is_err());
+        assert!(parse(&["seed", "--env", "production"]).is_err());
+    }
+}
@@ -4,6 +4,12 @@
 
 use std::error::Error;
 
+use tracing::{event, Level};
+
+use configuration::{Config, Environment};
+
+use crate::cli::{Command, MigrateCommand, USAGE};
+
 #[cfg(feature = "jemalloc")]
 use jemallocator::Jemalloc;
 
@@ -12,8 +18,11 @@ use jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
+// Modules
+mod cli;
+
 /// Entry point of the backend application. It loads environment variables,
-/// initializes the logging system and starts the server.
+/// initializes the logging system and runs the command given (starts the server by default).
 ///
 /// # Returns
 /// Result with generic error.
@@ -29,7 +38,76 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
         .compact()
         .try_init()?;
 
-    server::start(None).await?;
+    match Command::parse(std::env::args().skip(1))? {
+        Command::Serve => server::start(None).await?,
+        Command::Migrate(command) => migrate(command).await?,
+        Command::Seed(environment) => seed(&environment.to_string()).await?,
+        Command::Help => println!("{USAGE}"),
+    }
+
+    Ok(())
+}
+
+/// Runs a migration command against the database.
+///
+/// # Arguments
+/// * `command` - Migration command to run.
+///
+/// # Returns
+/// Result with generic error.
+async fn migrate(command: MigrateCommand) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let config = Config::new()?;
+    let pool = database::connect(&config.database, None).await?;
+
+    match command {
+        MigrateCommand::Up => {
+            database::migrate_up(&pool).await?;
+
+            event!(Level::INFO, "Migrations applied");
+        }
+
+        MigrateCommand::Down => match database::migrate_down(&pool).await? {
+            Some(version) => event!(Level::INFO, "Migration {version} reverted"),
+            None => event!(Level::INFO, "No migration to revert"),
+        },
+
+        MigrateCommand::Status => {
+            for migration in database::migrate_status(&pool).await? {
+                let state = if migration.applied {
+                    "applied"
+                } else {
+                    "pending"
+                };
+
+                println!(
+                    "{} {:<8} {}",
+                    migration.version, state, migration.description
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Inserts the seed data of an environment into the database.
+///
+/// # Arguments
+/// * `environment` - Name of the environment.
+///
+/// # Returns
+/// Result with generic error.
+async fn seed(environment: &str) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let config = Config::new()?;
+
+    // Whatever the seed requested, the production database never receives fake accounts
+    if Environment::Production.equals(&config.environment) {
+        return Err("The production database must not be seeded".into());
+    }
+
+    let pool = database::connect(&config.database, None).await?;
+
+    database::seed(&pool, environment).await?;
 
     Ok(())
 }
@@ -0,0 +1,3 @@
+-- $1: ID of the user concerned by the actions
+
+UPDATE audit_logs SET changes = '{}' WHERE user_id = $1;
@@ -0,0 +1,8 @@
+-- $1: ID of the link to consume
+
+DELETE FROM user_magic_links
+WHERE id = $1
+RETURNING
+    id,
+    user_id,
+    expires_at;
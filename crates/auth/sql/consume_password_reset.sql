@@ -0,0 +1,8 @@
+-- $1: ID of the password reset to consume
+
+DELETE FROM user_password_resets
+WHERE id = $1
+RETURNING
+    id,
+    user_id,
+    expires_at;
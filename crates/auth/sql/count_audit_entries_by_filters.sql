@@ -0,0 +1,14 @@
+-- $1: ID of the user concerned by the actions (optional)
+-- $2: ID of the user that performed the actions (optional)
+-- $3: Action performed (optional)
+-- $4: Start of the time range, included (optional)
+-- $5: End of the time range, excluded (optional)
+
+SELECT COUNT(*) AS "count!"
+FROM audit_logs
+WHERE
+    ($1::UUID IS NULL OR user_id = $1::UUID) AND
+    ($2::UUID IS NULL OR actor_id = $2::UUID) AND
+    ($3::VARCHAR IS NULL OR action = $3::VARCHAR) AND
+    ($4::TIMESTAMPTZ IS NULL OR created_at >= $4::TIMESTAMPTZ) AND
+    ($5::TIMESTAMPTZ IS NULL OR created_at < $5::TIMESTAMPTZ);
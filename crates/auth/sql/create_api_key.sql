@@ -0,0 +1,15 @@
+-- $1: User ID
+-- $2: Name of the key
+-- $3: Hash of the key
+-- $4: Scopes of the key
+
+INSERT INTO user_api_keys (user_id, name, key_hash, scopes)
+VALUES ($1, $2, $3, $4)
+RETURNING
+    id,
+    user_id,
+    name,
+    key_hash,
+    scopes,
+    last_used_at,
+    created_at;
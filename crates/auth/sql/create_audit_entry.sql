@@ -0,0 +1,8 @@
+-- $1: ID of the user that performed the action (optional)
+-- $2: ID of the user concerned by the action (optional)
+-- $3: Action performed
+-- $4: Fields changed
+-- $5: Request ID (optional)
+
+INSERT INTO audit_logs (actor_id, user_id, action, changes, request_id)
+VALUES ($1, $2, $3, $4, $5);
@@ -0,0 +1,6 @@
+-- $1: User ID
+-- $2: Name of the provider
+-- $3: Identifier of the user at the provider
+
+INSERT INTO user_identities (user_id, provider, subject)
+VALUES ($1, $2, $3);
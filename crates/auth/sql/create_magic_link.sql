@@ -0,0 +1,13 @@
+-- $1: User ID
+-- $2: Expires at
+
+INSERT INTO user_magic_links (user_id, expires_at)
+VALUES ($1, $2)
+ON CONFLICT (user_id) DO UPDATE
+SET
+    id = uuid_generate_v4(),
+    expires_at = EXCLUDED.expires_at
+RETURNING
+    id,
+    user_id,
+    expires_at;
@@ -0,0 +1,8 @@
+-- $1: ID of the user
+-- $2: Hashes of the recovery codes
+
+WITH deleted AS (
+    DELETE FROM user_recovery_codes WHERE user_id = $1
+)
+INSERT INTO user_recovery_codes (user_id, code)
+SELECT $1, UNNEST($2::VARCHAR[]);
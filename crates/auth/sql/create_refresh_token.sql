@@ -0,0 +1,15 @@
+-- $1: User ID
+-- $2: Family ID
+-- $3: Hash of the token
+-- $4: Expires at
+
+INSERT INTO user_refresh_tokens (user_id, family_id, token_hash, expires_at)
+VALUES ($1, $2, $3, $4)
+RETURNING
+    id,
+    user_id,
+    family_id,
+    token_hash,
+    expires_at,
+    used_at,
+    revoked_at;
@@ -0,0 +1,14 @@
+-- $1: User ID
+-- $2: IP address of the client
+-- $3: User agent of the client
+
+INSERT INTO user_sessions (user_id, ip_address, user_agent)
+VALUES ($1, $2, $3)
+RETURNING
+    id,
+    user_id,
+    ip_address,
+    user_agent,
+    created_at,
+    last_seen_at,
+    FALSE AS "current!: _";
@@ -0,0 +1,23 @@
+-- $1: Email
+-- $2: First name
+-- $3: Last name
+-- $4: Password (hashed)
+-- $5: Name of the provider
+-- $6: Identifier of the user at the provider
+
+WITH new_user AS (
+    INSERT INTO users (email, first_name, last_name, password)
+    VALUES ($1, $2, $3, $4)
+    RETURNING id, email, role, password, token_version
+), new_identity AS (
+    INSERT INTO user_identities (user_id, provider, subject)
+    SELECT id, $5, $6 FROM new_user
+)
+SELECT
+    id AS "id!",
+    email AS "email!",
+    role AS "role!: _",
+    password AS "password!",
+    token_version AS "token_version!",
+    TRUE AS "email_confirmed!: _"
+FROM new_user;
@@ -0,0 +1,4 @@
+-- $1: ID of the user
+-- $2: ID of the key to delete
+
+DELETE FROM user_api_keys WHERE user_id = $1 AND id = $2;
@@ -0,0 +1,4 @@
+-- $1: ID of the user
+-- $2: ID of the session to delete
+
+DELETE FROM user_sessions WHERE user_id = $1 AND id = $2;
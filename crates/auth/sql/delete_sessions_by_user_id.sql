@@ -0,0 +1,3 @@
+-- $1: ID of the user
+
+DELETE FROM user_sessions WHERE user_id = $1;
@@ -5,8 +5,9 @@ SELECT
     u.email,
     u.role AS "role: _",
     u.password,
+    u.token_version,
     uc.id IS NULL AS "email_confirmed!: _"
 FROM users u
 LEFT JOIN user_confirmations uc ON uc.user_id = u.id
-WHERE u.email = $1
+WHERE u.email = $1 AND u.deleted_at IS NULL
 LIMIT 1;
@@ -0,0 +1,15 @@
+-- $1: Name of the provider
+-- $2: Identifier of the user at the provider
+
+SELECT
+    u.id,
+    u.email,
+    u.role AS "role: _",
+    u.password,
+    u.token_version,
+    uc.id IS NULL AS "email_confirmed!: _"
+FROM user_identities ui
+JOIN users u ON u.id = ui.user_id
+LEFT JOIN user_confirmations uc ON uc.user_id = u.id
+WHERE ui.provider = $1 AND ui.subject = $2 AND u.deleted_at IS NULL
+LIMIT 1;
@@ -0,0 +1,12 @@
+-- $1: ID of the key
+
+SELECT
+    id,
+    user_id,
+    name,
+    key_hash,
+    scopes,
+    last_used_at,
+    created_at
+FROM user_api_keys
+WHERE id = $1;
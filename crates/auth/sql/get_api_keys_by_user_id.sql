@@ -0,0 +1,13 @@
+-- $1: ID of the user
+
+SELECT
+    id,
+    user_id,
+    name,
+    key_hash,
+    scopes,
+    last_used_at,
+    created_at
+FROM user_api_keys
+WHERE user_id = $1
+ORDER BY created_at DESC;
@@ -0,0 +1,13 @@
+-- $1: ID of the user concerned by the actions
+
+SELECT
+    id,
+    actor_id,
+    user_id,
+    action,
+    changes AS "changes: _",
+    request_id,
+    created_at
+FROM audit_logs
+WHERE user_id = $1
+ORDER BY created_at, id;
@@ -0,0 +1,9 @@
+-- $1: ID of the user
+
+SELECT
+    urc.id,
+    urc.user_id,
+    urc.code,
+    urc.used_at
+FROM user_recovery_codes urc
+WHERE urc.user_id = $1 AND urc.used_at IS NULL;
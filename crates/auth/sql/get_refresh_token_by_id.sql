@@ -0,0 +1,12 @@
+-- $1: ID of the refresh token
+
+SELECT
+    id,
+    user_id,
+    family_id,
+    token_hash,
+    expires_at,
+    used_at,
+    revoked_at
+FROM user_refresh_tokens
+WHERE id = $1;
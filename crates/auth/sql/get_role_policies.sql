@@ -0,0 +1,5 @@
+SELECT
+    rp.role AS "role: _",
+    rp.totp_required
+FROM role_policies rp
+ORDER BY rp.role;
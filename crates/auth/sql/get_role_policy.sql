@@ -0,0 +1,8 @@
+-- $1: Role
+
+SELECT
+    rp.role AS "role: _",
+    rp.totp_required
+FROM role_policies rp
+WHERE rp.role = $1
+LIMIT 1;
@@ -0,0 +1,13 @@
+-- $1: ID of the user
+
+SELECT
+    us.id,
+    us.user_id,
+    us.ip_address,
+    us.user_agent,
+    us.created_at,
+    us.last_seen_at,
+    FALSE AS "current!: _"
+FROM user_sessions us
+WHERE us.user_id = $1
+ORDER BY us.last_seen_at DESC;
@@ -0,0 +1,10 @@
+-- $1: ID of the user
+
+SELECT
+    ut.user_id,
+    ut.secret,
+    ut.enabled,
+    ut.last_used_step
+FROM user_totp ut
+WHERE ut.user_id = $1
+LIMIT 1;
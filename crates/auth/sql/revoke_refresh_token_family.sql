@@ -0,0 +1,5 @@
+-- $1: ID of the family
+
+UPDATE user_refresh_tokens
+SET revoked_at = now()
+WHERE family_id = $1 AND revoked_at IS NULL;
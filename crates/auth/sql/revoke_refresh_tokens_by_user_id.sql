@@ -0,0 +1,9 @@
+-- $1: User ID
+
+-- The access tokens issued before are revoked as well
+WITH revoked_user AS (
+    UPDATE users SET token_version = token_version + 1 WHERE id = $1
+)
+UPDATE user_refresh_tokens
+SET revoked_at = now()
+WHERE user_id = $1 AND revoked_at IS NULL;
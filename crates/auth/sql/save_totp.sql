@@ -0,0 +1,12 @@
+-- $1: ID of the user
+-- $2: Secret
+-- $3: Enabled
+-- $4: Last used step
+
+INSERT INTO user_totp (user_id, secret, enabled, last_used_step)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (user_id) DO UPDATE
+SET
+    secret = EXCLUDED.secret,
+    enabled = EXCLUDED.enabled,
+    last_used_step = EXCLUDED.last_used_step;
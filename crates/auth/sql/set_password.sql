@@ -0,0 +1,4 @@
+-- $1: User ID
+-- $2: Hashed password
+
+UPDATE users SET password = $2 WHERE id = $1;
@@ -0,0 +1,10 @@
+-- $1: Role
+-- $2: Second factor required
+
+INSERT INTO role_policies (role, totp_required)
+VALUES ($1, $2)
+ON CONFLICT (role) DO UPDATE
+SET totp_required = EXCLUDED.totp_required
+RETURNING
+    role AS "role: _",
+    totp_required;
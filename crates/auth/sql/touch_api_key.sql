@@ -0,0 +1,5 @@
+-- $1: ID of the key
+
+UPDATE user_api_keys
+SET last_used_at = now()
+WHERE id = $1;
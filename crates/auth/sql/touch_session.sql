@@ -0,0 +1,13 @@
+-- $1: ID of the session to update
+
+UPDATE user_sessions
+SET last_seen_at = now()
+WHERE id = $1
+RETURNING
+    id,
+    user_id,
+    ip_address,
+    user_agent,
+    created_at,
+    last_seen_at,
+    TRUE AS "current!: _";
@@ -0,0 +1,18 @@
+-- $1: ID of the user
+-- $2: ID of the key
+-- $3: Name of the key (unchanged if NULL)
+-- $4: Scopes of the key (unchanged if NULL)
+
+UPDATE user_api_keys
+SET
+    name = COALESCE($3, name),
+    scopes = COALESCE($4, scopes)
+WHERE user_id = $1 AND id = $2
+RETURNING
+    id,
+    user_id,
+    name,
+    key_hash,
+    scopes,
+    last_used_at,
+    created_at;
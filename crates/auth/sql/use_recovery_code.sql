@@ -0,0 +1,5 @@
+-- $1: ID of the recovery code
+
+UPDATE user_recovery_codes
+SET used_at = now()
+WHERE id = $1 AND used_at IS NULL;
@@ -0,0 +1,5 @@
+-- $1: ID of the refresh token
+
+UPDATE user_refresh_tokens
+SET used_at = now()
+WHERE id = $1 AND used_at IS NULL AND revoked_at IS NULL;
@@ -0,0 +1,143 @@
+//! List of endpoints used to manage the API keys of the users.
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use tracing::instrument;
+use uuid::Uuid;
+use validator::Validate;
+
+use common_core::UseCase;
+use common_state::AppState;
+use common_web::extractor::FormOrJson;
+use database::{Cache, Db};
+
+use crate::application::{
+    CreateApiKey, CreateApiKeyStores, ListApiKeys, ListApiKeysStores, RevokeApiKey,
+    RevokeApiKeyStores, UpdateApiKey, UpdateApiKeyStores,
+};
+use crate::domain::api_key::{ApiKeyRequest, ApiKeyUpdate};
+use crate::domain::auth::Auth;
+use crate::infrastructure::{CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
+use crate::prelude::*;
+
+/// Builds a router for the API keys endpoints.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/users/current/api_keys",
+            get(get_current_user_api_keys).post(post_current_user_api_key),
+        )
+        .route(
+            "/users/current/api_keys/:api_key_id",
+            delete(delete_current_user_api_key).patch(patch_current_user_api_key),
+        )
+}
+
+/// Handler used to list the API keys of the currently logged user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn get_current_user_api_keys(auth: Auth, db: Db) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    let user = auth.try_user()?;
+
+    let db = db.into_shared();
+
+    let stores = ListApiKeysStores {
+        auth: SQLxAuthStore::new(&db),
+    };
+
+    let api_keys = ListApiKeys::new(stores).handle(user.id).await?;
+
+    Ok(Json(api_keys))
+}
+
+/// Handler used to create an API key for the currently logged user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn post_current_user_api_key(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<ApiKeyRequest>,
+) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    request.validate()?;
+
+    let user = auth.try_user()?;
+
+    let db = db.into_shared();
+
+    let stores = CreateApiKeyStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let api_key = CreateApiKey::new(stores).handle((user.id, request)).await?;
+
+    Ok((StatusCode::CREATED, Json(api_key)))
+}
+
+/// Handler used to rename or change the scopes of an API key of the currently logged user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn patch_current_user_api_key(
+    auth: Auth,
+    Path(api_key_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+    FormOrJson(update): FormOrJson<ApiKeyUpdate>,
+) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    update.validate()?;
+
+    let user = auth.try_user()?;
+
+    let db = db.into_shared();
+
+    let stores = UpdateApiKeyStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let api_key = UpdateApiKey::new(stores)
+        .handle((user.id, api_key_id, update))
+        .await?;
+
+    Ok(Json(api_key))
+}
+
+/// Handler used to revoke an API key of the currently logged user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn delete_current_user_api_key(
+    auth: Auth,
+    Path(api_key_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    let user = auth.try_user()?;
+
+    let db = db.into_shared();
+
+    let stores = RevokeApiKeyStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    RevokeApiKey::new(stores)
+        .handle((user.id, api_key_id))
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,54 @@
+//! List of endpoints used to query the audit trail (by an admin user).
+
+use axum::extract::{OriginalUri, Query};
+use axum::http::header::LINK;
+use axum::response::{AppendHeaders, IntoResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use tracing::instrument;
+
+use common_core::UseCase;
+use common_state::AppState;
+use common_web::pagination::PageRequest;
+use database::Db;
+
+use crate::application::{ListAuditEntries, ListAuditEntriesStores};
+use crate::domain::audit::AuditFilters;
+use crate::domain::permission::AuditRead;
+use crate::extractor::Authorized;
+use crate::infrastructure::SQLxAuditStore;
+use crate::prelude::*;
+
+/// Builds a router for the audit trail endpoints.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new().route("/audit", get(get_audit_entries))
+}
+
+/// Handler used to list the entries of the audit trail that match some filters (most recent
+/// first).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn get_audit_entries(
+    auth: Authorized<AuditRead>,
+    OriginalUri(uri): OriginalUri,
+    Query(filters): Query<AuditFilters>,
+    Query(page): Query<PageRequest>,
+    db: Db,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = ListAuditEntriesStores {
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let entries = ListAuditEntries::new(stores)
+        .handle((filters, page.clone()))
+        .await?;
+
+    let links = entries.links(&uri, &page).map(|links| (LINK, links));
+
+    Ok((AppendHeaders(links), Json(entries)))
+}
@@ -1,29 +1,72 @@
 //! List of endpoints used for authentication process (login, logout, ...).
 
+use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::routing::post;
-use axum::Router;
+use axum::{Json, Router};
 use tracing::instrument;
 use validator::Validate;
 
 use common_core::UseCase;
 use common_state::AppState;
 use common_web::extractor::FormOrJson;
-use database::Db;
+use database::{Cache, Db};
+use mailer::FakeMailer;
 
-use crate::application::{Login, LoginStores, Logout};
-use crate::domain::auth::{Auth, AuthCredentials};
-use crate::infrastructure::SQLxAuthStore;
+use crate::application::{
+    ConfirmTotp, ConfirmTotpStores, EnrollTotp, EnrollTotpStores, Login, LoginSecondFactor,
+    LoginSecondFactorStores, LoginStores, Logout, LogoutStores, MagicLogin, MagicLoginStores,
+    ResetPassword, ResetPasswordStores, SendMagicLink, SendMagicLinkStores, SendPasswordReset,
+    SendPasswordResetStores,
+};
+use crate::domain::auth::{
+    Auth, AuthCredentials, MagicLinkCredentials, MagicLinkRequest, PasswordReset,
+    PasswordResetRequest,
+};
+use crate::domain::totp::{SecondFactorCredentials, TotpCode};
+use crate::infrastructure::{CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
 use crate::prelude::*;
+use crate::rate_limit_layer;
 
 /// Builds a router for the authorization endpoints.
 ///
+/// # Arguments
+/// * `state`: Application state.
+///
 /// # Returns
 /// An Axum router.
-pub(crate) fn router() -> Router<AppState> {
+pub(crate) fn router(state: &AppState) -> Router<AppState> {
     Router::new()
-        .route("/login", post(login))
+        .route("/login", post(login).route_layer(rate_limit_layer!(state)))
+        .route(
+            "/login/magic",
+            post(magic_login).route_layer(rate_limit_layer!(state)),
+        )
+        .route(
+            "/login/magic/send",
+            post(send_magic_link).route_layer(rate_limit_layer!(state, requests_only)),
+        )
+        .route(
+            "/login/totp",
+            post(login_second_factor).route_layer(rate_limit_layer!(state)),
+        )
+        .route(
+            "/login/totp/enroll",
+            post(login_enroll_totp).route_layer(rate_limit_layer!(state)),
+        )
+        .route(
+            "/login/totp/confirm",
+            post(login_confirm_totp).route_layer(rate_limit_layer!(state)),
+        )
         .route("/logout", post(logout))
+        .route(
+            "/password/forgot",
+            post(send_password_reset).route_layer(rate_limit_layer!(state, requests_only)),
+        )
+        .route(
+            "/password/reset",
+            post(reset_password).route_layer(rate_limit_layer!(state)),
+        )
 }
 
 /// Login handler.
@@ -32,6 +75,7 @@ pub(crate) fn router() -> Router<AppState> {
 pub(crate) async fn login(
     auth: Auth,
     db: Db,
+    cache: Cache,
     FormOrJson(credentials): FormOrJson<AuthCredentials>,
 ) -> ApiResult<impl IntoResponse> {
     credentials.validate()?;
@@ -39,15 +83,183 @@ pub(crate) async fn login(
     let db = db.into_shared();
 
     let stores = LoginStores {
-        auth: SQLxAuthStore::new(&db),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
     Login::new(stores).handle((auth, credentials)).await
 }
 
+/// Passwordless login handler (using the token received by email).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn magic_login(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(credentials): FormOrJson<MagicLinkCredentials>,
+) -> ApiResult<impl IntoResponse> {
+    credentials.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = MagicLoginStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    MagicLogin::new(stores).handle((auth, credentials)).await
+}
+
+/// Handler used to send a link to login without password.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn send_magic_link(
+    State(state): State<AppState>,
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<MagicLinkRequest>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = SendMagicLinkStores {
+        mailer: FakeMailer::new(),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    SendMagicLink::new(state.config, stores)
+        .handle(request.email)
+        .await
+}
+
+/// Handler used to send a link to choose a new password.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn send_password_reset(
+    State(state): State<AppState>,
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<PasswordResetRequest>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = SendPasswordResetStores {
+        mailer: FakeMailer::new(),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    SendPasswordReset::new(state.config, stores)
+        .handle(request.email)
+        .await
+}
+
+/// Handler used to choose a new password (using the token received by email).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn reset_password(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<PasswordReset>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = ResetPasswordStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    ResetPassword::new(stores, db).handle(request).await
+}
+
+/// Handler used to complete a login with a second factor.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn login_second_factor(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(credentials): FormOrJson<SecondFactorCredentials>,
+) -> ApiResult<impl IntoResponse> {
+    credentials.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = LoginSecondFactorStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    LoginSecondFactor::new(stores)
+        .handle((auth, credentials))
+        .await
+}
+
+/// Handler used to enroll to the TOTP second factor during a login (when required by the role of
+/// the user).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn login_enroll_totp(
+    State(state): State<AppState>,
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = EnrollTotpStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let enrollment = EnrollTotp::new(state.config, stores).handle(auth).await?;
+
+    Ok(Json(enrollment))
+}
+
+/// Handler used to confirm the enrollment to the TOTP second factor and complete the login.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn login_confirm_totp(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(code): FormOrJson<TotpCode>,
+) -> ApiResult<impl IntoResponse> {
+    code.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = ConfirmTotpStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let codes = ConfirmTotp::new(stores).handle((auth, code)).await?;
+
+    Ok(Json(codes))
+}
+
 /// Logout handler.
 #[instrument]
 #[axum::debug_handler(state = AppState)]
-pub(crate) async fn logout(auth: Auth) -> ApiResult<impl IntoResponse> {
-    Logout::new().handle(auth).await
+pub(crate) async fn logout(auth: Auth, db: Db) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = LogoutStores {
+        auth: SQLxAuthStore::new(&db),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    Logout::new(stores).handle(auth).await
 }
@@ -0,0 +1,29 @@
+//! List of endpoints used to monitor the cache (by an admin user).
+
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use tracing::instrument;
+
+use common_state::AppState;
+use database::cache_stats;
+
+use crate::domain::permission::CacheRead;
+use crate::extractor::Authorized;
+use crate::prelude::*;
+
+/// Builds a router for the cache endpoints.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new().route("/cache/stats", get(get_cache_stats))
+}
+
+/// Handler used to get the number of hits and misses of each cache (since the start of the
+/// application).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn get_cache_stats(_auth: Authorized<CacheRead>) -> ApiResult<impl IntoResponse> {
+    Ok(Json(cache_stats()))
+}
@@ -1,14 +1,43 @@
 //! List of endpoints provided by this crate.
 
+mod api_key;
+mod audit;
 mod auth;
+mod cache;
+mod oauth;
+mod rate_limit;
+mod token;
+mod totp;
 mod user_confirmation;
+mod user_session;
+
+pub use rate_limit::{rate_limit, rate_limit_requests};
 
 /// Builds a router for the authorization crate.
 ///
+/// # Arguments
+/// * `state`: Application state (used by the rate limiting middleware).
+///
+/// # Returns
+/// An Axum router.
+pub fn router(state: common_state::AppState) -> axum::Router<common_state::AppState> {
+    axum::Router::new()
+        .merge(auth::router(&state))
+        .merge(oauth::router())
+        .merge(token::router(&state))
+        .merge(user_confirmation::router(&state))
+}
+
+/// Builds a router for the endpoints of the authorization crate that require an authenticated
+/// user. It must be nested in the `/api` scope.
+///
 /// # Returns
 /// An Axum router.
-pub fn router() -> axum::Router<common_state::AppState> {
+pub fn api_router() -> axum::Router<common_state::AppState> {
     axum::Router::new()
-        .merge(auth::router())
-        .merge(user_confirmation::router())
+        .merge(api_key::router())
+        .merge(audit::router())
+        .merge(cache::router())
+        .merge(totp::router())
+        .merge(user_session::router())
 }
@@ -0,0 +1,65 @@
+//! List of endpoints used to login with an OAuth2 / OpenID Connect provider.
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Redirect};
+use axum::routing::get;
+use axum::Router;
+use tracing::instrument;
+
+use common_core::UseCase;
+use common_state::AppState;
+use database::{Cache, Db};
+
+use crate::application::{AuthorizeOAuth, OAuthLogin, OAuthLoginStores};
+use crate::domain::auth::Auth;
+use crate::domain::oauth::OAuthCallback;
+use crate::infrastructure::{CachedAuthStore, HttpOAuthClient, SQLxAuditStore, SQLxAuthStore};
+use crate::prelude::*;
+
+/// Builds a router for the OAuth endpoints.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new()
+        .route("/oauth/:provider/authorize", get(authorize))
+        .route("/oauth/callback", get(callback))
+}
+
+/// Handler used to redirect the user to the login page of a provider.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn authorize(
+    State(state): State<AppState>,
+    auth: Auth,
+    Path(provider): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    let url = AuthorizeOAuth::new(state.config)
+        .handle((auth, provider))
+        .await?;
+
+    Ok(Redirect::to(&url))
+}
+
+/// Handler called by the provider once the user has logged in.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn callback(
+    State(state): State<AppState>,
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    Query(callback): Query<OAuthCallback>,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = OAuthLoginStores {
+        oauth: HttpOAuthClient::new(),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    OAuthLogin::new(state.config, stores)
+        .handle((auth, callback))
+        .await
+}
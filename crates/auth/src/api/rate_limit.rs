@@ -0,0 +1,145 @@
+//! Middleware protecting the authentication endpoints against brute-force attacks.
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{FromRequest, State};
+use axum::http::{Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use common_state::AppState;
+use common_web::extractor::FormOrJson;
+
+use crate::domain::auth::Auth;
+use crate::domain::rate_limit::{RateLimitTarget, RateLimiter};
+use crate::extractor::metadata;
+use crate::infrastructure::RedisRateLimitStore;
+use crate::prelude::*;
+
+/// Maximum size of the bodies read to find the target of the requests.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Macro used to create the rate limiting middleware of an endpoint.
+///
+/// The failed authentications of the endpoint lock the accounts, unless `requests_only` is
+/// given (endpoints sending emails, whose responses don't depend on the credentials).
+///
+/// ```ignore
+/// Router::new().route(
+///     "/profile",
+///     post(register_user).route_layer(rate_limit_layer!(state, requests_only)),
+/// )
+/// ```
+#[macro_export]
+macro_rules! rate_limit_layer {
+    ($state: ident) => {
+        axum::middleware::from_fn_with_state($state.clone(), $crate::rate_limit)
+    };
+    ($state: ident, requests_only) => {
+        axum::middleware::from_fn_with_state($state.clone(), $crate::rate_limit_requests)
+    };
+}
+
+/// Limits the number of requests by client IP address and by target email, and locks the
+/// accounts after too many failed authentications (i.e. `401 Unauthorized` responses).
+///
+/// # Arguments
+/// * `state`: Application state.
+/// * `auth`: Authentication object (used to get the email of the logged user, if any).
+/// * `request`: HTTP request.
+/// * `next`: Next middleware in the chain.
+///
+/// # Returns
+/// Result containing the next response, or an error if a limit is reached.
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    auth: Option<Auth>,
+    request: Request<Body>,
+    next: Next,
+) -> ApiResult<Response> {
+    limit(state, auth, request, next, true).await
+}
+
+/// Limits the number of requests by client IP address and by target email, without counting
+/// the authentications (e.g. a password reset request must not forget the failures of an account).
+///
+/// # Arguments
+/// * `state`: Application state.
+/// * `auth`: Authentication object (used to get the email of the logged user, if any).
+/// * `request`: HTTP request.
+/// * `next`: Next middleware in the chain.
+///
+/// # Returns
+/// Result containing the next response, or an error if a limit is reached.
+pub async fn rate_limit_requests(
+    State(state): State<AppState>,
+    auth: Option<Auth>,
+    request: Request<Body>,
+    next: Next,
+) -> ApiResult<Response> {
+    limit(state, auth, request, next, false).await
+}
+
+/// Applies the limits to a request.
+///
+/// # Arguments
+/// * `state`: Application state.
+/// * `auth`: Authentication object.
+/// * `request`: HTTP request.
+/// * `next`: Next middleware in the chain.
+/// * `authentication`: Whether the response of the endpoint is the result of an authentication.
+///
+/// # Returns
+/// Result containing the next response, or an error if a limit is reached.
+async fn limit(
+    state: AppState,
+    auth: Option<Auth>,
+    request: Request<Body>,
+    next: Next,
+    authentication: bool,
+) -> ApiResult<Response> {
+    let settings = state.config.auth.rate_limit.clone();
+
+    if !settings.enabled {
+        return Ok(next.run(request).await);
+    }
+
+    let (parts, body) = request.into_parts();
+
+    let route = parts.uri.path().to_string();
+
+    let ip_address = metadata(&parts, state.config.application.trust_proxy_headers)
+        .ip_address
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // The body is read to find the target and then given back to the handler
+    let bytes = to_bytes(body, MAX_BODY_SIZE)
+        .await
+        .map_err(|_| Error::PayloadTooLarge)?;
+
+    let copy = Request::from_parts(parts.clone(), Body::from(bytes.clone()));
+
+    let email = FormOrJson::<RateLimitTarget>::from_request(copy, &state)
+        .await
+        .ok()
+        .and_then(|FormOrJson(target)| target.email)
+        .or_else(|| auth.and_then(|auth| auth.user.map(|user| user.email)))
+        .map(|email| email.trim().to_lowercase());
+
+    let limiter = RateLimiter::new(settings, RedisRateLimitStore::new(state.redis.clone()));
+
+    limiter.check(&route, &ip_address, email.as_deref()).await?;
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+
+    if let Some(email) = email.as_ref().filter(|_| authentication) {
+        match response.status() {
+            StatusCode::UNAUTHORIZED => limiter.record_failure(email).await?,
+            status if status.is_success() => limiter.record_success(email).await?,
+            _ => (),
+        }
+    }
+
+    Ok(response)
+}
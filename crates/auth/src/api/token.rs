@@ -0,0 +1,80 @@
+//! List of endpoints used to obtain bearer tokens (for clients that don't use cookies).
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::post;
+use axum::Router;
+use tracing::instrument;
+use validator::Validate;
+
+use common_core::UseCase;
+use common_state::AppState;
+use common_web::extractor::FormOrJson;
+use database::{Cache, Db};
+
+use crate::application::{IssueTokens, IssueTokensStores, RevokeToken, RevokeTokenStores};
+use crate::domain::auth::Auth;
+use crate::domain::token::{TokenRequest, TokenRevocationRequest};
+use crate::infrastructure::{CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
+use crate::prelude::*;
+use crate::rate_limit_layer;
+
+/// Builds a router for the bearer tokens endpoints.
+///
+/// # Arguments
+/// * `state`: Application state.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/token",
+            post(issue_tokens).route_layer(rate_limit_layer!(state)),
+        )
+        .route("/token/revoke", post(revoke_token))
+}
+
+/// Handler used to obtain an access token and a refresh token (password or refresh token grant).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn issue_tokens(
+    State(state): State<AppState>,
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<TokenRequest>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = IssueTokensStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    IssueTokens::new(state.config, stores).handle(request).await
+}
+
+/// Handler used to revoke a refresh token (and all the tokens issued from the same login).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn revoke_token(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<TokenRevocationRequest>,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = RevokeTokenStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    RevokeToken::new(stores).handle(request).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
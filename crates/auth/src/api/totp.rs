@@ -0,0 +1,132 @@
+//! List of endpoints used to manage the second factor of the users.
+
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use tracing::instrument;
+use validator::Validate;
+
+use common_core::UseCase;
+use common_state::AppState;
+use common_web::extractor::FormOrJson;
+use database::{Cache, Db};
+
+use crate::application::{
+    ConfirmTotp, ConfirmTotpStores, EnrollTotp, EnrollTotpStores, ListRolePolicies,
+    ListRolePoliciesStores, SetRolePolicy, SetRolePolicyStores,
+};
+use crate::domain::auth::Auth;
+use crate::domain::auth_user::AuthUserRole;
+use crate::domain::permission::{RolesRead, RolesUpdate};
+use crate::domain::totp::{AuthRolePolicy, AuthRolePolicyUpdate, TotpCode};
+use crate::extractor::Authorized;
+use crate::infrastructure::{CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
+use crate::prelude::*;
+
+/// Builds a router for the second factor endpoints.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new()
+        .route("/users/current/totp", post(enroll_current_user_totp))
+        .route(
+            "/users/current/totp/confirm",
+            post(confirm_current_user_totp),
+        )
+        .route("/auth/policies", get(get_role_policies))
+        .route("/auth/policies/:role", put(put_role_policy))
+}
+
+/// Handler used to start the enrollment of the currently logged user to the TOTP second factor.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn enroll_current_user_totp(
+    State(state): State<AppState>,
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = EnrollTotpStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let enrollment = EnrollTotp::new(state.config, stores).handle(auth).await?;
+
+    Ok(Json(enrollment))
+}
+
+/// Handler used to confirm the enrollment of the currently logged user to the TOTP second factor.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn confirm_current_user_totp(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(code): FormOrJson<TotpCode>,
+) -> ApiResult<impl IntoResponse> {
+    code.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = ConfirmTotpStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let codes = ConfirmTotp::new(stores).handle((auth, code)).await?;
+
+    Ok(Json(codes))
+}
+
+/// Handler used to list the security policies of the roles.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn get_role_policies(
+    _: Authorized<RolesRead>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = ListRolePoliciesStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+    };
+
+    let policies = ListRolePolicies::new(stores).handle(()).await?;
+
+    Ok(Json(policies))
+}
+
+/// Handler used to update the security policy of a role.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn put_role_policy(
+    auth: Authorized<RolesUpdate>,
+    Path(role): Path<AuthUserRole>,
+    db: Db,
+    cache: Cache,
+    FormOrJson(update): FormOrJson<AuthRolePolicyUpdate>,
+) -> ApiResult<impl IntoResponse> {
+    update.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = SetRolePolicyStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let policy = AuthRolePolicy {
+        role,
+        totp_required: update.totp_required,
+    };
+
+    let policy = SetRolePolicy::new(stores).handle(policy).await?;
+
+    Ok(Json(policy))
+}
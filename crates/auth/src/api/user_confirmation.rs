@@ -9,24 +9,34 @@ use uuid::Uuid;
 
 use common_core::UseCase;
 use common_state::AppState;
-use database::Db;
+use database::{Cache, Db};
 use mailer::FakeMailer;
 
 use crate::application::{
     ConfirmEmail, ConfirmEmailStores, SendEmailConfirmation, SendEmailConfirmationStores,
 };
 use crate::domain::auth::Auth;
-use crate::infrastructure::SQLxAuthStore;
+use crate::infrastructure::{CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
 use crate::prelude::*;
+use crate::rate_limit_layer;
 
 /// Builds a router for the authorization endpoints.
 ///
+/// # Arguments
+/// * `state`: Application state.
+///
 /// # Returns
 /// An Axum router.
-pub(crate) fn router() -> Router<AppState> {
+pub(crate) fn router(state: &AppState) -> Router<AppState> {
     Router::new()
-        .route("/confirm", post(confirm_email))
-        .route("/send_confirmation", post(send_email_confirmation))
+        .route(
+            "/confirm",
+            post(confirm_email).route_layer(rate_limit_layer!(state, requests_only)),
+        )
+        .route(
+            "/send_confirmation",
+            post(send_email_confirmation).route_layer(rate_limit_layer!(state, requests_only)),
+        )
 }
 
 /// Parameters for the email confirmation endpoint.
@@ -43,11 +53,13 @@ pub(crate) async fn confirm_email(
     auth: Auth,
     Query(params): Query<ConfirmEmailParams>,
     db: Db,
+    cache: Cache,
 ) -> ApiResult<impl IntoResponse> {
     let db = db.into_shared();
 
     let stores = ConfirmEmailStores {
-        auth: SQLxAuthStore::new(&db),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
     ConfirmEmail::new(stores).handle(params.token).await
@@ -60,6 +72,7 @@ pub(crate) async fn send_email_confirmation(
     auth: Auth,
     State(state): State<AppState>,
     db: Db,
+    cache: Cache,
 ) -> ApiResult<impl IntoResponse> {
     let user = auth.try_user()?;
 
@@ -67,7 +80,8 @@ pub(crate) async fn send_email_confirmation(
 
     let stores = SendEmailConfirmationStores {
         mailer: FakeMailer::new(),
-        auth: SQLxAuthStore::new(&db),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
     SendEmailConfirmation::new(state.config, stores, db)
@@ -0,0 +1,106 @@
+//! List of endpoints used to manage the sessions of the users.
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use tracing::instrument;
+use uuid::Uuid;
+
+use common_core::UseCase;
+use common_state::AppState;
+use database::{Cache, Db};
+
+use crate::application::{
+    ListSessions, ListSessionsStores, RevokeSession, RevokeSessionStores, RevokeSessions,
+    RevokeSessionsStores,
+};
+use crate::domain::auth::Auth;
+use crate::domain::permission::SessionsDelete;
+use crate::extractor::Authorized;
+use crate::infrastructure::{CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
+use crate::prelude::*;
+
+/// Builds a router for the sessions endpoints.
+///
+/// # Returns
+/// An Axum router.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new()
+        .route("/users/current/sessions", get(get_current_user_sessions))
+        .route(
+            "/users/current/sessions/:session_id",
+            delete(delete_current_user_session),
+        )
+        .route("/users/:user_id/sessions", delete(delete_user_sessions))
+}
+
+/// Handler used to list the sessions of the currently logged user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn get_current_user_sessions(auth: Auth, db: Db) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    let user = auth.try_user()?;
+
+    let db = db.into_shared();
+
+    let stores = ListSessionsStores {
+        auth: SQLxAuthStore::new(&db),
+    };
+
+    let sessions = ListSessions::new(stores)
+        .handle((user.id, auth.session_id))
+        .await?;
+
+    Ok(Json(sessions))
+}
+
+/// Handler used to revoke a session of the currently logged user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn delete_current_user_session(
+    auth: Auth,
+    Path(session_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    let user = auth.try_user()?;
+
+    let db = db.into_shared();
+
+    let stores = RevokeSessionStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    RevokeSession::new(stores)
+        .handle((user.id, session_id))
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Handler used by an admin to revoke all sessions of a user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn delete_user_sessions(
+    auth: Authorized<SessionsDelete>,
+    Path(user_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = RevokeSessionsStores {
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    RevokeSessions::new(stores).handle(user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,98 @@
+//! Use-case for starting a login with an OAuth provider.
+
+use common_core::UseCase;
+use configuration::Config;
+
+use crate::domain::auth::Auth;
+use crate::domain::oauth::OAuthPending;
+use crate::prelude::*;
+
+/// OAuth authorization use-case structure.
+pub(crate) struct AuthorizeOAuth {
+    /// Application configuration.
+    config: Config,
+}
+
+impl AuthorizeOAuth {
+    /// Creates a `AuthorizeOAuth` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    ///
+    /// # Returns
+    /// A `AuthorizeOAuth` instance.
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+impl UseCase for AuthorizeOAuth {
+    /// Session of the caller and name of the provider.
+    type Args = (Auth, String);
+
+    /// URL of the provider where the user must be redirected.
+    type Output = String;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (auth, provider) = args;
+
+        let settings = self
+            .config
+            .oauth
+            .providers
+            .get(&provider)
+            .ok_or(Error::OAuthProviderNotFound)?;
+
+        let pending = OAuthPending::generate(&provider);
+        let url = pending.authorize_url(settings)?;
+
+        // Kept until the provider calls back (replaces any previous request)
+        auth.session.insert(OAuthPending::KEY, pending).await?;
+
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::utils::{new_auth, oauth_settings, StubProvider};
+
+    #[tokio::test]
+    async fn test_authorize_oauth_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+
+        let mut config = Config::new()?;
+        config
+            .oauth
+            .providers
+            .insert("stub".to_string(), oauth_settings(&stub));
+
+        let auth = new_auth();
+        let session = auth.session.clone();
+
+        let url = AuthorizeOAuth::new(config)
+            .handle((auth, "stub".to_string()))
+            .await?;
+
+        let pending: Option<OAuthPending> = session.get(OAuthPending::KEY).await?;
+        let pending = pending.ok_or("Missing pending authorization")?;
+
+        assert!(url.starts_with(&format!("{}/authorize?", stub.url)));
+        assert!(url.contains(&format!("state={}", pending.state)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authorize_oauth_unknown_provider() -> Result<(), Box<dyn std::error::Error>> {
+        let res = AuthorizeOAuth::new(Config::new()?)
+            .handle((new_auth(), "unknown".to_string()))
+            .await;
+        assert!(matches!(res, Err(Error::OAuthProviderNotFound)));
+
+        Ok(())
+    }
+}
@@ -4,43 +4,52 @@ use uuid::Uuid;
 
 use common_core::UseCase;
 
-use crate::domain::port::AuthStore;
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth_user::Expiring;
+use crate::domain::port::{AuditStore, AuthStore};
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct ConfirmEmailStores<A>
+pub(crate) struct ConfirmEmailStores<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     /// Auth store.
     pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
 }
 
 /// User confirmation use-case structure.
-pub(crate) struct ConfirmEmail<A>
+pub(crate) struct ConfirmEmail<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     /// List of stores used.
-    stores: ConfirmEmailStores<A>,
+    stores: ConfirmEmailStores<A, B>,
 }
 
-impl<A> ConfirmEmail<A>
+impl<A, B> ConfirmEmail<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     /// Creates a `ConfirmEmail` use-case instance.
     ///
     /// # Returns
     /// A `ConfirmEmail` instance.
-    pub fn new(stores: ConfirmEmailStores<A>) -> Self {
+    pub fn new(stores: ConfirmEmailStores<A, B>) -> Self {
         Self { stores }
     }
 }
 
-impl<A> UseCase for ConfirmEmail<A>
+impl<A, B> UseCase for ConfirmEmail<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     type Args = Uuid;
     type Output = ();
@@ -64,6 +73,15 @@ where
         self.stores
             .auth
             .delete_user_confirmation_by_id(&confirmation_id)
+            .await?;
+
+        // Following the link proves that the caller is the user
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::EmailConfirm, Some(confirmation.user_id))
+                    .actor(confirmation.user_id),
+            )
             .await
     }
 }
@@ -74,8 +92,10 @@ mod tests {
 
     use chrono::{DateTime, Utc};
 
+    use crate::domain::audit::AuditAction;
     use crate::domain::auth_user::AuthUserConfirmation;
     use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
 
     #[tokio::test]
     async fn test_confirm_email_nominal() -> Result<(), Box<dyn std::error::Error>> {
@@ -101,7 +121,10 @@ mod tests {
 
         let confirmation_id = Uuid::new_v4();
 
-        let stores = ConfirmEmailStores { auth: auth_store };
+        let stores = ConfirmEmailStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::EmailConfirm]),
+        };
 
         let res = ConfirmEmail::new(stores).handle(confirmation_id).await;
         assert!(res.is_ok());
@@ -120,7 +143,10 @@ mod tests {
 
         let confirmation_id = Uuid::new_v4();
 
-        let stores = ConfirmEmailStores { auth: auth_store };
+        let stores = ConfirmEmailStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
 
         let res = ConfirmEmail::new(stores).handle(confirmation_id).await;
         assert!(matches!(res, Err(Error::ConfirmationLinkExpired)));
@@ -0,0 +1,276 @@
+//! Use-case for confirming the enrollment of a user to the TOTP second factor.
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth::Auth;
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::domain::totp::{RecoveryCodes, TotpCode};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ConfirmTotpStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// TOTP enrollment confirmation use-case structure.
+pub(crate) struct ConfirmTotp<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: ConfirmTotpStores<A, B>,
+}
+
+impl<A, B> ConfirmTotp<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `ConfirmTotp` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ConfirmTotp` instance.
+    pub fn new(stores: ConfirmTotpStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for ConfirmTotp<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = (Auth, TotpCode);
+    type Output = RecoveryCodes;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (mut auth, code) = args;
+
+        let user_id = auth.enrolling_user_id().await?;
+
+        let totp = self
+            .stores
+            .auth
+            .get_totp_by_user_id(&user_id)
+            .await?
+            .ok_or(Error::TotpNotEnrolled)?;
+
+        if totp.enabled {
+            return Err(Error::TotpAlreadyEnabled);
+        }
+
+        // The first code proves that the authenticator app is correctly configured
+        let Some(step) = totp.verify(&code.code)? else {
+            return Err(Error::InvalidSecondFactor);
+        };
+
+        let codes = totp.enable(step, &self.stores.auth).await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::TotpConfirm, Some(user_id)).actor(user_id))
+            .await?;
+
+        // The enrollment was required to complete a login
+        if auth.user().is_none() {
+            auth.clear_pending_second_factor().await?;
+
+            let user = self.stores.auth.get_user_by_id(&user_id).await?;
+
+            auth.login(&user, &self.stores.auth).await?;
+
+            self.stores
+                .audit
+                .record(AuditRecord::new(AuditAction::Login, Some(user.id)).actor(user.id))
+                .await?;
+        }
+
+        Ok(codes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth::PendingSecondFactor;
+    use crate::domain::auth_session::AuthSession;
+    use crate::domain::auth_user::AuthUser;
+    use crate::domain::port::MockAuthStore;
+    use crate::domain::totp::AuthTotp;
+    use crate::tests::utils::{mock_audit_store, new_auth};
+
+    fn mock_store(totp: &AuthTotp) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        let totp = totp.clone();
+
+        auth_store
+            .expect_get_totp_by_user_id()
+            .times(1)
+            .returning(move |_| {
+                let totp = totp.clone();
+                Box::pin(async move { Ok(Some(totp)) })
+            });
+
+        auth_store
+    }
+
+    #[tokio::test]
+    async fn test_confirm_totp_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp::generate(&user.id);
+
+        let mut auth_store = mock_store(&totp);
+
+        auth_store
+            .expect_save_totp()
+            .times(1)
+            .withf(|totp| totp.enabled && totp.last_used_step.is_some())
+            .returning(move |_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_create_recovery_codes()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(()) }));
+
+        auth_store.expect_create_session().never();
+
+        let mut auth = new_auth();
+        auth.user = Some(user);
+
+        let code = TotpCode {
+            code: totp.code_at(Utc::now().timestamp() as u64)?,
+        };
+
+        let stores = ConfirmTotpStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TotpConfirm]),
+        };
+
+        let codes = ConfirmTotp::new(stores).handle((auth, code)).await?;
+        assert!(!codes.recovery_codes.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirm_totp_pending_login() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp::generate(&user.id);
+
+        let mut auth_store = mock_store(&totp);
+
+        auth_store
+            .expect_save_totp()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_create_recovery_codes()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(()) }));
+
+        let found = user.clone();
+
+        auth_store
+            .expect_get_user_by_id()
+            .times(1)
+            .returning(move |_| {
+                let user = found.clone();
+                Box::pin(async move { Ok(user) })
+            });
+
+        auth_store
+            .expect_create_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthSession::default()) }));
+
+        let auth = new_auth();
+        let session = auth.session.clone();
+
+        let pending = PendingSecondFactor {
+            user_id: user.id,
+            expires_at: Utc::now() + Duration::minutes(5),
+            attempts: 0,
+        };
+
+        session
+            .insert(Auth::PENDING_SECOND_FACTOR_KEY, pending)
+            .await?;
+
+        let code = TotpCode {
+            code: totp.code_at(Utc::now().timestamp() as u64)?,
+        };
+
+        let stores = ConfirmTotpStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TotpConfirm, AuditAction::Login]),
+        };
+
+        ConfirmTotp::new(stores).handle((auth, code)).await?;
+
+        let logged: Option<AuthUser> = session.get(Auth::KEY).await?;
+        assert_eq!(logged, Some(user));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirm_totp_invalid_code() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp::generate(&user.id);
+
+        let mut auth_store = mock_store(&totp);
+
+        auth_store.expect_save_totp().never();
+
+        let mut auth = new_auth();
+        auth.user = Some(user);
+
+        let code = TotpCode {
+            code: totp.code_at(0)?,
+        };
+
+        let stores = ConfirmTotpStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = ConfirmTotp::new(stores).handle((auth, code)).await;
+        assert!(matches!(res, Err(Error::InvalidSecondFactor)));
+
+        Ok(())
+    }
+}
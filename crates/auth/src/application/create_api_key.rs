@@ -0,0 +1,155 @@
+//! Use-case for creating an API key for a user.
+
+use tracing::{event, Level};
+
+use common_core::UseCase;
+
+use crate::domain::api_key::{ApiKeyRequest, ApiKeyValue, CreatedApiKey};
+use crate::domain::audit::{AuditAction, AuditChanges, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct CreateApiKeyStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// API key creation use-case structure.
+pub(crate) struct CreateApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: CreateApiKeyStores<A, B>,
+}
+
+impl<A, B> CreateApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `CreateApiKey` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `CreateApiKey` instance.
+    pub fn new(stores: CreateApiKeyStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for CreateApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// ID of the user and information about the key.
+    type Args = (Uuid, ApiKeyRequest);
+    type Output = CreatedApiKey;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (user_id, request) = args;
+
+        let secret = ApiKeyValue::generate_secret();
+
+        let api_key = self
+            .stores
+            .auth
+            .create_api_key(
+                &user_id,
+                &request.name,
+                &ApiKeyValue::hash_secret(&secret)?,
+                &request.scopes,
+            )
+            .await?;
+
+        let key = ApiKeyValue {
+            id: api_key.id,
+            secret,
+        };
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::ApiKeyCreate, Some(user_id))
+                    .changes(AuditChanges::diff(None, Some(&api_key))),
+            )
+            .await?;
+
+        event!(Level::INFO, "API key {} created", api_key.id);
+
+        Ok(CreatedApiKey {
+            api_key,
+            key: key.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::rand::*;
+
+    use crate::domain::api_key::AuthApiKey;
+    use crate::domain::audit::AuditAction;
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_create_api_key_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_create_api_key()
+            .times(1)
+            .returning(|user_id, name, key_hash, scopes| {
+                let api_key = AuthApiKey {
+                    id: Uuid::new_v4(),
+                    user_id: *user_id,
+                    name: name.to_string(),
+                    key_hash: key_hash.to_string(),
+                    scopes: scopes.to_vec(),
+                    ..Default::default()
+                };
+                Box::pin(async move { Ok(api_key) })
+            });
+
+        let stores = CreateApiKeyStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::ApiKeyCreate]),
+        };
+
+        let request = ApiKeyRequest {
+            name: random_string(),
+            scopes: vec!["users:read".to_string()],
+        };
+
+        let created = CreateApiKey::new(stores)
+            .handle((random_id(), request.clone()))
+            .await?;
+
+        assert_eq!(created.api_key.name, request.name);
+        assert_eq!(created.api_key.scopes, request.scopes);
+
+        // Only the hash of the key is stored
+        let value: ApiKeyValue = created.key.parse()?;
+        assert_eq!(value.id, created.api_key.id);
+        assert_ne!(created.api_key.key_hash, value.secret);
+        assert!(created.api_key.matches(&value).await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Use-case for starting the enrollment of a user to the TOTP second factor.
+
+use common_core::UseCase;
+use configuration::Config;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth::Auth;
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::domain::totp::{AuthTotp, TotpEnrollment};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct EnrollTotpStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// TOTP enrollment use-case structure.
+pub(crate) struct EnrollTotp<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: EnrollTotpStores<A, B>,
+}
+
+impl<A, B> EnrollTotp<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `EnrollTotp` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `EnrollTotp` instance.
+    pub fn new(config: Config, stores: EnrollTotpStores<A, B>) -> Self {
+        Self { config, stores }
+    }
+}
+
+impl<A, B> UseCase for EnrollTotp<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = Auth;
+    type Output = TotpEnrollment;
+    type Error = Error;
+
+    async fn handle(&self, auth: Self::Args) -> Result<Self::Output, Self::Error> {
+        let user_id = auth.enrolling_user_id().await?;
+
+        let user = self
+            .stores
+            .auth
+            .get_user_by_id(&user_id)
+            .await
+            .map_err(|_| Error::UserNotFound)?;
+
+        let current = self.stores.auth.get_totp_by_user_id(&user.id).await?;
+
+        if current.is_some_and(|totp| totp.enabled) {
+            return Err(Error::TotpAlreadyEnabled);
+        }
+
+        // A new secret is generated each time, until the enrollment is confirmed
+        let totp = AuthTotp::generate(&user.id);
+
+        self.stores.auth.save_totp(&totp).await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::TotpEnroll, Some(user.id)).actor(user.id))
+            .await?;
+
+        Ok(TotpEnrollment {
+            uri: totp.uri(&self.config.auth.totp_issuer, &user.email)?,
+            secret: totp.secret,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth_user::AuthUser;
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::{mock_audit_store, new_auth};
+
+    fn mock_store(user: &AuthUser, totp: Option<AuthTotp>) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        let user = user.clone();
+
+        auth_store
+            .expect_get_user_by_id()
+            .times(1)
+            .returning(move |_| {
+                let user = user.clone();
+                Box::pin(async move { Ok(user) })
+            });
+
+        auth_store
+            .expect_get_totp_by_user_id()
+            .times(1)
+            .returning(move |_| {
+                let totp = totp.clone();
+                Box::pin(async move { Ok(totp) })
+            });
+
+        auth_store
+    }
+
+    #[tokio::test]
+    async fn test_enroll_totp_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            email: random_email(),
+            ..Default::default()
+        };
+
+        let mut auth_store = mock_store(&user, None);
+
+        auth_store
+            .expect_save_totp()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(()) }));
+
+        let mut auth = new_auth();
+        auth.user = Some(user);
+
+        let config = Config::new()?;
+        let stores = EnrollTotpStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TotpEnroll]),
+        };
+
+        let enrollment = EnrollTotp::new(config, stores).handle(auth).await?;
+        assert!(enrollment.uri.starts_with("otpauth://totp/"));
+        assert!(enrollment.uri.contains(&enrollment.secret));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_enroll_totp_already_enabled() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            email: random_email(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp {
+            enabled: true,
+            ..AuthTotp::generate(&user.id)
+        };
+
+        let mut auth_store = mock_store(&user, Some(totp));
+
+        auth_store.expect_save_totp().never();
+
+        let mut auth = new_auth();
+        auth.user = Some(user);
+
+        let config = Config::new()?;
+        let stores = EnrollTotpStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = EnrollTotp::new(config, stores).handle(auth).await;
+        assert!(matches!(res, Err(Error::TotpAlreadyEnabled)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_enroll_totp_anonymous() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store.expect_save_totp().never();
+
+        let config = Config::new()?;
+        let stores = EnrollTotpStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = EnrollTotp::new(config, stores).handle(new_auth()).await;
+        assert!(matches!(res, Err(Error::SecondFactorNotPending)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,591 @@
+//! Use-case for issuing bearer tokens (token endpoint).
+
+use chrono::Duration;
+use tracing::{event, Level};
+
+use common_core::UseCase;
+use configuration::Config;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth_user::{AuthUser, Expiring};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::domain::token::{GrantType, JwtCodec, RefreshTokenValue, TokenRequest, TokenResponse};
+use crate::domain::totp::SecondFactorCredentials;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct IssueTokensStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Tokens issuance use-case structure.
+pub(crate) struct IssueTokens<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: IssueTokensStores<A, B>,
+}
+
+impl<A, B> IssueTokens<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `IssueTokens` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `IssueTokens` instance.
+    pub fn new(config: Config, stores: IssueTokensStores<A, B>) -> Self {
+        Self { config, stores }
+    }
+
+    /// Checks the credentials of the user (and the second factor if needed).
+    ///
+    /// # Arguments
+    /// * `request`: Request sent to the token endpoint.
+    ///
+    /// # Returns
+    /// A result containing the user.
+    async fn authenticate(&self, request: &TokenRequest) -> ApiResult<AuthUser> {
+        let (Some(email), Some(password)) = (&request.email, &request.password) else {
+            return Err(Error::InvalidGrant);
+        };
+
+        let user = self
+            .stores
+            .auth
+            .find_user_by_email(email)
+            .await
+            .map_err(|_| Error::UserNotFound)?;
+
+        if !password.matches(&user.password).await.unwrap_or(false) {
+            event!(Level::ERROR, "Invalid credentials");
+            return Err(Error::Unauthorized);
+        }
+
+        if !user.is_email_confirmed() {
+            return Err(Error::EmailNotConfirmed);
+        }
+
+        let enabled = self
+            .stores
+            .auth
+            .get_totp_by_user_id(&user.id)
+            .await?
+            .is_some_and(|totp| totp.enabled);
+
+        if enabled {
+            let credentials = SecondFactorCredentials {
+                code: request.code.clone(),
+                recovery_code: request.recovery_code.clone(),
+            };
+
+            if credentials.code.is_none() && credentials.recovery_code.is_none() {
+                return Err(Error::SecondFactorRequired);
+            }
+
+            if !credentials.check(&user.id, &self.stores.auth).await? {
+                event!(Level::ERROR, "Invalid second factor");
+                return Err(Error::InvalidSecondFactor);
+            }
+        } else if self
+            .stores
+            .auth
+            .get_role_policy(&user.role)
+            .await?
+            .totp_required
+        {
+            return Err(Error::SecondFactorEnrollmentRequired);
+        }
+
+        Ok(user)
+    }
+
+    /// Consumes a refresh token. If the token has already been used, all the tokens of its family
+    /// are revoked.
+    ///
+    /// # Arguments
+    /// * `request`: Request sent to the token endpoint.
+    ///
+    /// # Returns
+    /// A result containing the user and the ID of the family of the token.
+    async fn rotate(&self, request: &TokenRequest) -> ApiResult<(AuthUser, Uuid)> {
+        let value: RefreshTokenValue = request
+            .refresh_token
+            .as_deref()
+            .ok_or(Error::InvalidGrant)?
+            .parse()?;
+
+        let token = self
+            .stores
+            .auth
+            .get_refresh_token_by_id(&value.id)
+            .await?
+            .filter(|token| token.matches(&value))
+            .ok_or(Error::RefreshTokenNotFound)?;
+
+        // The second use of a token can only be made with a stolen token (or by a thief)
+        if token.is_spent() || !self.stores.auth.use_refresh_token(&token.id).await? {
+            event!(Level::WARN, "Refresh token reused: revoke family");
+
+            self.stores
+                .auth
+                .revoke_refresh_token_family(&token.family_id)
+                .await?;
+
+            return Err(Error::RefreshTokenReused);
+        }
+
+        if token.is_expired() {
+            return Err(Error::RefreshTokenExpired);
+        }
+
+        // Make sure the user still exists
+        let user = self
+            .stores
+            .auth
+            .get_user_by_id(&token.user_id)
+            .await
+            .map_err(|_| Error::UserNotFound)?;
+
+        Ok((user, token.family_id))
+    }
+}
+
+impl<A, B> UseCase for IssueTokens<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = TokenRequest;
+    type Output = TokenResponse;
+    type Error = Error;
+
+    async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
+        let codec = JwtCodec::from_config(&self.config)?;
+
+        let (user, family_id) = match request.grant_type {
+            GrantType::Password => (self.authenticate(&request).await?, Uuid::new_v4()),
+            GrantType::RefreshToken => self.rotate(&request).await?,
+        };
+        let user_id = user.id;
+
+        let secret = RefreshTokenValue::generate_secret();
+        let timeout = Duration::days(self.config.auth.jwt.refresh_token_lifetime_days.into());
+
+        let record = self
+            .stores
+            .auth
+            .create_refresh_token(
+                &user_id,
+                &family_id,
+                &RefreshTokenValue::hash_secret(&secret),
+                &timeout,
+            )
+            .await?;
+
+        let refresh_token = RefreshTokenValue {
+            id: record.id,
+            secret,
+        };
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::TokenIssue, Some(user_id)).actor(user_id))
+            .await?;
+
+        event!(Level::INFO, "Tokens issued for user {}", user_id);
+
+        Ok(TokenResponse::new(
+            codec.encode(&user)?,
+            codec.lifetime(),
+            &refresh_token,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
+    use security::password::Password;
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::port::MockAuthStore;
+    use crate::domain::token::AuthRefreshToken;
+    use crate::domain::totp::{AuthRolePolicy, AuthTotp};
+    use crate::tests::utils::mock_audit_store;
+
+    fn password_request(password: &Password) -> TokenRequest {
+        TokenRequest {
+            grant_type: GrantType::Password,
+            email: Some(random_email()),
+            password: Some(password.clone()),
+            code: None,
+            recovery_code: None,
+            refresh_token: None,
+        }
+    }
+
+    fn refresh_request(value: &RefreshTokenValue) -> TokenRequest {
+        TokenRequest {
+            grant_type: GrantType::RefreshToken,
+            email: None,
+            password: None,
+            code: None,
+            recovery_code: None,
+            refresh_token: Some(value.to_string()),
+        }
+    }
+
+    fn mock_user_store(password: &Password, totp: Option<AuthTotp>) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        let user = AuthUser {
+            id: random_id(),
+            password: password.hashed().unwrap(),
+            email_confirmed: true,
+            ..Default::default()
+        };
+
+        auth_store.expect_find_user_by_email().returning(move |_| {
+            let user = user.clone();
+            Box::pin(async move { Ok(user) })
+        });
+
+        auth_store.expect_get_totp_by_user_id().returning(move |_| {
+            let totp = totp.clone();
+            Box::pin(async move { Ok(totp) })
+        });
+
+        auth_store
+            .expect_get_role_policy()
+            .returning(|_| Box::pin(async move { Ok(AuthRolePolicy::default()) }));
+
+        auth_store
+    }
+
+    fn mock_token_store(token: &AuthRefreshToken) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        let token = token.clone();
+
+        auth_store
+            .expect_get_refresh_token_by_id()
+            .times(1)
+            .returning(move |_| {
+                let token = token.clone();
+                Box::pin(async move { Ok(Some(token)) })
+            });
+
+        auth_store.expect_get_user_by_id().returning(|id| {
+            let id = *id;
+            Box::pin(async move {
+                Ok(AuthUser {
+                    id,
+                    ..Default::default()
+                })
+            })
+        });
+
+        auth_store
+    }
+
+    fn expect_create_refresh_token(auth_store: &mut MockAuthStore) {
+        auth_store.expect_create_refresh_token().times(1).returning(
+            |user_id, family_id, token_hash, timeout| {
+                let token = AuthRefreshToken {
+                    id: Uuid::new_v4(),
+                    user_id: *user_id,
+                    family_id: *family_id,
+                    token_hash: token_hash.to_string(),
+                    expires_at: Utc::now() + *timeout,
+                    ..Default::default()
+                };
+                Box::pin(async move { Ok(token) })
+            },
+        );
+    }
+
+    fn refresh_token() -> (RefreshTokenValue, AuthRefreshToken) {
+        let value = RefreshTokenValue {
+            id: random_id(),
+            secret: RefreshTokenValue::generate_secret(),
+        };
+
+        let token = AuthRefreshToken {
+            id: value.id,
+            user_id: random_id(),
+            family_id: random_id(),
+            token_hash: value.hash(),
+            expires_at: Utc::now() + Duration::days(1),
+            ..Default::default()
+        };
+
+        (value, token)
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_password() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let config = Config::new()?;
+        let password = random_password();
+
+        let mut auth_store = mock_user_store(&password, None);
+        expect_create_refresh_token(&mut auth_store);
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TokenIssue]),
+        };
+
+        let tokens = IssueTokens::new(config.clone(), stores)
+            .handle(password_request(&password))
+            .await?;
+
+        assert_eq!(tokens.token_type, "Bearer");
+
+        let claims = JwtCodec::from_config(&config)?.decode(&tokens.access_token)?;
+        assert_eq!(tokens.expires_in, claims.exp - claims.iat);
+
+        let value: RefreshTokenValue = tokens.refresh_token.parse()?;
+        assert!(!value.secret.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_invalid_password() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let mut auth_store = mock_user_store(&random_password(), None);
+        auth_store.expect_create_refresh_token().never();
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(password_request(&random_password()))
+            .await;
+
+        assert!(matches!(res, Err(Error::Unauthorized)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_second_factor() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let password = random_password();
+
+        let totp = AuthTotp {
+            enabled: true,
+            ..AuthTotp::generate(&random_id())
+        };
+
+        // Missing second factor
+        let mut auth_store = mock_user_store(&password, Some(totp.clone()));
+        auth_store.expect_create_refresh_token().never();
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(password_request(&password))
+            .await;
+
+        assert!(matches!(res, Err(Error::SecondFactorRequired)));
+
+        // Valid second factor
+        let mut auth_store = mock_user_store(&password, Some(totp.clone()));
+        auth_store
+            .expect_save_totp()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+        expect_create_refresh_token(&mut auth_store);
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TokenIssue]),
+        };
+
+        let request = TokenRequest {
+            code: Some(totp.code_at(Utc::now().timestamp() as u64)?),
+            ..password_request(&password)
+        };
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(request)
+            .await;
+
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_refresh() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let (value, token) = refresh_token();
+        let family_id = token.family_id;
+
+        let mut auth_store = mock_token_store(&token);
+        auth_store
+            .expect_use_refresh_token()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(true) }));
+        auth_store.expect_revoke_refresh_token_family().never();
+
+        // A new token is issued in the same family
+        auth_store
+            .expect_create_refresh_token()
+            .withf(move |_, family, _, _| *family == family_id)
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async move { Ok(AuthRefreshToken::default()) }));
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TokenIssue]),
+        };
+
+        let tokens = IssueTokens::new(Config::new()?, stores)
+            .handle(refresh_request(&value))
+            .await?;
+
+        let new_value: RefreshTokenValue = tokens.refresh_token.parse()?;
+        assert_ne!(new_value.secret, value.secret);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_refresh_reused() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let (value, token) = refresh_token();
+        let token = AuthRefreshToken {
+            used_at: Some(Utc::now()),
+            ..token
+        };
+
+        let mut auth_store = mock_token_store(&token);
+        auth_store.expect_use_refresh_token().never();
+        auth_store
+            .expect_revoke_refresh_token_family()
+            .withf(move |family_id| *family_id == token.family_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+        auth_store.expect_create_refresh_token().never();
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(refresh_request(&value))
+            .await;
+
+        assert!(matches!(res, Err(Error::RefreshTokenReused)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_tokens_refresh_invalid() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        // Wrong secret
+        let (value, token) = refresh_token();
+
+        let mut auth_store = mock_token_store(&token);
+        auth_store.expect_use_refresh_token().never();
+        auth_store.expect_create_refresh_token().never();
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let request = refresh_request(&RefreshTokenValue {
+            secret: RefreshTokenValue::generate_secret(),
+            ..value.clone()
+        });
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(request)
+            .await;
+
+        assert!(matches!(res, Err(Error::RefreshTokenNotFound)));
+
+        // Expired
+        let token = AuthRefreshToken {
+            expires_at: Utc::now() - Duration::minutes(1),
+            ..token
+        };
+
+        let mut auth_store = mock_token_store(&token);
+        auth_store
+            .expect_use_refresh_token()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(true) }));
+        auth_store.expect_create_refresh_token().never();
+
+        let stores = IssueTokensStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(refresh_request(&value))
+            .await;
+
+        assert!(matches!(res, Err(Error::RefreshTokenExpired)));
+
+        // Missing token
+        let stores = IssueTokensStores {
+            auth: MockAuthStore::new(),
+            audit: mock_audit_store(&[]),
+        };
+
+        let request = TokenRequest {
+            refresh_token: None,
+            ..refresh_request(&value)
+        };
+
+        let res = IssueTokens::new(Config::new()?, stores)
+            .handle(request)
+            .await;
+
+        assert!(matches!(res, Err(Error::InvalidGrant)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+//! Use-case for listing the API keys of a user.
+
+use common_core::UseCase;
+
+use crate::domain::api_key::AuthApiKey;
+use crate::domain::port::AuthStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ListApiKeysStores<A>
+where
+    A: AuthStore,
+{
+    /// Auth store.
+    pub auth: A,
+}
+
+/// API keys listing use-case structure.
+pub(crate) struct ListApiKeys<A>
+where
+    A: AuthStore,
+{
+    /// List of stores used.
+    stores: ListApiKeysStores<A>,
+}
+
+impl<A> ListApiKeys<A>
+where
+    A: AuthStore,
+{
+    /// Creates a `ListApiKeys` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ListApiKeys` instance.
+    pub fn new(stores: ListApiKeysStores<A>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A> UseCase for ListApiKeys<A>
+where
+    A: AuthStore,
+{
+    /// ID of the user.
+    type Args = Uuid;
+    type Output = Vec<AuthApiKey>;
+    type Error = Error;
+
+    async fn handle(&self, user_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.stores.auth.get_api_keys_by_user_id(&user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::port::MockAuthStore;
+
+    #[tokio::test]
+    async fn test_list_api_keys_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_api_keys_by_user_id()
+            .times(1)
+            .returning(move |user_id| {
+                let api_key = AuthApiKey {
+                    user_id: *user_id,
+                    ..Default::default()
+                };
+                Box::pin(async move { Ok(vec![api_key]) })
+            });
+
+        let stores = ListApiKeysStores { auth: auth_store };
+
+        let user_id = Uuid::new_v4();
+
+        let api_keys = ListApiKeys::new(stores).handle(user_id).await?;
+        assert_eq!(api_keys.len(), 1);
+        assert_eq!(api_keys[0].user_id, user_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Use-case for querying the audit trail.
+
+use common_core::UseCase;
+use common_web::pagination::{Page, PageRequest};
+
+use crate::domain::audit::{AuditEntry, AuditFilters};
+use crate::domain::port::AuditStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ListAuditEntriesStores<A>
+where
+    A: AuditStore,
+{
+    /// Audit store.
+    pub audit: A,
+}
+
+/// Audit trail listing use-case structure.
+pub(crate) struct ListAuditEntries<A>
+where
+    A: AuditStore,
+{
+    /// List of stores used.
+    stores: ListAuditEntriesStores<A>,
+}
+
+impl<A> ListAuditEntries<A>
+where
+    A: AuditStore,
+{
+    /// Creates a `ListAuditEntries` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ListAuditEntries` instance.
+    pub fn new(stores: ListAuditEntriesStores<A>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A> UseCase for ListAuditEntries<A>
+where
+    A: AuditStore,
+{
+    type Args = (AuditFilters, PageRequest);
+    type Output = Page<AuditEntry>;
+    type Error = Error;
+
+    async fn handle(&self, (filters, page): Self::Args) -> Result<Self::Output, Self::Error> {
+        self.stores.audit.get_page_by_filters(filters, page).await
+    }
+}
@@ -0,0 +1,54 @@
+//! Use-case for listing the security policies of the roles.
+
+use common_core::UseCase;
+
+use crate::domain::port::AuthStore;
+use crate::domain::totp::AuthRolePolicy;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ListRolePoliciesStores<A>
+where
+    A: AuthStore,
+{
+    /// Auth store.
+    pub auth: A,
+}
+
+/// Role policies listing use-case structure.
+pub(crate) struct ListRolePolicies<A>
+where
+    A: AuthStore,
+{
+    /// List of stores used.
+    stores: ListRolePoliciesStores<A>,
+}
+
+impl<A> ListRolePolicies<A>
+where
+    A: AuthStore,
+{
+    /// Creates a `ListRolePolicies` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ListRolePolicies` instance.
+    pub fn new(stores: ListRolePoliciesStores<A>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A> UseCase for ListRolePolicies<A>
+where
+    A: AuthStore,
+{
+    type Args = ();
+    type Output = Vec<AuthRolePolicy>;
+    type Error = Error;
+
+    async fn handle(&self, _: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.stores.auth.get_role_policies().await
+    }
+}
@@ -0,0 +1,113 @@
+//! Use-case for listing the sessions of a user.
+
+use common_core::UseCase;
+
+use crate::domain::auth_session::AuthSession;
+use crate::domain::port::AuthStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ListSessionsStores<A>
+where
+    A: AuthStore,
+{
+    /// Auth store.
+    pub auth: A,
+}
+
+/// Sessions listing use-case structure.
+pub(crate) struct ListSessions<A>
+where
+    A: AuthStore,
+{
+    /// List of stores used.
+    stores: ListSessionsStores<A>,
+}
+
+impl<A> ListSessions<A>
+where
+    A: AuthStore,
+{
+    /// Creates a `ListSessions` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ListSessions` instance.
+    pub fn new(stores: ListSessionsStores<A>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A> UseCase for ListSessions<A>
+where
+    A: AuthStore,
+{
+    /// ID of the user and ID of the session of the caller (if any).
+    type Args = (Uuid, Option<Uuid>);
+    type Output = Vec<AuthSession>;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (user_id, current_session_id) = args;
+
+        let mut sessions = self.stores.auth.get_sessions_by_user_id(&user_id).await?;
+
+        for session in sessions.iter_mut() {
+            session.current = current_session_id == Some(session.id);
+        }
+
+        Ok(sessions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::port::MockAuthStore;
+
+    #[tokio::test]
+    async fn test_list_sessions_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        let user_id = Uuid::new_v4();
+        let current_id = Uuid::new_v4();
+
+        auth_store
+            .expect_get_sessions_by_user_id()
+            .times(1)
+            .returning(move |user_id| {
+                let user_id = *user_id;
+
+                Box::pin(async move {
+                    Ok(vec![
+                        AuthSession {
+                            id: current_id,
+                            user_id,
+                            ..Default::default()
+                        },
+                        AuthSession {
+                            id: Uuid::new_v4(),
+                            user_id,
+                            ..Default::default()
+                        },
+                    ])
+                })
+            });
+
+        let stores = ListSessionsStores { auth: auth_store };
+
+        let sessions = ListSessions::new(stores)
+            .handle((user_id, Some(current_id)))
+            .await?;
+
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions.iter().all(|s| s.user_id == user_id));
+        assert!(sessions[0].current);
+        assert!(!sessions[1].current);
+
+        Ok(())
+    }
+}
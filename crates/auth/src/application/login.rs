@@ -2,31 +2,38 @@
 
 use common_core::UseCase;
 
-use crate::domain::auth::{Auth, AuthCredentials};
-use crate::domain::port::AuthStore;
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth::{Auth, AuthCredentials, LoginStatus};
+use crate::domain::port::{AuditStore, AuthStore};
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct LoginStores<A>
+pub(crate) struct LoginStores<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     /// Auth store.
     pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
 }
 
 /// Login use-case structure.
-pub(crate) struct Login<A>
+pub(crate) struct Login<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     /// List of stores used.
-    stores: LoginStores<A>,
+    stores: LoginStores<A, B>,
 }
 
-impl<A> Login<A>
+impl<A, B> Login<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     /// Creates a `Login` use-case instance.
     ///
@@ -35,17 +42,18 @@ where
     ///
     /// # Returns
     /// A `Login` instance.
-    pub fn new(stores: LoginStores<A>) -> Self {
+    pub fn new(stores: LoginStores<A, B>) -> Self {
         Self { stores }
     }
 }
 
-impl<A> UseCase for Login<A>
+impl<A, B> UseCase for Login<A, B>
 where
     A: AuthStore,
+    B: AuditStore,
 {
     type Args = (Auth, AuthCredentials);
-    type Output = ();
+    type Output = LoginStatus;
     type Error = Error;
 
     async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
@@ -66,7 +74,16 @@ where
             return Err(Error::EmailNotConfirmed);
         }
 
-        // Create the session for this user
-        auth.login(&user).await
+        // Create the session for this user (unless a second factor is needed)
+        let status = auth.challenge_or_login(&user, &self.stores.auth).await?;
+
+        if matches!(status, LoginStatus::LoggedIn) {
+            self.stores
+                .audit
+                .record(AuditRecord::new(AuditAction::Login, Some(user.id)).actor(user.id))
+                .await?;
+        }
+
+        Ok(status)
     }
 }
@@ -0,0 +1,326 @@
+//! Use-case for completing the login of a user with his second factor.
+
+use common_core::UseCase;
+use tracing::{event, Level};
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth::{Auth, LoginStatus};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::domain::totp::SecondFactorCredentials;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct LoginSecondFactorStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Second factor login use-case structure.
+pub(crate) struct LoginSecondFactor<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: LoginSecondFactorStores<A, B>,
+}
+
+impl<A, B> LoginSecondFactor<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `LoginSecondFactor` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `LoginSecondFactor` instance.
+    pub fn new(stores: LoginSecondFactorStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for LoginSecondFactor<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = (Auth, SecondFactorCredentials);
+    type Output = LoginStatus;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (mut auth, credentials) = args;
+
+        let pending = auth.pending_second_factor().await?;
+
+        if !credentials
+            .check(&pending.user_id, &self.stores.auth)
+            .await?
+        {
+            event!(Level::ERROR, "Invalid second factor");
+
+            auth.reject_second_factor(pending).await?;
+
+            return Err(Error::InvalidSecondFactor);
+        }
+
+        auth.clear_pending_second_factor().await?;
+
+        let user = self
+            .stores
+            .auth
+            .get_user_by_id(&pending.user_id)
+            .await
+            .map_err(|_| Error::UserNotFound)?;
+
+        // Create the session for this user
+        auth.login(&user, &self.stores.auth).await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::Login, Some(user.id)).actor(user.id))
+            .await?;
+
+        Ok(LoginStatus::LoggedIn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use test_utils::rand::*;
+    use utils::hashing::hash_string;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth::PendingSecondFactor;
+    use crate::domain::auth_session::AuthSession;
+    use crate::domain::auth_user::AuthUser;
+    use crate::domain::port::MockAuthStore;
+    use crate::domain::totp::{AuthRecoveryCode, AuthTotp};
+    use crate::tests::utils::{mock_audit_store, new_auth};
+
+    async fn pending_auth(user_id: &Uuid) -> Result<Auth, Box<dyn std::error::Error>> {
+        let auth = new_auth();
+
+        let pending = PendingSecondFactor {
+            user_id: *user_id,
+            expires_at: Utc::now() + Duration::minutes(5),
+            attempts: 0,
+        };
+
+        auth.session
+            .insert(Auth::PENDING_SECOND_FACTOR_KEY, pending)
+            .await?;
+
+        Ok(auth)
+    }
+
+    fn mock_store(user: &AuthUser, totp: &AuthTotp) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        let user = user.clone();
+        let totp = totp.clone();
+
+        auth_store
+            .expect_get_totp_by_user_id()
+            .times(1)
+            .returning(move |_| {
+                let totp = totp.clone();
+                Box::pin(async move { Ok(Some(totp)) })
+            });
+
+        auth_store.expect_get_user_by_id().returning(move |_| {
+            let user = user.clone();
+            Box::pin(async move { Ok(user) })
+        });
+
+        auth_store
+    }
+
+    #[tokio::test]
+    async fn test_login_second_factor_code() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp {
+            enabled: true,
+            ..AuthTotp::generate(&user.id)
+        };
+
+        let mut auth_store = mock_store(&user, &totp);
+
+        auth_store
+            .expect_save_totp()
+            .times(1)
+            .withf(|totp| totp.last_used_step.is_some())
+            .returning(move |_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_create_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthSession::default()) }));
+
+        let auth = pending_auth(&user.id).await?;
+        let session = auth.session.clone();
+
+        let credentials = SecondFactorCredentials {
+            code: Some(totp.code_at(Utc::now().timestamp() as u64)?),
+            recovery_code: None,
+        };
+
+        let stores = LoginSecondFactorStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::Login]),
+        };
+
+        let res = LoginSecondFactor::new(stores)
+            .handle((auth, credentials))
+            .await;
+        assert!(matches!(res, Ok(LoginStatus::LoggedIn)));
+
+        let pending: Option<PendingSecondFactor> =
+            session.get(Auth::PENDING_SECOND_FACTOR_KEY).await?;
+        assert!(pending.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_login_second_factor_recovery_code() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp {
+            enabled: true,
+            ..AuthTotp::generate(&user.id)
+        };
+
+        let mut auth_store = mock_store(&user, &totp);
+
+        let recovery_code = random_string().to_lowercase();
+
+        let stored = AuthRecoveryCode {
+            id: random_id(),
+            user_id: user.id,
+            code: hash_string(&recovery_code)?,
+            used_at: None,
+        };
+
+        auth_store
+            .expect_get_recovery_codes_by_user_id()
+            .times(1)
+            .returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(vec![stored]) })
+            });
+
+        auth_store
+            .expect_use_recovery_code()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(true) }));
+
+        auth_store
+            .expect_create_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthSession::default()) }));
+
+        let credentials = SecondFactorCredentials {
+            code: None,
+            recovery_code: Some(recovery_code),
+        };
+
+        let stores = LoginSecondFactorStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::Login]),
+        };
+
+        let res = LoginSecondFactor::new(stores)
+            .handle((pending_auth(&user.id).await?, credentials))
+            .await;
+        assert!(matches!(res, Ok(LoginStatus::LoggedIn)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_login_second_factor_invalid() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            ..Default::default()
+        };
+
+        let totp = AuthTotp {
+            enabled: true,
+            ..AuthTotp::generate(&user.id)
+        };
+
+        let mut auth_store = mock_store(&user, &totp);
+
+        auth_store.expect_create_session().never();
+
+        let auth = pending_auth(&user.id).await?;
+        let session = auth.session.clone();
+
+        let credentials = SecondFactorCredentials {
+            code: Some(totp.code_at(0)?),
+            recovery_code: None,
+        };
+
+        let stores = LoginSecondFactorStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = LoginSecondFactor::new(stores)
+            .handle((auth, credentials))
+            .await;
+        assert!(matches!(res, Err(Error::InvalidSecondFactor)));
+
+        let pending: Option<PendingSecondFactor> =
+            session.get(Auth::PENDING_SECOND_FACTOR_KEY).await?;
+        assert_eq!(pending.map(|pending| pending.attempts), Some(1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_login_second_factor_not_pending() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store.expect_create_session().never();
+
+        let credentials = SecondFactorCredentials {
+            code: Some("123456".to_string()),
+            recovery_code: None,
+        };
+
+        let stores = LoginSecondFactorStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = LoginSecondFactor::new(stores)
+            .handle((new_auth(), credentials))
+            .await;
+        assert!(matches!(res, Err(Error::SecondFactorNotPending)));
+
+        Ok(())
+    }
+}
@@ -2,28 +2,72 @@
 
 use common_core::UseCase;
 
+use crate::domain::audit::{AuditAction, AuditRecord};
 use crate::domain::auth::Auth;
+use crate::domain::port::{AuditStore, AuthStore};
 use crate::prelude::*;
 
+/// Stores used by this use-case.
+pub(crate) struct LogoutStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
 /// Logout use-case structure.
-pub(crate) struct Logout {}
+pub(crate) struct Logout<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: LogoutStores<A, B>,
+}
 
-impl Logout {
+impl<A, B> Logout<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
     /// Creates a `Logout` use-case instance.
     ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
     /// # Returns
     /// A `Logout` instance.
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(stores: LogoutStores<A, B>) -> Self {
+        Self { stores }
     }
 }
 
-impl UseCase for Logout {
+impl<A, B> UseCase for Logout<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
     type Args = Auth;
     type Output = ();
     type Error = Error;
 
     async fn handle(&self, mut auth: Self::Args) -> Result<Self::Output, Self::Error> {
-        auth.logout().await
+        let user_id = auth.user().as_ref().map(|user| user.id);
+
+        auth.logout(&self.stores.auth).await?;
+
+        if user_id.is_some() {
+            self.stores
+                .audit
+                .record(AuditRecord::new(AuditAction::Logout, user_id))
+                .await?;
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,325 @@
+//! Use-case for login a user with a link received by email.
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth::{Auth, LoginStatus, MagicLinkCredentials};
+use crate::domain::auth_user::Expiring;
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct MagicLoginStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Passwordless login use-case structure.
+pub(crate) struct MagicLogin<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: MagicLoginStores<A, B>,
+}
+
+impl<A, B> MagicLogin<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `MagicLogin` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `MagicLogin` instance.
+    pub fn new(stores: MagicLoginStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for MagicLogin<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = (Auth, MagicLinkCredentials);
+    type Output = LoginStatus;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (mut auth, credentials) = args;
+
+        // The link is deleted right away so that it can't be used twice
+        let link = self
+            .stores
+            .auth
+            .consume_magic_link(&credentials.token)
+            .await?
+            .ok_or(Error::MagicLinkNotFound)?;
+
+        let user = self
+            .stores
+            .auth
+            .find_user_by_email(&credentials.email)
+            .await
+            .map_err(|_| Error::UserNotFound)?;
+
+        if !user.is(&link.user_id) {
+            return Err(Error::MagicLinkNotFound);
+        }
+
+        if link.is_expired() {
+            return Err(Error::MagicLinkExpired);
+        }
+
+        // Following the link proves that the user owns the email address
+        if !user.is_email_confirmed() {
+            self.stores
+                .auth
+                .delete_user_confirmation_by_user_id(&user.id)
+                .await?;
+        }
+
+        // Create the session for this user (unless a second factor is needed)
+        let status = auth.challenge_or_login(&user, &self.stores.auth).await?;
+
+        if matches!(status, LoginStatus::LoggedIn) {
+            self.stores
+                .audit
+                .record(AuditRecord::new(AuditAction::Login, Some(user.id)).actor(user.id))
+                .await?;
+        }
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth::PendingSecondFactor;
+    use crate::domain::auth_session::AuthSession;
+    use crate::domain::auth_user::{AuthMagicLink, AuthUser};
+    use crate::domain::port::MockAuthStore;
+    use crate::domain::totp::{AuthRolePolicy, AuthTotp};
+    use crate::tests::utils::{mock_audit_store, new_auth};
+
+    fn user_and_link(expires_in: Duration) -> (AuthUser, AuthMagicLink) {
+        let user = AuthUser {
+            id: random_id(),
+            email: random_email(),
+            email_confirmed: true,
+            ..Default::default()
+        };
+
+        let link = AuthMagicLink {
+            id: random_id(),
+            user_id: user.id,
+            expires_at: Utc::now() + expires_in,
+        };
+
+        (user, link)
+    }
+
+    fn mock_store(user: &AuthUser, link: &AuthMagicLink) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        let link = link.clone();
+        let user = user.clone();
+
+        auth_store
+            .expect_consume_magic_link()
+            .times(1)
+            .returning(move |_| {
+                let link = link.clone();
+                Box::pin(async move { Ok(Some(link)) })
+            });
+
+        auth_store
+            .expect_find_user_by_email()
+            .times(1)
+            .returning(move |_| {
+                let user = user.clone();
+                Box::pin(async move { Ok(user) })
+            });
+
+        auth_store
+    }
+
+    fn mock_second_factor(auth_store: &mut MockAuthStore, totp: Option<AuthTotp>) {
+        auth_store.expect_get_totp_by_user_id().returning(move |_| {
+            let totp = totp.clone();
+            Box::pin(async move { Ok(totp) })
+        });
+
+        auth_store
+            .expect_get_role_policy()
+            .returning(move |_| Box::pin(async move { Ok(AuthRolePolicy::default()) }));
+    }
+
+    #[tokio::test]
+    async fn test_magic_login_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let (user, link) = user_and_link(Duration::minutes(15));
+
+        let mut auth_store = mock_store(&user, &link);
+
+        mock_second_factor(&mut auth_store, None);
+
+        auth_store
+            .expect_create_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthSession::default()) }));
+
+        let credentials = MagicLinkCredentials {
+            email: user.email.clone(),
+            token: link.id,
+        };
+
+        let stores = MagicLoginStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::Login]),
+        };
+
+        let res = MagicLogin::new(stores)
+            .handle((new_auth(), credentials))
+            .await;
+        assert!(matches!(res, Ok(LoginStatus::LoggedIn)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_magic_login_second_factor() -> Result<(), Box<dyn std::error::Error>> {
+        let (user, link) = user_and_link(Duration::minutes(15));
+
+        let mut auth_store = mock_store(&user, &link);
+
+        let totp = AuthTotp {
+            enabled: true,
+            ..AuthTotp::generate(&user.id)
+        };
+
+        mock_second_factor(&mut auth_store, Some(totp));
+
+        auth_store.expect_create_session().never();
+
+        let credentials = MagicLinkCredentials {
+            email: user.email.clone(),
+            token: link.id,
+        };
+
+        let stores = MagicLoginStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let auth = new_auth();
+        let session = auth.session.clone();
+
+        let res = MagicLogin::new(stores).handle((auth, credentials)).await;
+        assert!(matches!(res, Ok(LoginStatus::SecondFactorRequired)));
+
+        let pending: Option<PendingSecondFactor> =
+            session.get(Auth::PENDING_SECOND_FACTOR_KEY).await?;
+        assert_eq!(pending.map(|pending| pending.user_id), Some(user.id));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_magic_login_expired() -> Result<(), Box<dyn std::error::Error>> {
+        let (user, link) = user_and_link(Duration::minutes(-1));
+
+        let mut auth_store = mock_store(&user, &link);
+
+        auth_store.expect_create_session().never();
+
+        let credentials = MagicLinkCredentials {
+            email: user.email.clone(),
+            token: link.id,
+        };
+
+        let stores = MagicLoginStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = MagicLogin::new(stores)
+            .handle((new_auth(), credentials))
+            .await;
+        assert!(matches!(res, Err(Error::MagicLinkExpired)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_magic_login_other_user() -> Result<(), Box<dyn std::error::Error>> {
+        let (user, mut link) = user_and_link(Duration::minutes(15));
+        link.user_id = random_id();
+
+        let mut auth_store = mock_store(&user, &link);
+
+        auth_store.expect_create_session().never();
+
+        let credentials = MagicLinkCredentials {
+            email: user.email.clone(),
+            token: link.id,
+        };
+
+        let stores = MagicLoginStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = MagicLogin::new(stores)
+            .handle((new_auth(), credentials))
+            .await;
+        assert!(matches!(res, Err(Error::MagicLinkNotFound)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_magic_login_unknown_link() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_consume_magic_link()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(None) }));
+
+        let credentials = MagicLinkCredentials {
+            email: random_email(),
+            token: random_id(),
+        };
+
+        let stores = MagicLoginStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = MagicLogin::new(stores)
+            .handle((new_auth(), credentials))
+            .await;
+        assert!(matches!(res, Err(Error::MagicLinkNotFound)));
+
+        Ok(())
+    }
+}
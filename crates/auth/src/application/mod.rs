@@ -1,11 +1,53 @@
 //! List of use-cases used by the api layer.
 
+mod authorize_oauth;
 mod confirm_email;
+mod confirm_totp;
+mod create_api_key;
+mod enroll_totp;
+mod issue_tokens;
+mod list_api_keys;
+mod list_audit_entries;
+mod list_role_policies;
+mod list_sessions;
 mod login;
+mod login_second_factor;
 mod logout;
+mod magic_login;
+mod oauth_login;
+mod reset_password;
+mod revoke_api_key;
+mod revoke_session;
+mod revoke_sessions;
+mod revoke_token;
 mod send_email_confirmation;
+mod send_magic_link;
+mod send_password_reset;
+mod set_role_policy;
+mod update_api_key;
 
+pub(crate) use authorize_oauth::AuthorizeOAuth;
 pub(crate) use confirm_email::{ConfirmEmail, ConfirmEmailStores};
+pub(crate) use confirm_totp::{ConfirmTotp, ConfirmTotpStores};
+pub(crate) use create_api_key::{CreateApiKey, CreateApiKeyStores};
+pub(crate) use enroll_totp::{EnrollTotp, EnrollTotpStores};
+pub(crate) use issue_tokens::{IssueTokens, IssueTokensStores};
+pub(crate) use list_api_keys::{ListApiKeys, ListApiKeysStores};
+pub(crate) use list_audit_entries::{ListAuditEntries, ListAuditEntriesStores};
+pub(crate) use list_role_policies::{ListRolePolicies, ListRolePoliciesStores};
+pub(crate) use list_sessions::{ListSessions, ListSessionsStores};
 pub(crate) use login::{Login, LoginStores};
-pub(crate) use logout::Logout;
+pub(crate) use login_second_factor::{LoginSecondFactor, LoginSecondFactorStores};
+pub(crate) use logout::{Logout, LogoutStores};
+pub(crate) use magic_login::{MagicLogin, MagicLoginStores};
+pub(crate) use oauth_login::{OAuthLogin, OAuthLoginStores};
+pub(crate) use reset_password::{ResetPassword, ResetPasswordStores};
+pub(crate) use revoke_api_key::{RevokeApiKey, RevokeApiKeyStores};
+pub(crate) use revoke_session::{RevokeSession, RevokeSessionStores};
+pub(crate) use revoke_sessions::{RevokeSessions, RevokeSessionsStores};
+pub(crate) use revoke_token::{RevokeToken, RevokeTokenStores};
 pub(crate) use send_email_confirmation::{SendEmailConfirmation, SendEmailConfirmationStores};
+pub(crate) use send_magic_link::{SendMagicLink, SendMagicLinkStores};
+pub(crate) use send_password_reset::{SendPasswordReset, SendPasswordResetStores};
+pub(crate) use set_role_policy::{SetRolePolicy, SetRolePolicyStores};
+pub(crate) use update_api_key::{UpdateApiKey, UpdateApiKeyStores};
@@ -0,0 +1,469 @@
+//! Use-case for login a user after the callback of an OAuth provider.
+
+use tracing::{event, Level};
+
+use common_core::UseCase;
+use configuration::Config;
+
+use crate::domain::audit::{AuditAction, AuditChanges, AuditRecord};
+use crate::domain::auth::{Auth, LoginStatus};
+use crate::domain::auth_user::{AuthUser, Expiring};
+use crate::domain::oauth::{OAuthCallback, OAuthPending, OAuthUserInfo};
+use crate::domain::port::{AuditStore, AuthStore, OAuthClient};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct OAuthLoginStores<A, B, C>
+where
+    A: OAuthClient,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// OAuth client.
+    pub oauth: A,
+
+    /// Auth store.
+    pub auth: B,
+
+    /// Audit store.
+    pub audit: C,
+}
+
+/// OAuth login use-case structure.
+pub(crate) struct OAuthLogin<A, B, C>
+where
+    A: OAuthClient,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: OAuthLoginStores<A, B, C>,
+}
+
+impl<A, B, C> OAuthLogin<A, B, C>
+where
+    A: OAuthClient,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Creates a `OAuthLogin` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `OAuthLogin` instance.
+    pub fn new(config: Config, stores: OAuthLoginStores<A, B, C>) -> Self {
+        Self { config, stores }
+    }
+
+    /// Finds the user matching the identity returned by the provider. The user is created if
+    /// needed.
+    ///
+    /// # Arguments
+    /// * `provider`: Name of the provider.
+    /// * `info`: Information about the user returned by the provider.
+    ///
+    /// # Returns
+    /// A result containing the user.
+    async fn match_or_create_user(
+        &self,
+        provider: &str,
+        info: &OAuthUserInfo,
+    ) -> ApiResult<AuthUser> {
+        if let Some(user) = self
+            .stores
+            .auth
+            .find_user_by_identity(provider, &info.sub)
+            .await?
+        {
+            return Ok(user);
+        }
+
+        // Accounts can only be matched with an email that the provider has verified
+        let email = info.verified_email()?;
+
+        match self.stores.auth.find_user_by_email(email).await {
+            Ok(user) => {
+                event!(Level::INFO, "Link {provider} identity to {:?}", user);
+
+                self.stores
+                    .auth
+                    .create_identity(&user.id, provider, &info.sub)
+                    .await?;
+
+                let changes = AuditChanges::default().field("provider", None, Some(provider));
+
+                self.stores
+                    .audit
+                    .record(
+                        AuditRecord::new(AuditAction::IdentityLink, Some(user.id))
+                            .actor(user.id)
+                            .changes(changes),
+                    )
+                    .await?;
+
+                // The provider has verified the email
+                if !user.is_email_confirmed() {
+                    self.stores
+                        .auth
+                        .delete_user_confirmation_by_user_id(&user.id)
+                        .await?;
+                }
+
+                self.stores.auth.get_user_by_id(&user.id).await
+            }
+
+            Err(_) => {
+                event!(Level::INFO, "Create user from {provider} identity");
+
+                let user = self
+                    .stores
+                    .auth
+                    .create_user_with_identity(provider, info)
+                    .await?;
+
+                let changes =
+                    AuditChanges::diff(None, Some(&user)).field("provider", None, Some(provider));
+
+                self.stores
+                    .audit
+                    .record(
+                        AuditRecord::new(AuditAction::UserCreate, Some(user.id))
+                            .actor(user.id)
+                            .changes(changes),
+                    )
+                    .await?;
+
+                Ok(user)
+            }
+        }
+    }
+}
+
+impl<A, B, C> UseCase for OAuthLogin<A, B, C>
+where
+    A: OAuthClient,
+    B: AuthStore,
+    C: AuditStore,
+{
+    type Args = (Auth, OAuthCallback);
+    type Output = LoginStatus;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (mut auth, callback) = args;
+
+        // The pending request can only be used once
+        let pending: OAuthPending = auth
+            .session
+            .remove(OAuthPending::KEY)
+            .await?
+            .ok_or(Error::OAuthStateMismatch)?;
+
+        if pending.state != callback.state || pending.is_expired() {
+            event!(Level::ERROR, "Invalid OAuth state");
+            return Err(Error::OAuthStateMismatch);
+        }
+
+        let settings = self
+            .config
+            .oauth
+            .providers
+            .get(&pending.provider)
+            .ok_or(Error::OAuthProviderNotFound)?;
+
+        let tokens = self
+            .stores
+            .oauth
+            .exchange_code(settings, &callback.code, &pending.code_verifier)
+            .await?;
+
+        tokens.check_nonce(&pending.nonce)?;
+
+        let info = self
+            .stores
+            .oauth
+            .get_user_info(settings, &tokens.access_token)
+            .await?;
+
+        let user = self.match_or_create_user(&pending.provider, &info).await?;
+
+        // Create the session for this user (unless a second factor is needed)
+        let status = auth.challenge_or_login(&user, &self.stores.auth).await?;
+
+        if matches!(status, LoginStatus::LoggedIn) {
+            self.stores
+                .audit
+                .record(AuditRecord::new(AuditAction::Login, Some(user.id)).actor(user.id))
+                .await?;
+        }
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth_session::AuthSession;
+    use crate::domain::port::MockAuthStore;
+    use crate::domain::totp::AuthRolePolicy;
+    use crate::infrastructure::HttpOAuthClient;
+    use crate::tests::utils::{mock_audit_store, new_auth, oauth_settings, StubProvider};
+
+    /// Prepares a configuration and a session as if the user had been redirected to the stub
+    /// provider.
+    async fn authorize(
+        stub: &StubProvider,
+    ) -> Result<(Config, Auth, OAuthCallback), Box<dyn std::error::Error>> {
+        let mut config = Config::new()?;
+        config
+            .oauth
+            .providers
+            .insert("stub".to_string(), oauth_settings(stub));
+
+        let pending = OAuthPending {
+            code_verifier: stub.code_verifier.clone(),
+            nonce: stub.nonce.clone(),
+            ..OAuthPending::generate("stub")
+        };
+
+        let callback = OAuthCallback {
+            code: stub.code.clone(),
+            state: pending.state.clone(),
+        };
+
+        let auth = new_auth();
+        auth.session.insert(OAuthPending::KEY, pending).await?;
+
+        Ok((config, auth, callback))
+    }
+
+    fn mock_login(auth_store: &mut MockAuthStore) {
+        auth_store
+            .expect_get_totp_by_user_id()
+            .returning(move |_| Box::pin(async move { Ok(None) }));
+
+        auth_store
+            .expect_get_role_policy()
+            .returning(move |_| Box::pin(async move { Ok(AuthRolePolicy::default()) }));
+
+        auth_store
+            .expect_create_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthSession::default()) }));
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_known_identity() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+        let (config, auth, callback) = authorize(&stub).await?;
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_find_user_by_identity()
+            .times(1)
+            .withf(|provider, _| provider == "stub")
+            .returning(move |_, _| Box::pin(async move { Ok(Some(AuthUser::default())) }));
+
+        auth_store.expect_create_user_with_identity().never();
+
+        mock_login(&mut auth_store);
+
+        let stores = OAuthLoginStores {
+            oauth: HttpOAuthClient::new(),
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::Login]),
+        };
+
+        let res = OAuthLogin::new(config, stores)
+            .handle((auth, callback))
+            .await;
+        assert!(matches!(res, Ok(LoginStatus::LoggedIn)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_link_existing_user() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+        let (config, auth, callback) = authorize(&stub).await?;
+
+        let user = AuthUser {
+            id: random_id(),
+            email: stub.user_info.email.clone().unwrap_or_default(),
+            email_confirmed: true,
+            ..Default::default()
+        };
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_find_user_by_identity()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(None) }));
+
+        let found = user.clone();
+
+        auth_store
+            .expect_find_user_by_email()
+            .times(1)
+            .returning(move |_| {
+                let user = found.clone();
+                Box::pin(async move { Ok(user) })
+            });
+
+        let user_id = user.id;
+
+        auth_store
+            .expect_create_identity()
+            .times(1)
+            .withf(move |id, provider, _| *id == user_id && provider == "stub")
+            .returning(move |_, _, _| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_get_user_by_id()
+            .times(1)
+            .returning(move |_| {
+                let user = user.clone();
+                Box::pin(async move { Ok(user) })
+            });
+
+        auth_store.expect_create_user_with_identity().never();
+
+        mock_login(&mut auth_store);
+
+        let stores = OAuthLoginStores {
+            oauth: HttpOAuthClient::new(),
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::IdentityLink, AuditAction::Login]),
+        };
+
+        let res = OAuthLogin::new(config, stores)
+            .handle((auth, callback))
+            .await;
+        assert!(matches!(res, Ok(LoginStatus::LoggedIn)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_create_user() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+        let (config, auth, callback) = authorize(&stub).await?;
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_find_user_by_identity()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(None) }));
+
+        auth_store
+            .expect_find_user_by_email()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Err(Error::UserNotFound) }));
+
+        let info = stub.user_info.clone();
+
+        auth_store
+            .expect_create_user_with_identity()
+            .times(1)
+            .withf(move |provider, user_info| provider == "stub" && *user_info == info)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthUser::default()) }));
+
+        mock_login(&mut auth_store);
+
+        let stores = OAuthLoginStores {
+            oauth: HttpOAuthClient::new(),
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::UserCreate, AuditAction::Login]),
+        };
+
+        let res = OAuthLogin::new(config, stores)
+            .handle((auth, callback))
+            .await;
+        assert!(matches!(res, Ok(LoginStatus::LoggedIn)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_state_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+        let (config, auth, mut callback) = authorize(&stub).await?;
+
+        callback.state = random_string();
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store.expect_create_session().never();
+
+        let stores = OAuthLoginStores {
+            oauth: HttpOAuthClient::new(),
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let session = auth.session.clone();
+
+        let res = OAuthLogin::new(config, stores)
+            .handle((auth, callback))
+            .await;
+        assert!(matches!(res, Err(Error::OAuthStateMismatch)));
+
+        // The pending request can't be replayed
+        let pending: Option<OAuthPending> = session.get(OAuthPending::KEY).await?;
+        assert!(pending.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_nonce_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        let mut stub = StubProvider::start().await?;
+        let (config, auth, callback) = authorize(&stub).await?;
+
+        // The stub keeps returning its own nonce
+        stub.nonce = random_string();
+        auth.session
+            .insert(
+                OAuthPending::KEY,
+                OAuthPending {
+                    state: callback.state.clone(),
+                    code_verifier: stub.code_verifier.clone(),
+                    nonce: stub.nonce.clone(),
+                    ..OAuthPending::generate("stub")
+                },
+            )
+            .await?;
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store.expect_find_user_by_identity().never();
+        auth_store.expect_create_session().never();
+
+        let stores = OAuthLoginStores {
+            oauth: HttpOAuthClient::new(),
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = OAuthLogin::new(config, stores)
+            .handle((auth, callback))
+            .await;
+        assert!(matches!(res, Err(Error::OAuthNonceMismatch)));
+
+        Ok(())
+    }
+}
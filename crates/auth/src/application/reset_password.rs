@@ -0,0 +1,254 @@
+//! Use-case for choosing a new password with a link received by email.
+
+use tracing::{event, Level};
+
+use common_core::UseCase;
+use database::Transactional;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::auth::PasswordReset;
+use crate::domain::auth_user::Expiring;
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ResetPasswordStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Password reset use-case structure.
+pub(crate) struct ResetPassword<A, B, T>
+where
+    A: AuthStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    /// List of stores used.
+    stores: ResetPasswordStores<A, B>,
+
+    /// Database handle.
+    db: T,
+}
+
+impl<A, B, T> ResetPassword<A, B, T>
+where
+    A: AuthStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    /// Creates a `ResetPassword` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    /// * `db`: Database handle.
+    ///
+    /// # Returns
+    /// A `ResetPassword` instance.
+    pub fn new(stores: ResetPasswordStores<A, B>, db: T) -> Self {
+        Self { stores, db }
+    }
+}
+
+impl<A, B, T> UseCase for ResetPassword<A, B, T>
+where
+    A: AuthStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    type Args = PasswordReset;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
+        // The password is only changed if the sessions and tokens are revoked as well
+        let uow = self.db.begin().await?;
+
+        // The link is deleted right away so that it can't be used twice
+        let reset = self
+            .stores
+            .auth
+            .consume_password_reset(&request.token)
+            .await?
+            .ok_or(Error::PasswordResetNotFound)?;
+
+        if reset.is_expired() {
+            return Err(Error::PasswordResetExpired);
+        }
+
+        self.stores
+            .auth
+            .set_password(&reset.user_id, &request.password.hashed()?)
+            .await?;
+
+        // Whoever was using the previous password must be logged out
+        self.stores
+            .auth
+            .delete_sessions_by_user_id(&reset.user_id)
+            .await?;
+
+        self.stores
+            .auth
+            .revoke_refresh_tokens_by_user_id(&reset.user_id)
+            .await?;
+
+        // Following the link proves that the caller is the user
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::PasswordReset, Some(reset.user_id))
+                    .actor(reset.user_id),
+            )
+            .await?;
+
+        uow.commit().await?;
+
+        event!(Level::INFO, "Password reset for user {}", reset.user_id);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use database::DetachedDb;
+
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth_user::AuthPasswordReset;
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
+
+    fn mock_store(reset: Option<AuthPasswordReset>) -> MockAuthStore {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_consume_password_reset()
+            .times(1)
+            .returning(move |_| {
+                let reset = reset.clone();
+                Box::pin(async move { Ok(reset) })
+            });
+
+        auth_store
+    }
+
+    #[tokio::test]
+    async fn test_reset_password_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let reset = AuthPasswordReset {
+            id: random_id(),
+            user_id: random_id(),
+            expires_at: Utc::now() + Duration::minutes(15),
+        };
+
+        let user_id = reset.user_id;
+        let password = random_password();
+
+        let mut auth_store = mock_store(Some(reset.clone()));
+
+        let expected = password.clone();
+
+        auth_store
+            .expect_set_password()
+            .withf(move |id, _| *id == user_id)
+            .times(1)
+            .returning(move |_, hashed| {
+                let expected = expected.clone();
+                let hashed = hashed.clone();
+                Box::pin(async move {
+                    assert!(expected.matches(&hashed).await.unwrap());
+                    Ok(())
+                })
+            });
+
+        auth_store
+            .expect_delete_sessions_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_revoke_refresh_tokens_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = ResetPasswordStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::PasswordReset]),
+        };
+
+        let request = PasswordReset {
+            token: reset.id,
+            password,
+        };
+
+        let res = ResetPassword::new(stores, DetachedDb).handle(request).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reset_password_expired() -> Result<(), Box<dyn std::error::Error>> {
+        let reset = AuthPasswordReset {
+            id: random_id(),
+            user_id: random_id(),
+            expires_at: Utc::now() - Duration::minutes(1),
+        };
+
+        let mut auth_store = mock_store(Some(reset.clone()));
+
+        auth_store.expect_set_password().never();
+        auth_store.expect_delete_sessions_by_user_id().never();
+
+        let stores = ResetPasswordStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let request = PasswordReset {
+            token: reset.id,
+            password: random_password(),
+        };
+
+        let res = ResetPassword::new(stores, DetachedDb).handle(request).await;
+        assert!(matches!(res, Err(Error::PasswordResetExpired)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reset_password_unknown_link() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = mock_store(None);
+
+        auth_store.expect_set_password().never();
+
+        let stores = ResetPasswordStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let request = PasswordReset {
+            token: random_id(),
+            password: random_password(),
+        };
+
+        let res = ResetPassword::new(stores, DetachedDb).handle(request).await;
+        assert!(matches!(res, Err(Error::PasswordResetNotFound)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+//! Use-case for revoking an API key of a user.
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditChanges, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RevokeApiKeyStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// API key revocation use-case structure.
+pub(crate) struct RevokeApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: RevokeApiKeyStores<A, B>,
+}
+
+impl<A, B> RevokeApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `RevokeApiKey` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RevokeApiKey` instance.
+    pub fn new(stores: RevokeApiKeyStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for RevokeApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// ID of the user and ID of the key to revoke.
+    type Args = (Uuid, Uuid);
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (user_id, id) = args;
+
+        let before = self.stores.auth.get_api_key_by_id(&id).await?;
+
+        // The key will be rejected by the `Auth` extractor on its next use.
+        self.stores.auth.delete_api_key(&user_id, &id).await?;
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::ApiKeyRevoke, Some(user_id))
+                    .changes(AuditChanges::diff(before.as_ref(), None)),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_revoke_api_key_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_api_key_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(None) }));
+
+        auth_store
+            .expect_delete_api_key()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(()) }));
+
+        let stores = RevokeApiKeyStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::ApiKeyRevoke]),
+        };
+
+        let res = RevokeApiKey::new(stores)
+            .handle((Uuid::new_v4(), Uuid::new_v4()))
+            .await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_api_key_not_found() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_api_key_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(None) }));
+
+        auth_store
+            .expect_delete_api_key()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Err(Error::ApiKeyNotFound) }));
+
+        let stores = RevokeApiKeyStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = RevokeApiKey::new(stores)
+            .handle((Uuid::new_v4(), Uuid::new_v4()))
+            .await;
+        assert!(matches!(res, Err(Error::ApiKeyNotFound)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+//! Use-case for revoking a session of a user.
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditChanges, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RevokeSessionStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Session revocation use-case structure.
+pub(crate) struct RevokeSession<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: RevokeSessionStores<A, B>,
+}
+
+impl<A, B> RevokeSession<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `RevokeSession` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RevokeSession` instance.
+    pub fn new(stores: RevokeSessionStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for RevokeSession<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// ID of the user and ID of the session to revoke.
+    type Args = (Uuid, Uuid);
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (user_id, session_id) = args;
+
+        // The session will be rejected by the `Auth` extractor on its next use.
+        self.stores
+            .auth
+            .delete_session(&user_id, &session_id)
+            .await?;
+
+        let changes = AuditChanges::default().field("session_id", Some(session_id), None);
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::SessionRevoke, Some(user_id)).changes(changes))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_revoke_session_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_delete_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(()) }));
+
+        let stores = RevokeSessionStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::SessionRevoke]),
+        };
+
+        let res = RevokeSession::new(stores)
+            .handle((Uuid::new_v4(), Uuid::new_v4()))
+            .await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_not_found() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_delete_session()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Err(Error::UserSessionNotFound) }));
+
+        let stores = RevokeSessionStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = RevokeSession::new(stores)
+            .handle((Uuid::new_v4(), Uuid::new_v4()))
+            .await;
+        assert!(matches!(res, Err(Error::UserSessionNotFound)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! Use-case for revoking all sessions of a user.
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RevokeSessionsStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Sessions revocation use-case structure.
+pub(crate) struct RevokeSessions<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: RevokeSessionsStores<A, B>,
+}
+
+impl<A, B> RevokeSessions<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `RevokeSessions` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RevokeSessions` instance.
+    pub fn new(stores: RevokeSessionsStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for RevokeSessions<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// ID of the user.
+    type Args = Uuid;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, user_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Make sure the user exists (the caller is not the one missing)
+        self.stores
+            .auth
+            .get_user_by_id(&user_id)
+            .await
+            .map_err(|e| match e {
+                Error::SQLx(sqlx::Error::RowNotFound) => Error::TargetUserNotFound,
+                e => e,
+            })?;
+
+        self.stores
+            .auth
+            .delete_sessions_by_user_id(&user_id)
+            .await?;
+
+        // Bearer tokens can't be refreshed anymore
+        self.stores
+            .auth
+            .revoke_refresh_tokens_by_user_id(&user_id)
+            .await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(
+                AuditAction::SessionRevokeAll,
+                Some(user_id),
+            ))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth_user::AuthUser;
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_revoke_sessions_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_user_by_id()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(AuthUser::default()) }));
+
+        auth_store
+            .expect_delete_sessions_by_user_id()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_revoke_refresh_tokens_by_user_id()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(()) }));
+
+        let stores = RevokeSessionsStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::SessionRevokeAll]),
+        };
+
+        let res = RevokeSessions::new(stores).handle(Uuid::new_v4()).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_sessions_user_not_found() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_user_by_id()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Err(sqlx::Error::RowNotFound.into()) }));
+
+        let stores = RevokeSessionsStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = RevokeSessions::new(stores).handle(Uuid::new_v4()).await;
+        assert!(matches!(res, Err(Error::TargetUserNotFound)));
+
+        Ok(())
+    }
+}
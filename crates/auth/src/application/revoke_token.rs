@@ -0,0 +1,179 @@
+//! Use-case for revoking a refresh token (and all the tokens of its family).
+
+use tracing::{event, Level};
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::domain::token::{RefreshTokenValue, TokenRevocationRequest};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RevokeTokenStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Token revocation use-case structure.
+pub(crate) struct RevokeToken<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: RevokeTokenStores<A, B>,
+}
+
+impl<A, B> RevokeToken<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `RevokeToken` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RevokeToken` instance.
+    pub fn new(stores: RevokeTokenStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for RevokeToken<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = TokenRevocationRequest;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Invalid tokens are ignored (RFC 7009, section 2.2)
+        let Ok(value) = request.token.parse::<RefreshTokenValue>() else {
+            return Ok(());
+        };
+
+        let token = self
+            .stores
+            .auth
+            .get_refresh_token_by_id(&value.id)
+            .await?
+            .filter(|token| token.matches(&value));
+
+        if let Some(token) = token {
+            self.stores
+                .auth
+                .revoke_refresh_token_family(&token.family_id)
+                .await?;
+
+            // Presenting the token proves that the caller is the user
+            self.stores
+                .audit
+                .record(
+                    AuditRecord::new(AuditAction::TokenRevoke, Some(token.user_id))
+                        .actor(token.user_id),
+                )
+                .await?;
+
+            event!(
+                Level::INFO,
+                "Refresh tokens revoked for user {}",
+                token.user_id
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::port::MockAuthStore;
+    use crate::domain::token::AuthRefreshToken;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_revoke_token_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let value = RefreshTokenValue {
+            id: random_id(),
+            secret: RefreshTokenValue::generate_secret(),
+        };
+
+        let token = AuthRefreshToken {
+            id: value.id,
+            family_id: random_id(),
+            token_hash: value.hash(),
+            ..Default::default()
+        };
+
+        let family_id = token.family_id;
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_refresh_token_by_id()
+            .times(1)
+            .returning(move |_| {
+                let token = token.clone();
+                Box::pin(async move { Ok(Some(token)) })
+            });
+
+        auth_store
+            .expect_revoke_refresh_token_family()
+            .withf(move |id| *id == family_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = RevokeTokenStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::TokenRevoke]),
+        };
+
+        let request = TokenRevocationRequest {
+            token: value.to_string(),
+        };
+
+        let res = RevokeToken::new(stores).handle(request).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_token_invalid() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store.expect_get_refresh_token_by_id().never();
+        auth_store.expect_revoke_refresh_token_family().never();
+
+        let stores = RevokeTokenStores {
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let request = TokenRevocationRequest {
+            token: random_string(),
+        };
+
+        let res = RevokeToken::new(stores).handle(request).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+}
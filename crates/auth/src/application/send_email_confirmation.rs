@@ -4,60 +4,71 @@ use chrono::Duration;
 
 use common_core::UseCase;
 use configuration::Config;
-use database::SharedDb;
+use database::Transactional;
 use mailer::MailerProvider;
 
+use crate::domain::audit::{AuditAction, AuditRecord};
 use crate::domain::auth_user::AuthUser;
-use crate::domain::port::AuthStore;
+use crate::domain::port::{AuditStore, AuthStore};
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct SendEmailConfirmationStores<A, B>
+pub(crate) struct SendEmailConfirmationStores<A, B, C>
 where
     A: MailerProvider,
     B: AuthStore,
+    C: AuditStore,
 {
     /// Mailer provider.
     pub mailer: A,
 
     /// Auth store.
     pub auth: B,
+
+    /// Audit store.
+    pub audit: C,
 }
 
 /// User confirmation use-case structure.
-pub(crate) struct SendEmailConfirmation<A, B>
+pub(crate) struct SendEmailConfirmation<A, B, C, T>
 where
     A: MailerProvider,
     B: AuthStore,
+    C: AuditStore,
+    T: Transactional,
 {
     /// Application configuration.
     config: Config,
 
     /// List of stores used.
-    stores: SendEmailConfirmationStores<A, B>,
+    stores: SendEmailConfirmationStores<A, B, C>,
 
     /// Database handle.
-    db: SharedDb,
+    db: T,
 }
 
-impl<A, B> SendEmailConfirmation<A, B>
+impl<A, B, C, T> SendEmailConfirmation<A, B, C, T>
 where
     A: MailerProvider,
     B: AuthStore,
+    C: AuditStore,
+    T: Transactional,
 {
     /// Creates a `SendEmailConfirmation` use-case instance.
     ///
     /// # Returns
     /// A `SendEmailConfirmation` instance.
-    pub fn new(config: Config, stores: SendEmailConfirmationStores<A, B>, db: SharedDb) -> Self {
+    pub fn new(config: Config, stores: SendEmailConfirmationStores<A, B, C>, db: T) -> Self {
         Self { config, stores, db }
     }
 }
 
-impl<A, B> UseCase for SendEmailConfirmation<A, B>
+impl<A, B, C, T> UseCase for SendEmailConfirmation<A, B, C, T>
 where
     A: MailerProvider,
     B: AuthStore,
+    C: AuditStore,
+    T: Transactional,
 {
     type Args = AuthUser;
     type Output = ();
@@ -68,8 +79,8 @@ where
             Duration::hours(self.config.auth.email_confirmation_timeout_hours.into());
 
         if confirmation_timeout_hours.num_hours() > 0 {
-            // Start a new transaction to avoid creating a user without confirmation
-            self.db.lock().await.start_transaction().await?;
+            // Nothing is changed if the email can't be sent (rolled back when dropped)
+            let uow = self.db.begin().await?;
 
             // Delete existing confirmation if any
             self.stores
@@ -92,8 +103,15 @@ where
                 .send_email_confirmation(&user.email, &confirmation.id, &redirect_url)
                 .await?;
 
-            // Commit the changes
-            self.db.lock().await.commit_transaction().await?;
+            self.stores
+                .audit
+                .record(AuditRecord::new(
+                    AuditAction::EmailConfirmationRequest,
+                    Some(user.id),
+                ))
+                .await?;
+
+            uow.commit().await?;
         }
 
         Ok(())
@@ -105,16 +123,16 @@ mod tests {
     use super::*;
 
     use configuration::Config;
+    use database::DetachedDb;
     use mailer::MockMailerProvider;
-    use test_utils::database::setup_test_database;
 
+    use crate::domain::audit::AuditAction;
     use crate::domain::auth_user::AuthUserConfirmation;
     use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
 
     #[tokio::test]
     async fn test_send_email_confirmation_nominal() -> Result<(), Box<dyn std::error::Error>> {
-        let db = setup_test_database().await?;
-
         let mut mailer = MockMailerProvider::new();
         let mut auth_store = MockAuthStore::new();
 
@@ -138,11 +156,12 @@ mod tests {
         let stores = SendEmailConfirmationStores {
             mailer,
             auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::EmailConfirmationRequest]),
         };
 
         let user = AuthUser::default();
 
-        let res = SendEmailConfirmation::new(config, stores, db)
+        let res = SendEmailConfirmation::new(config, stores, DetachedDb)
             .handle(user)
             .await;
         assert!(res.is_ok());
@@ -0,0 +1,182 @@
+//! Use-case for sending a link to choose a new password (forgotten password).
+
+use chrono::Duration;
+use tracing::{event, Level};
+
+use common_core::UseCase;
+use configuration::Config;
+use mailer::MailerProvider;
+
+use crate::domain::audit::{AuditAction, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct SendPasswordResetStores<A, B, C>
+where
+    A: MailerProvider,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Mailer provider.
+    pub mailer: A,
+
+    /// Auth store.
+    pub auth: B,
+
+    /// Audit store.
+    pub audit: C,
+}
+
+/// Password reset link sending use-case structure.
+pub(crate) struct SendPasswordReset<A, B, C>
+where
+    A: MailerProvider,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: SendPasswordResetStores<A, B, C>,
+}
+
+impl<A, B, C> SendPasswordReset<A, B, C>
+where
+    A: MailerProvider,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Creates a `SendPasswordReset` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `SendPasswordReset` instance.
+    pub fn new(config: Config, stores: SendPasswordResetStores<A, B, C>) -> Self {
+        Self { config, stores }
+    }
+}
+
+impl<A, B, C> UseCase for SendPasswordReset<A, B, C>
+where
+    A: MailerProvider,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Email of the user.
+    type Args = String;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, email: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Don't tell the caller whether the user exists or not
+        let Ok(user) = self.stores.auth.find_user_by_email(&email).await else {
+            event!(Level::WARN, "Password reset requested for an unknown user");
+            return Ok(());
+        };
+
+        let timeout = Duration::minutes(self.config.auth.password_reset_timeout_minutes.into());
+
+        let reset = self
+            .stores
+            .auth
+            .create_password_reset(&user.id, &timeout)
+            .await?;
+
+        let redirect_url = std::env::var("FRONTEND_URL")?;
+
+        self.stores
+            .mailer
+            .send_password_reset(&user.email, &reset.id, &redirect_url)
+            .await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(
+                AuditAction::PasswordResetRequest,
+                Some(user.id),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mailer::MockMailerProvider;
+
+    use crate::domain::audit::AuditAction;
+    use crate::domain::auth_user::{AuthPasswordReset, AuthUser};
+    use crate::domain::port::MockAuthStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_send_password_reset_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let mut mailer = MockMailerProvider::new();
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_find_user_by_email()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(AuthUser::default()) }));
+
+        auth_store
+            .expect_create_password_reset()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthPasswordReset::default()) }));
+
+        mailer
+            .expect_send_password_reset()
+            .times(1)
+            .returning(move |_, _, _| Box::pin(async move { Ok(()) }));
+
+        let stores = SendPasswordResetStores {
+            mailer,
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::PasswordResetRequest]),
+        };
+
+        let res = SendPasswordReset::new(Config::new()?, stores)
+            .handle(test_utils::rand::random_email())
+            .await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_password_reset_unknown_user() -> Result<(), Box<dyn std::error::Error>> {
+        let mut mailer = MockMailerProvider::new();
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_find_user_by_email()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Err(Error::UserNotFound) }));
+
+        auth_store.expect_create_password_reset().never();
+        mailer.expect_send_password_reset().never();
+
+        let stores = SendPasswordResetStores {
+            mailer,
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = SendPasswordReset::new(Config::new()?, stores)
+            .handle(test_utils::rand::random_email())
+            .await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+}
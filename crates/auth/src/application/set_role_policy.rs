@@ -0,0 +1,73 @@
+//! Use-case for updating the security policy of a role.
+
+use common_core::UseCase;
+
+use crate::domain::audit::{AuditAction, AuditChanges, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::domain::totp::AuthRolePolicy;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct SetRolePolicyStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Role policy update use-case structure.
+pub(crate) struct SetRolePolicy<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: SetRolePolicyStores<A, B>,
+}
+
+impl<A, B> SetRolePolicy<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `SetRolePolicy` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `SetRolePolicy` instance.
+    pub fn new(stores: SetRolePolicyStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for SetRolePolicy<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    type Args = AuthRolePolicy;
+    type Output = AuthRolePolicy;
+    type Error = Error;
+
+    async fn handle(&self, policy: Self::Args) -> Result<Self::Output, Self::Error> {
+        let before = self.stores.auth.get_role_policy(&policy.role).await?;
+
+        let policy = self.stores.auth.set_role_policy(&policy).await?;
+
+        let changes = AuditChanges::diff(Some(&before), Some(&policy));
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::RolePolicyUpdate, None).changes(changes))
+            .await?;
+
+        Ok(policy)
+    }
+}
@@ -0,0 +1,147 @@
+//! Use-case for updating an API key of a user.
+
+use common_core::UseCase;
+
+use crate::domain::api_key::{ApiKeyUpdate, AuthApiKey};
+use crate::domain::audit::{AuditAction, AuditChanges, AuditRecord};
+use crate::domain::port::{AuditStore, AuthStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct UpdateApiKeyStores<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Auth store.
+    pub auth: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// API key update use-case structure.
+pub(crate) struct UpdateApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: UpdateApiKeyStores<A, B>,
+}
+
+impl<A, B> UpdateApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// Creates a `UpdateApiKey` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: Stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `UpdateApiKey` instance.
+    pub fn new(stores: UpdateApiKeyStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for UpdateApiKey<A, B>
+where
+    A: AuthStore,
+    B: AuditStore,
+{
+    /// ID of the user, ID of the key and changes to apply.
+    type Args = (Uuid, Uuid, ApiKeyUpdate);
+    type Output = AuthApiKey;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (user_id, id, update) = args;
+
+        let before = self.stores.auth.get_api_key_by_id(&id).await?;
+
+        let api_key = self
+            .stores
+            .auth
+            .update_api_key(&user_id, &id, update.name, update.scopes)
+            .await?;
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::ApiKeyUpdate, Some(user_id))
+                    .changes(AuditChanges::diff(before.as_ref(), Some(&api_key))),
+            )
+            .await?;
+
+        Ok(api_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::port::{MockAuditStore, MockAuthStore};
+
+    #[tokio::test]
+    async fn test_update_api_key_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_api_key_by_id()
+            .times(1)
+            .returning(|id| {
+                let api_key = AuthApiKey {
+                    id: *id,
+                    name: "old".to_string(),
+                    ..Default::default()
+                };
+                Box::pin(async move { Ok(Some(api_key)) })
+            });
+
+        auth_store
+            .expect_update_api_key()
+            .withf(|_, _, name, scopes| name.as_deref() == Some("ci") && scopes.is_none())
+            .times(1)
+            .returning(|_, id, name, _| {
+                let api_key = AuthApiKey {
+                    id: *id,
+                    name: name.unwrap_or_default(),
+                    ..Default::default()
+                };
+                Box::pin(async move { Ok(api_key) })
+            });
+
+        // Only the name has changed
+        let mut audit_store = MockAuditStore::new();
+
+        audit_store
+            .expect_record()
+            .withf(|record| {
+                record.action == AuditAction::ApiKeyUpdate
+                    && record.changes.0.keys().collect::<Vec<_>>() == vec!["name"]
+            })
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = UpdateApiKeyStores {
+            auth: auth_store,
+            audit: audit_store,
+        };
+
+        let update = ApiKeyUpdate {
+            name: Some("ci".to_string()),
+            scopes: None,
+        };
+
+        let api_key = UpdateApiKey::new(stores)
+            .handle((Uuid::new_v4(), Uuid::new_v4(), update))
+            .await?;
+        assert_eq!(api_key.name, "ci");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+//! Personal API keys (machine-to-machine access) related entities.
+
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+use validator::{Validate, ValidationError};
+
+use utils::hashing::{hash_string, verify};
+
+use crate::prelude::*;
+
+/// Name of the header used to provide an API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// API key stored for a user.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct AuthApiKey {
+    /// Unique record identifier.
+    pub id: Uuid,
+
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Name given by the user.
+    pub name: String,
+
+    /// Hash of the secret part of the key.
+    #[debug(skip)]
+    #[serde(skip)]
+    pub key_hash: String,
+
+    /// Scopes granted to the key (e.g. `users:read`).
+    pub scopes: Vec<String>,
+
+    /// Date of the last request made with the key.
+    pub last_used_at: Option<DateTime<Utc>>,
+
+    /// Date of creation of the key.
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuthApiKey {
+    /// Checks if the secret provided by the client matches this key.
+    ///
+    /// # Arguments
+    /// * `value`: Key provided by the client.
+    ///
+    /// # Returns
+    /// A result containing `true` if the secret matches.
+    pub async fn matches(&self, value: &ApiKeyValue) -> ApiResult<bool> {
+        if self.id != value.id {
+            return Ok(false);
+        }
+
+        verify(&value.secret, &self.key_hash)
+            .await
+            .map_err(Error::Hashing)
+    }
+}
+
+/// API key as provided to the client (`<id>.<secret>`).
+#[derive(Clone, PartialEq, derive_more::Debug)]
+pub struct ApiKeyValue {
+    /// ID of the stored key.
+    pub id: Uuid,
+
+    /// Random secret.
+    #[debug(skip)]
+    pub secret: String,
+}
+
+impl ApiKeyValue {
+    /// Generates a new random secret.
+    ///
+    /// # Returns
+    /// The secret.
+    pub fn generate_secret() -> String {
+        format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+    }
+
+    /// Hashes a secret so that it can be stored.
+    ///
+    /// # Arguments
+    /// * `secret`: Secret to hash.
+    ///
+    /// # Returns
+    /// A result containing the hash.
+    pub fn hash_secret(secret: &str) -> ApiResult<String> {
+        hash_string(secret).map_err(Error::Hashing)
+    }
+}
+
+impl FromStr for ApiKeyValue {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (id, secret) = value.trim().split_once('.').ok_or(Error::Unauthorized)?;
+
+        Ok(Self {
+            id: Uuid::parse_str(id).map_err(|_| Error::Unauthorized)?,
+            secret: secret.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for ApiKeyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.id, self.secret)
+    }
+}
+
+/// Checks that the scopes have the form `<resource>:<action>`.
+///
+/// # Arguments
+/// * `scopes`: Scopes to check.
+///
+/// # Returns
+/// An empty result or a validation error.
+fn validate_scopes(scopes: &[String]) -> Result<(), ValidationError> {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c == '_' || c == '*')
+    };
+
+    let all_valid = scopes.iter().all(|scope| {
+        scope
+            .split_once(':')
+            .is_some_and(|(resource, action)| valid(resource) && valid(action))
+    });
+
+    if !all_valid {
+        return Err(ValidationError::new("invalid_scope"));
+    }
+
+    Ok(())
+}
+
+/// Structure used to create an API key.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, Validate)]
+pub struct ApiKeyRequest {
+    /// See `AuthApiKey::name`.
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// See `AuthApiKey::scopes`.
+    #[serde(default)]
+    #[validate(custom(function = "validate_scopes"))]
+    pub scopes: Vec<String>,
+}
+
+/// Structure used to update an API key.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, Validate)]
+pub struct ApiKeyUpdate {
+    /// See `AuthApiKey::name`.
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+
+    /// See `AuthApiKey::scopes`.
+    #[validate(custom(function = "validate_scopes"))]
+    pub scopes: Option<Vec<String>>,
+}
+
+/// API key just created (the key is only displayed once to the user).
+#[derive(Clone, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct CreatedApiKey {
+    /// Information about the key.
+    #[serde(flatten)]
+    pub api_key: AuthApiKey,
+
+    /// Key to be sent in the `X-Api-Key` header.
+    #[debug(skip)]
+    pub key: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::rand::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_api_key_value() -> Result<(), Box<dyn std::error::Error>> {
+        let value = ApiKeyValue {
+            id: random_id(),
+            secret: ApiKeyValue::generate_secret(),
+        };
+
+        let parsed: ApiKeyValue = value.to_string().parse()?;
+        assert_eq!(parsed, value);
+
+        let api_key = AuthApiKey {
+            id: value.id,
+            key_hash: ApiKeyValue::hash_secret(&value.secret)?,
+            ..Default::default()
+        };
+        assert!(api_key.matches(&value).await?);
+
+        let other = ApiKeyValue {
+            secret: ApiKeyValue::generate_secret(),
+            ..value.clone()
+        };
+        assert!(!api_key.matches(&other).await?);
+
+        let other = ApiKeyValue {
+            id: random_id(),
+            ..value
+        };
+        assert!(!api_key.matches(&other).await?);
+
+        assert!("invalid".parse::<ApiKeyValue>().is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_key_scopes() -> Result<(), Box<dyn std::error::Error>> {
+        let request = ApiKeyRequest {
+            name: random_string(),
+            scopes: vec!["users:read".to_string(), "users:*".to_string()],
+        };
+        assert!(request.validate().is_ok());
+
+        for scope in ["users", "users:", ":read", "Users:read", "users:read:all"] {
+            let request = ApiKeyRequest {
+                scopes: vec![scope.to_string()],
+                ..request.clone()
+            };
+            assert!(request.validate().is_err(), "{scope}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+//! Audit trail related entities: the changes made to the users and to their authentication data.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::prelude::*;
+
+/// Name of the header containing the identifier of the request.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Fields never recorded in the audit trail (a field is skipped if its name contains one of
+/// these): secrets, and data that changes at each update anyway.
+const EXCLUDED_FIELDS: [&str; 6] = [
+    "password",
+    "hash",
+    "secret",
+    "token",
+    "confirmation",
+    "updated_at",
+];
+
+/// List of actions recorded in the audit trail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AuditAction {
+    /// An API key has been created.
+    #[serde(rename = "api_key.create")]
+    ApiKeyCreate,
+
+    /// An API key has been revoked.
+    #[serde(rename = "api_key.revoke")]
+    ApiKeyRevoke,
+
+    /// An API key has been updated.
+    #[serde(rename = "api_key.update")]
+    ApiKeyUpdate,
+
+    /// An identity of an OAuth provider has been linked to the user.
+    #[serde(rename = "auth.identity_link")]
+    IdentityLink,
+
+    /// The user has logged in.
+    #[serde(rename = "auth.login")]
+    Login,
+
+    /// The user has logged out.
+    #[serde(rename = "auth.logout")]
+    Logout,
+
+    /// A login link has been sent to the user.
+    #[serde(rename = "auth.magic_link_request")]
+    MagicLinkRequest,
+
+    /// The user has chosen a new password after a reset.
+    #[serde(rename = "auth.password_reset")]
+    PasswordReset,
+
+    /// A password reset link has been sent to the user.
+    #[serde(rename = "auth.password_reset_request")]
+    PasswordResetRequest,
+
+    /// An invitation has been accepted.
+    #[serde(rename = "invitation.accept")]
+    InvitationAccept,
+
+    /// A user has been invited.
+    #[serde(rename = "invitation.create")]
+    InvitationCreate,
+
+    /// An invitation has been sent again.
+    #[serde(rename = "invitation.resend")]
+    InvitationResend,
+
+    /// An invitation has been revoked.
+    #[serde(rename = "invitation.revoke")]
+    InvitationRevoke,
+
+    /// The security policy of a role has been updated.
+    #[serde(rename = "role_policy.update")]
+    RolePolicyUpdate,
+
+    /// A session of the user has been revoked.
+    #[serde(rename = "session.revoke")]
+    SessionRevoke,
+
+    /// All sessions of the user have been revoked.
+    #[serde(rename = "session.revoke_all")]
+    SessionRevokeAll,
+
+    /// Tokens have been issued to the user.
+    #[serde(rename = "token.issue")]
+    TokenIssue,
+
+    /// A refresh token has been revoked.
+    #[serde(rename = "token.revoke")]
+    TokenRevoke,
+
+    /// The enrollment of a second factor has been confirmed.
+    #[serde(rename = "totp.confirm")]
+    TotpConfirm,
+
+    /// The user has started the enrollment of a second factor.
+    #[serde(rename = "totp.enroll")]
+    TotpEnroll,
+
+    /// A user has been created.
+    #[serde(rename = "user.create")]
+    UserCreate,
+
+    /// A user has been deleted.
+    #[serde(rename = "user.delete")]
+    UserDelete,
+
+    /// The change of email of a user has been confirmed.
+    #[serde(rename = "user.email_change_confirm")]
+    EmailChangeConfirm,
+
+    /// A change of email has been requested for a user.
+    #[serde(rename = "user.email_change_request")]
+    EmailChangeRequest,
+
+    /// The change of email of a user has been reverted.
+    #[serde(rename = "user.email_change_revert")]
+    EmailChangeRevert,
+
+    /// The personal data of a user has been erased.
+    #[serde(rename = "user.erase")]
+    UserErase,
+
+    /// The user has confirmed its email.
+    #[serde(rename = "user.email_confirm")]
+    EmailConfirm,
+
+    /// A new email confirmation link has been sent to the user.
+    #[serde(rename = "user.email_confirmation_request")]
+    EmailConfirmationRequest,
+
+    /// The password of a user has been changed.
+    #[serde(rename = "user.password_change")]
+    PasswordChange,
+
+    /// The deleted users have been purged.
+    #[serde(rename = "user.purge")]
+    UserPurge,
+
+    /// A deleted user has been restored.
+    #[serde(rename = "user.restore")]
+    UserRestore,
+
+    /// A user has been updated.
+    #[serde(rename = "user.update")]
+    UserUpdate,
+}
+
+impl AuditAction {
+    /// Gets the name of the action, as stored in database.
+    ///
+    /// # Returns
+    /// The name of the action (e.g. `user.update`).
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+}
+
+/// Change of a field: its values before and after (`null` if the field didn't exist).
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct AuditChange {
+    /// Value before the change.
+    pub before: Value,
+
+    /// Value after the change.
+    pub after: Value,
+}
+
+/// List of the fields changed, by name.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct AuditChanges(pub BTreeMap<String, AuditChange>);
+
+impl AuditChanges {
+    /// Computes the fields that differ between two versions of an entity. The sensitive fields
+    /// (passwords, hashes, secrets, tokens) are never recorded.
+    ///
+    /// # Arguments
+    /// * `before`: Version before the change (or None for a creation).
+    /// * `after`: Version after the change (or None for a deletion).
+    ///
+    /// # Returns
+    /// An `AuditChanges` instance.
+    pub fn diff<T>(before: Option<&T>, after: Option<&T>) -> Self
+    where
+        T: Serialize,
+    {
+        let fields = |entity: Option<&T>| match entity.map(serde_json::to_value) {
+            Some(Ok(Value::Object(fields))) => fields,
+            _ => serde_json::Map::new(),
+        };
+
+        let before = fields(before);
+        let mut after = fields(after);
+
+        let mut changes = Self::default();
+
+        for (name, before) in before {
+            let after = after.remove(&name).unwrap_or_default();
+            changes = changes.field(&name, before, after);
+        }
+
+        for (name, after) in after {
+            changes = changes.field(&name, Value::Null, after);
+        }
+
+        changes
+    }
+
+    /// Adds the change of a field (ignored if the values are equal or if the field is
+    /// sensitive).
+    ///
+    /// # Arguments
+    /// * `name`: Name of the field.
+    /// * `before`: Value before the change.
+    /// * `after`: Value after the change.
+    ///
+    /// # Returns
+    /// The updated `AuditChanges` instance.
+    pub fn field<T>(mut self, name: &str, before: T, after: T) -> Self
+    where
+        T: Serialize,
+    {
+        if EXCLUDED_FIELDS
+            .iter()
+            .any(|excluded| name.contains(excluded))
+        {
+            return self;
+        }
+
+        let before = serde_json::to_value(before).unwrap_or_default();
+        let after = serde_json::to_value(after).unwrap_or_default();
+
+        if before != after {
+            self.0
+                .insert(name.to_string(), AuditChange { before, after });
+        }
+
+        self
+    }
+
+    /// Checks if no field has changed.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Entry to be recorded in the audit trail.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditRecord {
+    /// Action performed.
+    pub action: AuditAction,
+
+    /// ID of the user concerned by the action (or None if not related to a user).
+    pub user_id: Option<Uuid>,
+
+    /// ID of the user that performed the action (or None to use the caller of the request).
+    pub actor_id: Option<Uuid>,
+
+    /// Fields changed.
+    pub changes: AuditChanges,
+}
+
+impl AuditRecord {
+    /// Creates an entry without any change.
+    ///
+    /// # Arguments
+    /// * `action`: Action performed.
+    /// * `user_id`: ID of the user concerned by the action.
+    ///
+    /// # Returns
+    /// An `AuditRecord` instance.
+    pub fn new(action: AuditAction, user_id: Option<Uuid>) -> Self {
+        Self {
+            action,
+            user_id,
+            actor_id: None,
+            changes: AuditChanges::default(),
+        }
+    }
+
+    /// Sets the fields changed.
+    ///
+    /// # Arguments
+    /// * `changes`: Fields changed.
+    ///
+    /// # Returns
+    /// The updated `AuditRecord` instance.
+    pub fn changes(mut self, changes: AuditChanges) -> Self {
+        self.changes = changes;
+        self
+    }
+
+    /// Sets the user that performed the action, for the actions performed by a caller that is
+    /// not logged in yet (e.g. a login).
+    ///
+    /// # Arguments
+    /// * `actor_id`: ID of the user.
+    ///
+    /// # Returns
+    /// The updated `AuditRecord` instance.
+    pub fn actor(mut self, actor_id: Uuid) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+}
+
+/// Entry of the audit trail.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AuditEntry {
+    /// Unique record identifier.
+    pub id: Uuid,
+
+    /// ID of the user that performed the action (or None if performed anonymously or by the
+    /// system).
+    pub actor_id: Option<Uuid>,
+
+    /// ID of the user concerned by the action.
+    pub user_id: Option<Uuid>,
+
+    /// Action performed.
+    pub action: AuditAction,
+
+    /// Fields changed.
+    pub changes: AuditChanges,
+
+    /// Identifier of the request that performed the action.
+    pub request_id: Option<String>,
+
+    /// Date of the action.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Structure that list all filters available for querying the audit trail.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct AuditFilters {
+    /// ID of the user concerned by the actions (or None).
+    pub user_id: Option<Uuid>,
+
+    /// ID of the user that performed the actions (or None).
+    pub actor_id: Option<Uuid>,
+
+    /// Action performed (or None).
+    pub action: Option<AuditAction>,
+
+    /// Start of the time range, included (or None).
+    pub from: Option<DateTime<Utc>>,
+
+    /// End of the time range, excluded (or None).
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Information about the request recorded along with the entries of the audit trail.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuditContext {
+    /// ID of the user calling the endpoint (or None if not logged in).
+    pub actor_id: Option<Uuid>,
+
+    /// Identifier of the request.
+    pub request_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Entity {
+        name: &'static str,
+        role: &'static str,
+        password: &'static str,
+        updated_at: i64,
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = Entity {
+            name: "John",
+            role: "guest",
+            password: "hash1",
+            updated_at: 1,
+        };
+
+        let after = Entity {
+            name: "John",
+            role: "admin",
+            password: "hash2",
+            updated_at: 2,
+        };
+
+        let changes = AuditChanges::diff(Some(&before), Some(&after));
+        assert_eq!(changes.0.len(), 1);
+        assert_eq!(
+            changes.0.get("role"),
+            Some(&AuditChange {
+                before: Value::from("guest"),
+                after: Value::from("admin"),
+            })
+        );
+
+        // Creation
+        let changes = AuditChanges::diff(None, Some(&after));
+        assert_eq!(
+            changes.0.keys().collect::<Vec<_>>(),
+            vec![&"name".to_string(), &"role".to_string()]
+        );
+        assert_eq!(changes.0.get("name").map(|c| &c.before), Some(&Value::Null));
+
+        // Deletion
+        let changes = AuditChanges::diff(Some(&before), None);
+        assert_eq!(changes.0.get("name").map(|c| &c.after), Some(&Value::Null));
+
+        // Nothing changed
+        assert!(AuditChanges::diff(Some(&before), Some(&before)).is_empty());
+    }
+
+    #[test]
+    fn test_field() {
+        let changes = AuditChanges::default()
+            .field("scopes", vec!["users:read"], vec![])
+            .field("key_hash", "a", "b")
+            .field("name", "same", "same");
+
+        assert_eq!(changes.0.keys().collect::<Vec<_>>(), vec!["scopes"]);
+    }
+
+    #[test]
+    fn test_action() {
+        assert_eq!(AuditAction::UserUpdate.name(), "user.update");
+        assert_eq!(AuditAction::Login.name(), "auth.login");
+    }
+}
@@ -3,7 +3,9 @@
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
 use axum::middleware::Next;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
 use tracing::{event, Level};
@@ -11,8 +13,12 @@ use validator::Validate;
 
 use security::password::Password;
 
-use crate::domain::auth_user::AuthUser;
+use crate::domain::audit::AuditContext;
+use crate::domain::auth_session::AuthSessionMetadata;
+use crate::domain::auth_user::{AuthUser, Expiring};
 use crate::domain::error::Error;
+use crate::domain::permission::covers;
+use crate::domain::port::AuthStore;
 use crate::prelude::*;
 
 /// Structure used to store the credentials that must be provided by a user to check it's
@@ -30,6 +36,104 @@ pub struct AuthCredentials {
     pub password: Password,
 }
 
+/// Structure used to request a link to login without password.
+#[derive(Clone, Debug, Deserialize, Serialize, Validate)]
+pub struct MagicLinkRequest {
+    /// Email of the user that wants to login.
+    #[validate(email)]
+    pub email: String,
+}
+
+/// Structure used to store the credentials provided by a user that follows a link to login
+/// without password.
+#[derive(Clone, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct MagicLinkCredentials {
+    /// Email used during authentication.
+    #[validate(email)]
+    pub email: String,
+
+    /// One-time token received by email.
+    #[debug(skip)]
+    pub token: Uuid,
+}
+
+/// Structure used to request a link to choose a new password.
+#[derive(Clone, Debug, Deserialize, Serialize, Validate)]
+pub struct PasswordResetRequest {
+    /// Email of the user that forgot his password.
+    #[validate(email)]
+    pub email: String,
+}
+
+/// Structure used to choose a new password with the token received by email.
+#[derive(Clone, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct PasswordReset {
+    /// One-time token received by email.
+    #[debug(skip)]
+    pub token: Uuid,
+
+    /// New password.
+    #[debug(skip)]
+    #[validate(nested)]
+    pub password: Password,
+}
+
+/// Duration allowed to provide the second factor after a successful first factor.
+const SECOND_FACTOR_TIMEOUT_MINUTES: i64 = 5;
+
+/// Number of invalid second factors accepted before the login must be restarted.
+const SECOND_FACTOR_MAX_ATTEMPTS: u32 = 5;
+
+/// Outcome of a login attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginStatus {
+    /// The session has been created.
+    LoggedIn,
+
+    /// The user must provide a code generated by his authenticator app (or a recovery code).
+    SecondFactorRequired,
+
+    /// The role of the user requires a second factor but the user has not enrolled yet.
+    SecondFactorEnrollmentRequired,
+}
+
+impl IntoResponse for LoginStatus {
+    fn into_response(self) -> Response {
+        match self {
+            Self::LoggedIn => StatusCode::OK.into_response(),
+
+            status => (StatusCode::ACCEPTED, Json(LoginStatusBody { status })).into_response(),
+        }
+    }
+}
+
+/// Body of the response sent when the login is not complete.
+#[derive(Serialize)]
+struct LoginStatusBody {
+    /// Status of the login.
+    status: LoginStatus,
+}
+
+/// Login waiting for the second factor of the user (stored in the session).
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PendingSecondFactor {
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Date after which the login must be restarted.
+    pub expires_at: DateTime<Utc>,
+
+    /// Number of invalid second factors provided.
+    pub attempts: u32,
+}
+
+impl Expiring for PendingSecondFactor {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
 /// Structure used to store all needed information for authentication.
 /// This structure aims to be declared as argument of the HTTP endpoints.
 #[derive(Debug)]
@@ -39,12 +143,31 @@ pub struct Auth {
 
     /// Session store.
     pub session: Session,
+
+    /// Identifier of the session record in database (set if a user is logged in).
+    pub session_id: Option<Uuid>,
+
+    /// Scopes granted to the caller (set if the caller uses an API key, which restricts its
+    /// accesses).
+    pub scopes: Option<Vec<String>>,
+
+    /// Information about the client calling the endpoint.
+    pub metadata: AuthSessionMetadata,
+
+    /// Identifier of the request (see the `x-request-id` header).
+    pub request_id: Option<String>,
 }
 
 impl Auth {
     /// Key used to store the user information in the session.
     pub const KEY: &'static str = "auth_user";
 
+    /// Key used to store the identifier of the session record in the session.
+    pub const SESSION_ID_KEY: &'static str = "auth_session_id";
+
+    /// Key used to store the login waiting for a second factor in the session.
+    pub const PENDING_SECOND_FACTOR_KEY: &'static str = "auth_pending_second_factor";
+
     /// Get the user information from the session.
     ///
     /// # Returns
@@ -53,6 +176,17 @@ impl Auth {
         &self.user
     }
 
+    /// Gets the information about the request to be recorded in the audit trail.
+    ///
+    /// # Returns
+    /// The audit context, with the caller as actor.
+    pub fn audit_context(&self) -> AuditContext {
+        AuditContext {
+            actor_id: self.user.as_ref().map(|user| user.id),
+            request_id: self.request_id.clone(),
+        }
+    }
+
     /// Get the user information from the session as Result.
     ///
     /// # Returns
@@ -61,6 +195,75 @@ impl Auth {
         self.user.as_ref().ok_or(Error::UserNotFound).cloned()
     }
 
+    /// Checks if the caller has been granted a scope. Callers that don't use an API key are granted
+    /// all scopes.
+    ///
+    /// # Arguments
+    /// * `scope`: Scope to check (e.g. `users:read`).
+    ///
+    /// # Returns
+    /// `true` if the scope is granted.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        let Some(scopes) = &self.scopes else {
+            return true;
+        };
+
+        scopes.iter().any(|granted| covers(granted, scope))
+    }
+
+    /// Checks that the caller is granted a permission, both by the role of the user and by the
+    /// scopes of the API key (if any).
+    ///
+    /// # Arguments
+    /// * `permission`: Permission required (e.g. `users:delete`).
+    ///
+    /// # Returns
+    /// Result indicating success, `Error::Unauthorized` if no user is logged or
+    /// `Error::Forbidden`.
+    pub fn require_permission(&self, permission: &str) -> ApiResult<()> {
+        let user = self.user.as_ref().ok_or(Error::Unauthorized)?;
+
+        if !user.role.has_permission(permission) || !self.has_scope(permission) {
+            event!(Level::WARN, "{:?} is missing permission {permission}", user);
+            return Err(Error::Forbidden);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the caller is granted a permission, or is the user targeted by the action
+    /// (e.g. a user can update himself without having the `users:update` permission).
+    ///
+    /// # Arguments
+    /// * `permission`: Permission required to act on other users.
+    /// * `user_id`: ID of the user targeted by the action.
+    ///
+    /// # Returns
+    /// Result indicating success, `Error::Unauthorized` if no user is logged or
+    /// `Error::Forbidden`.
+    pub fn require_permission_or_self(&self, permission: &str, user_id: &Uuid) -> ApiResult<()> {
+        let user = self.user.as_ref().ok_or(Error::Unauthorized)?;
+
+        // API keys still need to be granted the scope to act on their owner
+        if user.is(user_id) && self.has_scope(permission) {
+            return Ok(());
+        }
+
+        self.require_permission(permission)
+    }
+
+    /// Checks that the caller doesn't use an API key (some actions, like the management of the
+    /// API keys, require a real login).
+    ///
+    /// # Returns
+    /// Result indicating success, or `Error::Forbidden`.
+    pub fn require_login(&self) -> ApiResult<()> {
+        match self.scopes {
+            Some(_) => Err(Error::Forbidden),
+            None => Ok(()),
+        }
+    }
+
     /// Try to authenticate a user by checking its credentials.
     ///
     /// # Arguments
@@ -90,10 +293,14 @@ impl Auth {
     ///
     /// # Arguments
     /// * `user`: User obtained from the database.
+    /// * `store`: Store used to record the session.
     ///
     /// # Returns
     /// Result indicating success or failure.
-    pub async fn login(&mut self, user: &AuthUser) -> ApiResult<()> {
+    pub async fn login<A>(&mut self, user: &AuthUser, store: &A) -> ApiResult<()>
+    where
+        A: AuthStore,
+    {
         let auth_user = Some(user.clone());
 
         if self.user.is_none() {
@@ -101,20 +308,148 @@ impl Auth {
             self.session.cycle_id().await?;
         }
 
+        // Replace the record of the previous session (if any)
+        self.delete_session_record(store).await?;
+
+        let record = store.create_session(&user.id, &self.metadata).await?;
+
         self.session.insert(Self::KEY, auth_user.clone()).await?;
+        self.session.insert(Self::SESSION_ID_KEY, record.id).await?;
+        self.clear_pending_second_factor().await?;
 
         self.user = auth_user;
+        self.session_id = Some(record.id);
 
         event!(Level::INFO, "Successfully logged in as {:?}", self.user);
 
         Ok(())
     }
 
+    /// Creates a session for the user unless a second factor is needed. In that case, the login is
+    /// kept pending in the session until the second factor is provided.
+    ///
+    /// # Arguments
+    /// * `user`: User obtained from the database (the first factor has already been checked).
+    /// * `store`: Store used to get the second factor configuration and record the session.
+    ///
+    /// # Returns
+    /// Result containing the status of the login.
+    pub async fn challenge_or_login<A>(
+        &mut self,
+        user: &AuthUser,
+        store: &A,
+    ) -> ApiResult<LoginStatus>
+    where
+        A: AuthStore,
+    {
+        let enabled = store
+            .get_totp_by_user_id(&user.id)
+            .await?
+            .is_some_and(|totp| totp.enabled);
+
+        let status = if enabled {
+            LoginStatus::SecondFactorRequired
+        } else if store.get_role_policy(&user.role).await?.totp_required {
+            LoginStatus::SecondFactorEnrollmentRequired
+        } else {
+            self.login(user, store).await?;
+            return Ok(LoginStatus::LoggedIn);
+        };
+
+        let pending = PendingSecondFactor {
+            user_id: user.id,
+            expires_at: Utc::now() + Duration::minutes(SECOND_FACTOR_TIMEOUT_MINUTES),
+            attempts: 0,
+        };
+
+        self.session
+            .insert(Self::PENDING_SECOND_FACTOR_KEY, pending)
+            .await?;
+
+        event!(Level::INFO, "Second factor needed for {:?}", user);
+
+        Ok(status)
+    }
+
+    /// Gets the login waiting for a second factor.
+    ///
+    /// # Returns
+    /// Result containing the pending login, or an error if there's none (or if it's expired).
+    pub async fn pending_second_factor(&self) -> ApiResult<PendingSecondFactor> {
+        let pending: PendingSecondFactor = self
+            .session
+            .get(Self::PENDING_SECOND_FACTOR_KEY)
+            .await?
+            .ok_or(Error::SecondFactorNotPending)?;
+
+        if pending.is_expired() {
+            self.clear_pending_second_factor().await?;
+            return Err(Error::SecondFactorNotPending);
+        }
+
+        Ok(pending)
+    }
+
+    /// Gets the user that is enrolling a second factor: either the logged user or the user whose
+    /// login is waiting for the enrollment.
+    ///
+    /// # Returns
+    /// Result containing the ID of the user.
+    pub async fn enrolling_user_id(&self) -> ApiResult<Uuid> {
+        match &self.user {
+            Some(user) => Ok(user.id),
+            None => Ok(self.pending_second_factor().await?.user_id),
+        }
+    }
+
+    /// Records an invalid second factor. The pending login is dropped once too many attempts have
+    /// been made.
+    ///
+    /// # Arguments
+    /// * `pending`: Pending login.
+    ///
+    /// # Returns
+    /// Result indicating success or failure.
+    pub async fn reject_second_factor(&self, mut pending: PendingSecondFactor) -> ApiResult<()> {
+        pending.attempts += 1;
+
+        if pending.attempts >= SECOND_FACTOR_MAX_ATTEMPTS {
+            event!(Level::WARN, "Too many invalid second factors");
+            return self.clear_pending_second_factor().await;
+        }
+
+        self.session
+            .insert(Self::PENDING_SECOND_FACTOR_KEY, pending)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drops the login waiting for a second factor.
+    ///
+    /// # Returns
+    /// Result indicating success or failure.
+    pub async fn clear_pending_second_factor(&self) -> ApiResult<()> {
+        self.session
+            .remove::<PendingSecondFactor>(Self::PENDING_SECOND_FACTOR_KEY)
+            .await?;
+
+        Ok(())
+    }
+
     /// Deletes the current session.
     ///
+    /// # Arguments
+    /// * `store`: Store used to delete the session record.
+    ///
     /// # Returns
     /// Result indicating success or failure.
-    pub async fn logout(&mut self) -> ApiResult<()> {
+    pub async fn logout<A>(&mut self, store: &A) -> ApiResult<()>
+    where
+        A: AuthStore,
+    {
+        self.delete_session_record(store).await?;
+
         let user = self.user.take();
 
         self.session.flush().await?;
@@ -123,6 +458,28 @@ impl Auth {
 
         Ok(())
     }
+
+    /// Deletes the record of the current session, if any. A record that has already been revoked
+    /// is ignored.
+    ///
+    /// # Arguments
+    /// * `store`: Store used to delete the session record.
+    ///
+    /// # Returns
+    /// Result indicating success or failure.
+    async fn delete_session_record<A>(&mut self, store: &A) -> ApiResult<()>
+    where
+        A: AuthStore,
+    {
+        if let (Some(user), Some(session_id)) = (&self.user, self.session_id.take()) {
+            match store.delete_session(&user.id, &session_id).await {
+                Ok(()) | Err(Error::UserSessionNotFound) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Checks if the user is authenticated. If not, it returns a 401 Unauthorized response.
@@ -146,6 +503,48 @@ pub async fn require_authentication(
     }
 }
 
+/// Checks if the caller is granted a permission. If not, it returns a 403 Forbidden response (or
+/// a 401 Unauthorized response if no user is logged).
+///
+/// # Arguments
+/// * `auth`: Authentication object containing user information.
+/// * `permission`: Permission required.
+/// * `request`: HTTP request.
+/// * `next`: Next middleware in the chain.
+///
+/// # Returns
+/// Result containing the next response or an error response.
+pub async fn require_permission(
+    auth: Auth,
+    permission: &'static str,
+    request: Request<Body>,
+    next: Next,
+) -> ApiResult<Response> {
+    auth.require_permission(permission)?;
+
+    Ok(next.run(request).await)
+}
+
+/// Macro used to create a middleware that checks if the caller is granted a permission.
+///
+/// ```ignore
+/// Router::new().route(
+///     "/:user_id",
+///     delete(delete_user_by_id).route_layer(require_permission!(state, "users:delete")),
+/// )
+/// ```
+#[macro_export]
+macro_rules! require_permission {
+    ($state: expr, $permission: expr) => {
+        axum::middleware::from_fn_with_state(
+            $state,
+            |auth: $crate::Auth, request: axum::extract::Request, next: axum::middleware::Next| {
+                $crate::require_permission(auth, $permission, request, next)
+            },
+        )
+    };
+}
+
 /// Macro used to create a middleware that checks if the user is authenticated.
 #[macro_export]
 macro_rules! require_authentication {
@@ -156,12 +555,31 @@ macro_rules! require_authentication {
 
 #[cfg(test)]
 mod tests {
-    use test_utils::rand::*;
-
+    use axum::http::header::AUTHORIZATION;
+    use axum::routing::{delete, get};
+    use axum::Router;
+    use bb8_redis::{bb8, RedisConnectionManager};
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::util::ServiceExt;
+    use tower_sessions::{MemoryStore, SessionManagerLayer};
+
+    use common_core::UseCase;
+    use common_state::{AppState, Replicas};
+    use configuration::Config;
     use security::password::{set_checks, Checks};
+    use test_utils::database::setup_test_database;
+    use test_utils::rand::*;
 
     use super::*;
 
+    use crate::application::{ResetPassword, ResetPasswordStores};
+    use crate::domain::auth_user::AuthUserRole;
+    use crate::domain::token::JwtCodec;
+    use crate::infrastructure::{SQLxAuditStore, SQLxAuthStore};
+    use crate::tests::utils::create_user;
+
     #[tokio::test]
     async fn test_credentials_validation_email() -> Result<(), Box<dyn std::error::Error>> {
         set_checks(Checks {
@@ -209,4 +627,228 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_auth_scopes() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth = crate::tests::utils::new_auth();
+        assert!(auth.has_scope("users:delete"));
+        assert!(auth.require_login().is_ok());
+
+        auth.scopes = Some(vec!["users:read".to_string(), "sessions:*".to_string()]);
+        assert!(auth.has_scope("users:read"));
+        assert!(!auth.has_scope("users:delete"));
+        assert!(auth.has_scope("sessions:delete"));
+        assert!(matches!(auth.require_login(), Err(Error::Forbidden)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_auth_permissions() -> Result<(), Box<dyn std::error::Error>> {
+        let mut auth = crate::tests::utils::new_auth();
+        let other_id = random_id();
+
+        assert!(matches!(
+            auth.require_permission("users:delete"),
+            Err(Error::Unauthorized)
+        ));
+
+        // Normal users can only act on themselves
+        let user = AuthUser {
+            id: random_id(),
+            role: AuthUserRole::Normal,
+            ..Default::default()
+        };
+
+        let user_id = user.id;
+        auth.user = Some(user);
+
+        assert!(matches!(
+            auth.require_permission("users:delete"),
+            Err(Error::Forbidden)
+        ));
+        assert!(auth
+            .require_permission_or_self("users:update", &user_id)
+            .is_ok());
+        assert!(matches!(
+            auth.require_permission_or_self("users:update", &other_id),
+            Err(Error::Forbidden)
+        ));
+
+        // Admins can act on everyone, unless restricted by the scopes of an API key
+        auth.user = Some(AuthUser {
+            id: user_id,
+            role: AuthUserRole::Admin,
+            ..Default::default()
+        });
+
+        assert!(auth.require_permission("users:delete").is_ok());
+        assert!(auth
+            .require_permission_or_self("users:update", &other_id)
+            .is_ok());
+
+        auth.scopes = Some(vec!["users:read".to_string()]);
+
+        assert!(auth.require_permission("users:read").is_ok());
+        assert!(matches!(
+            auth.require_permission("users:delete"),
+            Err(Error::Forbidden)
+        ));
+        assert!(matches!(
+            auth.require_permission_or_self("users:update", &user_id),
+            Err(Error::Forbidden)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_require_permission_layer() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let db = setup_test_database().await?;
+
+        let pool = PgPoolOptions::new()
+            .connect(&std::env::var("DATABASE_URL_TEST")?)
+            .await?;
+
+        // Redis is not used by this route
+        let redis = bb8::Pool::builder()
+            .build_unchecked(RedisConnectionManager::new("redis://127.0.0.1:1")?);
+
+        let config = Config::new()?;
+        let codec = JwtCodec::from_config(&config)?;
+        let state = AppState::new(config, pool, Replicas::default(), redis);
+
+        // Number of times the handler has run
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let handler = {
+            let calls = calls.clone();
+            move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                StatusCode::NO_CONTENT
+            }
+        };
+
+        let router = Router::new()
+            .route(
+                "/users/:user_id",
+                delete(handler)
+                    .route_layer(crate::require_permission!(state.clone(), "users:delete")),
+            )
+            .layer(SessionManagerLayer::new(MemoryStore::default()))
+            .with_state(state);
+
+        let send = |token: Option<String>| {
+            let mut request = Request::delete(format!("/users/{}", random_id()));
+
+            if let Some(token) = token {
+                request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+            }
+
+            router.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let mut users = Vec::new();
+
+        for role in [AuthUserRole::Normal, AuthUserRole::Admin] {
+            let user = AuthUser {
+                email: random_email(),
+                password: random_password(),
+                role,
+                ..Default::default()
+            };
+
+            users.push(create_user(&user, &db).await?);
+        }
+
+        // The handler doesn't run without the permission
+        let response = send(None).await?;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = send(Some(codec.encode(&users[0])?)).await?;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let response = send(Some(codec.encode(&users[1])?)).await?;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_access_token_revoked() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let db = setup_test_database().await?;
+
+        let pool = PgPoolOptions::new()
+            .connect(&std::env::var("DATABASE_URL_TEST")?)
+            .await?;
+
+        // Redis is not used by this route
+        let redis = bb8::Pool::builder()
+            .build_unchecked(RedisConnectionManager::new("redis://127.0.0.1:1")?);
+
+        let config = Config::new()?;
+        let codec = JwtCodec::from_config(&config)?;
+        let state = AppState::new(config, pool, Replicas::default(), redis);
+
+        let router = Router::new()
+            .route("/me", get(|_: Auth| async { StatusCode::NO_CONTENT }))
+            .layer(SessionManagerLayer::new(MemoryStore::default()))
+            .with_state(state);
+
+        let send = |token: &str| {
+            let request = Request::get("/me")
+                .header(AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+
+            router.clone().oneshot(request)
+        };
+
+        let user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&user, &db).await?;
+        let token = codec.encode(&user)?;
+
+        let response = send(&token).await?;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        // Resetting the password revokes the tokens issued before
+        let reset = SQLxAuthStore::new(&db)
+            .create_password_reset(&user.id, &Duration::minutes(5))
+            .await?;
+
+        let stores = ResetPasswordStores {
+            auth: SQLxAuthStore::new(&db),
+            audit: SQLxAuditStore::new(&db, AuditContext::default()),
+        };
+
+        ResetPassword::new(stores, db.clone())
+            .handle(PasswordReset {
+                token: reset.id,
+                password: random_password(),
+            })
+            .await?;
+
+        let response = send(&token).await?;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // The tokens issued after are valid
+        let user = SQLxAuthStore::new(&db).get_user_by_id(&user.id).await?;
+        let response = send(&codec.encode(&user)?).await?;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,40 @@
+//! Authentication session related entities.
+
+use chrono::{DateTime, Utc};
+
+use crate::prelude::*;
+
+/// Information about the client that opened a session.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct AuthSessionMetadata {
+    /// IP address of the client.
+    pub ip_address: Option<String>,
+
+    /// User agent of the client.
+    pub user_agent: Option<String>,
+}
+
+/// Session opened by a user.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct AuthSession {
+    /// Unique record identifier.
+    pub id: Uuid,
+
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// IP address of the client that opened the session.
+    pub ip_address: Option<String>,
+
+    /// User agent of the client that opened the session.
+    pub user_agent: Option<String>,
+
+    /// Date of creation of the session.
+    pub created_at: DateTime<Utc>,
+
+    /// Date of the last request made with the session.
+    pub last_seen_at: DateTime<Utc>,
+
+    /// Whether this is the session used by the caller.
+    pub current: bool,
+}
@@ -1,6 +1,9 @@
 //! Authentication user related entities.
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
 use validator::Validate;
 
 use security::password::Password;
@@ -34,10 +37,20 @@ pub struct AuthUser {
     /// Role of the user.
     pub role: AuthUserRole,
 
-    /// Password of the user (hashed of course).
+    /// Password of the user (hashed of course). Only set when read from the store: it's neither
+    /// kept in the session nor in the cache.
     #[debug(skip)]
+    #[serde(skip)]
     pub password: Password,
 
+    /// Fingerprint of the password (see `AuthUser::fingerprint`).
+    #[debug(skip)]
+    pub password_fingerprint: String,
+
+    /// Version of the access tokens of the user: the tokens issued with a previous version are
+    /// revoked (the password has changed, the user has been deleted, etc).
+    pub token_version: i32,
+
     /// Email confirmed.
     pub email_confirmed: bool,
 }
@@ -77,7 +90,36 @@ impl AuthUser {
     pub fn hash(&self) -> &[u8] {
         // We're using the password as a unique hash so that if the user changes its password,
         // the session is invalidated.
-        self.password.as_bytes()
+        self.password_fingerprint.as_bytes()
+    }
+
+    /// Computes the fingerprint of a password, used in place of its hash wherever the user is
+    /// kept out of the database.
+    ///
+    /// # Arguments
+    /// * `password`: Hashed password.
+    ///
+    /// # Returns
+    /// The fingerprint.
+    pub fn fingerprint(password: &Password) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(password.as_bytes()))
+    }
+}
+
+/// Entity that is only valid until a given date (confirmation links, one-time tokens, etc).
+pub trait Expiring {
+    /// Gets the date of expiration.
+    ///
+    /// # Returns
+    /// The date of expiration.
+    fn expires_at(&self) -> DateTime<Utc>;
+
+    /// Checks if the entity is expired.
+    ///
+    /// # Returns
+    /// `true` if the entity is expired, `false` otherwise.
+    fn is_expired(&self) -> bool {
+        self.expires_at() < Utc::now()
     }
 }
 
@@ -94,13 +136,47 @@ pub struct AuthUserConfirmation {
     pub expires_at: DateTime<Utc>,
 }
 
-impl AuthUserConfirmation {
-    /// Checks if the confirmation is expired.
-    ///
-    /// # Returns
-    /// `true` if the confirmation is expired, `false` otherwise.
-    pub fn is_expired(&self) -> bool {
-        self.expires_at < Utc::now()
+impl Expiring for AuthUserConfirmation {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+/// One-time link used to login without password.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct AuthMagicLink {
+    /// Unique record identifier (used as token).
+    pub id: Uuid,
+
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Date of expiration of the token.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Expiring for AuthMagicLink {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+/// One-time link used to choose a new password.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct AuthPasswordReset {
+    /// Unique record identifier (used as token).
+    pub id: Uuid,
+
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Date of expiration of the token.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Expiring for AuthPasswordReset {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
     }
 }
 
@@ -176,10 +252,44 @@ mod tests {
             ..Default::default()
         };
 
-        let bytes = auth_user.password.as_bytes();
+        let bytes = auth_user.password_fingerprint.as_bytes();
 
         assert_eq!(auth_user.hash(), bytes);
 
+        // The password isn't serialized (sessions and cache), only its fingerprint
+        let password = random_password().hashed()?;
+        let auth_user = AuthUser {
+            password_fingerprint: AuthUser::fingerprint(&password),
+            password: password.clone(),
+            ..auth_user
+        };
+
+        let value = serde_json::to_value(&auth_user)?;
+        assert!(value.get("password").is_none());
+        assert!(!value.to_string().contains(password.as_str()));
+
+        let restored: AuthUser = serde_json::from_value(value)?;
+        assert_eq!(restored.hash(), auth_user.hash());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expiring() -> Result<(), Box<dyn std::error::Error>> {
+        let confirmation = AuthUserConfirmation {
+            expires_at: Utc::now() - chrono::Duration::seconds(1),
+            ..Default::default()
+        };
+
+        assert!(confirmation.is_expired());
+
+        let link = AuthMagicLink {
+            expires_at: Utc::now() + chrono::Duration::minutes(15),
+            ..Default::default()
+        };
+
+        assert!(!link.is_expired());
+
         Ok(())
     }
 }
@@ -1,7 +1,8 @@
 //! This file contains all possible errors handled in this crate. If also
 //! provides the conversions from other error types.
 
-use axum::http::StatusCode;
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderValue, StatusCode};
 use thiserror::Error;
 
 use common_core::ApiError;
@@ -12,6 +13,15 @@ pub type ApiResult<T> = Result<T, Error>;
 /// Enumerates the possible errors used in this crate.
 #[derive(Debug, Error)]
 pub enum Error {
+    /// The account is temporarily locked after too many failed authentications (contains the
+    /// number of seconds before the account is unlocked).
+    #[error("Account is temporarily locked")]
+    AccountLocked(u64),
+
+    /// The API key is not found.
+    #[error("API key not found")]
+    ApiKeyNotFound,
+
     /// The user has not confirmed his email.
     #[error("User's confirmation link is expired")]
     ConfirmationLinkExpired,
@@ -32,10 +42,110 @@ pub enum Error {
     #[error(transparent)]
     Env(#[from] std::env::VarError),
 
+    /// The user is not allowed to perform the action.
+    #[error("Forbidden")]
+    Forbidden,
+
+    /// Generic hashing error.
+    #[error(transparent)]
+    Hashing(utils::error::Error),
+
+    /// The grant sent to the token endpoint is missing some fields.
+    #[error("Invalid grant")]
+    InvalidGrant,
+
+    /// The cursor of a page is not valid.
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
+    /// The second factor provided is not valid.
+    #[error("Invalid second factor")]
+    InvalidSecondFactor,
+
+    /// Generic JSON error.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// Generic JWT error.
+    #[error(transparent)]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+
+    /// The link used to login without password is expired.
+    #[error("Login link is expired")]
+    MagicLinkExpired,
+
+    /// The link used to login without password is not found (or already used).
+    #[error("Login link is not found")]
+    MagicLinkNotFound,
+
     /// Generic mailer variable error.
     #[error(transparent)]
     Mailer(#[from] mailer::Error),
 
+    /// The email returned by the OAuth provider is missing or not verified.
+    #[error("OAuth email is not verified")]
+    OAuthEmailNotVerified,
+
+    /// The ID token returned by the OAuth provider was not issued for the authorization request.
+    #[error("OAuth nonce mismatch")]
+    OAuthNonceMismatch,
+
+    /// Error returned while communicating with the OAuth provider.
+    #[error("OAuth provider error: {0}")]
+    OAuthProvider(String),
+
+    /// The OAuth provider is not configured.
+    #[error("OAuth provider not found")]
+    OAuthProviderNotFound,
+
+    /// The OAuth callback doesn't match any pending authorization request.
+    #[error("OAuth state mismatch")]
+    OAuthStateMismatch,
+
+    /// The link used to choose a new password is expired.
+    #[error("Password reset link is expired")]
+    PasswordResetExpired,
+
+    /// The link used to choose a new password is not found (or already used).
+    #[error("Password reset link is not found")]
+    PasswordResetNotFound,
+
+    /// The body of the request is too large.
+    #[error("Payload too large")]
+    PayloadTooLarge,
+
+    /// Generic Redis error.
+    #[error("{0}")]
+    Redis(String),
+
+    /// The refresh token is expired.
+    #[error("Refresh token is expired")]
+    RefreshTokenExpired,
+
+    /// The refresh token is not found.
+    #[error("Refresh token is not found")]
+    RefreshTokenNotFound,
+
+    /// The refresh token has already been used or revoked (all tokens of its family are revoked).
+    #[error("Refresh token has already been used")]
+    RefreshTokenReused,
+
+    /// The role of the user requires a second factor but the user has not enrolled yet.
+    #[error("Second factor enrollment required")]
+    SecondFactorEnrollmentRequired,
+
+    /// No login is waiting for a second factor.
+    #[error("No pending login")]
+    SecondFactorNotPending,
+
+    /// The user must provide a second factor.
+    #[error("Second factor required")]
+    SecondFactorRequired,
+
+    /// Security error.
+    #[error(transparent)]
+    Security(#[from] security::Error),
+
     /// The user session is not found.
     #[error(transparent)]
     Session(#[from] tower_sessions::session::Error),
@@ -48,6 +158,27 @@ pub enum Error {
     #[error(transparent)]
     SQLx(#[from] sqlx::Error),
 
+    /// Generic TOTP error.
+    #[error("{0}")]
+    Totp(String),
+
+    /// The client sent too many requests (contains the number of seconds before new requests are
+    /// accepted).
+    #[error("Too many requests")]
+    TooManyRequests(u64),
+
+    /// The user targeted by a request (not the caller) is not found in database.
+    #[error("User not found")]
+    TargetUserNotFound,
+
+    /// The TOTP is already enabled for the user.
+    #[error("TOTP already enabled")]
+    TotpAlreadyEnabled,
+
+    /// The user has not started the TOTP enrollment.
+    #[error("TOTP not enrolled")]
+    TotpNotEnrolled,
+
     /// Cannot authorize a user.
     #[error("Unauthorized")]
     Unauthorized,
@@ -56,6 +187,10 @@ pub enum Error {
     #[error("User not found")]
     UserNotFound,
 
+    /// The session of the user is not found in database.
+    #[error("User session not found")]
+    UserSessionNotFound,
+
     /// Validation error.
     #[error("Unprocessable entity")]
     Validation(#[from] validator::ValidationErrors),
@@ -65,16 +200,58 @@ impl axum::response::IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
         let message = self.to_string();
 
+        let retry_after = match self {
+            Self::AccountLocked(seconds) | Self::TooManyRequests(seconds) => Some(seconds),
+            _ => None,
+        };
+
         let (rc, code) = match self {
+            Self::AccountLocked(_) => (StatusCode::LOCKED, "ACCOUNT_LOCKED"),
+            Self::ApiKeyNotFound => (StatusCode::NOT_FOUND, "API_KEY_NOT_FOUND"),
             Self::ConfirmationLinkExpired => (StatusCode::FORBIDDEN, "CONFIRMATION_LINK_EXPIRED"),
             Self::ConfirmationNotFound => (StatusCode::NOT_FOUND, "CONFIRMATION_NOT_FOUND"),
             Self::EmailNotConfirmed => (StatusCode::UNAUTHORIZED, "EMAIL_NOT_CONFIRMED"),
+            Self::Forbidden => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+            Self::InvalidCursor => (StatusCode::BAD_REQUEST, "BAD_REQUEST"),
+            Self::InvalidGrant => (StatusCode::BAD_REQUEST, "INVALID_GRANT"),
+            Self::InvalidSecondFactor => (StatusCode::UNAUTHORIZED, "INVALID_SECOND_FACTOR"),
+            Self::MagicLinkExpired => (StatusCode::FORBIDDEN, "MAGIC_LINK_EXPIRED"),
+            Self::MagicLinkNotFound => (StatusCode::UNAUTHORIZED, "MAGIC_LINK_NOT_FOUND"),
+            Self::OAuthEmailNotVerified => (StatusCode::FORBIDDEN, "OAUTH_EMAIL_NOT_VERIFIED"),
+            Self::OAuthNonceMismatch => (StatusCode::UNAUTHORIZED, "OAUTH_NONCE_MISMATCH"),
+            Self::OAuthProvider(_) => (StatusCode::BAD_GATEWAY, "OAUTH_PROVIDER_ERROR"),
+            Self::OAuthProviderNotFound => (StatusCode::NOT_FOUND, "OAUTH_PROVIDER_NOT_FOUND"),
+            Self::OAuthStateMismatch => (StatusCode::UNAUTHORIZED, "OAUTH_STATE_MISMATCH"),
+            Self::PasswordResetExpired => (StatusCode::FORBIDDEN, "PASSWORD_RESET_EXPIRED"),
+            Self::PasswordResetNotFound => (StatusCode::UNAUTHORIZED, "PASSWORD_RESET_NOT_FOUND"),
+            Self::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE"),
+            Self::RefreshTokenExpired => (StatusCode::UNAUTHORIZED, "REFRESH_TOKEN_EXPIRED"),
+            Self::RefreshTokenNotFound => (StatusCode::UNAUTHORIZED, "REFRESH_TOKEN_NOT_FOUND"),
+            Self::RefreshTokenReused => (StatusCode::UNAUTHORIZED, "REFRESH_TOKEN_REUSED"),
+            Self::SecondFactorEnrollmentRequired => {
+                (StatusCode::FORBIDDEN, "SECOND_FACTOR_ENROLLMENT_REQUIRED")
+            }
+            Self::SecondFactorNotPending => (StatusCode::UNAUTHORIZED, "SECOND_FACTOR_NOT_PENDING"),
+            Self::SecondFactorRequired => (StatusCode::UNAUTHORIZED, "SECOND_FACTOR_REQUIRED"),
+            Self::TargetUserNotFound => (StatusCode::NOT_FOUND, "USER_NOT_FOUND"),
+            Self::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, "TOO_MANY_REQUESTS"),
+            Self::TotpAlreadyEnabled => (StatusCode::CONFLICT, "TOTP_ALREADY_ENABLED"),
+            Self::TotpNotEnrolled => (StatusCode::NOT_FOUND, "TOTP_NOT_ENROLLED"),
             Self::Unauthorized => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
             Self::UserNotFound => (StatusCode::UNAUTHORIZED, "USER_NOT_FOUND"),
+            Self::UserSessionNotFound => (StatusCode::NOT_FOUND, "USER_SESSION_NOT_FOUND"),
             Self::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "UNPROCESSABLE_ENTITY"),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_SERVER_ERROR"),
         };
 
-        (rc, ApiError::new(code, message)).into_response()
+        let mut response = (rc, ApiError::new(code, message)).into_response();
+
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds));
+        }
+
+        response
     }
 }
@@ -1,6 +1,14 @@
 //! List of all entities used in this crate and eventually by other crates.
 
+pub(crate) mod api_key;
+pub(crate) mod audit;
 pub(crate) mod auth;
+pub(crate) mod auth_session;
 pub(crate) mod auth_user;
 pub(crate) mod error;
+pub(crate) mod oauth;
+pub(crate) mod permission;
 pub(crate) mod port;
+pub(crate) mod rate_limit;
+pub(crate) mod token;
+pub(crate) mod totp;
@@ -0,0 +1,314 @@
+//! OAuth2 / OpenID Connect related entities.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use configuration::OAuthProviderSettings;
+
+use crate::domain::auth_user::Expiring;
+use crate::prelude::*;
+
+/// Duration allowed to the user to login at the provider.
+const OAUTH_TIMEOUT_MINUTES: i64 = 10;
+
+/// Authorization request waiting for the provider to call back (stored in the session).
+#[derive(Clone, Deserialize, Serialize, derive_more::Debug)]
+pub struct OAuthPending {
+    /// Name of the provider.
+    pub provider: String,
+
+    /// Value used to bind the callback to this request (CSRF protection).
+    #[debug(skip)]
+    pub state: String,
+
+    /// Value that must be found in the ID token (replay protection).
+    #[debug(skip)]
+    pub nonce: String,
+
+    /// PKCE secret sent during the code exchange.
+    #[debug(skip)]
+    pub code_verifier: String,
+
+    /// Date after which the callback is rejected.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Expiring for OAuthPending {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+impl OAuthPending {
+    /// Key used to store the pending authorization in the session.
+    pub const KEY: &'static str = "auth_oauth_pending";
+
+    /// Creates a new authorization request with random values.
+    ///
+    /// # Arguments
+    /// * `provider`: Name of the provider.
+    ///
+    /// # Returns
+    /// A new `OAuthPending` instance.
+    pub fn generate(provider: &str) -> Self {
+        let random = || Uuid::new_v4().simple().to_string();
+
+        Self {
+            provider: provider.to_string(),
+            state: random(),
+            nonce: random(),
+            // 64 characters, in the range allowed by RFC 7636
+            code_verifier: format!("{}{}", random(), random()),
+            expires_at: Utc::now() + Duration::minutes(OAUTH_TIMEOUT_MINUTES),
+        }
+    }
+
+    /// Computes the PKCE challenge sent to the authorization endpoint (S256 method).
+    ///
+    /// # Returns
+    /// The challenge.
+    pub fn code_challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.code_verifier.as_bytes()))
+    }
+
+    /// Builds the URL of the provider where the user must be redirected.
+    ///
+    /// # Arguments
+    /// * `settings`: Settings of the provider.
+    ///
+    /// # Returns
+    /// A result containing the URL.
+    pub fn authorize_url(&self, settings: &OAuthProviderSettings) -> ApiResult<String> {
+        let scopes = settings.scopes.join(" ");
+        let challenge = self.code_challenge();
+
+        let url = Url::parse_with_params(
+            &settings.authorize_url,
+            &[
+                ("response_type", "code"),
+                ("client_id", &settings.client_id),
+                ("redirect_uri", &settings.redirect_url),
+                ("scope", &scopes),
+                ("state", &self.state),
+                ("nonce", &self.nonce),
+                ("code_challenge", &challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| Error::OAuthProvider(e.to_string()))?;
+
+        Ok(url.into())
+    }
+}
+
+/// Parameters sent by the provider to the callback endpoint.
+#[derive(Clone, Deserialize, Serialize, derive_more::Debug)]
+pub struct OAuthCallback {
+    /// Authorization code to be exchanged.
+    #[debug(skip)]
+    pub code: String,
+
+    /// Value provided in the authorization request.
+    pub state: String,
+}
+
+/// Tokens returned by the provider after the code exchange.
+#[derive(Clone, Default, Deserialize, Serialize, derive_more::Debug)]
+pub struct OAuthTokens {
+    /// Token used to call the APIs of the provider.
+    #[debug(skip)]
+    pub access_token: String,
+
+    /// OpenID Connect identity token (not provided by pure OAuth2 providers).
+    #[debug(skip)]
+    pub id_token: Option<String>,
+}
+
+impl OAuthTokens {
+    /// Checks that the ID token (if any) has been issued for the authorization request.
+    ///
+    /// The token is received directly from the token endpoint over TLS so its signature is not
+    /// checked (OpenID Connect Core, section 3.1.3.7).
+    ///
+    /// # Arguments
+    /// * `nonce`: Value provided in the authorization request.
+    ///
+    /// # Returns
+    /// A result indicating whether the nonce matches.
+    pub fn check_nonce(&self, nonce: &str) -> ApiResult<()> {
+        let Some(id_token) = &self.id_token else {
+            return Ok(());
+        };
+
+        #[derive(Deserialize)]
+        struct Claims {
+            nonce: Option<String>,
+        }
+
+        let claims: Claims = id_token
+            .split('.')
+            .nth(1)
+            .and_then(|payload| URL_SAFE_NO_PAD.decode(payload).ok())
+            .and_then(|payload| serde_json::from_slice(&payload).ok())
+            .ok_or(Error::OAuthProvider("Invalid ID token".to_string()))?;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(Error::OAuthNonceMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+/// Information about the user returned by the provider.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct OAuthUserInfo {
+    /// Identifier of the user at the provider.
+    pub sub: String,
+
+    /// Email of the user.
+    pub email: Option<String>,
+
+    /// Whether the provider has verified the email.
+    pub email_verified: Option<bool>,
+
+    /// First name of the user.
+    pub given_name: Option<String>,
+
+    /// Last name of the user.
+    pub family_name: Option<String>,
+}
+
+impl OAuthUserInfo {
+    /// Gets the email of the user if it has been verified by the provider.
+    ///
+    /// # Returns
+    /// A result containing the email.
+    pub fn verified_email(&self) -> ApiResult<&str> {
+        match (&self.email, self.email_verified) {
+            (Some(email), Some(true)) => Ok(email),
+            _ => Err(Error::OAuthEmailNotVerified),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> OAuthProviderSettings {
+        OAuthProviderSettings {
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            authorize_url: "https://provider.com/authorize".to_string(),
+            token_url: "https://provider.com/token".to_string(),
+            userinfo_url: "https://provider.com/userinfo".to_string(),
+            redirect_url: "http://localhost:8080/oauth/callback".to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+        }
+    }
+
+    fn id_token(claims: &str) -> String {
+        format!("header.{}.signature", URL_SAFE_NO_PAD.encode(claims))
+    }
+
+    #[tokio::test]
+    async fn test_oauth_code_challenge() -> Result<(), Box<dyn std::error::Error>> {
+        // Example of RFC 7636 (appendix B)
+        let pending = OAuthPending {
+            code_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+            ..OAuthPending::generate("provider")
+        };
+
+        assert_eq!(
+            pending.code_challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_authorize_url() -> Result<(), Box<dyn std::error::Error>> {
+        let pending = OAuthPending::generate("provider");
+
+        let url = Url::parse(&pending.authorize_url(&settings())?)?;
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        assert!(url.as_str().starts_with("https://provider.com/authorize?"));
+        assert_eq!(param("client_id").as_deref(), Some("client"));
+        assert_eq!(param("scope").as_deref(), Some("openid email"));
+        assert_eq!(param("state"), Some(pending.state.clone()));
+        assert_eq!(param("nonce"), Some(pending.nonce.clone()));
+        assert_eq!(param("code_challenge"), Some(pending.code_challenge()));
+        assert_eq!(param("code_challenge_method").as_deref(), Some("S256"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_check_nonce() -> Result<(), Box<dyn std::error::Error>> {
+        let tokens = OAuthTokens::default();
+        assert!(tokens.check_nonce("nonce").is_ok());
+
+        let tokens = OAuthTokens {
+            id_token: Some(id_token(r#"{"sub":"1","nonce":"nonce"}"#)),
+            ..Default::default()
+        };
+        assert!(tokens.check_nonce("nonce").is_ok());
+        assert!(matches!(
+            tokens.check_nonce("other"),
+            Err(Error::OAuthNonceMismatch)
+        ));
+
+        let tokens = OAuthTokens {
+            id_token: Some(id_token(r#"{"sub":"1"}"#)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            tokens.check_nonce("nonce"),
+            Err(Error::OAuthNonceMismatch)
+        ));
+
+        let tokens = OAuthTokens {
+            id_token: Some("invalid".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            tokens.check_nonce("nonce"),
+            Err(Error::OAuthProvider(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_verified_email() -> Result<(), Box<dyn std::error::Error>> {
+        let mut info = OAuthUserInfo {
+            sub: "1".to_string(),
+            email: Some("john@doe.com".to_string()),
+            email_verified: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(info.verified_email()?, "john@doe.com");
+
+        info.email_verified = None;
+        assert!(matches!(
+            info.verified_email(),
+            Err(Error::OAuthEmailNotVerified)
+        ));
+
+        Ok(())
+    }
+}
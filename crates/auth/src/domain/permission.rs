@@ -0,0 +1,127 @@
+//! Permissions granted to the users depending on their role.
+
+use crate::domain::auth_user::AuthUserRole;
+
+/// Permission that can be required by an endpoint (see the `Authorized` extractor).
+pub trait Permission: Send + Sync {
+    /// Name of the permission (`<resource>:<action>`).
+    const NAME: &'static str;
+}
+
+/// Declares the permissions as types, so that they can be used as extractors parameters.
+macro_rules! permissions {
+    ($($(#[$doc: meta])* $name: ident => $value: literal,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default)]
+            pub struct $name;
+
+            impl Permission for $name {
+                const NAME: &'static str = $value;
+            }
+        )*
+    };
+}
+
+permissions! {
+    /// Read the audit trail.
+    AuditRead => "audit:read",
+
+    /// Read the statistics of the cache.
+    CacheRead => "cache:read",
+
+    /// Read the security policies of the roles.
+    RolesRead => "roles:read",
+
+    /// Update the security policies of the roles.
+    RolesUpdate => "roles:update",
+
+    /// Revoke the sessions of any user.
+    SessionsDelete => "sessions:delete",
+
+    /// Create a user.
+    UsersCreate => "users:create",
+
+    /// Delete any user.
+    UsersDelete => "users:delete",
+
+    /// Read any user.
+    UsersRead => "users:read",
+
+    /// Update any user.
+    UsersUpdate => "users:update",
+}
+
+/// Checks if a granted permission (or scope) covers the one required. `<resource>:*` grants all
+/// actions on a resource and `*` grants everything.
+///
+/// # Arguments
+/// * `granted`: Permission granted.
+/// * `required`: Permission required.
+///
+/// # Returns
+/// `true` if the permission is covered.
+pub(crate) fn covers(granted: &str, required: &str) -> bool {
+    if granted == "*" || granted == required {
+        return true;
+    }
+
+    match (granted.split_once(':'), required.split_once(':')) {
+        (Some((resource, "*")), Some((required_resource, _))) => resource == required_resource,
+        _ => false,
+    }
+}
+
+impl AuthUserRole {
+    /// Gets the permissions granted to the users of the role.
+    ///
+    /// # Returns
+    /// The list of permissions.
+    pub fn permissions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Admin => &["*"],
+            Self::Normal | Self::Guest => &[],
+        }
+    }
+
+    /// Checks if the users of the role are granted a permission.
+    ///
+    /// # Arguments
+    /// * `permission`: Permission to check (e.g. `users:delete`).
+    ///
+    /// # Returns
+    /// `true` if the permission is granted.
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions()
+            .iter()
+            .any(|granted| covers(granted, permission))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_covers() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(covers("*", UsersDelete::NAME));
+        assert!(covers("users:*", UsersDelete::NAME));
+        assert!(covers("users:delete", UsersDelete::NAME));
+        assert!(!covers("users:read", UsersDelete::NAME));
+        assert!(!covers("roles:*", UsersDelete::NAME));
+        assert!(!covers("users", UsersDelete::NAME));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_role_permissions() -> Result<(), Box<dyn std::error::Error>> {
+        for permission in [UsersRead::NAME, UsersDelete::NAME, RolesUpdate::NAME] {
+            assert!(AuthUserRole::Admin.has_permission(permission));
+            assert!(!AuthUserRole::Normal.has_permission(permission));
+            assert!(!AuthUserRole::Guest.has_permission(permission));
+        }
+
+        Ok(())
+    }
+}
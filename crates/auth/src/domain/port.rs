@@ -3,7 +3,19 @@
 use chrono::Duration;
 use futures::future::BoxFuture;
 
-use crate::domain::auth_user::{AuthUser, AuthUserConfirmation};
+use common_web::pagination::{Page, PageRequest};
+use configuration::OAuthProviderSettings;
+use security::password::Password;
+
+use crate::domain::api_key::AuthApiKey;
+use crate::domain::audit::{AuditEntry, AuditFilters, AuditRecord};
+use crate::domain::auth_session::{AuthSession, AuthSessionMetadata};
+use crate::domain::auth_user::{
+    AuthMagicLink, AuthPasswordReset, AuthUser, AuthUserConfirmation, AuthUserRole,
+};
+use crate::domain::oauth::{OAuthTokens, OAuthUserInfo};
+use crate::domain::token::AuthRefreshToken;
+use crate::domain::totp::{AuthRecoveryCode, AuthRolePolicy, AuthTotp};
 use crate::prelude::*;
 
 /// Authentication store APIs.
@@ -18,6 +30,51 @@ pub trait AuthStore: Send + Sync {
     /// A result containing the user if found, or an error.
     fn find_user_by_email(&self, email: &str) -> BoxFuture<'static, ApiResult<AuthUser>>;
 
+    /// Find a user by the identity provided by an OAuth provider.
+    ///
+    /// # Arguments
+    /// * `provider`: Name of the provider.
+    /// * `subject`: Identifier of the user at the provider.
+    ///
+    /// # Returns
+    /// A result containing the user if found.
+    fn find_user_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthUser>>>;
+
+    /// Links an existing user to the identity provided by an OAuth provider.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `provider`: Name of the provider.
+    /// * `subject`: Identifier of the user at the provider.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn create_identity(
+        &self,
+        user_id: &Uuid,
+        provider: &str,
+        subject: &str,
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Creates a user (with an unusable password) from the information returned by an OAuth
+    /// provider and links it to the identity.
+    ///
+    /// # Arguments
+    /// * `provider`: Name of the provider.
+    /// * `info`: Information about the user returned by the provider.
+    ///
+    /// # Returns
+    /// A result containing the user created.
+    fn create_user_with_identity(
+        &self,
+        provider: &str,
+        info: &OAuthUserInfo,
+    ) -> BoxFuture<'static, ApiResult<AuthUser>>;
+
     /// Find a user by its ID.
     ///
     /// # Arguments
@@ -73,4 +130,466 @@ pub trait AuthStore: Send + Sync {
         user_id: &Uuid,
         confirmation_timeout_hours: &Duration,
     ) -> BoxFuture<'static, ApiResult<AuthUserConfirmation>>;
+
+    /// Creates a session entry for a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `metadata`: Information about the client opening the session.
+    ///
+    /// # Returns
+    /// A result containing the session created.
+    fn create_session(
+        &self,
+        user_id: &Uuid,
+        metadata: &AuthSessionMetadata,
+    ) -> BoxFuture<'static, ApiResult<AuthSession>>;
+
+    /// Marks a session as seen now.
+    ///
+    /// # Arguments
+    /// * `id`: Session's ID.
+    ///
+    /// # Returns
+    /// A result containing the session if it still exists (i.e. has not been revoked).
+    fn touch_session(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<Option<AuthSession>>>;
+
+    /// Gets all sessions of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// A result containing the list of sessions (most recently seen first).
+    fn get_sessions_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthSession>>>;
+
+    /// Deletes a session of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `id`: Session's ID.
+    ///
+    /// # Returns
+    /// An empty result or `Error::UserSessionNotFound` if the user has no such session.
+    fn delete_session(&self, user_id: &Uuid, id: &Uuid) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Deletes all sessions of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn delete_sessions_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Creates a link to login without password for a user. Any previous link of the user is
+    /// replaced.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `timeout`: Validity duration of the link.
+    ///
+    /// # Returns
+    /// A result containing the link created.
+    fn create_magic_link(
+        &self,
+        user_id: &Uuid,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthMagicLink>>;
+
+    /// Deletes a link to login without password and returns it, so that it can't be used twice.
+    ///
+    /// # Arguments
+    /// * `id`: Link's ID.
+    ///
+    /// # Returns
+    /// A result containing the link if it existed.
+    fn consume_magic_link(&self, id: &Uuid)
+        -> BoxFuture<'static, ApiResult<Option<AuthMagicLink>>>;
+
+    /// Creates a link to choose a new password for a user. Any previous link of the user is
+    /// replaced.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `timeout`: Validity duration of the link.
+    ///
+    /// # Returns
+    /// A result containing the link created.
+    fn create_password_reset(
+        &self,
+        user_id: &Uuid,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthPasswordReset>>;
+
+    /// Deletes a link to choose a new password and returns it, so that it can't be used twice.
+    ///
+    /// # Arguments
+    /// * `id`: Link's ID.
+    ///
+    /// # Returns
+    /// A result containing the link if it existed.
+    fn consume_password_reset(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthPasswordReset>>>;
+
+    /// Updates the password of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `password`: New password (already hashed).
+    ///
+    /// # Returns
+    /// An empty result.
+    fn set_password(
+        &self,
+        user_id: &Uuid,
+        password: &Password,
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Gets the TOTP configuration of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// A result containing the configuration if the user has started an enrollment.
+    fn get_totp_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthTotp>>>;
+
+    /// Creates or updates the TOTP configuration of a user.
+    ///
+    /// # Arguments
+    /// * `totp`: TOTP configuration.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn save_totp(&self, totp: &AuthTotp) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Replaces the recovery codes of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `codes`: Hashes of the new recovery codes.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn create_recovery_codes(
+        &self,
+        user_id: &Uuid,
+        codes: &[String],
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Gets the unused recovery codes of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// A result containing the list of recovery codes.
+    fn get_recovery_codes_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthRecoveryCode>>>;
+
+    /// Marks a recovery code as used.
+    ///
+    /// # Arguments
+    /// * `id`: Recovery code's ID.
+    ///
+    /// # Returns
+    /// A result containing `false` if the code had already been used.
+    fn use_recovery_code(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<bool>>;
+
+    /// Gets the security policies of all roles.
+    ///
+    /// # Returns
+    /// A result containing the list of policies.
+    fn get_role_policies(&self) -> BoxFuture<'static, ApiResult<Vec<AuthRolePolicy>>>;
+
+    /// Gets the security policy of a role.
+    ///
+    /// # Arguments
+    /// * `role`: Role of the user.
+    ///
+    /// # Returns
+    /// A result containing the policy.
+    fn get_role_policy(&self, role: &AuthUserRole)
+        -> BoxFuture<'static, ApiResult<AuthRolePolicy>>;
+
+    /// Updates the security policy of a role.
+    ///
+    /// # Arguments
+    /// * `policy`: New policy.
+    ///
+    /// # Returns
+    /// A result containing the policy updated.
+    fn set_role_policy(
+        &self,
+        policy: &AuthRolePolicy,
+    ) -> BoxFuture<'static, ApiResult<AuthRolePolicy>>;
+
+    /// Creates a refresh token for a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `family_id`: ID of the family of the token.
+    /// * `token_hash`: Hash of the secret part of the token.
+    /// * `timeout`: Validity duration of the token.
+    ///
+    /// # Returns
+    /// A result containing the token created.
+    fn create_refresh_token(
+        &self,
+        user_id: &Uuid,
+        family_id: &Uuid,
+        token_hash: &str,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthRefreshToken>>;
+
+    /// Gets a refresh token by its ID.
+    ///
+    /// # Arguments
+    /// * `id`: Refresh token's ID.
+    ///
+    /// # Returns
+    /// A result containing the token if found.
+    fn get_refresh_token_by_id(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthRefreshToken>>>;
+
+    /// Marks a refresh token as used.
+    ///
+    /// # Arguments
+    /// * `id`: Refresh token's ID.
+    ///
+    /// # Returns
+    /// A result containing `false` if the token had already been used or revoked.
+    fn use_refresh_token(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<bool>>;
+
+    /// Revokes all refresh tokens of a family.
+    ///
+    /// # Arguments
+    /// * `family_id`: ID of the family.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn revoke_refresh_token_family(&self, family_id: &Uuid) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Revokes all refresh tokens of a user, along with the access tokens issued before.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn revoke_refresh_tokens_by_user_id(&self, user_id: &Uuid)
+        -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Creates an API key for a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `name`: Name of the key.
+    /// * `key_hash`: Hash of the secret part of the key.
+    /// * `scopes`: Scopes granted to the key.
+    ///
+    /// # Returns
+    /// A result containing the key created.
+    fn create_api_key(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        key_hash: &str,
+        scopes: &[String],
+    ) -> BoxFuture<'static, ApiResult<AuthApiKey>>;
+
+    /// Gets an API key by its ID.
+    ///
+    /// # Arguments
+    /// * `id`: API key's ID.
+    ///
+    /// # Returns
+    /// A result containing the key if found.
+    fn get_api_key_by_id(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<Option<AuthApiKey>>>;
+
+    /// Gets all API keys of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// A result containing the list of keys (most recent first).
+    fn get_api_keys_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthApiKey>>>;
+
+    /// Updates the name and/or the scopes of an API key of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `id`: API key's ID.
+    /// * `name`: New name (unchanged if `None`).
+    /// * `scopes`: New scopes (unchanged if `None`).
+    ///
+    /// # Returns
+    /// A result containing the key updated or `Error::ApiKeyNotFound` if the user has no such key.
+    fn update_api_key(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+        name: Option<String>,
+        scopes: Option<Vec<String>>,
+    ) -> BoxFuture<'static, ApiResult<AuthApiKey>>;
+
+    /// Deletes an API key of a user.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    /// * `id`: API key's ID.
+    ///
+    /// # Returns
+    /// An empty result or `Error::ApiKeyNotFound` if the user has no such key.
+    fn delete_api_key(&self, user_id: &Uuid, id: &Uuid) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Marks an API key as used now.
+    ///
+    /// # Arguments
+    /// * `id`: API key's ID.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn touch_api_key(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<()>>;
+}
+
+/// OAuth2 / OpenID Connect provider APIs.
+#[cfg_attr(any(test, feature = "mock"), mockall::automock)]
+pub trait OAuthClient: Send + Sync {
+    /// Exchanges an authorization code for tokens.
+    ///
+    /// # Arguments
+    /// * `settings`: Settings of the provider.
+    /// * `code`: Authorization code received in the callback.
+    /// * `code_verifier`: PKCE secret of the authorization request.
+    ///
+    /// # Returns
+    /// A result containing the tokens.
+    fn exchange_code(
+        &self,
+        settings: &OAuthProviderSettings,
+        code: &str,
+        code_verifier: &str,
+    ) -> BoxFuture<'static, ApiResult<OAuthTokens>>;
+
+    /// Gets the information about the user owning an access token.
+    ///
+    /// # Arguments
+    /// * `settings`: Settings of the provider.
+    /// * `access_token`: Access token returned by the code exchange.
+    ///
+    /// # Returns
+    /// A result containing the information about the user.
+    fn get_user_info(
+        &self,
+        settings: &OAuthProviderSettings,
+        access_token: &str,
+    ) -> BoxFuture<'static, ApiResult<OAuthUserInfo>>;
+}
+
+/// Storage of the counters used to limit the number of requests.
+#[cfg_attr(any(test, feature = "mock"), mockall::automock)]
+pub trait RateLimitStore: Send + Sync {
+    /// Increments a counter. The counter is created if needed and is reset once the window is
+    /// elapsed.
+    ///
+    /// # Arguments
+    /// * `key`: Key of the counter.
+    /// * `window`: Duration of the window.
+    ///
+    /// # Returns
+    /// A result containing the value of the counter and the number of seconds before it's reset.
+    fn increment(&self, key: &str, window: &Duration) -> BoxFuture<'static, ApiResult<(u64, u64)>>;
+
+    /// Gets the number of seconds before a key expires.
+    ///
+    /// # Arguments
+    /// * `key`: Key to check.
+    ///
+    /// # Returns
+    /// A result containing the number of seconds if the key exists.
+    fn expires_in(&self, key: &str) -> BoxFuture<'static, ApiResult<Option<u64>>>;
+
+    /// Creates a key that expires after a given duration.
+    ///
+    /// # Arguments
+    /// * `key`: Key to create.
+    /// * `duration`: Duration before the key expires.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn set(&self, key: &str, duration: &Duration) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Deletes a key.
+    ///
+    /// # Arguments
+    /// * `key`: Key to delete.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn delete(&self, key: &str) -> BoxFuture<'static, ApiResult<()>>;
+}
+
+/// Audit trail store APIs.
+#[cfg_attr(any(test, feature = "mock"), mockall::automock)]
+pub trait AuditStore: Send + Sync {
+    /// Records an entry in the audit trail, along with the information about the request.
+    ///
+    /// # Arguments
+    /// * `record`: Entry to record.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn record(&self, record: AuditRecord) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Get a page of the entries that match some filters (most recent first).
+    ///
+    /// # Arguments
+    /// * `filters`: Filters to apply.
+    /// * `page`: Page requested.
+    ///
+    /// # Returns
+    /// A result containing the page of entries.
+    fn get_page_by_filters(
+        &self,
+        filters: AuditFilters,
+        page: PageRequest,
+    ) -> BoxFuture<'static, ApiResult<Page<AuditEntry>>>;
+
+    /// Gets all entries concerning a user (oldest first). The actions performed by the user on
+    /// other users are excluded, as their changes are the personal data of these users.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// A result containing the list of entries.
+    fn get_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<Vec<AuditEntry>>>;
+
+    /// Removes the changes recorded in the entries concerning a user (they may contain personal
+    /// data). The entries themselves are kept.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// An empty result.
+    fn anonymize_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<()>>;
 }
@@ -0,0 +1,307 @@
+//! Brute-force protection of the authentication endpoints.
+
+use chrono::Duration;
+use tracing::{event, Level};
+
+use configuration::RateLimitSettings;
+
+use crate::domain::port::RateLimitStore;
+use crate::prelude::*;
+
+/// Fields of the request body used to identify the account targeted by a request.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RateLimitTarget {
+    /// Email of the account.
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// Limits the number of requests by client and by account, and locks the accounts after too many
+/// failed authentications.
+pub(crate) struct RateLimiter<S>
+where
+    S: RateLimitStore,
+{
+    /// Rate limiting settings.
+    settings: RateLimitSettings,
+
+    /// Store of the counters.
+    store: S,
+}
+
+impl<S> RateLimiter<S>
+where
+    S: RateLimitStore,
+{
+    /// Creates a new `RateLimiter` instance.
+    ///
+    /// # Arguments
+    /// * `settings`: Rate limiting settings.
+    /// * `store`: Store of the counters.
+    ///
+    /// # Returns
+    /// A `RateLimiter` instance.
+    pub fn new(settings: RateLimitSettings, store: S) -> Self {
+        Self { settings, store }
+    }
+
+    /// Counts a request and checks that it can be processed.
+    ///
+    /// # Arguments
+    /// * `route`: Path of the endpoint called.
+    /// * `ip_address`: IP address of the client.
+    /// * `email`: Email of the account targeted by the request (if any).
+    ///
+    /// # Returns
+    /// An empty result, or an error if the account is locked or a limit is reached.
+    pub async fn check(&self, route: &str, ip_address: &str, email: Option<&str>) -> ApiResult<()> {
+        if let Some(email) = email {
+            if let Some(seconds) = self.store.expires_in(&lock_key(email)).await? {
+                return Err(Error::AccountLocked(seconds));
+            }
+        }
+
+        let window = Duration::seconds(self.settings.window_seconds.into());
+
+        let (count, seconds) = self
+            .store
+            .increment(&format!("ip:{route}:{ip_address}"), &window)
+            .await?;
+
+        if count > self.settings.max_requests_per_ip.into() {
+            event!(
+                Level::WARN,
+                "Too many requests on {route} from {ip_address}"
+            );
+            return Err(Error::TooManyRequests(seconds));
+        }
+
+        if let Some(email) = email {
+            let (count, seconds) = self
+                .store
+                .increment(&format!("email:{route}:{email}"), &window)
+                .await?;
+
+            if count > self.settings.max_requests_per_email.into() {
+                event!(Level::WARN, "Too many requests on {route} for {email}");
+                return Err(Error::TooManyRequests(seconds));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records a failed authentication and locks the account if there are too many of them.
+    ///
+    /// # Arguments
+    /// * `email`: Email of the account.
+    ///
+    /// # Returns
+    /// An empty result.
+    pub async fn record_failure(&self, email: &str) -> ApiResult<()> {
+        let window = Duration::minutes(self.settings.failures_window_minutes.into());
+
+        let (failures, _) = self.store.increment(&failures_key(email), &window).await?;
+
+        if failures >= self.settings.max_failures.into() {
+            let duration = Duration::minutes(self.settings.lock_minutes.into());
+
+            self.store.set(&lock_key(email), &duration).await?;
+            self.store.delete(&failures_key(email)).await?;
+
+            event!(
+                Level::WARN,
+                "Account {email} locked after {failures} failures"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Records a successful authentication (the failures are forgotten).
+    ///
+    /// # Arguments
+    /// * `email`: Email of the account.
+    ///
+    /// # Returns
+    /// An empty result.
+    pub async fn record_success(&self, email: &str) -> ApiResult<()> {
+        self.store.delete(&failures_key(email)).await
+    }
+}
+
+/// Builds the key of the counter of failed authentications of an account.
+///
+/// # Arguments
+/// * `email`: Email of the account.
+///
+/// # Returns
+/// The key.
+fn failures_key(email: &str) -> String {
+    format!("failures:{email}")
+}
+
+/// Builds the key used to lock an account.
+///
+/// # Arguments
+/// * `email`: Email of the account.
+///
+/// # Returns
+/// The key.
+fn lock_key(email: &str) -> String {
+    format!("lock:{email}")
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::*;
+
+    use test_utils::rand::*;
+
+    use super::*;
+
+    use crate::domain::port::MockRateLimitStore;
+
+    fn settings() -> RateLimitSettings {
+        RateLimitSettings {
+            enabled: true,
+            window_seconds: 60,
+            max_requests_per_ip: 10,
+            max_requests_per_email: 3,
+            max_failures: 5,
+            failures_window_minutes: 15,
+            lock_minutes: 15,
+        }
+    }
+
+    fn mock_increment(store: &mut MockRateLimitStore, prefix: &'static str, count: u64) {
+        store
+            .expect_increment()
+            .withf(move |key, _| key.starts_with(prefix))
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok((count, 42)) }));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let email = random_email();
+
+        let mut store = MockRateLimitStore::new();
+
+        store
+            .expect_expires_in()
+            .with(eq(lock_key(&email)))
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(None) }));
+
+        mock_increment(&mut store, "ip:", 10);
+        mock_increment(&mut store, "email:", 3);
+
+        let limiter = RateLimiter::new(settings(), store);
+
+        limiter.check("/login", "127.0.0.1", Some(&email)).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_ip() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockRateLimitStore::new();
+
+        store.expect_expires_in().never();
+
+        mock_increment(&mut store, "ip:", 11);
+
+        let limiter = RateLimiter::new(settings(), store);
+
+        let res = limiter.check("/confirm", "127.0.0.1", None).await;
+        assert!(matches!(res, Err(Error::TooManyRequests(42))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_email() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockRateLimitStore::new();
+
+        store
+            .expect_expires_in()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(None) }));
+
+        mock_increment(&mut store, "ip:", 1);
+        mock_increment(&mut store, "email:", 4);
+
+        let limiter = RateLimiter::new(settings(), store);
+
+        let res = limiter
+            .check("/login", "127.0.0.1", Some(&random_email()))
+            .await;
+        assert!(matches!(res, Err(Error::TooManyRequests(42))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_locked() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockRateLimitStore::new();
+
+        store
+            .expect_expires_in()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(Some(600)) }));
+
+        store.expect_increment().never();
+
+        let limiter = RateLimiter::new(settings(), store);
+
+        let res = limiter
+            .check("/login", "127.0.0.1", Some(&random_email()))
+            .await;
+        assert!(matches!(res, Err(Error::AccountLocked(600))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_failures() -> Result<(), Box<dyn std::error::Error>> {
+        let email = random_email();
+
+        // Not enough failures to lock the account
+        let mut store = MockRateLimitStore::new();
+
+        mock_increment(&mut store, "failures:", 4);
+
+        store.expect_set().never();
+
+        RateLimiter::new(settings(), store)
+            .record_failure(&email)
+            .await?;
+
+        // The account is locked
+        let mut store = MockRateLimitStore::new();
+
+        mock_increment(&mut store, "failures:", 5);
+
+        store
+            .expect_set()
+            .withf({
+                let email = email.clone();
+                move |key, duration| *key == lock_key(&email) && *duration == Duration::minutes(15)
+            })
+            .times(1)
+            .returning(|_, _| Box::pin(async move { Ok(()) }));
+
+        store
+            .expect_delete()
+            .with(eq(failures_key(&email)))
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        RateLimiter::new(settings(), store)
+            .record_failure(&email)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,510 @@
+//! Bearer tokens (JWT access tokens and opaque refresh tokens) related entities.
+
+use axum::http::header::CACHE_CONTROL;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use validator::Validate;
+
+use configuration::{Config, JwtSettings};
+use security::password::Password;
+
+use crate::domain::auth_user::{AuthUser, Expiring};
+use crate::prelude::*;
+
+/// Type of the tokens returned by the token endpoint.
+const TOKEN_TYPE: &str = "Bearer";
+
+/// Claims of the access tokens.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AccessClaims {
+    /// User's ID.
+    pub sub: Uuid,
+
+    /// Version of the tokens of the user (see `AuthUser::token_version`).
+    pub ver: i32,
+
+    /// Issuer of the token.
+    pub iss: String,
+
+    /// Audience of the token.
+    pub aud: String,
+
+    /// Date of creation of the token (Unix timestamp).
+    pub iat: i64,
+
+    /// Date of expiration of the token (Unix timestamp).
+    pub exp: i64,
+}
+
+/// Signs and checks the access tokens.
+#[derive(Clone, derive_more::Debug)]
+pub struct JwtCodec {
+    /// Header of the tokens (contains the algorithm used).
+    header: Header,
+
+    /// Key used to sign the tokens.
+    #[debug(skip)]
+    encoding_key: EncodingKey,
+
+    /// Key used to check the signature of the tokens.
+    #[debug(skip)]
+    decoding_key: DecodingKey,
+
+    /// Rules applied to the claims when a token is checked.
+    validation: Validation,
+
+    /// Issuer of the tokens.
+    issuer: String,
+
+    /// Audience of the tokens.
+    audience: String,
+
+    /// Lifetime of the tokens.
+    lifetime: Duration,
+}
+
+impl JwtCodec {
+    /// Creates a new codec.
+    ///
+    /// # Arguments
+    /// * `settings`: Bearer tokens settings.
+    /// * `private_key`: Secret (HMAC algorithms) or PEM encoded private key.
+    /// * `public_key`: Secret (HMAC algorithms) or PEM encoded public key.
+    ///
+    /// # Returns
+    /// A result containing the codec.
+    pub fn new(settings: &JwtSettings, private_key: &[u8], public_key: &[u8]) -> ApiResult<Self> {
+        let algorithm = Algorithm::from_str(&settings.algorithm)?;
+
+        let (encoding_key, decoding_key) = match algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => (
+                EncodingKey::from_secret(private_key),
+                DecodingKey::from_secret(public_key),
+            ),
+
+            Algorithm::ES256 | Algorithm::ES384 => (
+                EncodingKey::from_ec_pem(private_key)?,
+                DecodingKey::from_ec_pem(public_key)?,
+            ),
+
+            Algorithm::EdDSA => (
+                EncodingKey::from_ed_pem(private_key)?,
+                DecodingKey::from_ed_pem(public_key)?,
+            ),
+
+            _ => (
+                EncodingKey::from_rsa_pem(private_key)?,
+                DecodingKey::from_rsa_pem(public_key)?,
+            ),
+        };
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_issuer(&[&settings.issuer]);
+        validation.set_audience(&[&settings.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        Ok(Self {
+            header: Header::new(algorithm),
+            encoding_key,
+            decoding_key,
+            validation,
+            issuer: settings.issuer.clone(),
+            audience: settings.audience.clone(),
+            lifetime: Duration::minutes(settings.access_token_lifetime_minutes.into()),
+        })
+    }
+
+    /// Creates a new codec using the keys provided by the environment: `JWT_SECRET` for the HMAC
+    /// algorithms, `JWT_PRIVATE_KEY` and `JWT_PUBLIC_KEY` for the others.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    ///
+    /// # Returns
+    /// A result containing the codec.
+    pub fn from_config(config: &Config) -> ApiResult<Self> {
+        let settings = &config.auth.jwt;
+
+        if settings.algorithm.starts_with("HS") {
+            let secret = std::env::var("JWT_SECRET")?;
+
+            return Self::new(settings, secret.as_bytes(), secret.as_bytes());
+        }
+
+        let private_key = std::env::var("JWT_PRIVATE_KEY")?;
+        let public_key = std::env::var("JWT_PUBLIC_KEY")?;
+
+        Self::new(settings, private_key.as_bytes(), public_key.as_bytes())
+    }
+
+    /// Gets the lifetime of the tokens.
+    ///
+    /// # Returns
+    /// The lifetime.
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// Creates a signed access token for a user.
+    ///
+    /// # Arguments
+    /// * `user`: User.
+    ///
+    /// # Returns
+    /// A result containing the token.
+    pub fn encode(&self, user: &AuthUser) -> ApiResult<String> {
+        let now = Utc::now();
+
+        let claims = AccessClaims {
+            sub: user.id,
+            ver: user.token_version,
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
+            iat: now.timestamp(),
+            exp: (now + self.lifetime).timestamp(),
+        };
+
+        Ok(jsonwebtoken::encode(
+            &self.header,
+            &claims,
+            &self.encoding_key,
+        )?)
+    }
+
+    /// Checks the signature and the claims of an access token.
+    ///
+    /// # Arguments
+    /// * `token`: Token provided by the client.
+    ///
+    /// # Returns
+    /// A result containing the claims, or `Error::Unauthorized` if the token is not valid.
+    pub fn decode(&self, token: &str) -> ApiResult<AccessClaims> {
+        jsonwebtoken::decode(token, &self.decoding_key, &self.validation)
+            .map(|data| data.claims)
+            .map_err(|_| Error::Unauthorized)
+    }
+}
+
+/// Refresh token stored for a user. Each use of a refresh token issues a new one in the same
+/// family, so that the reuse of a token (i.e. a stolen token) can be detected.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct AuthRefreshToken {
+    /// Unique record identifier.
+    pub id: Uuid,
+
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Identifier shared by all tokens issued from the same login.
+    pub family_id: Uuid,
+
+    /// Hash of the secret part of the token.
+    #[debug(skip)]
+    pub token_hash: String,
+
+    /// Date of expiration of the token.
+    pub expires_at: DateTime<Utc>,
+
+    /// Date of usage of the token.
+    pub used_at: Option<DateTime<Utc>>,
+
+    /// Date of revocation of the token.
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Expiring for AuthRefreshToken {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+impl AuthRefreshToken {
+    /// Checks if the secret provided by the client matches this token.
+    ///
+    /// # Arguments
+    /// * `value`: Token provided by the client.
+    ///
+    /// # Returns
+    /// `true` if the secret matches.
+    pub fn matches(&self, value: &RefreshTokenValue) -> bool {
+        self.id == value.id && self.token_hash == value.hash()
+    }
+
+    /// Checks if the token has already been used or revoked.
+    ///
+    /// # Returns
+    /// `true` if the token can't be used anymore.
+    pub fn is_spent(&self) -> bool {
+        self.used_at.is_some() || self.revoked_at.is_some()
+    }
+}
+
+/// Refresh token as provided to the client (`<id>.<secret>`).
+#[derive(Clone, PartialEq, derive_more::Debug)]
+pub struct RefreshTokenValue {
+    /// ID of the stored token.
+    pub id: Uuid,
+
+    /// Random secret.
+    #[debug(skip)]
+    pub secret: String,
+}
+
+impl RefreshTokenValue {
+    /// Generates a new random secret.
+    ///
+    /// # Returns
+    /// The secret.
+    pub fn generate_secret() -> String {
+        format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+    }
+
+    /// Hashes a secret so that it can be stored. The secret is random, a fast hash is enough.
+    ///
+    /// # Arguments
+    /// * `secret`: Secret to hash.
+    ///
+    /// # Returns
+    /// The hash.
+    pub fn hash_secret(secret: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(secret.as_bytes()))
+    }
+
+    /// Hashes the secret of the token.
+    ///
+    /// # Returns
+    /// The hash.
+    pub fn hash(&self) -> String {
+        Self::hash_secret(&self.secret)
+    }
+}
+
+impl FromStr for RefreshTokenValue {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (id, secret) = value.split_once('.').ok_or(Error::RefreshTokenNotFound)?;
+
+        Ok(Self {
+            id: Uuid::parse_str(id).map_err(|_| Error::RefreshTokenNotFound)?,
+            secret: secret.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for RefreshTokenValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.id, self.secret)
+    }
+}
+
+/// Grants accepted by the token endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantType {
+    /// Tokens are issued in exchange of the credentials of the user.
+    Password,
+
+    /// Tokens are issued in exchange of a refresh token.
+    RefreshToken,
+}
+
+/// Request sent to the token endpoint (RFC 6749). The fields needed depend on the grant.
+#[derive(Clone, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct TokenRequest {
+    /// Grant used to obtain the tokens.
+    pub grant_type: GrantType,
+
+    /// Email of the user (password grant).
+    #[validate(email)]
+    pub email: Option<String>,
+
+    /// Password of the user (password grant).
+    #[debug(skip)]
+    #[validate(nested)]
+    pub password: Option<Password>,
+
+    /// Code generated by an authenticator app (password grant, if a second factor is needed).
+    #[debug(skip)]
+    #[validate(length(equal = 6))]
+    pub code: Option<String>,
+
+    /// Recovery code (password grant, if a second factor is needed).
+    #[debug(skip)]
+    pub recovery_code: Option<String>,
+
+    /// Refresh token (refresh token grant).
+    #[debug(skip)]
+    pub refresh_token: Option<String>,
+}
+
+/// Request sent to revoke a refresh token (RFC 7009).
+#[derive(Clone, Deserialize, Serialize, derive_more::Debug)]
+pub struct TokenRevocationRequest {
+    /// Refresh token to revoke (along with all the tokens of its family).
+    #[debug(skip)]
+    pub token: String,
+}
+
+/// Tokens returned by the token endpoint.
+#[derive(Clone, Deserialize, Serialize, derive_more::Debug)]
+pub struct TokenResponse {
+    /// Token to be sent in the `Authorization` header.
+    #[debug(skip)]
+    pub access_token: String,
+
+    /// Type of the access token (always `Bearer`).
+    pub token_type: String,
+
+    /// Lifetime of the access token (in seconds).
+    pub expires_in: i64,
+
+    /// Token used to obtain a new access token.
+    #[debug(skip)]
+    pub refresh_token: String,
+}
+
+impl TokenResponse {
+    /// Creates a new response.
+    ///
+    /// # Arguments
+    /// * `access_token`: Signed access token.
+    /// * `lifetime`: Lifetime of the access token.
+    /// * `refresh_token`: Refresh token.
+    ///
+    /// # Returns
+    /// A new `TokenResponse` instance.
+    pub fn new(
+        access_token: String,
+        lifetime: Duration,
+        refresh_token: &RefreshTokenValue,
+    ) -> Self {
+        Self {
+            access_token,
+            token_type: TOKEN_TYPE.to_string(),
+            expires_in: lifetime.num_seconds(),
+            refresh_token: refresh_token.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for TokenResponse {
+    fn into_response(self) -> Response {
+        // Tokens must not be cached (RFC 6749, section 5.1)
+        ([(CACHE_CONTROL, "no-store")], Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::rand::*;
+
+    use super::*;
+
+    fn settings() -> JwtSettings {
+        JwtSettings {
+            algorithm: "HS256".to_string(),
+            issuer: "axum-skeleton".to_string(),
+            audience: "axum-skeleton".to_string(),
+            access_token_lifetime_minutes: 15,
+            refresh_token_lifetime_days: 30,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwt_codec() -> Result<(), Box<dyn std::error::Error>> {
+        let codec = JwtCodec::new(&settings(), b"secret", b"secret")?;
+        let user = AuthUser {
+            id: random_id(),
+            token_version: 3,
+            ..Default::default()
+        };
+
+        let token = codec.encode(&user)?;
+
+        let claims = codec.decode(&token)?;
+        assert_eq!(claims.sub, user.id);
+        assert_eq!(claims.ver, 3);
+        assert_eq!(claims.exp - claims.iat, 15 * 60);
+
+        // Wrong signature
+        let other = JwtCodec::new(&settings(), b"other", b"other")?;
+        assert!(matches!(other.decode(&token), Err(Error::Unauthorized)));
+
+        // Wrong audience
+        let other = JwtCodec::new(
+            &JwtSettings {
+                audience: "other".to_string(),
+                ..settings()
+            },
+            b"secret",
+            b"secret",
+        )?;
+        assert!(matches!(other.decode(&token), Err(Error::Unauthorized)));
+
+        assert!(matches!(codec.decode("invalid"), Err(Error::Unauthorized)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_jwt_codec_algorithm() -> Result<(), Box<dyn std::error::Error>> {
+        let res = JwtCodec::new(
+            &JwtSettings {
+                algorithm: "none".to_string(),
+                ..settings()
+            },
+            b"secret",
+            b"secret",
+        );
+        assert!(matches!(res, Err(Error::Jwt(_))));
+
+        // Asymmetric algorithms need PEM keys
+        let res = JwtCodec::new(
+            &JwtSettings {
+                algorithm: "RS256".to_string(),
+                ..settings()
+            },
+            b"secret",
+            b"secret",
+        );
+        assert!(matches!(res, Err(Error::Jwt(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_value() -> Result<(), Box<dyn std::error::Error>> {
+        let value = RefreshTokenValue {
+            id: random_id(),
+            secret: RefreshTokenValue::generate_secret(),
+        };
+
+        let parsed: RefreshTokenValue = value.to_string().parse()?;
+        assert_eq!(parsed, value);
+
+        let record = AuthRefreshToken {
+            id: value.id,
+            token_hash: RefreshTokenValue::hash_secret(&value.secret),
+            ..Default::default()
+        };
+        assert!(record.matches(&value));
+
+        let other = RefreshTokenValue {
+            secret: RefreshTokenValue::generate_secret(),
+            ..value
+        };
+        assert!(!record.matches(&other));
+
+        assert!("invalid".parse::<RefreshTokenValue>().is_err());
+        assert!("invalid.secret".parse::<RefreshTokenValue>().is_err());
+
+        Ok(())
+    }
+}
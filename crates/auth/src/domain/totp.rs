@@ -0,0 +1,382 @@
+//! Two-factor authentication (TOTP, RFC 6238) related entities.
+
+use chrono::{DateTime, Utc};
+use totp_rs::{Algorithm, Secret, TOTP};
+use validator::Validate;
+
+use utils::hashing::{hash_string, verify};
+
+use crate::domain::auth_user::AuthUserRole;
+use crate::domain::port::AuthStore;
+use crate::prelude::*;
+
+/// Number of digits of the TOTP codes.
+const TOTP_DIGITS: usize = 6;
+
+/// Duration of a TOTP step (in seconds).
+const TOTP_STEP: u64 = 30;
+
+/// Number of steps accepted before and after the current one (to handle clocks drift).
+const TOTP_SKEW: u64 = 1;
+
+/// Number of recovery codes generated for a user.
+const RECOVERY_CODES_COUNT: usize = 10;
+
+/// TOTP configuration of a user.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct AuthTotp {
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Shared secret (base32 encoded).
+    #[debug(skip)]
+    pub secret: String,
+
+    /// Whether the enrollment has been confirmed by the user.
+    pub enabled: bool,
+
+    /// Last step for which a code has been accepted (used to prevent codes replay).
+    pub last_used_step: Option<i64>,
+}
+
+impl AuthTotp {
+    /// Creates a new TOTP configuration (not enabled) with a random secret.
+    ///
+    /// # Arguments
+    /// * `user_id`: User's ID.
+    ///
+    /// # Returns
+    /// A new `AuthTotp` instance.
+    pub fn generate(user_id: &Uuid) -> Self {
+        let Secret::Encoded(secret) = Secret::generate_secret().to_encoded() else {
+            unreachable!("Secret has been encoded");
+        };
+
+        Self {
+            user_id: *user_id,
+            secret,
+            enabled: false,
+            last_used_step: None,
+        }
+    }
+
+    /// Builds the TOTP generator.
+    ///
+    /// # Arguments
+    /// * `issuer`: Name of the application displayed in authenticator apps.
+    /// * `account`: Name of the account displayed in authenticator apps.
+    ///
+    /// # Returns
+    /// A result containing the TOTP generator.
+    fn totp(&self, issuer: &str, account: &str) -> ApiResult<TOTP> {
+        let secret = Secret::Encoded(self.secret.clone())
+            .to_bytes()
+            .map_err(|e| Error::Totp(e.to_string()))?;
+
+        TOTP::new(
+            Algorithm::SHA1,
+            TOTP_DIGITS,
+            0,
+            TOTP_STEP,
+            secret,
+            Some(issuer.to_string()),
+            account.to_string(),
+        )
+        .map_err(|e| Error::Totp(e.to_string()))
+    }
+
+    /// Gets the `otpauth://` URI to be provided to authenticator apps.
+    ///
+    /// # Arguments
+    /// * `issuer`: Name of the application displayed in authenticator apps.
+    /// * `account`: Name of the account displayed in authenticator apps.
+    ///
+    /// # Returns
+    /// A result containing the URI.
+    pub fn uri(&self, issuer: &str, account: &str) -> ApiResult<String> {
+        Ok(self.totp(issuer, account)?.get_url())
+    }
+
+    /// Checks a code provided by the user. A code can only be used once.
+    ///
+    /// # Arguments
+    /// * `code`: Code to check.
+    ///
+    /// # Returns
+    /// A result containing the step matching the code, if valid.
+    pub fn verify(&self, code: &str) -> ApiResult<Option<i64>> {
+        // Issuer and account are not part of the codes computation
+        let totp = self.totp("", "")?;
+
+        let now = Utc::now().timestamp() as u64;
+        let current = now / TOTP_STEP;
+
+        let step = ((current - TOTP_SKEW)..=(current + TOTP_SKEW))
+            .find(|step| totp.check(code.trim(), step * TOTP_STEP))
+            .map(|step| step as i64);
+
+        Ok(step.filter(|step| self.last_used_step.is_none_or(|last| *step > last)))
+    }
+
+    /// Generates the code expected at a given time (used to simulate an authenticator app).
+    ///
+    /// # Arguments
+    /// * `timestamp`: Unix timestamp (in seconds).
+    ///
+    /// # Returns
+    /// A result containing the code.
+    #[cfg(test)]
+    pub(crate) fn code_at(&self, timestamp: u64) -> ApiResult<String> {
+        Ok(self.totp("", "")?.generate(timestamp))
+    }
+
+    /// Enables the TOTP after a successful verification and generates new recovery codes.
+    ///
+    /// # Arguments
+    /// * `step`: Step of the code used to confirm the enrollment.
+    /// * `store`: Store used to save the configuration and recovery codes.
+    ///
+    /// # Returns
+    /// A result containing the recovery codes (in plain text).
+    pub(crate) async fn enable<A>(mut self, step: i64, store: &A) -> ApiResult<RecoveryCodes>
+    where
+        A: AuthStore,
+    {
+        self.enabled = true;
+        self.last_used_step = Some(step);
+
+        store.save_totp(&self).await?;
+
+        let codes = RecoveryCodes::generate();
+
+        store
+            .create_recovery_codes(&self.user_id, &codes.hashed()?)
+            .await?;
+
+        Ok(codes)
+    }
+}
+
+/// Information needed by the user to configure an authenticator app.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct TotpEnrollment {
+    /// Shared secret (base32 encoded).
+    pub secret: String,
+
+    /// URI that can be converted to a QR code.
+    pub uri: String,
+}
+
+/// Code generated by an authenticator app.
+#[derive(Clone, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct TotpCode {
+    /// Code to check.
+    #[debug(skip)]
+    #[validate(length(equal = 6))]
+    pub code: String,
+}
+
+/// Second factor provided by a user during login: either a code generated by an authenticator
+/// app or a recovery code.
+#[derive(Clone, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct SecondFactorCredentials {
+    /// Code generated by an authenticator app.
+    #[debug(skip)]
+    #[validate(length(equal = 6))]
+    pub code: Option<String>,
+
+    /// Recovery code.
+    #[debug(skip)]
+    pub recovery_code: Option<String>,
+}
+
+impl SecondFactorCredentials {
+    /// Checks the second factor provided by the user and marks it as used.
+    ///
+    /// # Arguments
+    /// * `user_id`: ID of the user.
+    /// * `store`: Store used to get and update the TOTP configuration and recovery codes.
+    ///
+    /// # Returns
+    /// A result containing `true` if the second factor is valid.
+    pub(crate) async fn check<A>(&self, user_id: &Uuid, store: &A) -> ApiResult<bool>
+    where
+        A: AuthStore,
+    {
+        let mut totp = store
+            .get_totp_by_user_id(user_id)
+            .await?
+            .filter(|totp| totp.enabled)
+            .ok_or(Error::TotpNotEnrolled)?;
+
+        if let Some(code) = &self.code {
+            let Some(step) = totp.verify(code)? else {
+                return Ok(false);
+            };
+
+            // Prevent the code from being replayed
+            totp.last_used_step = Some(step);
+            store.save_totp(&totp).await?;
+
+            return Ok(true);
+        }
+
+        if let Some(recovery_code) = &self.recovery_code {
+            for code in store.get_recovery_codes_by_user_id(user_id).await? {
+                if code.matches(recovery_code).await? {
+                    return store.use_recovery_code(&code.id).await;
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// List of single-use recovery codes (in plain text, only displayed once to the user).
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct RecoveryCodes {
+    /// Recovery codes.
+    #[debug(skip)]
+    pub recovery_codes: Vec<String>,
+}
+
+impl RecoveryCodes {
+    /// Generates a new list of random recovery codes.
+    ///
+    /// # Returns
+    /// A new `RecoveryCodes` instance.
+    pub fn generate() -> Self {
+        let recovery_codes = (0..RECOVERY_CODES_COUNT)
+            .map(|_| {
+                let value = Uuid::new_v4().simple().to_string();
+                format!("{}-{}", &value[..5], &value[5..10])
+            })
+            .collect();
+
+        Self { recovery_codes }
+    }
+
+    /// Hashes the recovery codes so that they can be stored.
+    ///
+    /// # Returns
+    /// A result containing the list of hashes.
+    pub fn hashed(&self) -> ApiResult<Vec<String>> {
+        self.recovery_codes
+            .iter()
+            .map(|code| hash_string(code).map_err(Error::Hashing))
+            .collect()
+    }
+}
+
+/// Recovery code stored for a user.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct AuthRecoveryCode {
+    /// Unique record identifier.
+    pub id: Uuid,
+
+    /// User's ID.
+    pub user_id: Uuid,
+
+    /// Hash of the code.
+    #[debug(skip)]
+    pub code: String,
+
+    /// Date of usage of the code.
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl AuthRecoveryCode {
+    /// Checks if a code provided by the user matches this recovery code.
+    ///
+    /// # Arguments
+    /// * `code`: Code to check.
+    ///
+    /// # Returns
+    /// A result containing `true` if the code matches.
+    pub async fn matches(&self, code: &str) -> ApiResult<bool> {
+        verify(&code.trim().to_lowercase(), &self.code)
+            .await
+            .map_err(Error::Hashing)
+    }
+}
+
+/// Security policy applied to the users of a role.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct AuthRolePolicy {
+    /// Role concerned.
+    pub role: AuthUserRole,
+
+    /// Whether the users must use a second factor to login.
+    pub totp_required: bool,
+}
+
+/// Structure used to update the security policy of a role.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, Validate)]
+pub struct AuthRolePolicyUpdate {
+    /// See `AuthRolePolicy::totp_required`.
+    pub totp_required: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::rand::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_totp_verify() -> Result<(), Box<dyn std::error::Error>> {
+        let mut totp = AuthTotp::generate(&random_id());
+
+        let generator = totp.totp("", "")?;
+        let now = Utc::now().timestamp() as u64;
+        let code = generator.generate(now);
+
+        let step = totp.verify(&code)?;
+        assert_eq!(step, Some((now / TOTP_STEP) as i64));
+
+        // Previous step is accepted (clocks drift)
+        let code = generator.generate(now - TOTP_STEP);
+        assert!(totp.verify(&code)?.is_some());
+
+        // Too old
+        let code = generator.generate(now - 3 * TOTP_STEP);
+        assert!(totp.verify(&code)?.is_none());
+
+        // Replay
+        let code = generator.generate(now);
+        totp.last_used_step = step;
+        assert!(totp.verify(&code)?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_totp_uri() -> Result<(), Box<dyn std::error::Error>> {
+        let totp = AuthTotp::generate(&random_id());
+
+        let uri = totp.uri("axum-skeleton", "john@doe.com")?;
+        assert!(uri.starts_with("otpauth://totp/axum-skeleton:john%40doe.com?"));
+        assert!(uri.contains(&format!("secret={}", totp.secret)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recovery_codes() -> Result<(), Box<dyn std::error::Error>> {
+        let codes = RecoveryCodes::generate();
+        assert_eq!(codes.recovery_codes.len(), RECOVERY_CODES_COUNT);
+
+        let hashed = codes.hashed()?;
+
+        let recovery_code = AuthRecoveryCode {
+            code: hashed[0].clone(),
+            ..Default::default()
+        };
+
+        assert!(recovery_code.matches(&codes.recovery_codes[0]).await?);
+        assert!(!recovery_code.matches(&codes.recovery_codes[1]).await?);
+
+        Ok(())
+    }
+}
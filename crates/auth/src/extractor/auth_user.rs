@@ -3,19 +3,26 @@
 //! later.
 
 use async_trait::async_trait;
-use axum::extract::{FromRef, FromRequestParts};
+use axum::extract::{ConnectInfo, FromRef, FromRequestParts};
+use axum::http::header::{AUTHORIZATION, USER_AGENT};
 use axum::http::request::Parts;
+use std::net::SocketAddr;
 use tower_sessions::Session;
 use tracing::{event, Level};
+use uuid::Uuid;
 
 use common_state::AppState;
-use database::Db;
+use database::{Cache, Db};
 
+use crate::domain::api_key::{ApiKeyValue, API_KEY_HEADER};
+use crate::domain::audit::REQUEST_ID_HEADER;
 use crate::domain::auth::Auth;
+use crate::domain::auth_session::AuthSessionMetadata;
 use crate::domain::auth_user::AuthUser;
 use crate::domain::error::Error;
 use crate::domain::port::AuthStore;
-use crate::infrastructure::SQLxAuthStore;
+use crate::domain::token::JwtCodec;
+use crate::infrastructure::{CachedAuthStore, SQLxAuthStore};
 
 #[async_trait]
 impl<S> FromRequestParts<S> for Auth
@@ -32,14 +39,91 @@ where
             .map_err(|_| Error::SessionNotFound)?;
 
         let user: Option<AuthUser> = session.get(Self::KEY).await?;
+        let session_id: Option<Uuid> = session.get(Self::SESSION_ID_KEY).await?;
 
         // Get handle to the user store
-        let AppState { pool, .. } = AppState::from_ref(state);
-        let db = Db::new(pool);
-        let store = SQLxAuthStore::new(&db.into_shared());
+        let AppState {
+            config,
+            pool,
+            replicas,
+            redis,
+            ..
+        } = AppState::from_ref(state);
+        let db = Db::new(pool).with_replicas(replicas).into_shared();
+        let cache = Cache::new(redis, config.cache.clone());
+        let store = CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache);
+
+        // Bearer tokens take precedence over the session
+        if let Some(token) = bearer_token(parts) {
+            let claims = JwtCodec::from_config(&config)?.decode(token)?;
+
+            let user = store
+                .get_user_by_id(&claims.sub)
+                .await
+                .map_err(|_| Error::Unauthorized)?;
+
+            if user.token_version != claims.ver {
+                event!(Level::WARN, "Access token revoked");
+                return Err(Error::Unauthorized);
+            }
+
+            return Ok(Auth {
+                user: Some(user),
+                session,
+                session_id: None,
+                scopes: None,
+                metadata: metadata(parts, config.application.trust_proxy_headers),
+                request_id: request_id(parts),
+            });
+        }
+
+        // API keys restrict the accesses of the user to the scopes of the key
+        if let Some(key) = header(parts, API_KEY_HEADER) {
+            let value: ApiKeyValue = key.parse()?;
+
+            let api_key = store
+                .get_api_key_by_id(&value.id)
+                .await?
+                .ok_or(Error::Unauthorized)?;
+
+            if !api_key.matches(&value).await? {
+                event!(Level::WARN, "Invalid API key");
+                return Err(Error::Unauthorized);
+            }
+
+            store.touch_api_key(&api_key.id).await?;
+
+            let user = store
+                .get_user_by_id(&api_key.user_id)
+                .await
+                .map_err(|_| Error::Unauthorized)?;
+
+            return Ok(Auth {
+                user: Some(user),
+                session,
+                session_id: None,
+                scopes: Some(api_key.scopes),
+                metadata: metadata(parts, config.application.trust_proxy_headers),
+                request_id: request_id(parts),
+            });
+        }
 
         // Fetch user from store (in case it has changed since session creation)
         let user = if let Some(session_user) = user {
+            // Check that the session has not been revoked
+            let record = match session_id {
+                Some(session_id) => store.touch_session(&session_id).await?,
+                None => None,
+            };
+
+            if record.is_none() {
+                event!(Level::WARN, "Session revoked: invalidate session");
+
+                session.flush().await?;
+
+                return Err(Error::Unauthorized);
+            }
+
             let user = store.get_user_by_id(&session_user.id).await?;
 
             if user.hash() != session_user.hash() {
@@ -55,6 +139,118 @@ where
             None
         };
 
-        Ok(Auth { user, session })
+        Ok(Auth {
+            user,
+            session,
+            session_id,
+            scopes: None,
+            metadata: metadata(parts, config.application.trust_proxy_headers),
+            request_id: request_id(parts),
+        })
+    }
+}
+
+/// Gets the value of a header of the request.
+///
+/// # Arguments
+/// * `parts`: Parts of the HTTP request.
+/// * `name`: Name of the header.
+///
+/// # Returns
+/// The value of the header if any.
+fn header<'a>(parts: &'a Parts, name: &str) -> Option<&'a str> {
+    parts
+        .headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Gets the token provided in the `Authorization` header (`Bearer` scheme).
+///
+/// # Arguments
+/// * `parts`: Parts of the HTTP request.
+///
+/// # Returns
+/// The token if any.
+fn bearer_token(parts: &Parts) -> Option<&str> {
+    header(parts, AUTHORIZATION.as_str())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Gets the identifier of the request (generated by the request ID layers of the server, which
+/// replace any value sent by the client).
+///
+/// # Arguments
+/// * `parts`: Parts of the HTTP request.
+///
+/// # Returns
+/// The identifier if any.
+fn request_id(parts: &Parts) -> Option<String> {
+    header(parts, REQUEST_ID_HEADER).map(str::to_string)
+}
+
+/// Gets the information about the client from the request.
+///
+/// # Arguments
+/// * `parts`: Parts of the HTTP request.
+/// * `trust_proxy_headers`: Whether the client address set by a reverse proxy is used.
+///
+/// # Returns
+/// The client information.
+pub(crate) fn metadata(parts: &Parts, trust_proxy_headers: bool) -> AuthSessionMetadata {
+    let value = |name: &str| header(parts, name).map(str::to_string);
+
+    // Any client can send these headers: they're only used when a reverse proxy sets them. The
+    // proxy appends the address of its client to the forwarded list.
+    let forwarded = || {
+        value("x-forwarded-for")
+            .and_then(|value| value.rsplit(',').next().map(|ip| ip.trim().to_string()))
+            .or_else(|| value("x-real-ip"))
+    };
+
+    let ip_address = trust_proxy_headers.then(forwarded).flatten().or_else(|| {
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string())
+    });
+
+    AuthSessionMetadata {
+        ip_address,
+        user_agent: value(USER_AGENT.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    fn parts() -> Parts {
+        let (mut parts, _) = Request::builder()
+            .header("x-forwarded-for", "6.6.6.6, 10.0.0.1")
+            .header("x-real-ip", "7.7.7.7")
+            .body(())
+            .unwrap()
+            .into_parts();
+        parts
+            .extensions
+            .insert(ConnectInfo(SocketAddr::from(([192, 168, 0, 1], 4242))));
+        parts
+    }
+
+    #[test]
+    fn test_metadata_ignores_proxy_headers() {
+        let metadata = metadata(&parts(), false);
+
+        assert_eq!(metadata.ip_address.as_deref(), Some("192.168.0.1"));
+    }
+
+    #[test]
+    fn test_metadata_trusts_proxy_headers() {
+        let metadata = metadata(&parts(), true);
+
+        assert_eq!(metadata.ip_address.as_deref(), Some("10.0.0.1"));
     }
 }
@@ -0,0 +1,67 @@
+//! Extractor used to check that the caller is granted a permission before the handler runs.
+
+use async_trait::async_trait;
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use common_state::AppState;
+
+use crate::domain::auth::Auth;
+use crate::domain::error::Error;
+use crate::domain::permission::Permission;
+
+/// `Auth` of a caller that is granted the permission `P`. The request is rejected (403) otherwise.
+///
+/// ```ignore
+/// async fn delete_user_by_id(
+///     _: Authorized<UsersDelete>,
+///     Path(user_id): Path<Uuid>,
+/// ) -> impl IntoResponse {
+///     // ...
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Authorized<P>
+where
+    P: Permission,
+{
+    /// Authentication object of the caller.
+    pub auth: Auth,
+
+    /// Permission granted.
+    permission: PhantomData<P>,
+}
+
+impl<P> Deref for Authorized<P>
+where
+    P: Permission,
+{
+    type Target = Auth;
+
+    fn deref(&self) -> &Self::Target {
+        &self.auth
+    }
+}
+
+#[async_trait]
+impl<S, P> FromRequestParts<S> for Authorized<P>
+where
+    S: Send + Sync,
+    AppState: FromRef<S>,
+    P: Permission,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = Auth::from_request_parts(parts, state).await?;
+
+        auth.require_permission(P::NAME)?;
+
+        Ok(Self {
+            auth,
+            permission: PhantomData,
+        })
+    }
+}
@@ -1,3 +1,7 @@
 //! List of Axum extractors related to authentication.
 
 mod auth_user;
+mod authorized;
+
+pub(crate) use auth_user::metadata;
+pub use authorized::Authorized;
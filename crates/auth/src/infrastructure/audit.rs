@@ -0,0 +1,383 @@
+//! SQLx implementation of the `AuditStore` trait.
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::FromRow;
+
+use common_web::pagination::{Cursor, Page, PageRequest};
+use database::SharedDb;
+
+use crate::domain::audit::{AuditChanges, AuditContext, AuditEntry, AuditFilters, AuditRecord};
+use crate::domain::port::AuditStore;
+use crate::prelude::*;
+
+/// Mirrors the `audit_logs`'s table.
+#[derive(Clone, Debug, FromRow)]
+pub(crate) struct DbAuditEntry {
+    /// See `AuditEntry::id`.
+    pub id: Uuid,
+
+    /// See `AuditEntry::actor_id`.
+    pub actor_id: Option<Uuid>,
+
+    /// See `AuditEntry::user_id`.
+    pub user_id: Option<Uuid>,
+
+    /// See `AuditEntry::action`.
+    pub action: String,
+
+    /// See `AuditEntry::changes`.
+    pub changes: Json<AuditChanges>,
+
+    /// See `AuditEntry::request_id`.
+    pub request_id: Option<String>,
+
+    /// See `AuditEntry::created_at`.
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbAuditEntry> for AuditEntry {
+    type Error = Error;
+
+    fn try_from(db_entry: DbAuditEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: db_entry.id,
+            actor_id: db_entry.actor_id,
+            user_id: db_entry.user_id,
+            action: serde_json::from_value(Value::String(db_entry.action))?,
+            changes: db_entry.changes.0,
+            request_id: db_entry.request_id,
+            created_at: db_entry.created_at,
+        })
+    }
+}
+
+/// Position of the last entry of a page (the entries are sorted by date, then by ID).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct DbAuditCursor {
+    /// Date of the entry.
+    pub created_at: DateTime<Utc>,
+
+    /// ID of the entry.
+    pub id: Uuid,
+}
+
+/// SLQx's implementation of the `AuditStore` trait.
+#[derive(Debug)]
+pub struct SQLxAuditStore {
+    /// Database connection pool.
+    db: SharedDb,
+
+    /// Information about the request recorded along with the entries.
+    context: AuditContext,
+}
+
+impl SQLxAuditStore {
+    /// Creates a new instance of the SQLx audit store.
+    ///
+    /// # Arguments
+    /// * `db`: Database handle.
+    /// * `context`: Information about the request (see `Auth::audit_context`).
+    ///
+    /// # Returns
+    /// A new instance of `SQLxAuditStore`.
+    pub fn new(db: &SharedDb, context: AuditContext) -> Self {
+        Self {
+            db: db.clone(),
+            context,
+        }
+    }
+}
+
+impl AuditStore for SQLxAuditStore {
+    fn record(&self, record: AuditRecord) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let actor_id = record.actor_id.or(self.context.actor_id);
+        let request_id = self.context.request_id.clone();
+
+        Box::pin(async move {
+            sqlx::query_file!(
+                "sql/create_audit_entry.sql",
+                actor_id,
+                record.user_id,
+                record.action.name(),
+                Json(record.changes) as _,
+                request_id
+            )
+            .execute(db.lock().await.clone())
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn get_page_by_filters(
+        &self,
+        filters: AuditFilters,
+        page: PageRequest,
+    ) -> BoxFuture<'static, ApiResult<Page<AuditEntry>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let cursor = page
+                .cursor
+                .as_ref()
+                .map(|cursor| cursor.decode::<DbAuditCursor>().ok_or(Error::InvalidCursor))
+                .transpose()?;
+
+            let limit = page.limit() as usize;
+            let action = filters.action.map(|action| action.name());
+
+            // Fetch one more entry to know if there's a next page
+            let mut entries = sqlx::query_file_as!(
+                DbAuditEntry,
+                "sql/get_audit_entries_by_filters.sql",
+                filters.user_id,
+                filters.actor_id,
+                action,
+                filters.from,
+                filters.to,
+                cursor.as_ref().map(|cursor| cursor.created_at),
+                cursor.as_ref().map(|cursor| cursor.id),
+                limit as i64 + 1,
+                i64::from(page.offset())
+            )
+            .fetch_all(db.lock().await.clone())
+            .await?;
+
+            let next_cursor = if entries.len() > limit {
+                entries.truncate(limit);
+
+                entries.last().map(|entry| {
+                    Cursor::encode(&DbAuditCursor {
+                        created_at: entry.created_at,
+                        id: entry.id,
+                    })
+                })
+            } else {
+                None
+            };
+
+            let total = sqlx::query_file_scalar!(
+                "sql/count_audit_entries_by_filters.sql",
+                filters.user_id,
+                filters.actor_id,
+                action,
+                filters.from,
+                filters.to,
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(Page {
+                items: entries
+                    .into_iter()
+                    .map(AuditEntry::try_from)
+                    .collect::<ApiResult<_>>()?,
+                total,
+                next_cursor,
+            })
+        })
+    }
+
+    fn get_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<Vec<AuditEntry>>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            let entries = sqlx::query_file_as!(
+                DbAuditEntry,
+                "sql/get_audit_entries_by_user_id.sql",
+                user_id
+            )
+            .fetch_all(db.lock().await.clone())
+            .await?;
+
+            entries.into_iter().map(AuditEntry::try_from).collect()
+        })
+    }
+
+    fn anonymize_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/anonymize_audit_entries_by_user_id.sql", user_id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::database::setup_test_database;
+    use test_utils::rand::*;
+
+    use crate::domain::audit::AuditAction;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let actor_id = random_id();
+        let user_id = random_id();
+        let request_id = random_string();
+
+        let store = SQLxAuditStore::new(
+            &db,
+            AuditContext {
+                actor_id: Some(actor_id),
+                request_id: Some(request_id.clone()),
+            },
+        );
+
+        let changes = AuditChanges::default().field("role", "guest", "admin");
+
+        store
+            .record(
+                AuditRecord::new(AuditAction::UserUpdate, Some(user_id)).changes(changes.clone()),
+            )
+            .await?;
+
+        // The actor of the record takes precedence over the caller
+        store
+            .record(AuditRecord::new(AuditAction::Login, Some(user_id)).actor(user_id))
+            .await?;
+
+        let filters = AuditFilters {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+
+        let page = store
+            .get_page_by_filters(filters, PageRequest::default())
+            .await?;
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].action, AuditAction::Login);
+        assert_eq!(page.items[0].actor_id, Some(user_id));
+        assert_eq!(page.items[1].action, AuditAction::UserUpdate);
+        assert_eq!(page.items[1].actor_id, Some(actor_id));
+        assert_eq!(page.items[1].request_id, Some(request_id));
+        assert_eq!(page.items[1].changes, changes);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_page_by_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let actor_id = random_id();
+
+        let store = SQLxAuditStore::new(
+            &db,
+            AuditContext {
+                actor_id: Some(actor_id),
+                request_id: None,
+            },
+        );
+
+        let start = Utc::now();
+
+        for action in [
+            AuditAction::UserCreate,
+            AuditAction::UserUpdate,
+            AuditAction::UserUpdate,
+        ] {
+            store
+                .record(AuditRecord::new(action, Some(random_id())))
+                .await?;
+        }
+
+        let filters = || AuditFilters {
+            actor_id: Some(actor_id),
+            action: Some(AuditAction::UserUpdate),
+            from: Some(start),
+            ..Default::default()
+        };
+
+        // First page
+        let page = store
+            .get_page_by_filters(
+                filters(),
+                PageRequest {
+                    limit: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items.len(), 1);
+
+        // Next page with the cursor
+        let next = store
+            .get_page_by_filters(
+                filters(),
+                PageRequest {
+                    limit: Some(1),
+                    cursor: page.next_cursor.clone(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(next.items.len(), 1);
+        assert_ne!(next.items[0].id, page.items[0].id);
+        assert!(next.next_cursor.is_none());
+
+        // Time range
+        let page = store
+            .get_page_by_filters(
+                AuditFilters {
+                    to: Some(start),
+                    ..filters()
+                },
+                PageRequest::default(),
+            )
+            .await?;
+        assert_eq!(page.total, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_and_anonymize_by_user_id() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let user_id = random_id();
+        let other_id = random_id();
+
+        let store = SQLxAuditStore::new(&db, AuditContext::default());
+
+        let changes = AuditChanges::default().field("email", "old@mail.com", "new@mail.com");
+
+        store
+            .record(AuditRecord::new(AuditAction::UserUpdate, Some(user_id)).changes(changes))
+            .await?;
+
+        // Action performed by the user on another one
+        store
+            .record(AuditRecord::new(AuditAction::UserDelete, Some(other_id)).actor(user_id))
+            .await?;
+
+        // Only the entries concerning the user are returned
+        let entries = store.get_by_user_id(&user_id).await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::UserUpdate);
+        assert!(!entries[0].changes.is_empty());
+
+        store.anonymize_by_user_id(&user_id).await?;
+
+        let entries = store.get_by_user_id(&user_id).await?;
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].changes.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,530 @@
+//! Read-through cache (stored in Redis) of the `AuthStore` trait.
+
+use chrono::Duration;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+use database::{Cache, SharedDb};
+use security::password::Password;
+
+use crate::domain::api_key::AuthApiKey;
+use crate::domain::auth_session::{AuthSession, AuthSessionMetadata};
+use crate::domain::auth_user::{
+    AuthMagicLink, AuthPasswordReset, AuthUser, AuthUserConfirmation, AuthUserRole,
+};
+use crate::domain::oauth::OAuthUserInfo;
+use crate::domain::port::AuthStore;
+use crate::domain::token::AuthRefreshToken;
+use crate::domain::totp::{AuthRecoveryCode, AuthRolePolicy, AuthTotp};
+use crate::prelude::*;
+
+/// Name of the cache of the authenticated users (looked up by ID).
+const AUTH_USER_CACHE: &str = "auth_user";
+
+/// Entries cached for each user: the authenticated user (this crate) and the profile (`user`
+/// crate). They're all removed when the user changes.
+const USER_ENTRIES: [&str; 2] = ["auth", "profile"];
+
+/// Builds the key of an entry cached for a user.
+///
+/// # Arguments
+/// * `user_id`: ID of the user.
+/// * `entry`: Entry (one of `auth` or `profile`).
+///
+/// # Returns
+/// The key of the entry.
+pub fn user_cache_key(user_id: &Uuid, entry: &str) -> String {
+    format!("user:{user_id}:{entry}")
+}
+
+/// Removes all the entries cached for a user once the changes made with the database handle are
+/// committed (see `Db::after_commit`): removed before, they could be cached again from the
+/// previous version of the user until the commit.
+///
+/// # Arguments
+/// * `db`: Database handle used for the changes.
+/// * `cache`: Cache handle.
+/// * `user_id`: ID of the user.
+pub async fn evict_user(db: &SharedDb, cache: &Cache, user_id: &Uuid) {
+    let keys = USER_ENTRIES.map(|entry| user_cache_key(user_id, entry));
+    let cache = cache.clone();
+
+    let db = db.lock().await.clone();
+
+    db.after_commit(Box::pin(async move { cache.delete(&keys).await }))
+        .await;
+}
+
+/// Caches an authenticated user, without its password (only its fingerprint).
+///
+/// # Arguments
+/// * `cache`: Cache handle.
+/// * `user`: User to be cached.
+async fn cache_auth_user(cache: &Cache, user: &AuthUser) {
+    cache
+        .set(
+            &user_cache_key(&user.id, "auth"),
+            user,
+            cache.settings().auth_user_ttl_seconds,
+        )
+        .await;
+}
+
+/// Decorator of an `AuthStore` caching the lookups of the users (done by every authenticated
+/// request). The entries of a user are removed by the calls that change it.
+///
+/// Inside a transaction, the cache is bypassed: the users may have been changed by it.
+#[derive(Debug)]
+pub struct CachedAuthStore<S: AuthStore> {
+    /// Decorated store.
+    store: Arc<S>,
+
+    /// Database handle of the decorated store.
+    db: SharedDb,
+
+    /// Cache handle.
+    cache: Cache,
+}
+
+impl<S: AuthStore> CachedAuthStore<S> {
+    /// Creates a new instance of the cached authentication store.
+    ///
+    /// # Arguments
+    /// * `store`: Store to be decorated.
+    /// * `db`: Database handle of the decorated store.
+    /// * `cache`: Cache handle.
+    ///
+    /// # Returns
+    /// A new instance of `CachedAuthStore`.
+    #[must_use]
+    pub fn new(store: S, db: &SharedDb, cache: &Cache) -> Self {
+        Self {
+            store: Arc::new(store),
+            db: db.clone(),
+            cache: cache.clone(),
+        }
+    }
+
+    /// Runs a call that changes a user then removes the entries cached for the user.
+    ///
+    /// # Arguments
+    /// * `user_id`: ID of the user changed.
+    /// * `call`: Call to the decorated store.
+    ///
+    /// # Returns
+    /// The result of the call.
+    fn evicting<T: Send + 'static>(
+        &self,
+        user_id: &Uuid,
+        call: BoxFuture<'static, ApiResult<T>>,
+    ) -> BoxFuture<'static, ApiResult<T>> {
+        let db = self.db.clone();
+        let cache = self.cache.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            let output = call.await?;
+
+            evict_user(&db, &cache, &user_id).await;
+
+            Ok(output)
+        })
+    }
+}
+
+impl<S: AuthStore + 'static> AuthStore for CachedAuthStore<S> {
+    fn find_user_by_email(&self, email: &str) -> BoxFuture<'static, ApiResult<AuthUser>> {
+        // Not cached: the password is verified on the user found
+        self.store.find_user_by_email(email)
+    }
+
+    fn find_user_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthUser>>> {
+        self.store.find_user_by_identity(provider, subject)
+    }
+
+    fn create_identity(
+        &self,
+        user_id: &Uuid,
+        provider: &str,
+        subject: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.create_identity(user_id, provider, subject)
+    }
+
+    fn create_user_with_identity(
+        &self,
+        provider: &str,
+        info: &OAuthUserInfo,
+    ) -> BoxFuture<'static, ApiResult<AuthUser>> {
+        self.store.create_user_with_identity(provider, info)
+    }
+
+    fn get_user_by_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<AuthUser>> {
+        let store = self.store.clone();
+        let db = self.db.clone();
+        let cache = self.cache.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            let db = db.lock().await.clone();
+            let in_transaction = db.in_transaction();
+            let key = user_cache_key(&user_id, "auth");
+
+            if !in_transaction {
+                if let Some(user) = cache.get(AUTH_USER_CACHE, &key).await {
+                    return Ok(user);
+                }
+
+                // A replica may not have caught up with the last change of the user yet
+                db.read_your_writes();
+            }
+
+            let user = AuthUser {
+                password: Password::default(),
+                ..store.get_user_by_id(&user_id).await?
+            };
+
+            if !in_transaction {
+                cache_auth_user(&cache, &user).await;
+            }
+
+            Ok(user)
+        })
+    }
+
+    fn get_user_confirmation_by_id(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<AuthUserConfirmation>> {
+        self.store.get_user_confirmation_by_id(id)
+    }
+
+    fn delete_user_confirmation_by_id(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let store = self.store.clone();
+        let db = self.db.clone();
+        let cache = self.cache.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            // The confirmation is needed to know which user is confirmed
+            let confirmation = store.get_user_confirmation_by_id(&id).await.ok();
+
+            store.delete_user_confirmation_by_id(&id).await?;
+
+            if let Some(confirmation) = confirmation {
+                evict_user(&db, &cache, &confirmation.user_id).await;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn delete_user_confirmation_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        self.evicting(
+            user_id,
+            self.store.delete_user_confirmation_by_user_id(user_id),
+        )
+    }
+
+    fn create_user_confirmation(
+        &self,
+        user_id: &Uuid,
+        confirmation_timeout_hours: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthUserConfirmation>> {
+        self.evicting(
+            user_id,
+            self.store
+                .create_user_confirmation(user_id, confirmation_timeout_hours),
+        )
+    }
+
+    fn create_session(
+        &self,
+        user_id: &Uuid,
+        metadata: &AuthSessionMetadata,
+    ) -> BoxFuture<'static, ApiResult<AuthSession>> {
+        self.store.create_session(user_id, metadata)
+    }
+
+    fn touch_session(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<Option<AuthSession>>> {
+        self.store.touch_session(id)
+    }
+
+    fn get_sessions_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthSession>>> {
+        self.store.get_sessions_by_user_id(user_id)
+    }
+
+    fn delete_session(&self, user_id: &Uuid, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.delete_session(user_id, id)
+    }
+
+    fn delete_sessions_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.delete_sessions_by_user_id(user_id)
+    }
+
+    fn create_magic_link(
+        &self,
+        user_id: &Uuid,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthMagicLink>> {
+        self.store.create_magic_link(user_id, timeout)
+    }
+
+    fn consume_magic_link(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthMagicLink>>> {
+        self.store.consume_magic_link(id)
+    }
+
+    fn create_password_reset(
+        &self,
+        user_id: &Uuid,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthPasswordReset>> {
+        self.store.create_password_reset(user_id, timeout)
+    }
+
+    fn consume_password_reset(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthPasswordReset>>> {
+        self.store.consume_password_reset(id)
+    }
+
+    fn set_password(
+        &self,
+        user_id: &Uuid,
+        password: &Password,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        self.evicting(user_id, self.store.set_password(user_id, password))
+    }
+
+    fn get_totp_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthTotp>>> {
+        self.store.get_totp_by_user_id(user_id)
+    }
+
+    fn save_totp(&self, totp: &AuthTotp) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.save_totp(totp)
+    }
+
+    fn create_recovery_codes(
+        &self,
+        user_id: &Uuid,
+        codes: &[String],
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.create_recovery_codes(user_id, codes)
+    }
+
+    fn get_recovery_codes_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthRecoveryCode>>> {
+        self.store.get_recovery_codes_by_user_id(user_id)
+    }
+
+    fn use_recovery_code(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<bool>> {
+        self.store.use_recovery_code(id)
+    }
+
+    fn get_role_policies(&self) -> BoxFuture<'static, ApiResult<Vec<AuthRolePolicy>>> {
+        self.store.get_role_policies()
+    }
+
+    fn get_role_policy(
+        &self,
+        role: &AuthUserRole,
+    ) -> BoxFuture<'static, ApiResult<AuthRolePolicy>> {
+        self.store.get_role_policy(role)
+    }
+
+    fn set_role_policy(
+        &self,
+        policy: &AuthRolePolicy,
+    ) -> BoxFuture<'static, ApiResult<AuthRolePolicy>> {
+        self.store.set_role_policy(policy)
+    }
+
+    fn create_refresh_token(
+        &self,
+        user_id: &Uuid,
+        family_id: &Uuid,
+        token_hash: &str,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthRefreshToken>> {
+        self.store
+            .create_refresh_token(user_id, family_id, token_hash, timeout)
+    }
+
+    fn get_refresh_token_by_id(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthRefreshToken>>> {
+        self.store.get_refresh_token_by_id(id)
+    }
+
+    fn use_refresh_token(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<bool>> {
+        self.store.use_refresh_token(id)
+    }
+
+    fn revoke_refresh_token_family(&self, family_id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.revoke_refresh_token_family(family_id)
+    }
+
+    fn revoke_refresh_tokens_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        self.evicting(
+            user_id,
+            self.store.revoke_refresh_tokens_by_user_id(user_id),
+        )
+    }
+
+    fn create_api_key(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        key_hash: &str,
+        scopes: &[String],
+    ) -> BoxFuture<'static, ApiResult<AuthApiKey>> {
+        self.store.create_api_key(user_id, name, key_hash, scopes)
+    }
+
+    fn get_api_key_by_id(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<Option<AuthApiKey>>> {
+        self.store.get_api_key_by_id(id)
+    }
+
+    fn get_api_keys_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthApiKey>>> {
+        self.store.get_api_keys_by_user_id(user_id)
+    }
+
+    fn update_api_key(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+        name: Option<String>,
+        scopes: Option<Vec<String>>,
+    ) -> BoxFuture<'static, ApiResult<AuthApiKey>> {
+        self.store.update_api_key(user_id, id, name, scopes)
+    }
+
+    fn delete_api_key(&self, user_id: &Uuid, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.delete_api_key(user_id, id)
+    }
+
+    fn touch_api_key(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.store.touch_api_key(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use database::cache_stats;
+    use test_utils::database::{setup_test_database, setup_unreachable_test_cache};
+    use test_utils::rand::*;
+
+    use crate::domain::port::MockAuthStore;
+
+    use super::*;
+
+    #[test]
+    fn test_user_cache_key() {
+        let user_id = random_id();
+
+        assert_eq!(
+            user_cache_key(&user_id, "auth"),
+            format!("user:{user_id}:auth")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_user_by_id_falls_back_to_store() -> Result<(), Box<dyn std::error::Error>> {
+        let user = AuthUser {
+            id: random_id(),
+            email: random_email(),
+            ..Default::default()
+        };
+
+        let mut store = MockAuthStore::new();
+
+        let expected = user.clone();
+        store.expect_get_user_by_id().times(2).returning(move |_| {
+            let user = expected.clone();
+            Box::pin(async move { Ok(user) })
+        });
+
+        let misses = cache_stats()
+            .get(AUTH_USER_CACHE)
+            .map_or(0, |stats| stats.misses);
+
+        let db = setup_test_database().await?;
+        let store = CachedAuthStore::new(store, &db, &setup_unreachable_test_cache()?);
+
+        // Redis can't be reached: both lookups are misses served by the store
+        assert_eq!(store.get_user_by_id(&user.id).await?, user);
+        assert_eq!(store.get_user_by_id(&user.id).await?, user);
+
+        assert!(cache_stats()[AUTH_USER_CACHE].misses >= misses + 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_password_without_cache() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockAuthStore::new();
+
+        store
+            .expect_set_password()
+            .times(1)
+            .returning(|_, _| Box::pin(async move { Ok(()) }));
+
+        let db = setup_test_database().await?;
+        let store = CachedAuthStore::new(store, &db, &setup_unreachable_test_cache()?);
+
+        // The change succeeds even if the entries can't be removed
+        let res = store.set_password(&random_id(), &random_password()).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_user_confirmation_by_id() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockAuthStore::new();
+
+        store
+            .expect_get_user_confirmation_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(AuthUserConfirmation::default()) }));
+
+        store
+            .expect_delete_user_confirmation_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let db = setup_test_database().await?;
+        let store = CachedAuthStore::new(store, &db, &setup_unreachable_test_cache()?);
+
+        let res = store.delete_user_confirmation_by_id(&random_id()).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+}
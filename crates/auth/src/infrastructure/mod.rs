@@ -6,9 +6,17 @@ use sqlx::{FromRow, Type};
 
 use database::SharedDb;
 use security::password::Password;
+use utils::hashing::hash_string;
 
-use crate::domain::auth_user::{AuthUser, AuthUserConfirmation, AuthUserRole};
+use crate::domain::api_key::AuthApiKey;
+use crate::domain::auth_session::{AuthSession, AuthSessionMetadata};
+use crate::domain::auth_user::{
+    AuthMagicLink, AuthPasswordReset, AuthUser, AuthUserConfirmation, AuthUserRole,
+};
+use crate::domain::oauth::OAuthUserInfo;
 use crate::domain::port::AuthStore;
+use crate::domain::token::AuthRefreshToken;
+use crate::domain::totp::{AuthRecoveryCode, AuthRolePolicy, AuthTotp};
 use crate::prelude::*;
 
 /// List of users roles.
@@ -62,22 +70,58 @@ pub(crate) struct DbAuthUser {
     #[debug(skip)]
     pub password: String,
 
+    /// See `AuthUser::token_version`.
+    pub token_version: i32,
+
     /// See `User::email_confirmed`.
     pub email_confirmed: bool,
 }
 
 impl From<DbAuthUser> for AuthUser {
     fn from(db_user: DbAuthUser) -> Self {
+        let password = Password::from(db_user.password);
+
         Self {
             id: db_user.id,
             email: db_user.email,
             role: db_user.role.into(),
-            password: Password::from(db_user.password),
+            password_fingerprint: AuthUser::fingerprint(&password),
+            password,
+            token_version: db_user.token_version,
             email_confirmed: db_user.email_confirmed,
         }
     }
 }
 
+/// Mirrors the `role_policies`'s table.
+#[derive(Clone, Debug, Default, FromRow, Deserialize, Serialize)]
+pub(crate) struct DbAuthRolePolicy {
+    /// See `AuthRolePolicy::role`.
+    pub role: DbAuthUserRole,
+
+    /// See `AuthRolePolicy::totp_required`.
+    pub totp_required: bool,
+}
+
+impl From<DbAuthRolePolicy> for AuthRolePolicy {
+    fn from(db_policy: DbAuthRolePolicy) -> Self {
+        Self {
+            role: db_policy.role.into(),
+            totp_required: db_policy.totp_required,
+        }
+    }
+}
+
+mod audit;
+mod cache;
+mod oauth;
+mod rate_limit;
+
+pub use audit::SQLxAuditStore;
+pub use cache::{evict_user, user_cache_key, CachedAuthStore};
+pub(crate) use oauth::HttpOAuthClient;
+pub(crate) use rate_limit::RedisRateLimitStore;
+
 /// SLQx's implementation of the `AuthStore` trait.
 #[derive(Debug)]
 pub struct SQLxAuthStore {
@@ -113,6 +157,81 @@ impl AuthStore for SQLxAuthStore {
         })
     }
 
+    fn find_user_by_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthUser>>> {
+        let db = self.db.clone();
+        let provider = provider.to_string();
+        let subject = subject.to_string();
+
+        Box::pin(async move {
+            let user = sqlx::query_file_as!(
+                DbAuthUser,
+                "sql/find_user_by_identity.sql",
+                provider,
+                subject
+            )
+            .fetch_optional(db.lock().await.clone())
+            .await?;
+
+            Ok(user.map(Into::into))
+        })
+    }
+
+    fn create_identity(
+        &self,
+        user_id: &Uuid,
+        provider: &str,
+        subject: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let provider = provider.to_string();
+        let subject = subject.to_string();
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/create_identity.sql", user_id, provider, subject)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn create_user_with_identity(
+        &self,
+        provider: &str,
+        info: &OAuthUserInfo,
+    ) -> BoxFuture<'static, ApiResult<AuthUser>> {
+        let db = self.db.clone();
+        let provider = provider.to_string();
+        let info = info.clone();
+
+        Box::pin(async move {
+            let email = info.verified_email()?;
+
+            // The user must login through the provider (or recover his password)
+            let password = hash_string(&Uuid::new_v4().to_string()).map_err(Error::Hashing)?;
+
+            let user = sqlx::query_file_as!(
+                DbAuthUser,
+                "sql/create_user_with_identity.sql",
+                email,
+                info.given_name,
+                info.family_name,
+                password,
+                provider,
+                info.sub
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(user.into())
+        })
+    }
+
     fn get_user_by_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<AuthUser>> {
         let db = self.db.clone();
         let user_id = *user_id;
@@ -197,59 +316,966 @@ impl AuthStore for SQLxAuthStore {
             Ok(confirmation)
         })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use test_utils::database::setup_test_database;
-    use test_utils::rand::*;
+    fn create_session(
+        &self,
+        user_id: &Uuid,
+        metadata: &AuthSessionMetadata,
+    ) -> BoxFuture<'static, ApiResult<AuthSession>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let metadata = metadata.clone();
 
-    use crate::tests::utils::*;
+        Box::pin(async move {
+            let session = sqlx::query_file_as!(
+                AuthSession,
+                "sql/create_session.sql",
+                user_id,
+                metadata.ip_address,
+                metadata.user_agent
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
 
-    use super::*;
+            Ok(session)
+        })
+    }
 
-    #[tokio::test]
-    async fn test_find_by_email() -> Result<(), Box<dyn std::error::Error>> {
-        let db = setup_test_database().await?;
+    fn touch_session(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<Option<AuthSession>>> {
+        let db = self.db.clone();
+        let id = *id;
 
-        let repo = SQLxAuthStore::new(&db);
+        Box::pin(async move {
+            let session = sqlx::query_file_as!(AuthSession, "sql/touch_session.sql", id)
+                .fetch_optional(db.lock().await.clone())
+                .await?;
 
-        let auth_user = AuthUser {
-            email: random_email(),
-            password: random_password(),
-            ..Default::default()
-        };
+            Ok(session)
+        })
+    }
 
-        let _ = create_user(&auth_user, &db).await?;
+    fn get_sessions_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthSession>>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
 
-        let user = repo.find_user_by_email(&auth_user.email).await?;
-        assert_eq!(user.email, auth_user.email);
+        Box::pin(async move {
+            let sessions =
+                sqlx::query_file_as!(AuthSession, "sql/get_sessions_by_user_id.sql", user_id)
+                    .fetch_all(db.lock().await.clone())
+                    .await?;
 
-        let res = repo.find_user_by_email(&random_email()).await;
-        assert!(res.is_err());
+            Ok(sessions)
+        })
+    }
 
-        Ok(())
+    fn delete_session(&self, user_id: &Uuid, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let id = *id;
+
+        Box::pin(async move {
+            let res = sqlx::query_file!("sql/delete_session.sql", user_id, id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            if res.rows_affected() == 0 {
+                return Err(Error::UserSessionNotFound);
+            }
+
+            Ok(())
+        })
     }
 
-    #[tokio::test]
-    async fn test_get_by_id() -> Result<(), Box<dyn std::error::Error>> {
-        let db = setup_test_database().await?;
+    fn delete_sessions_by_user_id(&self, user_id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
 
-        let repo = SQLxAuthStore::new(&db);
+        Box::pin(async move {
+            sqlx::query_file!("sql/delete_sessions_by_user_id.sql", user_id)
+                .execute(db.lock().await.clone())
+                .await?;
 
-        let auth_user = AuthUser {
-            email: random_email(),
-            password: random_password(),
-            ..Default::default()
-        };
+            Ok(())
+        })
+    }
 
-        let user = create_user(&auth_user, &db).await?;
+    fn create_magic_link(
+        &self,
+        user_id: &Uuid,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthMagicLink>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let timeout = *timeout;
 
-        let fetched = repo.get_user_by_id(&user.id).await?;
-        assert_eq!(fetched.id, user.id);
+        Box::pin(async move {
+            let link = sqlx::query_file_as!(
+                AuthMagicLink,
+                "sql/create_magic_link.sql",
+                user_id,
+                Utc::now() + timeout
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
 
-        let res = repo.get_user_by_id(&random_id()).await;
-        assert!(res.is_err());
+            Ok(link)
+        })
+    }
+
+    fn consume_magic_link(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthMagicLink>>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            let link = sqlx::query_file_as!(AuthMagicLink, "sql/consume_magic_link.sql", id)
+                .fetch_optional(db.lock().await.clone())
+                .await?;
+
+            Ok(link)
+        })
+    }
+
+    fn create_password_reset(
+        &self,
+        user_id: &Uuid,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthPasswordReset>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let timeout = *timeout;
+
+        Box::pin(async move {
+            let reset = sqlx::query_file_as!(
+                AuthPasswordReset,
+                "sql/create_password_reset.sql",
+                user_id,
+                Utc::now() + timeout
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(reset)
+        })
+    }
+
+    fn consume_password_reset(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthPasswordReset>>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            let reset =
+                sqlx::query_file_as!(AuthPasswordReset, "sql/consume_password_reset.sql", id)
+                    .fetch_optional(db.lock().await.clone())
+                    .await?;
+
+            Ok(reset)
+        })
+    }
+
+    fn set_password(
+        &self,
+        user_id: &Uuid,
+        password: &Password,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let password = password.clone();
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/set_password.sql", user_id, password.as_str())
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn get_totp_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthTotp>>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            let totp = sqlx::query_file_as!(AuthTotp, "sql/get_totp_by_user_id.sql", user_id)
+                .fetch_optional(db.lock().await.clone())
+                .await?;
+
+            Ok(totp)
+        })
+    }
+
+    fn save_totp(&self, totp: &AuthTotp) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let totp = totp.clone();
+
+        Box::pin(async move {
+            sqlx::query_file!(
+                "sql/save_totp.sql",
+                totp.user_id,
+                totp.secret,
+                totp.enabled,
+                totp.last_used_step
+            )
+            .execute(db.lock().await.clone())
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn create_recovery_codes(
+        &self,
+        user_id: &Uuid,
+        codes: &[String],
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let codes = codes.to_vec();
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/create_recovery_codes.sql", user_id, &codes)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn get_recovery_codes_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthRecoveryCode>>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            let codes = sqlx::query_file_as!(
+                AuthRecoveryCode,
+                "sql/get_recovery_codes_by_user_id.sql",
+                user_id
+            )
+            .fetch_all(db.lock().await.clone())
+            .await?;
+
+            Ok(codes)
+        })
+    }
+
+    fn use_recovery_code(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<bool>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            let res = sqlx::query_file!("sql/use_recovery_code.sql", id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(res.rows_affected() == 1)
+        })
+    }
+
+    fn get_role_policies(&self) -> BoxFuture<'static, ApiResult<Vec<AuthRolePolicy>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let policies = sqlx::query_file_as!(DbAuthRolePolicy, "sql/get_role_policies.sql")
+                .fetch_all(db.lock().await.clone())
+                .await?;
+
+            Ok(policies.into_iter().map(Into::into).collect())
+        })
+    }
+
+    fn get_role_policy(
+        &self,
+        role: &AuthUserRole,
+    ) -> BoxFuture<'static, ApiResult<AuthRolePolicy>> {
+        let db = self.db.clone();
+        let role: DbAuthUserRole = role.clone().into();
+
+        Box::pin(async move {
+            let policy = sqlx::query_file_as!(
+                DbAuthRolePolicy,
+                "sql/get_role_policy.sql",
+                role as DbAuthUserRole
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(policy.into())
+        })
+    }
+
+    fn set_role_policy(
+        &self,
+        policy: &AuthRolePolicy,
+    ) -> BoxFuture<'static, ApiResult<AuthRolePolicy>> {
+        let db = self.db.clone();
+        let role: DbAuthUserRole = policy.role.clone().into();
+        let totp_required = policy.totp_required;
+
+        Box::pin(async move {
+            let policy = sqlx::query_file_as!(
+                DbAuthRolePolicy,
+                "sql/set_role_policy.sql",
+                role as DbAuthUserRole,
+                totp_required
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(policy.into())
+        })
+    }
+
+    fn create_refresh_token(
+        &self,
+        user_id: &Uuid,
+        family_id: &Uuid,
+        token_hash: &str,
+        timeout: &Duration,
+    ) -> BoxFuture<'static, ApiResult<AuthRefreshToken>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let family_id = *family_id;
+        let token_hash = token_hash.to_string();
+        let timeout = *timeout;
+
+        Box::pin(async move {
+            let token = sqlx::query_file_as!(
+                AuthRefreshToken,
+                "sql/create_refresh_token.sql",
+                user_id,
+                family_id,
+                token_hash,
+                Utc::now() + timeout
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(token)
+        })
+    }
+
+    fn get_refresh_token_by_id(
+        &self,
+        id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<AuthRefreshToken>>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            let token =
+                sqlx::query_file_as!(AuthRefreshToken, "sql/get_refresh_token_by_id.sql", id)
+                    .fetch_optional(db.lock().await.clone())
+                    .await?;
+
+            Ok(token)
+        })
+    }
+
+    fn use_refresh_token(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<bool>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            let res = sqlx::query_file!("sql/use_refresh_token.sql", id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(res.rows_affected() == 1)
+        })
+    }
+
+    fn revoke_refresh_token_family(&self, family_id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let family_id = *family_id;
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/revoke_refresh_token_family.sql", family_id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn revoke_refresh_tokens_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/revoke_refresh_tokens_by_user_id.sql", user_id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn create_api_key(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        key_hash: &str,
+        scopes: &[String],
+    ) -> BoxFuture<'static, ApiResult<AuthApiKey>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let name = name.to_string();
+        let key_hash = key_hash.to_string();
+        let scopes = scopes.to_vec();
+
+        Box::pin(async move {
+            let api_key = sqlx::query_file_as!(
+                AuthApiKey,
+                "sql/create_api_key.sql",
+                user_id,
+                name,
+                key_hash,
+                &scopes
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(api_key)
+        })
+    }
+
+    fn get_api_key_by_id(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<Option<AuthApiKey>>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            let api_key = sqlx::query_file_as!(AuthApiKey, "sql/get_api_key_by_id.sql", id)
+                .fetch_optional(db.lock().await.clone())
+                .await?;
+
+            Ok(api_key)
+        })
+    }
+
+    fn get_api_keys_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> BoxFuture<'static, ApiResult<Vec<AuthApiKey>>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+
+        Box::pin(async move {
+            let api_keys =
+                sqlx::query_file_as!(AuthApiKey, "sql/get_api_keys_by_user_id.sql", user_id)
+                    .fetch_all(db.lock().await.clone())
+                    .await?;
+
+            Ok(api_keys)
+        })
+    }
+
+    fn update_api_key(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+        name: Option<String>,
+        scopes: Option<Vec<String>>,
+    ) -> BoxFuture<'static, ApiResult<AuthApiKey>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let id = *id;
+
+        Box::pin(async move {
+            let api_key = sqlx::query_file_as!(
+                AuthApiKey,
+                "sql/update_api_key.sql",
+                user_id,
+                id,
+                name,
+                scopes.as_deref()
+            )
+            .fetch_optional(db.lock().await.clone())
+            .await?;
+
+            api_key.ok_or(Error::ApiKeyNotFound)
+        })
+    }
+
+    fn delete_api_key(&self, user_id: &Uuid, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let user_id = *user_id;
+        let id = *id;
+
+        Box::pin(async move {
+            let res = sqlx::query_file!("sql/delete_api_key.sql", user_id, id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            if res.rows_affected() == 0 {
+                return Err(Error::ApiKeyNotFound);
+            }
+
+            Ok(())
+        })
+    }
+
+    fn touch_api_key(&self, id: &Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+        let id = *id;
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/touch_api_key.sql", id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::database::setup_test_database;
+    use test_utils::rand::*;
+
+    use crate::tests::utils::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_find_by_email() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let _ = create_user(&auth_user, &db).await?;
+
+        let user = repo.find_user_by_email(&auth_user.email).await?;
+        assert_eq!(user.email, auth_user.email);
+
+        let res = repo.find_user_by_email(&random_email()).await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        let fetched = repo.get_user_by_id(&user.id).await?;
+        assert_eq!(fetched.id, user.id);
+
+        let res = repo.get_user_by_id(&random_id()).await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sessions() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        let metadata = AuthSessionMetadata {
+            ip_address: Some("127.0.0.1".to_string()),
+            user_agent: Some(random_string()),
+        };
+
+        // Create
+        let first = repo.create_session(&user.id, &metadata).await?;
+        assert_eq!(first.user_id, user.id);
+        assert_eq!(first.ip_address, metadata.ip_address);
+        assert_eq!(first.user_agent, metadata.user_agent);
+
+        let second = repo
+            .create_session(&user.id, &AuthSessionMetadata::default())
+            .await?;
+
+        // Touch
+        let touched = repo.touch_session(&first.id).await?;
+        assert!(touched.is_some_and(|s| s.last_seen_at >= first.last_seen_at));
+
+        assert!(repo.touch_session(&random_id()).await?.is_none());
+
+        // List
+        let sessions = repo.get_sessions_by_user_id(&user.id).await?;
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, first.id);
+
+        // Delete
+        let res = repo.delete_session(&random_id(), &first.id).await;
+        assert!(matches!(res, Err(Error::UserSessionNotFound)));
+
+        repo.delete_session(&user.id, &first.id).await?;
+        assert!(repo.touch_session(&first.id).await?.is_none());
+        assert!(repo.touch_session(&second.id).await?.is_some());
+
+        repo.delete_sessions_by_user_id(&user.id).await?;
+        assert!(repo.get_sessions_by_user_id(&user.id).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_magic_links() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        let timeout = Duration::minutes(15);
+
+        // A new link replaces the previous one
+        let first = repo.create_magic_link(&user.id, &timeout).await?;
+        let second = repo.create_magic_link(&user.id, &timeout).await?;
+        assert_eq!(second.user_id, user.id);
+        assert_ne!(first.id, second.id);
+
+        assert!(repo.consume_magic_link(&first.id).await?.is_none());
+
+        // A link can be used only once
+        let link = repo.consume_magic_link(&second.id).await?;
+        assert_eq!(link, Some(second.clone()));
+
+        assert!(repo.consume_magic_link(&second.id).await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_password_resets() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        let timeout = Duration::minutes(15);
+
+        // A new link replaces the previous one
+        let first = repo.create_password_reset(&user.id, &timeout).await?;
+        let second = repo.create_password_reset(&user.id, &timeout).await?;
+        assert_eq!(second.user_id, user.id);
+        assert_ne!(first.id, second.id);
+
+        assert!(repo.consume_password_reset(&first.id).await?.is_none());
+
+        // A link can be used only once
+        let reset = repo.consume_password_reset(&second.id).await?;
+        assert_eq!(reset, Some(second.clone()));
+
+        assert!(repo.consume_password_reset(&second.id).await?.is_none());
+
+        // Update the password
+        let password = random_password();
+        repo.set_password(&user.id, &password.hashed()?).await?;
+
+        let updated = repo.get_user_by_id(&user.id).await?;
+        assert!(password.matches(&updated.password).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_totp() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        assert!(repo.get_totp_by_user_id(&user.id).await?.is_none());
+
+        let mut totp = AuthTotp::generate(&user.id);
+        repo.save_totp(&totp).await?;
+        assert_eq!(
+            repo.get_totp_by_user_id(&user.id).await?,
+            Some(totp.clone())
+        );
+
+        totp.enabled = true;
+        totp.last_used_step = Some(42);
+        repo.save_totp(&totp).await?;
+        assert_eq!(repo.get_totp_by_user_id(&user.id).await?, Some(totp));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recovery_codes() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        repo.create_recovery_codes(&user.id, &[random_string(), random_string()])
+            .await?;
+
+        let codes = vec![random_string(), random_string(), random_string()];
+        repo.create_recovery_codes(&user.id, &codes).await?;
+
+        let stored = repo.get_recovery_codes_by_user_id(&user.id).await?;
+        assert_eq!(stored.len(), codes.len());
+
+        assert!(repo.use_recovery_code(&stored[0].id).await?);
+        assert!(!repo.use_recovery_code(&stored[0].id).await?);
+
+        let stored = repo.get_recovery_codes_by_user_id(&user.id).await?;
+        assert_eq!(stored.len(), codes.len() - 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_role_policies() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let policies = repo.get_role_policies().await?;
+        assert_eq!(policies.len(), 3);
+
+        // Use a transaction so that other tests are not impacted
+        db.lock().await.start_transaction().await?;
+
+        let policy = AuthRolePolicy {
+            role: AuthUserRole::Guest,
+            totp_required: true,
+        };
+
+        assert_eq!(repo.set_role_policy(&policy).await?, policy);
+        assert_eq!(repo.get_role_policy(&AuthUserRole::Guest).await?, policy);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_identities() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let provider = random_string();
+
+        let info = OAuthUserInfo {
+            sub: random_string(),
+            email: Some(random_email()),
+            email_verified: Some(true),
+            ..Default::default()
+        };
+
+        assert!(repo
+            .find_user_by_identity(&provider, &info.sub)
+            .await?
+            .is_none());
+
+        let user = repo.create_user_with_identity(&provider, &info).await?;
+        assert_eq!(Some(user.email.clone()), info.email);
+        assert!(user.is_email_confirmed());
+
+        let found = repo.find_user_by_identity(&provider, &info.sub).await?;
+        assert_eq!(found, Some(user.clone()));
+
+        // Link another identity to the same user
+        let other = random_string();
+
+        repo.create_identity(&user.id, &other, &info.sub).await?;
+
+        let found = repo.find_user_by_identity(&other, &info.sub).await?;
+        assert_eq!(found, Some(user));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        let family_id = random_id();
+        let timeout = Duration::days(30);
+
+        // Create
+        let first = repo
+            .create_refresh_token(&user.id, &family_id, "first", &timeout)
+            .await?;
+        assert_eq!(first.user_id, user.id);
+        assert_eq!(first.family_id, family_id);
+        assert_eq!(first.token_hash, "first");
+
+        let second = repo
+            .create_refresh_token(&user.id, &family_id, "second", &timeout)
+            .await?;
+
+        let other = repo
+            .create_refresh_token(&user.id, &random_id(), "other", &timeout)
+            .await?;
+
+        assert_eq!(
+            repo.get_refresh_token_by_id(&first.id).await?,
+            Some(first.clone())
+        );
+        assert!(repo.get_refresh_token_by_id(&random_id()).await?.is_none());
+
+        // A token can be used only once
+        assert!(repo.use_refresh_token(&first.id).await?);
+        assert!(!repo.use_refresh_token(&first.id).await?);
+
+        let used = repo.get_refresh_token_by_id(&first.id).await?;
+        assert!(used.is_some_and(|token| token.used_at.is_some()));
+
+        // Revoke the family
+        repo.revoke_refresh_token_family(&family_id).await?;
+        assert!(!repo.use_refresh_token(&second.id).await?);
+
+        let fetched = repo.get_refresh_token_by_id(&other.id).await?;
+        assert!(fetched.is_some_and(|token| token.revoked_at.is_none()));
+
+        // Revoke all tokens of the user
+        repo.revoke_refresh_tokens_by_user_id(&user.id).await?;
+
+        let fetched = repo.get_refresh_token_by_id(&other.id).await?;
+        assert!(fetched.is_some_and(|token| token.revoked_at.is_some()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_keys() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+
+        let repo = SQLxAuthStore::new(&db);
+
+        let auth_user = AuthUser {
+            email: random_email(),
+            password: random_password(),
+            ..Default::default()
+        };
+
+        let user = create_user(&auth_user, &db).await?;
+
+        let scopes = vec!["users:read".to_string()];
+
+        // Create
+        let api_key = repo.create_api_key(&user.id, "ci", "hash", &scopes).await?;
+        assert_eq!(api_key.user_id, user.id);
+        assert_eq!(api_key.name, "ci");
+        assert_eq!(api_key.scopes, scopes);
+        assert!(api_key.last_used_at.is_none());
+
+        assert_eq!(
+            repo.get_api_key_by_id(&api_key.id).await?,
+            Some(api_key.clone())
+        );
+        assert!(repo.get_api_key_by_id(&random_id()).await?.is_none());
+
+        // Touch
+        repo.touch_api_key(&api_key.id).await?;
+
+        let touched = repo.get_api_key_by_id(&api_key.id).await?;
+        assert!(touched.is_some_and(|key| key.last_used_at.is_some()));
+
+        // Update
+        let updated = repo
+            .update_api_key(&user.id, &api_key.id, Some("deploy".to_string()), None)
+            .await?;
+        assert_eq!(updated.name, "deploy");
+        assert_eq!(updated.scopes, scopes);
+
+        let updated = repo
+            .update_api_key(&user.id, &api_key.id, None, Some(vec![]))
+            .await?;
+        assert_eq!(updated.name, "deploy");
+        assert!(updated.scopes.is_empty());
+
+        let res = repo
+            .update_api_key(&random_id(), &api_key.id, None, None)
+            .await;
+        assert!(matches!(res, Err(Error::ApiKeyNotFound)));
+
+        // List
+        let api_keys = repo.get_api_keys_by_user_id(&user.id).await?;
+        assert_eq!(api_keys.len(), 1);
+
+        // Delete
+        let res = repo.delete_api_key(&random_id(), &api_key.id).await;
+        assert!(matches!(res, Err(Error::ApiKeyNotFound)));
+
+        repo.delete_api_key(&user.id, &api_key.id).await?;
+        assert!(repo.get_api_keys_by_user_id(&user.id).await?.is_empty());
 
         Ok(())
     }
@@ -0,0 +1,129 @@
+//! HTTP implementation of the `OAuthClient` trait.
+
+use futures::future::BoxFuture;
+use reqwest::header::ACCEPT;
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+
+use configuration::OAuthProviderSettings;
+
+use crate::domain::oauth::{OAuthTokens, OAuthUserInfo};
+use crate::domain::port::OAuthClient;
+use crate::prelude::*;
+
+/// HTTP client used to communicate with the OAuth providers.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HttpOAuthClient {
+    /// HTTP client (shares the connections between the requests).
+    client: Client,
+}
+
+impl HttpOAuthClient {
+    /// Creates a new instance of the OAuth client.
+    ///
+    /// # Returns
+    /// A new instance of `HttpOAuthClient`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Sends a request to the provider and decodes the JSON response.
+///
+/// # Arguments
+/// * `request`: Request to be sent.
+///
+/// # Returns
+/// A result containing the decoded response.
+async fn send<T>(request: RequestBuilder) -> ApiResult<T>
+where
+    T: DeserializeOwned,
+{
+    let provider_error = |e: reqwest::Error| Error::OAuthProvider(e.to_string());
+
+    request
+        .header(ACCEPT, "application/json")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(provider_error)?
+        .json()
+        .await
+        .map_err(provider_error)
+}
+
+impl OAuthClient for HttpOAuthClient {
+    fn exchange_code(
+        &self,
+        settings: &OAuthProviderSettings,
+        code: &str,
+        code_verifier: &str,
+    ) -> BoxFuture<'static, ApiResult<OAuthTokens>> {
+        let request = self.client.post(&settings.token_url).form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &settings.redirect_url),
+            ("client_id", &settings.client_id),
+            ("client_secret", &settings.client_secret),
+            ("code_verifier", code_verifier),
+        ]);
+
+        Box::pin(send(request))
+    }
+
+    fn get_user_info(
+        &self,
+        settings: &OAuthProviderSettings,
+        access_token: &str,
+    ) -> BoxFuture<'static, ApiResult<OAuthUserInfo>> {
+        let request = self
+            .client
+            .get(&settings.userinfo_url)
+            .bearer_auth(access_token);
+
+        Box::pin(send(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::utils::{oauth_settings, StubProvider};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_exchange_code() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+        let settings = oauth_settings(&stub);
+
+        let client = HttpOAuthClient::new();
+
+        let tokens = client
+            .exchange_code(&settings, &stub.code, &stub.code_verifier)
+            .await?;
+        assert_eq!(tokens.access_token, stub.access_token);
+        assert!(tokens.id_token.is_some());
+
+        // Wrong PKCE secret
+        let res = client.exchange_code(&settings, &stub.code, "other").await;
+        assert!(matches!(res, Err(Error::OAuthProvider(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_user_info() -> Result<(), Box<dyn std::error::Error>> {
+        let stub = StubProvider::start().await?;
+        let settings = oauth_settings(&stub);
+
+        let client = HttpOAuthClient::new();
+
+        let info = client.get_user_info(&settings, &stub.access_token).await?;
+        assert_eq!(info, stub.user_info);
+
+        let res = client.get_user_info(&settings, "other").await;
+        assert!(matches!(res, Err(Error::OAuthProvider(_))));
+
+        Ok(())
+    }
+}
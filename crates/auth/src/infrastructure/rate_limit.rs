@@ -0,0 +1,139 @@
+//! Redis implementation of the `RateLimitStore` trait.
+
+use bb8_redis::redis;
+use chrono::Duration;
+use futures::future::BoxFuture;
+
+use common_state::RedisPool;
+
+use crate::domain::port::RateLimitStore;
+use crate::prelude::*;
+
+/// Prefix of the Redis keys used to store the counters.
+const REDIS_KEY_PREFIX: &str = "rate_limit:";
+
+/// Redis store of the rate limiting counters (shared between the instances of the server).
+#[derive(Clone, Debug)]
+pub(crate) struct RedisRateLimitStore {
+    /// Redis database handle.
+    pool: RedisPool,
+}
+
+impl RedisRateLimitStore {
+    /// Creates a new instance of the Redis rate limiting store.
+    ///
+    /// # Arguments
+    /// * `pool`: Redis database handle.
+    ///
+    /// # Returns
+    /// A new instance of `RedisRateLimitStore`.
+    pub fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Builds the Redis key of a counter.
+///
+/// # Arguments
+/// * `key`: Key of the counter.
+///
+/// # Returns
+/// The Redis key.
+fn redis_key(key: &str) -> String {
+    format!("{REDIS_KEY_PREFIX}{key}")
+}
+
+/// Converts a Redis error.
+///
+/// # Arguments
+/// * `e`: Error to be converted.
+///
+/// # Returns
+/// The error of this crate.
+fn redis_error(e: impl std::fmt::Display) -> Error {
+    Error::Redis(e.to_string())
+}
+
+impl RateLimitStore for RedisRateLimitStore {
+    fn increment(&self, key: &str, window: &Duration) -> BoxFuture<'static, ApiResult<(u64, u64)>> {
+        let pool = self.pool.clone();
+        let key = redis_key(key);
+        let window = window.num_seconds();
+
+        Box::pin(async move {
+            let mut conn = pool.get().await.map_err(redis_error)?;
+
+            // The counter is created with its expiration so that it can't live forever
+            let (count, seconds): (u64, i64) = redis::pipe()
+                .atomic()
+                .cmd("SET")
+                .arg(&key)
+                .arg(0)
+                .arg("EX")
+                .arg(window)
+                .arg("NX")
+                .ignore()
+                .cmd("INCR")
+                .arg(&key)
+                .cmd("TTL")
+                .arg(&key)
+                .query_async(&mut *conn)
+                .await
+                .map_err(redis_error)?;
+
+            Ok((count, seconds.max(0) as u64))
+        })
+    }
+
+    fn expires_in(&self, key: &str) -> BoxFuture<'static, ApiResult<Option<u64>>> {
+        let pool = self.pool.clone();
+        let key = redis_key(key);
+
+        Box::pin(async move {
+            let mut conn = pool.get().await.map_err(redis_error)?;
+
+            // Negative values are returned if the key doesn't exist or has no expiration
+            let seconds: i64 = redis::cmd("TTL")
+                .arg(&key)
+                .query_async(&mut *conn)
+                .await
+                .map_err(redis_error)?;
+
+            Ok((seconds > 0).then_some(seconds as u64))
+        })
+    }
+
+    fn set(&self, key: &str, duration: &Duration) -> BoxFuture<'static, ApiResult<()>> {
+        let pool = self.pool.clone();
+        let key = redis_key(key);
+        let duration = duration.num_seconds();
+
+        Box::pin(async move {
+            let mut conn = pool.get().await.map_err(redis_error)?;
+
+            redis::cmd("SET")
+                .arg(&key)
+                .arg(1)
+                .arg("EX")
+                .arg(duration)
+                .query_async::<()>(&mut *conn)
+                .await
+                .map_err(redis_error)
+        })
+    }
+
+    fn delete(&self, key: &str) -> BoxFuture<'static, ApiResult<()>> {
+        let pool = self.pool.clone();
+        let key = redis_key(key);
+
+        Box::pin(async move {
+            let mut conn = pool.get().await.map_err(redis_error)?;
+
+            redis::cmd("DEL")
+                .arg(&key)
+                .query_async::<()>(&mut *conn)
+                .await
+                .map_err(redis_error)
+        })
+    }
+}
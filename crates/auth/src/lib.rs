@@ -5,20 +5,38 @@
 //! - A `AuthBackend` structure passed in Axum as middleware. Its purpose is to implement the
 //!   authentication process.
 //! - An extractor providing a `AuthUser` for the Axum endpoints.
+//! - Permissions granted to the users by their role, checked by the `Authorized` extractor or the
+//!   `require_permission!` route layer before the handlers run.
 //! - Endpoints and use-cases use to login or logout a user.
 //!
-//! Note that this is a very basic implementation. You'll want, for example enable any kind of 2FA here.
+//! Users can also login with an external OAuth2 / OpenID Connect provider (authorization code flow
+//! with PKCE). The user is matched with (or linked to) an account through the email verified by
+//! the provider.
 //!
-//! Let's say we want to send one-time code by email to the user before allowing its login. In the
-//! `login` handler, instead of verifying the password of the user, and before creating the session,
-//! we'll proceed this way:
+//! Clients that can't use cookies (mobile apps, scripts, ...) can obtain a short-lived JWT access
+//! token (sent in the `Authorization: Bearer` header) and an opaque refresh token from the token
+//! endpoint. Refresh tokens are rotated at each use: a token used twice revokes all the tokens
+//! issued from the same login.
 //!
-//! 1. create a one-time code or select one in a predefined secret list.
-//! 2. send an email with this code to the user.
-//! 3. redirect the caller to a page to verify the code.
+//! Scripts and CI jobs can also use personal API keys (sent in the `X-Api-Key` header). The
+//! accesses of a request made with an API key are restricted to the scopes of the key.
 //!
-//! The frontend will then post the code entered by the user to another handler that will (upon
-//! success) create the session. The user is now logged in officially.
+//! Users that forgot their password can receive a single-use link by email to choose a new one.
+//! All the sessions and refresh tokens of the user are revoked once the password is changed.
+//!
+//! The endpoints that check credentials are rate limited by client and by target account, and the
+//! accounts are temporarily locked after too many failed authentications.
+//!
+//! Users can enable a second factor (TOTP, RFC 6238). In that case, the `login` handler doesn't
+//! create the session right away:
+//!
+//! 1. the credentials are checked and the login is kept pending in the session.
+//! 2. the caller is told that a second factor is required.
+//! 3. the frontend posts the code generated by the authenticator app (or a recovery code) to
+//!    another handler that will (upon success) create the session.
+//!
+//! The second factor can also be required for all users of a role. Users that are not enrolled yet
+//! must then enroll before their login can be completed.
 
 #![forbid(unsafe_code)]
 
@@ -34,12 +52,42 @@ mod prelude;
 mod tests;
 
 // Exports
-pub use api::router;
-pub use domain::auth::{require_authentication, Auth, AuthCredentials};
-pub use domain::auth_user::{AuthUser, AuthUserConfirmation, AuthUserRole};
+pub use api::{api_router, rate_limit, rate_limit_requests, router};
+pub use domain::api_key::{
+    ApiKeyRequest, ApiKeyUpdate, ApiKeyValue, AuthApiKey, CreatedApiKey, API_KEY_HEADER,
+};
+pub use domain::audit::{
+    AuditAction, AuditChange, AuditChanges, AuditContext, AuditEntry, AuditFilters, AuditRecord,
+};
+pub use domain::auth::{
+    require_authentication, require_permission, Auth, AuthCredentials, LoginStatus,
+    MagicLinkCredentials, MagicLinkRequest, PasswordReset, PasswordResetRequest,
+    PendingSecondFactor,
+};
+pub use domain::auth_session::{AuthSession, AuthSessionMetadata};
+pub use domain::auth_user::{
+    AuthMagicLink, AuthPasswordReset, AuthUser, AuthUserConfirmation, AuthUserRole, Expiring,
+};
 pub use domain::error::Error;
-pub use domain::port::AuthStore;
-pub use infrastructure::SQLxAuthStore;
+pub use domain::oauth::{OAuthCallback, OAuthPending, OAuthTokens, OAuthUserInfo};
+pub use domain::permission::{
+    AuditRead, CacheRead, Permission, RolesRead, RolesUpdate, SessionsDelete, UsersCreate,
+    UsersDelete, UsersRead, UsersUpdate,
+};
+pub use domain::port::{AuditStore, AuthStore, OAuthClient, RateLimitStore};
+pub use domain::rate_limit::RateLimitTarget;
+pub use domain::token::{
+    AccessClaims, AuthRefreshToken, GrantType, JwtCodec, RefreshTokenValue, TokenRequest,
+    TokenResponse, TokenRevocationRequest,
+};
+pub use domain::totp::{
+    AuthRecoveryCode, AuthRolePolicy, AuthRolePolicyUpdate, AuthTotp, RecoveryCodes,
+    SecondFactorCredentials, TotpCode, TotpEnrollment,
+};
+pub use extractor::Authorized;
+pub use infrastructure::{
+    evict_user, user_cache_key, CachedAuthStore, SQLxAuditStore, SQLxAuthStore,
+};
 
 #[cfg(feature = "mock")]
-pub use domain::port::MockAuthStore;
+pub use domain::port::{MockAuditStore, MockAuthStore, MockOAuthClient};
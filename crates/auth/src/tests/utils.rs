@@ -1,8 +1,26 @@
 //! All utilities needed to implement tests in this crate.
 
+use axum::extract::{Form, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tower_sessions::{MemoryStore, Session};
+
+use configuration::OAuthProviderSettings;
 use database::SharedDb;
+use test_utils::rand::*;
 
+use crate::domain::audit::AuditAction;
+use crate::domain::auth::Auth;
 use crate::domain::auth_user::AuthUser;
+use crate::domain::oauth::{OAuthPending, OAuthTokens, OAuthUserInfo};
+use crate::domain::port::MockAuditStore;
 use crate::infrastructure::{DbAuthUser, DbAuthUserRole};
 
 /// Creates a user entry in database from a struct `AuthUser`.
@@ -31,6 +49,7 @@ pub async fn create_user(
             email,
             role AS \"role!: _\",
             password,
+            token_version,
             TRUE AS \"email_confirmed!: _\"",
         user.email.clone(),
         role as DbAuthUserRole,
@@ -41,3 +60,161 @@ pub async fn create_user(
 
     Ok(user.into())
 }
+
+/// Creates an `Auth` structure without user, backed by a session stored in memory.
+///
+/// # Returns
+/// An `Auth` instance.
+pub fn new_auth() -> Auth {
+    Auth {
+        user: None,
+        session: Session::new(None, Arc::new(MemoryStore::default()), None),
+        session_id: None,
+        scopes: None,
+        metadata: Default::default(),
+        request_id: None,
+    }
+}
+
+/// OAuth provider started locally to test the authorization code flow.
+#[derive(Clone, Debug)]
+pub struct StubProvider {
+    /// Base URL of the provider.
+    pub url: String,
+
+    /// Authorization code accepted by the token endpoint.
+    pub code: String,
+
+    /// PKCE secret expected by the token endpoint.
+    pub code_verifier: String,
+
+    /// Nonce put in the ID token.
+    pub nonce: String,
+
+    /// Access token returned by the token endpoint.
+    pub access_token: String,
+
+    /// Information returned by the userinfo endpoint.
+    pub user_info: OAuthUserInfo,
+}
+
+impl StubProvider {
+    /// Starts the provider on a random port.
+    ///
+    /// # Returns
+    /// A result containing the provider.
+    pub async fn start() -> Result<Self, Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+
+        let pending = OAuthPending::generate("stub");
+
+        let stub = Self {
+            url: format!("http://{}", listener.local_addr()?),
+            code: random_string(),
+            code_verifier: pending.code_verifier,
+            nonce: pending.nonce,
+            access_token: random_string(),
+            user_info: OAuthUserInfo {
+                sub: random_string(),
+                email: Some(random_email()),
+                email_verified: Some(true),
+                given_name: Some(random_string()),
+                family_name: Some(random_string()),
+            },
+        };
+
+        let app = Router::new()
+            .route("/token", post(Self::token))
+            .route("/userinfo", get(Self::userinfo))
+            .with_state(stub.clone());
+
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Ok(stub)
+    }
+
+    /// Token endpoint.
+    async fn token(
+        State(stub): State<Self>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Result<Json<OAuthTokens>, StatusCode> {
+        let param = |name: &str| form.get(name).map(String::as_str);
+
+        if param("grant_type") != Some("authorization_code")
+            || param("code") != Some(stub.code.as_str())
+            || param("code_verifier") != Some(stub.code_verifier.as_str())
+        {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        let claims = format!(
+            r#"{{"sub":"{}","nonce":"{}"}}"#,
+            stub.user_info.sub, stub.nonce
+        );
+
+        Ok(Json(OAuthTokens {
+            access_token: stub.access_token,
+            id_token: Some(format!(
+                "header.{}.signature",
+                URL_SAFE_NO_PAD.encode(claims)
+            )),
+        }))
+    }
+
+    /// Userinfo endpoint.
+    async fn userinfo(
+        State(stub): State<Self>,
+        headers: HeaderMap,
+    ) -> Result<Json<OAuthUserInfo>, StatusCode> {
+        let expected = format!("Bearer {}", stub.access_token);
+
+        match headers.get(AUTHORIZATION) {
+            Some(value) if value == expected.as_str() => Ok(Json(stub.user_info)),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+}
+
+/// Builds the settings used to communicate with the stub provider.
+///
+/// # Arguments
+/// * `stub` - Provider started locally.
+///
+/// # Returns
+/// The settings of the provider.
+pub fn oauth_settings(stub: &StubProvider) -> OAuthProviderSettings {
+    OAuthProviderSettings {
+        client_id: random_string(),
+        client_secret: random_string(),
+        authorize_url: format!("{}/authorize", stub.url),
+        token_url: format!("{}/token", stub.url),
+        userinfo_url: format!("{}/userinfo", stub.url),
+        redirect_url: "http://localhost:8080/oauth/callback".to_string(),
+        scopes: vec!["openid".to_string(), "email".to_string()],
+    }
+}
+
+/// Creates a mocked audit store that expects some actions to be recorded (in any order).
+///
+/// # Arguments
+/// * `actions` - Actions expected.
+///
+/// # Returns
+/// A `MockAuditStore` instance.
+pub fn mock_audit_store(actions: &[AuditAction]) -> MockAuditStore {
+    let mut audit_store = MockAuditStore::new();
+
+    for action in actions {
+        let action = *action;
+
+        audit_store
+            .expect_record()
+            .withf(move |record| record.action == action)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+    }
+
+    audit_store.expect_record().never();
+
+    audit_store
+}
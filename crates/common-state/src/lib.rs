@@ -2,6 +2,8 @@
 
 #![forbid(unsafe_code)]
 
+mod replicas;
 mod state;
 
+pub use replicas::{Replica, Replicas};
 pub use state::{AppState, RedisPool};
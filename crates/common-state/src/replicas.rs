@@ -0,0 +1,116 @@
+//! Read replicas of the PostgreSQL database.
+
+use sqlx::postgres::PgPool;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Read replica of the PostgreSQL database.
+#[derive(Clone, Debug)]
+pub struct Replica {
+    /// Name of the replica (used in logs, never contains credentials).
+    pub name: String,
+
+    /// Pool of connections to the replica.
+    pub pool: PgPool,
+
+    /// Whether the replica is used for reads (it's not while it lags behind the primary).
+    in_rotation: Arc<AtomicBool>,
+}
+
+impl Replica {
+    /// Creates a new replica, out of rotation until its lag is checked.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the replica.
+    /// * `pool` - Pool of connections to the replica.
+    ///
+    /// # Returns
+    /// A new instance of Replica.
+    pub fn new(name: String, pool: PgPool) -> Self {
+        Self {
+            name,
+            pool,
+            in_rotation: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Checks if the replica is used for reads.
+    ///
+    /// # Returns
+    /// `true` if the replica is in rotation, `false` otherwise.
+    pub fn is_in_rotation(&self) -> bool {
+        self.in_rotation.load(Ordering::Relaxed)
+    }
+
+    /// Puts the replica in rotation or takes it out.
+    ///
+    /// # Arguments
+    /// * `in_rotation` - Whether the replica must be used for reads.
+    ///
+    /// # Returns
+    /// The previous value.
+    pub fn set_in_rotation(&self, in_rotation: bool) -> bool {
+        self.in_rotation.swap(in_rotation, Ordering::Relaxed)
+    }
+}
+
+/// Read replicas used in turn for the read-only queries.
+#[derive(Clone, Debug, Default)]
+pub struct Replicas {
+    /// List of replicas.
+    replicas: Arc<Vec<Replica>>,
+
+    /// Index of the next replica to be used.
+    next: Arc<AtomicUsize>,
+}
+
+impl Replicas {
+    /// Creates a new set of replicas.
+    ///
+    /// # Arguments
+    /// * `replicas` - List of replicas.
+    ///
+    /// # Returns
+    /// A new instance of Replicas.
+    pub fn new(replicas: Vec<Replica>) -> Self {
+        Self {
+            replicas: Arc::new(replicas),
+            next: Arc::default(),
+        }
+    }
+
+    /// Gets the list of replicas (in rotation or not).
+    ///
+    /// # Returns
+    /// A slice of replicas.
+    pub fn all(&self) -> &[Replica] {
+        &self.replicas
+    }
+
+    /// Checks if there's no replica at all.
+    ///
+    /// # Returns
+    /// `true` if no replica is configured, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.replicas.is_empty()
+    }
+
+    /// Picks the next replica in rotation (round-robin).
+    ///
+    /// # Returns
+    /// The pool of the replica or None if no replica is in rotation.
+    pub fn pick(&self) -> Option<PgPool> {
+        let count = self.replicas.len();
+
+        if count == 0 {
+            return None;
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        (0..count)
+            .map(|i| &self.replicas[(start + i) % count])
+            .find(|replica| replica.is_in_rotation())
+            .map(|replica| replica.pool.clone())
+    }
+}
@@ -6,6 +6,8 @@ use sqlx::postgres::PgPool;
 
 use configuration::Config;
 
+use crate::replicas::Replicas;
+
 /// Type used to manipulate a Redis database.
 pub type RedisPool = bb8::Pool<bb8_redis::RedisConnectionManager>;
 
@@ -18,6 +20,9 @@ pub struct AppState {
     /// PostgreSQL database handle.
     pub pool: PgPool,
 
+    /// Read replicas of the PostgreSQL database (may be empty).
+    pub replicas: Replicas,
+
     /// Redis database handle.
     pub redis: RedisPool,
 }
@@ -28,14 +33,16 @@ impl AppState {
     /// # Arguments
     /// * `config` - Configuration structure.
     /// * `pool` - PostgreSQL database handle.
+    /// * `replicas` - Read replicas of the PostgreSQL database.
     /// * `redis` - Redis database handle.
     ///
     /// # Returns
     /// New instance of AppState.
-    pub fn new(config: Config, pool: PgPool, redis: RedisPool) -> Self {
+    pub fn new(config: Config, pool: PgPool, replicas: Replicas, redis: RedisPool) -> Self {
         Self {
             config,
             pool,
+            replicas,
             redis,
         }
     }
@@ -0,0 +1,209 @@
+//! This file contains the structures used by the handlers supporting conditional requests
+//! (RFC 9110): the entity tag of a resource and the preconditions sent by the clients.
+
+use std::convert::Infallible;
+use std::fmt;
+
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use axum::response::{IntoResponseParts, ResponseParts};
+
+/// Strong entity tag of a resource, sent in the `ETag` header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ETag(String);
+
+impl ETag {
+    /// Creates an entity tag.
+    ///
+    /// # Arguments
+    /// * `version`: Version of the resource (must only contain visible ASCII characters, except
+    ///   double quotes).
+    ///
+    /// # Returns
+    /// An `ETag` instance.
+    pub fn new<T>(version: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self(format!("\"{version}\""))
+    }
+
+    /// Gets the tag (with its double quotes).
+    ///
+    /// # Returns
+    /// The tag as sent to the clients.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl IntoResponseParts for ETag {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Ok(value) = HeaderValue::from_str(&self.0) {
+            res.headers_mut().insert(ETAG, value);
+        }
+
+        Ok(res)
+    }
+}
+
+/// Preconditions of a request, received in the `If-Match` and `If-None-Match` headers.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preconditions {
+    /// List of tags of the `If-Match` header (or None if not provided).
+    pub if_match: Option<Vec<String>>,
+
+    /// List of tags of the `If-None-Match` header (or None if not provided).
+    pub if_none_match: Option<Vec<String>>,
+}
+
+impl Preconditions {
+    /// Builds the preconditions from the headers of a request.
+    ///
+    /// # Arguments
+    /// * `headers`: Headers of the request.
+    ///
+    /// # Returns
+    /// A `Preconditions` instance.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            if_match: tags(headers, IF_MATCH),
+            if_none_match: tags(headers, IF_NONE_MATCH),
+        }
+    }
+
+    /// Evaluates the `If-Match` header before modifying a resource (strong comparison).
+    ///
+    /// # Arguments
+    /// * `etag`: Current tag of the resource (or None if it doesn't exist).
+    ///
+    /// # Returns
+    /// `true` if the modification can be done, `false` if it must fail with `412 Precondition
+    /// Failed`.
+    pub fn if_match(&self, etag: Option<&ETag>) -> bool {
+        let Some(tags) = &self.if_match else {
+            return true;
+        };
+
+        let Some(etag) = etag else {
+            return false;
+        };
+
+        tags.iter().any(|tag| tag == "*" || tag == etag.as_str())
+    }
+
+    /// Evaluates the `If-None-Match` header before returning a resource (weak comparison).
+    ///
+    /// # Arguments
+    /// * `etag`: Current tag of the resource.
+    ///
+    /// # Returns
+    /// `true` if the resource must be returned, `false` if `304 Not Modified` must be returned
+    /// instead.
+    pub fn if_none_match(&self, etag: &ETag) -> bool {
+        let Some(tags) = &self.if_none_match else {
+            return true;
+        };
+
+        !tags
+            .iter()
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag.as_str())
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Preconditions
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// Gets the list of tags of a header (that can be repeated).
+///
+/// # Arguments
+/// * `headers`: Headers of the request.
+/// * `name`: Name of the header.
+///
+/// # Returns
+/// The list of tags or None if the header is not provided.
+fn tags(headers: &HeaderMap, name: HeaderName) -> Option<Vec<String>> {
+    let mut values = headers.get_all(name).iter().peekable();
+
+    values.peek()?;
+
+    Some(
+        values
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preconditions(if_match: &[&'static str], if_none_match: &[&'static str]) -> Preconditions {
+        let mut headers = HeaderMap::new();
+
+        for value in if_match {
+            headers.append(IF_MATCH, HeaderValue::from_static(value));
+        }
+
+        for value in if_none_match {
+            headers.append(IF_NONE_MATCH, HeaderValue::from_static(value));
+        }
+
+        Preconditions::from_headers(&headers)
+    }
+
+    #[test]
+    fn test_if_match() {
+        let etag = ETag::new("v2");
+        assert_eq!(etag.as_str(), "\"v2\"");
+
+        // No header: always allowed
+        assert!(preconditions(&[], &[]).if_match(Some(&etag)));
+        assert!(preconditions(&[], &[]).if_match(None));
+
+        assert!(preconditions(&["\"v2\""], &[]).if_match(Some(&etag)));
+        assert!(preconditions(&["\"v1\", \"v2\""], &[]).if_match(Some(&etag)));
+        assert!(preconditions(&["\"v1\"", "\"v2\""], &[]).if_match(Some(&etag)));
+        assert!(preconditions(&["*"], &[]).if_match(Some(&etag)));
+
+        assert!(!preconditions(&["\"v1\""], &[]).if_match(Some(&etag)));
+        assert!(!preconditions(&["W/\"v2\""], &[]).if_match(Some(&etag)));
+        assert!(!preconditions(&["*"], &[]).if_match(None));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = ETag::new("v2");
+
+        assert!(preconditions(&[], &[]).if_none_match(&etag));
+        assert!(preconditions(&[], &["\"v1\""]).if_none_match(&etag));
+
+        assert!(!preconditions(&[], &["\"v2\""]).if_none_match(&etag));
+        assert!(!preconditions(&[], &["W/\"v2\""]).if_none_match(&etag));
+        assert!(!preconditions(&[], &["\"v1\", \"v2\""]).if_none_match(&etag));
+        assert!(!preconditions(&[], &["*"]).if_none_match(&etag));
+    }
+}
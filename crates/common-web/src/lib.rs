@@ -2,4 +2,6 @@
 
 #![forbid(unsafe_code)]
 
+pub mod conditional;
 pub mod extractor;
+pub mod pagination;
@@ -0,0 +1,409 @@
+//! This file contains the structures used by the handlers returning paginated lists: the page
+//! requested (with offset or cursor), the sorting parameter and the response envelope.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use axum::http::{HeaderValue, Uri};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use url::form_urlencoded;
+
+/// Number of items returned when the limit is not provided.
+pub const DEFAULT_PAGE_LIMIT: u32 = 50;
+
+/// Maximum number of items that can be requested in one page.
+pub const MAX_PAGE_LIMIT: u32 = 500;
+
+/// Order of a sort.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Ascending order.
+    #[default]
+    Asc,
+
+    /// Descending order.
+    Desc,
+}
+
+/// Sorting parameter, received as `field` or `field:asc|desc`.
+///
+/// The field is deserialized as `F`, which is usually an enum listing the fields allowed for
+/// sorting: any other field is rejected.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sort<F> {
+    /// Field to sort by.
+    pub field: F,
+
+    /// Order of the sort.
+    pub order: SortOrder,
+}
+
+impl<F> Sort<F> {
+    /// Checks if the sort is in descending order.
+    ///
+    /// # Returns
+    /// `true` if descending, `false` otherwise.
+    pub fn is_descending(&self) -> bool {
+        self.order == SortOrder::Desc
+    }
+}
+
+impl<F> Serialize for Sort<F>
+where
+    F: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let field = serde_json::to_value(&self.field).map_err(serde::ser::Error::custom)?;
+        let field = field
+            .as_str()
+            .ok_or_else(|| serde::ser::Error::custom("Sort field must be a string"))?;
+
+        let order = match self.order {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        };
+
+        serializer.serialize_str(&format!("{field}:{order}"))
+    }
+}
+
+impl<'de, F> Deserialize<'de> for Sort<F>
+where
+    F: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SortVisitor<F>(PhantomData<F>);
+
+        impl<F> Visitor<'_> for SortVisitor<F>
+        where
+            F: DeserializeOwned,
+        {
+            type Value = Sort<F>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sort parameter like `field:asc` or `field:desc`")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                let (field, order) = value.split_once(':').unwrap_or((value, "asc"));
+
+                let field_deserializer: StrDeserializer<'_, E> = field.into_deserializer();
+                let order_deserializer: StrDeserializer<'_, E> = order.into_deserializer();
+
+                Ok(Sort {
+                    field: F::deserialize(field_deserializer)?,
+                    order: SortOrder::deserialize(order_deserializer)?,
+                })
+            }
+        }
+
+        deserializer.deserialize_str(SortVisitor(PhantomData))
+    }
+}
+
+/// Opaque cursor pointing after the last item of a page.
+///
+/// The content is defined by the store that builds it and is encoded as URL-safe base64 JSON.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Cursor(String);
+
+impl Cursor {
+    /// Encodes a value into a cursor.
+    ///
+    /// # Arguments
+    /// * `value`: Value to encode (usually the sort key of the last item).
+    ///
+    /// # Returns
+    /// A `Cursor` instance.
+    pub fn encode<T>(value: &T) -> Self
+    where
+        T: Serialize,
+    {
+        let json = serde_json::to_vec(value).unwrap_or_default();
+
+        Self(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    /// Decodes the value of the cursor.
+    ///
+    /// # Returns
+    /// The value decoded or `None` if the cursor is invalid.
+    pub fn decode<T>(&self) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        let json = URL_SAFE_NO_PAD.decode(&self.0).ok()?;
+
+        serde_json::from_slice(&json).ok()
+    }
+
+    /// Gets the encoded version of the cursor.
+    ///
+    /// # Returns
+    /// The string to be sent to the clients.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Page requested by a client, with an offset or a cursor (both can be combined, the offset
+/// being applied after the cursor).
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct PageRequest {
+    /// Maximum number of items to return (or None for the default value).
+    pub limit: Option<u32>,
+
+    /// Number of items to skip (or None).
+    pub offset: Option<u32>,
+
+    /// Cursor returned with the previous page (or None).
+    pub cursor: Option<Cursor>,
+}
+
+impl PageRequest {
+    /// Gets the number of items to return.
+    ///
+    /// # Returns
+    /// The limit requested, bounded to `[1, MAX_PAGE_LIMIT]`.
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    /// Gets the number of items to skip.
+    ///
+    /// # Returns
+    /// The offset requested or 0.
+    pub fn offset(&self) -> u32 {
+        self.offset.unwrap_or_default()
+    }
+}
+
+/// Envelope of a paginated list.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Page<T> {
+    /// Items of the page.
+    pub items: Vec<T>,
+
+    /// Total number of items matching the query (regardless of the pagination).
+    pub total: i64,
+
+    /// Cursor to be used to get the next page (or None if this page is the last one).
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Converts the items of the page.
+    ///
+    /// # Arguments
+    /// * `f`: Conversion function.
+    ///
+    /// # Returns
+    /// The page with the converted items.
+    pub fn map<U, M>(self, f: M) -> Page<U>
+    where
+        M: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+
+    /// Builds the value of the `Link` header (RFC 8288) of this page: the `first`, `prev` (when
+    /// using an offset) and `next` pages. The other query parameters are kept.
+    ///
+    /// # Arguments
+    /// * `uri`: URI of the request.
+    /// * `request`: Page requested.
+    ///
+    /// # Returns
+    /// The header value or `None` if it can't be built.
+    pub fn links(&self, uri: &Uri, request: &PageRequest) -> Option<HeaderValue> {
+        let limit = request.limit().to_string();
+        let offset = request.offset();
+
+        let mut links = vec![(
+            "first",
+            page_uri(uri, &[("limit", limit.as_str())], &["cursor", "offset"]),
+        )];
+
+        if offset > 0 && request.cursor.is_none() {
+            let prev = offset.saturating_sub(request.limit()).to_string();
+
+            links.push((
+                "prev",
+                page_uri(
+                    uri,
+                    &[("limit", limit.as_str()), ("offset", prev.as_str())],
+                    &["cursor"],
+                ),
+            ));
+        }
+
+        if let Some(cursor) = &self.next_cursor {
+            links.push((
+                "next",
+                page_uri(
+                    uri,
+                    &[("limit", limit.as_str()), ("cursor", cursor.as_str())],
+                    &["offset"],
+                ),
+            ));
+        }
+
+        let links = links
+            .into_iter()
+            .map(|(rel, uri)| format!("<{uri}>; rel=\"{rel}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        HeaderValue::from_str(&links).ok()
+    }
+}
+
+/// Builds the URI of another page by replacing some query parameters.
+///
+/// # Arguments
+/// * `uri`: URI of the request.
+/// * `set`: Query parameters to set.
+/// * `remove`: Query parameters to remove.
+///
+/// # Returns
+/// The path and query of the page.
+fn page_uri(uri: &Uri, set: &[(&str, &str)], remove: &[&str]) -> String {
+    let params = form_urlencoded::parse(uri.query().unwrap_or_default().as_bytes())
+        .filter(|(key, _)| !remove.contains(&key.as_ref()) && !set.iter().any(|(k, _)| k == key));
+
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .extend_pairs(set)
+        .finish();
+
+    format!("{}?{query}", uri.path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Field {
+        #[default]
+        CreatedAt,
+        Email,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Query {
+        sort: Option<Sort<Field>>,
+    }
+
+    fn parse(sort: &str) -> Option<Sort<Field>> {
+        serde_json::from_value::<Query>(serde_json::json!({ "sort": sort }))
+            .ok()?
+            .sort
+    }
+
+    #[test]
+    fn test_sort() {
+        assert_eq!(
+            parse("email:desc"),
+            Some(Sort {
+                field: Field::Email,
+                order: SortOrder::Desc,
+            })
+        );
+
+        assert_eq!(
+            parse("created_at"),
+            Some(Sort {
+                field: Field::CreatedAt,
+                order: SortOrder::Asc,
+            })
+        );
+
+        // Not whitelisted
+        assert_eq!(parse("password:asc"), None);
+        assert_eq!(parse("email:random"), None);
+
+        let sort = Sort {
+            field: Field::Email,
+            order: SortOrder::Desc,
+        };
+        assert_eq!(
+            serde_json::to_value(&sort).ok(),
+            Some(serde_json::json!("email:desc"))
+        );
+    }
+
+    #[test]
+    fn test_cursor() {
+        let cursor = Cursor::encode(&("key", 42));
+        assert_eq!(
+            cursor.decode::<(String, i32)>(),
+            Some(("key".to_string(), 42))
+        );
+
+        assert_eq!(
+            Cursor("invalid".to_string()).decode::<(String, i32)>(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_page_request() {
+        assert_eq!(PageRequest::default().limit(), DEFAULT_PAGE_LIMIT);
+        assert_eq!(PageRequest::default().offset(), 0);
+
+        let request = PageRequest {
+            limit: Some(MAX_PAGE_LIMIT + 1),
+            ..Default::default()
+        };
+        assert_eq!(request.limit(), MAX_PAGE_LIMIT);
+    }
+
+    #[test]
+    fn test_links() -> Result<(), Box<dyn std::error::Error>> {
+        let uri: Uri = "/api/users?role=admin&limit=10&offset=20".parse()?;
+
+        let request = PageRequest {
+            limit: Some(10),
+            offset: Some(20),
+            cursor: None,
+        };
+
+        let page = Page::<()> {
+            next_cursor: Some(Cursor("abc".to_string())),
+            ..Default::default()
+        };
+
+        let links = page.links(&uri, &request).ok_or("No links")?;
+        assert_eq!(
+            links.to_str()?,
+            "</api/users?role=admin&limit=10>; rel=\"first\", \
+             </api/users?role=admin&limit=10&offset=10>; rel=\"prev\", \
+             </api/users?role=admin&limit=10&cursor=abc>; rel=\"next\""
+        );
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 //! configuration. The configuration structure may be passed along all routes.
 
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fmt;
 
 use utils::filesystem::{relative_path, root_relative_path};
@@ -37,6 +38,10 @@ pub struct ApplicationSettings {
 
     /// Timeout value for routes (in seconds).
     pub timeout: u64,
+
+    /// Whether the client address is read from the `X-Forwarded-For` and `X-Real-IP` headers.
+    /// Only to be enabled behind a reverse proxy that sets them (they're forged otherwise).
+    pub trust_proxy_headers: bool,
 }
 
 /// Structure that contains all CORS settings.
@@ -59,11 +64,26 @@ pub struct PasswordSettings {
     pub pattern: PasswordPatternSettings,
 }
 
+/// Backends that can be used to store the user sessions.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStoreKind {
+    /// Sessions are kept in memory (lost at restart, not shared between
+    /// instances).
+    Memory,
+
+    /// Sessions are persisted in Redis.
+    Redis,
+}
+
 /// Structure that contains all sessions settings.
 #[derive(Clone, Debug, Deserialize)]
 pub struct SessionsSettings {
     /// Timeout for the user session.
     pub timeout_in_hours: u32,
+
+    /// Backend used to store the sessions.
+    pub store: SessionStoreKind,
 }
 
 /// Structure that contains all sessions settings.
@@ -71,6 +91,206 @@ pub struct SessionsSettings {
 pub struct AuthSettings {
     /// Timeout for the user's email confirmation.
     pub email_confirmation_timeout_hours: u32,
+
+    /// Timeout for confirming (or reverting) an email change.
+    pub email_change_timeout_hours: u32,
+
+    /// Timeout for accepting an invitation.
+    pub invitation_timeout_hours: u32,
+
+    /// Timeout for the passwordless login links.
+    pub magic_link_timeout_minutes: u32,
+
+    /// Timeout for the password recovery links.
+    pub password_reset_timeout_minutes: u32,
+
+    /// Name of the application displayed in the authenticator apps.
+    pub totp_issuer: String,
+
+    /// Bearer tokens settings.
+    pub jwt: JwtSettings,
+
+    /// Brute-force protection settings.
+    pub rate_limit: RateLimitSettings,
+
+    /// Self-registration settings.
+    pub registration: RegistrationSettings,
+}
+
+/// Structure that contains the brute-force protection settings of the authentication endpoints.
+/// The counters are stored in Redis.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimitSettings {
+    /// Whether the requests are counted.
+    pub enabled: bool,
+
+    /// Duration of the window in which the requests are counted.
+    pub window_seconds: u32,
+
+    /// Maximum number of requests allowed for a client IP address during the window.
+    pub max_requests_per_ip: u32,
+
+    /// Maximum number of requests allowed for a target email during the window.
+    pub max_requests_per_email: u32,
+
+    /// Number of consecutive failed authentications that locks an account.
+    pub max_failures: u32,
+
+    /// Duration after which the failed authentications are forgotten.
+    pub failures_window_minutes: u32,
+
+    /// Duration of the lock of an account.
+    pub lock_minutes: u32,
+}
+
+/// Structure that contains the database settings (the URLs are provided by environment
+/// variables).
+#[derive(Clone, Debug, Deserialize)]
+pub struct DatabaseSettings {
+    /// Minimum number of connections kept in the pool.
+    pub min_connections: u32,
+
+    /// Maximum number of connections of the pool.
+    pub max_connections: u32,
+
+    /// Maximum duration to wait for a connection of the pool.
+    pub acquire_timeout_seconds: u32,
+
+    /// Duration after which an unused connection is closed (never if not set).
+    pub idle_timeout_seconds: Option<u32>,
+
+    /// Duration after which a connection is closed (never if not set).
+    pub max_lifetime_seconds: Option<u32>,
+
+    /// Duration after which a statement is aborted by the database (never if not set).
+    pub statement_timeout_seconds: Option<u32>,
+
+    /// Duration from which a statement is logged as slow.
+    pub slow_query_threshold_ms: u32,
+
+    /// Whether the migrations are applied when the application starts.
+    pub auto_migrate: bool,
+
+    /// Replication lag from which a read replica is taken out of rotation.
+    pub replica_max_lag_seconds: u32,
+
+    /// Interval between two checks of the replication lag of the read replicas.
+    pub replica_check_interval_seconds: u32,
+}
+
+/// Structure that contains the settings of the cache of the users (stored in Redis).
+#[derive(Clone, Debug, Deserialize)]
+pub struct CacheSettings {
+    /// Whether the lookups of the users are cached.
+    pub enabled: bool,
+
+    /// Duration the authenticated users (used by every authenticated request) are cached.
+    pub auth_user_ttl_seconds: u32,
+
+    /// Duration the users profiles are cached.
+    pub user_ttl_seconds: u32,
+
+    /// Maximum duration of a call to Redis (the lookups fall back to the database after it).
+    pub timeout_ms: u32,
+}
+
+/// Structure that contains the users management settings.
+#[derive(Clone, Debug, Deserialize)]
+pub struct UsersSettings {
+    /// Number of days the deleted users are kept (and can be restored) before being purged.
+    pub deleted_retention_days: u32,
+
+    /// Interval between two purges of the deleted users (run by the worker).
+    pub purge_interval_minutes: u32,
+}
+
+/// Structure that contains the self-registration settings.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RegistrationSettings {
+    /// Whether the users can register themselves.
+    pub enabled: bool,
+
+    /// Domains of the emails allowed to register (all domains are allowed if empty).
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+
+    /// Domains of the emails denied to register (checked after the allowed ones).
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+}
+
+impl RegistrationSettings {
+    /// Checks if an email can be used to register.
+    ///
+    /// # Arguments
+    /// * `email`: Email to be checked.
+    ///
+    /// # Returns
+    /// `true` if the domain of the email is allowed and not denied.
+    pub fn is_email_allowed(&self, email: &str) -> bool {
+        let Some((_, domain)) = email.rsplit_once('@') else {
+            return false;
+        };
+
+        let matches = |domains: &[String]| domains.iter().any(|d| d.eq_ignore_ascii_case(domain));
+
+        (self.allowed_domains.is_empty() || matches(&self.allowed_domains))
+            && !matches(&self.denied_domains)
+    }
+}
+
+/// Structure that contains all bearer tokens settings. The signing keys are provided by
+/// environment variables.
+#[derive(Clone, Debug, Deserialize)]
+pub struct JwtSettings {
+    /// Algorithm used to sign the access tokens (e.g. `HS256`, `RS256`, `ES256`, `EdDSA`).
+    pub algorithm: String,
+
+    /// Issuer of the access tokens (`iss` claim).
+    pub issuer: String,
+
+    /// Audience of the access tokens (`aud` claim).
+    pub audience: String,
+
+    /// Lifetime of the access tokens.
+    pub access_token_lifetime_minutes: u32,
+
+    /// Lifetime of the refresh tokens.
+    pub refresh_token_lifetime_days: u32,
+}
+
+/// Structure that contains the settings of an OAuth2 / OpenID Connect provider.
+#[derive(Clone, Deserialize, derive_more::Debug)]
+pub struct OAuthProviderSettings {
+    /// Identifier of the application registered at the provider.
+    pub client_id: String,
+
+    /// Secret of the application registered at the provider.
+    #[debug(skip)]
+    pub client_secret: String,
+
+    /// URL of the authorization endpoint (where the user is redirected to login).
+    pub authorize_url: String,
+
+    /// URL of the endpoint used to exchange the authorization code.
+    pub token_url: String,
+
+    /// URL of the endpoint used to get the information of the user.
+    pub userinfo_url: String,
+
+    /// URL of the `/oauth/callback` endpoint, as registered at the provider.
+    pub redirect_url: String,
+
+    /// Scopes requested.
+    pub scopes: Vec<String>,
+}
+
+/// Structure that contains all OAuth2 / OpenID Connect settings.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct OAuthSettings {
+    /// Providers that can be used to login (indexed by name).
+    #[serde(default)]
+    pub providers: HashMap<String, OAuthProviderSettings>,
 }
 
 /// Structure that contains all passwords's pattern settings.
@@ -116,8 +336,21 @@ pub struct Config {
     /// Sessions configuration.
     pub sessions: SessionsSettings,
 
+    /// Database configuration.
+    pub database: DatabaseSettings,
+
+    /// Cache configuration.
+    pub cache: CacheSettings,
+
     /// Authentication configuration.
     pub auth: AuthSettings,
+
+    /// Users management configuration.
+    pub users: UsersSettings,
+
+    /// OAuth2 / OpenID Connect configuration.
+    #[serde(default)]
+    pub oauth: OAuthSettings,
 }
 
 /// Possible environment values.
@@ -4,5 +4,8 @@
 mod config;
 mod error;
 
-pub use config::{Config, Environment};
+pub use config::{
+    CacheSettings, Config, DatabaseSettings, Environment, JwtSettings, OAuthProviderSettings,
+    OAuthSettings, RateLimitSettings, RegistrationSettings, SessionStoreKind, UsersSettings,
+};
 pub use error::Error;
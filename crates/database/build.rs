@@ -0,0 +1,6 @@
+//! Build script used to rebuild the crate when migrations are added (they're embedded by
+//! `sqlx::migrate!`).
+
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,12 @@
+-- Create tables
+
+CREATE TABLE user_sessions (
+    id            UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id       UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ip_address    VARCHAR,
+    user_agent    VARCHAR,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    last_seen_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX user_sessions_user_id_idx ON user_sessions(user_id);
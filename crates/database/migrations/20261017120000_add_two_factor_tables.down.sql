@@ -0,0 +1,5 @@
+-- Drop tables
+
+DROP TABLE role_policies;
+DROP TABLE user_recovery_codes;
+DROP TABLE user_totp;
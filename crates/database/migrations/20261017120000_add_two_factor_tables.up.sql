@@ -0,0 +1,25 @@
+-- Create tables
+
+CREATE TABLE user_totp (
+    user_id         UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret          VARCHAR NOT NULL,
+    enabled         BOOLEAN NOT NULL DEFAULT FALSE,
+    last_used_step  BIGINT,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE TABLE user_recovery_codes (
+    id          UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code        VARCHAR NOT NULL,
+    used_at     TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX user_recovery_codes_user_id_idx ON user_recovery_codes(user_id);
+
+CREATE TABLE role_policies (
+    role            user_role PRIMARY KEY,
+    totp_required   BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+INSERT INTO role_policies (role) VALUES ('admin'), ('normal'), ('guest');
@@ -0,0 +1,13 @@
+-- Create tables
+
+CREATE TABLE user_identities (
+    id          UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider    VARCHAR NOT NULL,
+    subject     VARCHAR NOT NULL,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+
+    UNIQUE(provider, subject)
+);
+
+CREATE INDEX user_identities_user_id_idx ON user_identities(user_id);
@@ -0,0 +1,3 @@
+-- Drop tables
+
+DROP TABLE user_refresh_tokens;
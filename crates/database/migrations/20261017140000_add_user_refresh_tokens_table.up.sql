@@ -0,0 +1,15 @@
+-- Create tables
+
+CREATE TABLE user_refresh_tokens (
+    id          UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id   UUID NOT NULL,
+    token_hash  VARCHAR NOT NULL,
+    expires_at  TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at     TIMESTAMP WITH TIME ZONE,
+    revoked_at  TIMESTAMP WITH TIME ZONE,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX user_refresh_tokens_user_id_idx ON user_refresh_tokens(user_id);
+CREATE INDEX user_refresh_tokens_family_id_idx ON user_refresh_tokens(family_id);
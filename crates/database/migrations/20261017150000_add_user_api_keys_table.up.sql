@@ -0,0 +1,13 @@
+-- Create tables
+
+CREATE TABLE user_api_keys (
+    id            UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id       UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name          VARCHAR NOT NULL,
+    key_hash      VARCHAR NOT NULL,
+    scopes        VARCHAR[] NOT NULL DEFAULT '{}',
+    last_used_at  TIMESTAMP WITH TIME ZONE,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX user_api_keys_user_id_idx ON user_api_keys(user_id);
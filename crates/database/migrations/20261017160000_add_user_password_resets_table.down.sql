@@ -0,0 +1,3 @@
+-- Drop tables
+
+DROP TABLE user_password_resets;
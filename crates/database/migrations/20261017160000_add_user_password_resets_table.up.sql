@@ -0,0 +1,9 @@
+-- Create tables
+
+CREATE TABLE user_password_resets (
+    id          UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at  TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    UNIQUE(user_id)
+);
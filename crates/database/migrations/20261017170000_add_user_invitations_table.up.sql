@@ -0,0 +1,13 @@
+-- Create tables
+
+CREATE TABLE user_invitations (
+    id          UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_by  UUID REFERENCES users(id) ON DELETE SET NULL,
+    token       UUID NOT NULL DEFAULT uuid_generate_v4(),
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at  TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    UNIQUE(user_id),
+    UNIQUE(token)
+);
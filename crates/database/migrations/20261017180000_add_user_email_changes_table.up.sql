@@ -0,0 +1,15 @@
+-- Create tables
+
+CREATE TABLE user_email_changes (
+    id            UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id       UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    old_email     VARCHAR NOT NULL,
+    new_email     VARCHAR NOT NULL,
+    revert_token  UUID NOT NULL DEFAULT uuid_generate_v4(),
+    confirmed_at  TIMESTAMP WITH TIME ZONE,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at    TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    UNIQUE(user_id),
+    UNIQUE(revert_token)
+);
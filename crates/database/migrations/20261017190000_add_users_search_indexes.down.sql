@@ -0,0 +1,14 @@
+-- Drop indexes
+
+DROP INDEX users_search_text_trgm_idx;
+DROP INDEX users_email_trgm_idx;
+DROP INDEX users_last_name_trgm_idx;
+DROP INDEX users_first_name_trgm_idx;
+
+-- Update tables
+
+ALTER TABLE users DROP COLUMN search_text;
+
+-- Drop extensions
+
+DROP EXTENSION pg_trgm;
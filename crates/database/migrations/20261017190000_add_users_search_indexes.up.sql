@@ -0,0 +1,16 @@
+-- Create needed extensions
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Update tables
+
+ALTER TABLE users ADD COLUMN search_text TEXT GENERATED ALWAYS AS (
+    LOWER(COALESCE(first_name, '') || ' ' || COALESCE(last_name, '') || ' ' || email)
+) STORED;
+
+-- Create indexes
+
+CREATE INDEX users_first_name_trgm_idx ON users USING GIN (first_name gin_trgm_ops);
+CREATE INDEX users_last_name_trgm_idx ON users USING GIN (last_name gin_trgm_ops);
+CREATE INDEX users_email_trgm_idx ON users USING GIN (email gin_trgm_ops);
+CREATE INDEX users_search_text_trgm_idx ON users USING GIN (search_text gin_trgm_ops);
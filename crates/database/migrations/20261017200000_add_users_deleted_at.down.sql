@@ -0,0 +1,7 @@
+-- Drop indexes
+
+DROP INDEX users_deleted_at_idx;
+
+-- Update tables
+
+ALTER TABLE users DROP COLUMN deleted_at;
@@ -0,0 +1,17 @@
+-- Create tables
+
+-- No foreign keys: the entries must outlive the users (that can be purged).
+CREATE TABLE audit_logs (
+    id          UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    actor_id    UUID,
+    user_id     UUID,
+    action      VARCHAR NOT NULL,
+    changes     JSONB NOT NULL DEFAULT '{}',
+    request_id  VARCHAR,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX audit_logs_created_at_idx ON audit_logs(created_at, id);
+CREATE INDEX audit_logs_actor_id_idx ON audit_logs(actor_id, created_at);
+CREATE INDEX audit_logs_user_id_idx ON audit_logs(user_id, created_at);
+CREATE INDEX audit_logs_action_idx ON audit_logs(action, created_at);
@@ -0,0 +1,2 @@
+-- Nothing to do: the demo users are restored by the development seed.
+SELECT 1;
@@ -0,0 +1,7 @@
+-- Remove the demo users inserted by the creation of the users table (they're now part of the
+-- development seed, see `crates/database/seeds`). Only the untouched accounts are removed.
+
+DELETE FROM users
+WHERE
+    (email = 'john@doe.com' AND password = '$argon2id$v=19$m=16,t=2,p=1$YWJjZGVmZ2hpamtsbW5vcA$zs3MjnjdDjde5NfooJ0f+g')
+    OR (email = 'jane@doe.com' AND password = '$argon2id$v=19$m=16,t=2,p=1$YWJjZGVmZ2hpamtsbW5vcA$4kRXsgWWfcwrxbN9NOkX0A');
@@ -0,0 +1,11 @@
+-- Drop triggers
+
+DROP TRIGGER bump_token_version ON users;
+
+-- Drop functions
+
+DROP FUNCTION bump_token_version();
+
+-- Update tables
+
+ALTER TABLE users DROP COLUMN token_version;
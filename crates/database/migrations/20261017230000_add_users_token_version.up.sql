@@ -0,0 +1,30 @@
+-- Update tables
+
+-- Version of the access tokens of a user: the tokens issued before its last increment are
+-- rejected
+ALTER TABLE users ADD COLUMN token_version INTEGER NOT NULL DEFAULT 0;
+
+-- Create functions
+
+CREATE FUNCTION bump_token_version()
+RETURNS TRIGGER
+AS
+$$
+BEGIN
+    IF (
+        new.password IS DISTINCT FROM old.password OR
+        new.deleted_at IS DISTINCT FROM old.deleted_at
+    ) THEN
+        new.token_version := old.token_version + 1;
+    END IF;
+
+    RETURN new;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Create triggers
+
+-- The access tokens are revoked when the password changes (reset, erasure, etc) or when the user
+-- is deleted or restored
+CREATE TRIGGER bump_token_version BEFORE UPDATE ON users
+FOR EACH ROW EXECUTE PROCEDURE bump_token_version();
@@ -0,0 +1,9 @@
+-- Demo users (must never be inserted in production)
+INSERT INTO users (first_name, last_name, email, role, password)
+VALUES
+    -- Original password: johndoeisthebest
+    ('John', 'Doe', 'john@doe.com', 'admin', '$argon2id$v=19$m=16,t=2,p=1$YWJjZGVmZ2hpamtsbW5vcA$zs3MjnjdDjde5NfooJ0f+g'),
+
+    -- Original password: nothisisjaneofcourse
+    ('Jane', 'Doe', 'jane@doe.com', 'normal', '$argon2id$v=19$m=16,t=2,p=1$YWJjZGVmZ2hpamtsbW5vcA$4kRXsgWWfcwrxbN9NOkX0A')
+ON CONFLICT (email) DO NOTHING;
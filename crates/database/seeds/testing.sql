@@ -1,4 +1,5 @@
-DELETE FROM users WHERE email = ANY(ARRAY['giga@chad.com', 'nor@mal.com', 'gue@st.com']);
+-- Users of the API tests (password: 1aA#bbbb)
+DELETE FROM users WHERE email = ANY(ARRAY['giga@chad.com', 'nor@mal.com', 'gue@st.com', 'john@import.com', 'jane@import.com']);
 
 INSERT INTO users (id, first_name, last_name, email, role, password)
 VALUES
@@ -0,0 +1,219 @@
+//! Read-through cache stored in Redis.
+
+use bb8_redis::redis;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use common_state::RedisPool;
+use configuration::CacheSettings;
+
+use crate::prelude::*;
+
+/// Prefix of the Redis keys used to store the cached entries.
+const REDIS_KEY_PREFIX: &str = "cache:";
+
+/// Hit and miss counts of the caches (since the application started), by name of cache.
+static STATS: Mutex<BTreeMap<&'static str, CacheStats>> = Mutex::new(BTreeMap::new());
+
+/// Hit and miss counts of a cache.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CacheStats {
+    /// Number of lookups found in the cache.
+    pub hits: u64,
+
+    /// Number of lookups not found in the cache.
+    pub misses: u64,
+}
+
+/// Cache handle. The cache is an optimization: its errors are logged and the lookups fall back
+/// to the database.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    /// Redis database handle.
+    pool: RedisPool,
+
+    /// Cache settings.
+    settings: CacheSettings,
+}
+
+impl Cache {
+    /// Creates a new cache handle.
+    ///
+    /// # Arguments
+    /// * `pool` - Redis database handle.
+    /// * `settings` - Cache settings.
+    ///
+    /// # Returns
+    /// A new instance of Cache.
+    pub fn new(pool: RedisPool, settings: CacheSettings) -> Self {
+        Self { pool, settings }
+    }
+
+    /// Gets the cache settings.
+    ///
+    /// # Returns
+    /// The cache settings.
+    pub fn settings(&self) -> &CacheSettings {
+        &self.settings
+    }
+
+    /// Gets an entry from the cache and counts the hit or the miss.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the cache (used for the counts).
+    /// * `key` - Key of the entry.
+    ///
+    /// # Returns
+    /// The value of the entry, or None if not found (or if the cache is disabled).
+    pub async fn get<T: DeserializeOwned>(&self, name: &'static str, key: &str) -> Option<T> {
+        if !self.settings.enabled {
+            return None;
+        }
+
+        let value = match self.bounded(self.get_raw(key)).await {
+            Ok(Some(value)) => serde_json::from_str(&value)
+                .inspect_err(|e| event!(Level::WARN, "Invalid cache entry {key}: {e}"))
+                .ok(),
+
+            Ok(None) => None,
+
+            Err(e) => {
+                event!(Level::WARN, "Cannot read cache entry {key}: {e}");
+                None
+            }
+        };
+
+        count(name, value.is_some());
+
+        value
+    }
+
+    /// Stores an entry in the cache.
+    ///
+    /// # Arguments
+    /// * `key` - Key of the entry.
+    /// * `value` - Value of the entry.
+    /// * `ttl_seconds` - Duration the entry is kept.
+    pub async fn set<T: Serialize>(&self, key: &str, value: &T, ttl_seconds: u32) {
+        if !self.settings.enabled || ttl_seconds == 0 {
+            return;
+        }
+
+        if let Err(e) = self.bounded(self.set_raw(key, value, ttl_seconds)).await {
+            event!(Level::WARN, "Cannot write cache entry {key}: {e}");
+        }
+    }
+
+    /// Removes entries from the cache (done even if the cache is disabled so that no stale entry
+    /// is left when it's enabled again).
+    ///
+    /// # Arguments
+    /// * `keys` - Keys of the entries.
+    pub async fn delete(&self, keys: &[String]) {
+        if keys.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.bounded(self.delete_raw(keys)).await {
+            event!(
+                Level::ERROR,
+                "Cannot invalidate cache entries {keys:?}: {e}"
+            );
+        }
+    }
+
+    /// Bounds the duration of a call to Redis (so that the cache never slows the requests down
+    /// when Redis is unreachable).
+    async fn bounded<T>(&self, call: impl Future<Output = ApiResult<T>>) -> ApiResult<T> {
+        let timeout = Duration::from_millis(self.settings.timeout_ms.into());
+
+        tokio::time::timeout(timeout, call)
+            .await
+            .map_err(|_| Error::Cache("Timed out".to_string()))?
+    }
+
+    /// Reads the raw value of an entry.
+    async fn get_raw(&self, key: &str) -> ApiResult<Option<String>> {
+        let mut conn = self.pool.get().await.map_err(redis_error)?;
+
+        Ok(redis::cmd("GET")
+            .arg(redis_key(key))
+            .query_async(&mut *conn)
+            .await?)
+    }
+
+    /// Writes the value of an entry with its expiration.
+    async fn set_raw<T: Serialize>(&self, key: &str, value: &T, ttl_seconds: u32) -> ApiResult<()> {
+        let value = serde_json::to_string(value).map_err(|e| Error::Cache(e.to_string()))?;
+        let mut conn = self.pool.get().await.map_err(redis_error)?;
+
+        Ok(redis::cmd("SET")
+            .arg(redis_key(key))
+            .arg(value)
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async(&mut *conn)
+            .await?)
+    }
+
+    /// Deletes entries.
+    async fn delete_raw(&self, keys: &[String]) -> ApiResult<()> {
+        let mut conn = self.pool.get().await.map_err(redis_error)?;
+
+        Ok(redis::cmd("DEL")
+            .arg(keys.iter().map(|key| redis_key(key)).collect::<Vec<_>>())
+            .query_async(&mut *conn)
+            .await?)
+    }
+}
+
+/// Gets the hit and miss counts of the caches.
+///
+/// # Returns
+/// The counts by name of cache.
+pub fn cache_stats() -> BTreeMap<&'static str, CacheStats> {
+    STATS.lock().map(|stats| stats.clone()).unwrap_or_default()
+}
+
+/// Counts a lookup.
+///
+/// # Arguments
+/// * `name` - Name of the cache.
+/// * `hit` - Whether the entry was found.
+fn count(name: &'static str, hit: bool) {
+    if let Ok(mut stats) = STATS.lock() {
+        let stats = stats.entry(name).or_default();
+
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+    }
+}
+
+/// Builds the Redis key of an entry.
+///
+/// # Arguments
+/// * `key`: Key of the entry.
+///
+/// # Returns
+/// The Redis key.
+fn redis_key(key: &str) -> String {
+    format!("{REDIS_KEY_PREFIX}{key}")
+}
+
+/// Converts an error of the Redis pool.
+///
+/// # Arguments
+/// * `e`: Error to be converted.
+///
+/// # Returns
+/// The error of this crate.
+fn redis_error(e: impl std::fmt::Display) -> Error {
+    Error::Cache(e.to_string())
+}
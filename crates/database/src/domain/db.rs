@@ -7,26 +7,172 @@ use futures_util::TryStreamExt;
 use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
 use sqlx::{ConnectOptions, Database, Describe, Either, Execute, Executor, Postgres, Transaction};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::log::LevelFilter;
 
-use common_state::RedisPool;
+use common_state::{RedisPool, Replicas};
+use configuration::DatabaseSettings;
 
+use crate::domain::migration::migrate_up;
+use crate::domain::replica::{connect_replicas, is_read_only, monitor_replicas};
 use crate::prelude::*;
 
 // Alias for a shared database handle wrapped in an `Arc<Mutex<_>>`.
 pub type SharedDb = Arc<Mutex<Db>>;
 
+// Alias for a transaction shared by all the clones of a `Db`.
+pub(crate) type SharedTransaction = Arc<Mutex<TransactionState>>;
+
 /// PostgreSQL database handle.
 #[derive(Clone, Debug)]
 pub struct Db {
-    /// Database pool.
+    /// Database pool (primary database).
     pool: PgPool,
 
+    /// Read replicas used for the read-only statements (may be empty).
+    replicas: Replicas,
+
+    /// Whether the reads use the primary database (set once the handle has written, shared by
+    /// all the clones).
+    read_primary: Arc<AtomicBool>,
+
     /// Optional transaction. If set then this will be used instead of the pool.
-    tx: Option<Arc<Mutex<Transaction<'static, Postgres>>>>,
+    tx: Option<SharedTransaction>,
+}
+
+/// Task run once a transaction is committed.
+type AfterCommit = BoxFuture<'static, ()>;
+
+/// Transaction in progress and the savepoints opened inside of it.
+pub(crate) struct TransactionState {
+    /// Transaction.
+    tx: Transaction<'static, Postgres>,
+
+    /// Number of savepoints currently opened (i.e. depth of the nested transactions).
+    savepoints: usize,
+
+    /// Savepoint to be rolled back before the next statement (set when a unit of work is dropped
+    /// without being committed).
+    pending_rollback: Option<usize>,
+
+    /// Tasks run once the transaction is committed, with the savepoint in which each one has
+    /// been scheduled (0 if none).
+    after_commit: Vec<(usize, AfterCommit)>,
+}
+
+impl std::fmt::Debug for TransactionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransactionState")
+            .field("tx", &self.tx)
+            .field("savepoints", &self.savepoints)
+            .field("pending_rollback", &self.pending_rollback)
+            .field("after_commit", &self.after_commit.len())
+            .finish()
+    }
+}
+
+impl TransactionState {
+    /// Gets the number of savepoints currently opened.
+    pub(crate) fn savepoints(&self) -> usize {
+        self.savepoints
+    }
+
+    /// Opens a new savepoint.
+    async fn savepoint(&mut self) -> Result<(), sqlx::Error> {
+        self.flush().await?;
+
+        self.savepoints += 1;
+
+        let sql = format!("SAVEPOINT {}", savepoint_name(self.savepoints));
+        sqlx::query(&sql).execute(&mut *self.tx).await?;
+
+        Ok(())
+    }
+
+    /// Releases the last savepoint, keeping its changes.
+    async fn release(&mut self) -> Result<(), sqlx::Error> {
+        self.flush().await?;
+
+        let sql = format!("RELEASE SAVEPOINT {}", savepoint_name(self.savepoints));
+        sqlx::query(&sql).execute(&mut *self.tx).await?;
+
+        self.savepoints -= 1;
+
+        // The tasks are now part of the enclosing savepoint
+        for (savepoint, _) in &mut self.after_commit {
+            *savepoint = (*savepoint).min(self.savepoints);
+        }
+
+        Ok(())
+    }
+
+    /// Rolls back the last savepoint, discarding its changes.
+    async fn rollback(&mut self) -> Result<(), sqlx::Error> {
+        self.abort(self.savepoints);
+        self.flush().await
+    }
+
+    /// Marks a savepoint (and the ones opened after it) to be rolled back before the next
+    /// statement. This doesn't require any I/O so that it can be done when a unit of work is
+    /// dropped.
+    ///
+    /// # Arguments
+    /// * `savepoint` - Savepoint to roll back.
+    pub(crate) fn abort(&mut self, savepoint: usize) {
+        if savepoint == 0 || savepoint > self.savepoints {
+            return;
+        }
+
+        self.savepoints = savepoint - 1;
+
+        self.after_commit
+            .retain(|(scheduled, _)| *scheduled < savepoint);
+
+        self.pending_rollback = Some(
+            self.pending_rollback
+                .map_or(savepoint, |pending| pending.min(savepoint)),
+        );
+    }
+
+    /// Schedules a task to be run once the transaction is committed (it's discarded if the
+    /// current savepoint is rolled back).
+    ///
+    /// # Arguments
+    /// * `task` - Task to run.
+    fn schedule(&mut self, task: AfterCommit) {
+        self.after_commit.push((self.savepoints, task));
+    }
+
+    /// Rolls back the savepoint marked by `abort`, if any.
+    async fn flush(&mut self) -> Result<(), sqlx::Error> {
+        if let Some(savepoint) = self.pending_rollback.take() {
+            let name = savepoint_name(savepoint);
+
+            sqlx::query(&format!("ROLLBACK TO SAVEPOINT {name}"))
+                .execute(&mut *self.tx)
+                .await?;
+
+            sqlx::query(&format!("RELEASE SAVEPOINT {name}"))
+                .execute(&mut *self.tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds the name of a savepoint.
+///
+/// # Arguments
+/// * `savepoint` - Depth of the savepoint (starting at 1).
+///
+/// # Returns
+/// The name of the savepoint.
+fn savepoint_name(savepoint: usize) -> String {
+    format!("savepoint_{savepoint}")
 }
 
 impl Db {
@@ -38,7 +184,52 @@ impl Db {
     /// # Returns
     /// A new instance of `Db` initialized with the provided pool and no active transaction.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool, tx: None }
+        Self {
+            pool,
+            replicas: Replicas::default(),
+            read_primary: Arc::default(),
+            tx: None,
+        }
+    }
+
+    /// Uses read replicas for the read-only statements.
+    ///
+    /// # Arguments
+    /// * `replicas` - Read replicas.
+    ///
+    /// # Returns
+    /// The updated `Db` instance.
+    pub fn with_replicas(mut self, replicas: Replicas) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    /// Makes the handle (and its clones) read from the primary database, so that the changes
+    /// just written are visible whatever the replication lag.
+    pub fn read_your_writes(&self) {
+        self.read_primary.store(true, Ordering::Relaxed);
+    }
+
+    /// Selects the pool running a statement outside of a transaction: a replica in rotation for
+    /// a read-only statement, the primary otherwise. Once a write is seen, the handle reads from
+    /// the primary.
+    ///
+    /// # Arguments
+    /// * `sql` - SQL statement.
+    ///
+    /// # Returns
+    /// The pool to be used.
+    pub(crate) fn route(&self, sql: &str) -> PgPool {
+        if self.replicas.is_empty() || self.read_primary.load(Ordering::Relaxed) {
+            return self.pool.clone();
+        }
+
+        if !is_read_only(sql) {
+            self.read_your_writes();
+            return self.pool.clone();
+        }
+
+        self.replicas.pick().unwrap_or_else(|| self.pool.clone())
     }
 
     /// Converts the `Db` instance into a shared instance wrapped in an `Arc<Mutex<_>>`.
@@ -49,32 +240,128 @@ impl Db {
         Arc::new(Mutex::new(self))
     }
 
-    /// Starts a new transaction.
+    /// Starts a new transaction. If a transaction is already in progress, a savepoint is opened
+    /// instead so that the nested transaction can be committed or rolled back on its own.
+    ///
+    /// Once the transaction is started, the handle reads from the primary database (even after
+    /// the commit, so that the changes are visible whatever the replication lag).
     ///
     /// # Returns
     /// An `ApiResult` indicating success or failure.
     pub async fn start_transaction(&mut self) -> ApiResult<()> {
-        let tx = self.pool.begin().await?;
+        self.read_your_writes();
+
+        match &self.tx {
+            Some(tx) => tx.lock().await.savepoint().await?,
 
-        self.tx = Some(Arc::new(Mutex::new(tx)));
+            None => {
+                let tx = self.pool.begin().await?;
+
+                self.tx = Some(Arc::new(Mutex::new(TransactionState {
+                    tx,
+                    savepoints: 0,
+                    pending_rollback: None,
+                    after_commit: Vec::new(),
+                })));
+            }
+        }
 
         Ok(())
     }
 
-    /// Commits the current transaction, if any.
+    /// Commits the current transaction (or releases the last savepoint), if any. The tasks
+    /// scheduled by `after_commit` are run once the transaction is committed.
     ///
     /// # Returns
     /// An `ApiResult` indicating success or failure.
     pub async fn commit_transaction(&mut self) -> ApiResult<()> {
+        if let Some(tx) = &self.tx {
+            let mut state = tx.lock().await;
+
+            if state.savepoints > 0 {
+                return Ok(state.release().await?);
+            }
+        }
+
+        if let Some(tx) = self.tx.take() {
+            let mut state = Arc::into_inner(tx)
+                .ok_or(Error::TransactionInUse)?
+                .into_inner();
+
+            state.flush().await?;
+            state.tx.commit().await?;
+
+            for (_, task) in state.after_commit {
+                task.await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rolls back the current transaction (or the last savepoint), if any.
+    ///
+    /// # Returns
+    /// An `ApiResult` indicating success or failure.
+    pub async fn rollback_transaction(&mut self) -> ApiResult<()> {
+        if let Some(tx) = &self.tx {
+            let mut state = tx.lock().await;
+
+            if state.savepoints > 0 {
+                return Ok(state.rollback().await?);
+            }
+        }
+
         if let Some(tx) = self.tx.take() {
-            if let Some(tx) = Arc::into_inner(tx) {
-                let tx = tx.into_inner();
-                tx.commit().await?;
+            // If still in use, the transaction is rolled back when the last clone is dropped
+            if let Some(state) = Arc::into_inner(tx) {
+                state.into_inner().tx.rollback().await?;
             }
         }
 
         Ok(())
     }
+
+    /// Runs a task once the changes made with the handle are committed: right away outside of a
+    /// transaction, after the commit of the transaction in progress otherwise. The task is
+    /// discarded if the changes are rolled back.
+    ///
+    /// # Arguments
+    /// * `task` - Task to run.
+    pub async fn after_commit(&self, task: BoxFuture<'static, ()>) {
+        match &self.tx {
+            Some(tx) => tx.lock().await.schedule(task),
+            None => task.await,
+        }
+    }
+
+    /// Checks if a transaction is in progress on the handle.
+    ///
+    /// # Returns
+    /// `true` if the statements run in a transaction.
+    pub fn in_transaction(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// Gets the transaction in progress, if any.
+    pub(crate) fn transaction(&self) -> Option<&SharedTransaction> {
+        self.tx.as_ref()
+    }
+
+    /// Forgets the transaction in progress if it's the one given (it's rolled back as soon as
+    /// it's not used anymore).
+    ///
+    /// # Arguments
+    /// * `tx` - Transaction to forget.
+    pub(crate) fn discard_transaction(&mut self, tx: &Weak<Mutex<TransactionState>>) {
+        if self
+            .tx
+            .as_ref()
+            .is_some_and(|current| std::ptr::eq(Arc::as_ptr(current), tx.as_ptr()))
+        {
+            self.tx = None;
+        }
+    }
 }
 
 impl<'c> Executor<'c> for Db {
@@ -95,14 +382,15 @@ impl<'c> Executor<'c> for Db {
     {
         if let Some(tx) = self.tx {
             Box::pin(async_stream::try_stream! {
-                let mut guard = tx.lock().await;
-                let mut stream = guard.fetch_many(query);
+                let mut state = tx.lock().await;
+                state.flush().await?;
+                let mut stream = state.tx.fetch_many(query);
                 while let Some(item) = stream.try_next().await? {
                     yield item;
                 }
             })
         } else {
-            self.pool.fetch_many(query)
+            self.route(query.sql()).fetch_many(query)
         }
     }
 
@@ -115,11 +403,12 @@ impl<'c> Executor<'c> for Db {
     {
         if let Some(tx) = self.tx {
             Box::pin(async move {
-                let mut tx = tx.lock().await;
-                tx.fetch_optional(query).await
+                let mut state = tx.lock().await;
+                state.flush().await?;
+                state.tx.fetch_optional(query).await
             })
         } else {
-            self.pool.fetch_optional(query)
+            self.route(query.sql()).fetch_optional(query)
         }
     }
 
@@ -130,8 +419,9 @@ impl<'c> Executor<'c> for Db {
     ) -> BoxFuture<'e, Result<<Self::Database as Database>::Statement<'q>, sqlx::Error>> {
         if let Some(tx) = self.tx {
             Box::pin(async move {
-                let mut tx = tx.lock().await;
-                tx.prepare_with(sql, parameters).await
+                let mut state = tx.lock().await;
+                state.flush().await?;
+                state.tx.prepare_with(sql, parameters).await
             })
         } else {
             self.pool.prepare_with(sql, parameters)
@@ -144,8 +434,9 @@ impl<'c> Executor<'c> for Db {
     ) -> BoxFuture<'e, Result<Describe<Self::Database>, sqlx::Error>> {
         if let Some(tx) = self.tx {
             Box::pin(async move {
-                let mut tx = tx.lock().await;
-                tx.describe(sql).await
+                let mut state = tx.lock().await;
+                state.flush().await?;
+                state.tx.describe(sql).await
             })
         } else {
             self.pool.describe(sql)
@@ -153,33 +444,109 @@ impl<'c> Executor<'c> for Db {
     }
 }
 
-/// Initialize the database connection and run migrations.
+/// Builds the options of the connections to a PostgreSQL database.
+///
+/// # Arguments
+/// * `settings` - Database settings.
+/// * `url` - URL of the database.
+///
+/// # Returns
+/// A result with the connection options.
+pub(crate) fn connect_options(
+    settings: &DatabaseSettings,
+    url: &str,
+) -> ApiResult<PgConnectOptions> {
+    let mut options = PgConnectOptions::from_str(url)?
+        .log_statements(LevelFilter::Off)
+        .log_slow_statements(
+            LevelFilter::Warn,
+            Duration::from_millis(settings.slow_query_threshold_ms.into()),
+        );
+
+    if let Some(timeout) = settings.statement_timeout_seconds {
+        options = options.options([("statement_timeout", format!("{timeout}s"))]);
+    }
+
+    Ok(options)
+}
+
+/// Builds the options of a pool of connections to a PostgreSQL database.
+///
+/// # Arguments
+/// * `settings` - Database settings.
+///
+/// # Returns
+/// The pool options.
+pub(crate) fn pool_options(settings: &DatabaseSettings) -> PgPoolOptions {
+    let seconds = |value: Option<u32>| value.map(|value| Duration::from_secs(value.into()));
+
+    PgPoolOptions::new()
+        .min_connections(settings.min_connections)
+        .max_connections(settings.max_connections)
+        .acquire_timeout(Duration::from_secs(settings.acquire_timeout_seconds.into()))
+        .idle_timeout(seconds(settings.idle_timeout_seconds))
+        .max_lifetime(seconds(settings.max_lifetime_seconds))
+}
+
+/// Connects to the PostgreSQL database (without running the migrations).
+///
+/// # Arguments
+/// * `settings` - Database settings.
+/// * `db_env_variable` - Environment variable used to get the URL of the SQL database.
+///
+/// # Returns
+/// A result with the PostgresSQL pool.
+pub async fn connect(
+    settings: &DatabaseSettings,
+    db_env_variable: Option<&str>,
+) -> ApiResult<PgPool> {
+    let db_url = std::env::var(db_env_variable.unwrap_or("DATABASE_URL")).map_err(Error::Env)?;
+
+    Ok(pool_options(settings)
+        .connect_with(connect_options(settings, &db_url)?)
+        .await?)
+}
+
+/// Initialize the database connection and run migrations (if enabled). The read replicas
+/// listed in `DATABASE_REPLICA_URLS` (if any) are monitored in the background.
 ///
 /// # Arguments
+/// * `settings` - Database settings.
 /// * `db_env_variable` - Environment variable used to get the URL of the SQL database.
 /// * `redis_env_variable` - Environment variable used to get the URL of the Redis database.
 ///
-/// # Returns
-/// A result with the PostgresSQL pool and the Redis pool.
+/// # Returns
+/// A result with the PostgresSQL pool, the read replicas and the Redis pool.
 pub async fn initialize(
+    settings: &DatabaseSettings,
     db_env_variable: Option<&str>,
     redis_env_variable: Option<&str>,
-) -> ApiResult<(PgPool, RedisPool)> {
+) -> ApiResult<(PgPool, Replicas, RedisPool)> {
     // PostgresSQL
-    let db_url = std::env::var(db_env_variable.unwrap_or("DATABASE_URL")).map_err(Error::Env)?;
+    let pg_pool = connect(settings, db_env_variable).await?;
 
-    let options = PgConnectOptions::from_str(&db_url)?
-        .log_statements(LevelFilter::Off)
-        .log_slow_statements(LevelFilter::Warn, Duration::from_secs(1));
+    if settings.auto_migrate {
+        migrate_up(&pg_pool).await?;
+    } else {
+        event!(
+            Level::INFO,
+            "Migrations not applied (auto_migrate disabled)"
+        );
+    }
 
-    let pg_pool = PgPoolOptions::new()
-        .max_connections(8)
-        .connect_with(options)
-        .await?;
+    event!(Level::DEBUG, "PostgresSQL initialized");
 
-    sqlx::migrate!().run(&pg_pool).await?;
+    // Read replicas
+    let replicas = connect_replicas(settings)?;
+    monitor_replicas(replicas.clone(), settings);
 
-    event!(Level::DEBUG, "PostgresSQL initialized");
+    if !replicas.is_empty() {
+        event!(
+            Level::DEBUG,
+            "{} read replica(s) initialized",
+            replicas.all().len()
+        );
+    }
 
     // Redis
     let db_url = std::env::var(redis_env_variable.unwrap_or("REDIS_URL")).map_err(Error::Env)?;
@@ -189,5 +556,5 @@ pub async fn initialize(
 
     event!(Level::DEBUG, "Redis initialized");
 
-    Ok((pg_pool, redis_pool))
+    Ok((pg_pool, replicas, redis_pool))
 }
@@ -9,10 +9,18 @@ pub type ApiResult<T> = Result<T, Error>;
 /// Enumerates the possible errors used in this crate.
 #[derive(Debug, Error)]
 pub enum Error {
+    /// Cache error (Redis pool or serialization).
+    #[error("Cache error: {0}")]
+    Cache(String),
+
     /// Generic environment variable error.
     #[error("{0}")]
     Env(#[source] std::env::VarError),
 
+    /// Generic filesystem error.
+    #[error(transparent)]
+    Filesystem(#[from] utils::error::Error),
+
     /// SQLx migration error.
     #[error(transparent)]
     Migration(#[from] sqlx::migrate::MigrateError),
@@ -25,7 +33,15 @@ pub enum Error {
     #[error(transparent)]
     Redis(#[from] bb8_redis::redis::RedisError),
 
+    /// No seed data for the environment.
+    #[error("No seed for environment {0}")]
+    SeedNotFound(String),
+
     /// Generic SQLx error.
     #[error(transparent)]
     SQLx(#[from] sqlx::Error),
+
+    /// The transaction can't be committed as it's still used by another handle.
+    #[error("Transaction still in use")]
+    TransactionInUse,
 }
@@ -0,0 +1,132 @@
+//! Management of the migrations and of the seed data of the database.
+
+use sqlx::migrate::{Migrate, Migrator};
+use sqlx::postgres::PgPool;
+
+use utils::filesystem::{relative_path, root_relative_path};
+
+use crate::prelude::*;
+
+/// Migrations embedded in the application.
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// State of a migration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationStatus {
+    /// Version of the migration (i.e. its timestamp).
+    pub version: i64,
+
+    /// Description of the migration.
+    pub description: String,
+
+    /// Whether the migration has been applied.
+    pub applied: bool,
+}
+
+/// Applies all the pending migrations.
+///
+/// # Arguments
+/// * `pool` - PostgreSQL pool.
+///
+/// # Returns
+/// An `ApiResult` indicating success or failure.
+pub async fn migrate_up(pool: &PgPool) -> ApiResult<()> {
+    MIGRATOR.run(pool).await?;
+
+    Ok(())
+}
+
+/// Reverts the last migration applied.
+///
+/// # Arguments
+/// * `pool` - PostgreSQL pool.
+///
+/// # Returns
+/// The version of the migration reverted (or None if no migration is applied).
+pub async fn migrate_down(pool: &PgPool) -> ApiResult<Option<i64>> {
+    let mut applied = applied_versions(pool).await?;
+
+    let Some(last) = applied.pop() else {
+        return Ok(None);
+    };
+
+    // All the migrations above the target are reverted
+    MIGRATOR.undo(pool, applied.pop().unwrap_or(0)).await?;
+
+    Ok(Some(last))
+}
+
+/// Lists the migrations and whether they're applied.
+///
+/// # Arguments
+/// * `pool` - PostgreSQL pool.
+///
+/// # Returns
+/// The list of migrations (oldest first).
+pub async fn migrate_status(pool: &PgPool) -> ApiResult<Vec<MigrationStatus>> {
+    let applied = applied_versions(pool).await?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.contains(&migration.version),
+        })
+        .collect())
+}
+
+/// Inserts the seed data of an environment (e.g. demo accounts for the development). Seeds are
+/// SQL files named after the environments, kept apart from the migrations so that they're never
+/// applied implicitly.
+///
+/// # Arguments
+/// * `pool` - PostgreSQL pool.
+/// * `environment` - Name of the environment.
+///
+/// # Returns
+/// An `ApiResult` indicating success or failure.
+pub async fn seed(pool: &PgPool, environment: &str) -> ApiResult<()> {
+    let seeds_dir = relative_path("data/seeds").or(root_relative_path("crates/database/seeds"))?;
+
+    let path = seeds_dir.join(format!("{environment}.sql"));
+
+    if !path.is_file() {
+        return Err(Error::SeedNotFound(environment.to_string()));
+    }
+
+    let sql = std::fs::read_to_string(&path).map_err(utils::error::Error::Filesystem)?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(&sql).execute(&mut *tx).await?;
+    tx.commit().await?;
+
+    event!(Level::INFO, "Seed {path:?} applied");
+
+    Ok(())
+}
+
+/// Lists the versions of the migrations applied.
+///
+/// # Arguments
+/// * `pool` - PostgreSQL pool.
+///
+/// # Returns
+/// The list of versions (oldest first).
+async fn applied_versions(pool: &PgPool) -> ApiResult<Vec<i64>> {
+    let mut conn = pool.acquire().await?;
+
+    conn.ensure_migrations_table().await?;
+
+    let mut versions = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect::<Vec<_>>();
+
+    versions.sort_unstable();
+
+    Ok(versions)
+}
@@ -1,4 +1,8 @@
 //! List of all entities used in this crate and eventually by other crates.
 
+pub(crate) mod cache;
 pub(crate) mod db;
 pub(crate) mod error;
+pub(crate) mod migration;
+pub(crate) mod replica;
+pub(crate) mod unit_of_work;
@@ -0,0 +1,377 @@
+//! Unit of work: a transaction (or a savepoint if nested) that is rolled back unless committed.
+
+use futures_core::future::BoxFuture;
+use std::sync::{Arc, Weak};
+
+use tokio::sync::Mutex;
+
+use crate::domain::db::{SharedDb, TransactionState};
+use crate::prelude::*;
+
+/// Scope guard of a transaction. If it's dropped without being committed (e.g. when a use-case
+/// returns early on error), all the changes made since it has been started are rolled back.
+///
+/// Units of work can be nested (a savepoint is used for the inner ones) and must be committed
+/// or rolled back in the reverse order of their creation.
+///
+/// ```ignore
+/// let uow = UnitOfWork::begin(&db).await?;
+///
+/// store.create(data).await?; // rolled back if the next call fails
+/// store.update(id, data).await?;
+///
+/// uow.commit().await?;
+/// ```
+#[derive(Debug)]
+pub struct UnitOfWork {
+    /// Database handle (None if detached, see `DetachedDb`).
+    db: Option<SharedDb>,
+
+    /// Transaction in which the unit of work has been started.
+    tx: Weak<Mutex<TransactionState>>,
+
+    /// Savepoint opened by this unit of work (0 if it has started the transaction).
+    savepoint: usize,
+
+    /// Whether the unit of work has been committed or rolled back.
+    done: bool,
+}
+
+impl UnitOfWork {
+    /// Starts a new unit of work: a transaction, or a savepoint if a transaction is already in
+    /// progress on the database handle (all the stores sharing this handle are part of it).
+    ///
+    /// # Arguments
+    /// * `db` - Database handle.
+    ///
+    /// # Returns
+    /// A `UnitOfWork` instance.
+    pub async fn begin(db: &SharedDb) -> ApiResult<Self> {
+        let mut guard = db.lock().await;
+
+        guard.start_transaction().await?;
+
+        let tx = guard.transaction().ok_or(Error::NotFound)?;
+        let savepoint = tx.lock().await.savepoints();
+
+        Ok(Self {
+            db: Some(db.clone()),
+            tx: Arc::downgrade(tx),
+            savepoint,
+            done: false,
+        })
+    }
+
+    /// Creates a unit of work that isn't bound to any database: committing it or rolling it
+    /// back does nothing.
+    ///
+    /// # Returns
+    /// A `UnitOfWork` instance.
+    pub fn detached() -> Self {
+        Self {
+            db: None,
+            tx: Weak::new(),
+            savepoint: 0,
+            done: true,
+        }
+    }
+
+    /// Commits the changes made since the unit of work has been started.
+    ///
+    /// # Returns
+    /// An `ApiResult` indicating success or failure.
+    pub async fn commit(mut self) -> ApiResult<()> {
+        self.done = true;
+
+        match &self.db {
+            Some(db) => db.lock().await.commit_transaction().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Discards the changes made since the unit of work has been started.
+    ///
+    /// # Returns
+    /// An `ApiResult` indicating success or failure.
+    pub async fn rollback(mut self) -> ApiResult<()> {
+        self.done = true;
+
+        match &self.db {
+            Some(db) => db.lock().await.rollback_transaction().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for UnitOfWork {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+
+        let Some(db) = self.db.clone() else {
+            return;
+        };
+
+        event!(Level::DEBUG, "Unit of work dropped: rolling back");
+
+        // Nothing can be awaited here: the rollback is only scheduled and it's performed before
+        // the next statement (or when the transaction is dropped)
+        if self.savepoint > 0 {
+            let Some(tx) = self.tx.upgrade() else {
+                return;
+            };
+
+            let savepoint = self.savepoint;
+
+            if let Ok(mut state) = tx.try_lock() {
+                state.abort(savepoint);
+                return;
+            }
+
+            spawn(async move { tx.lock().await.abort(savepoint) });
+        } else {
+            let tx = self.tx.clone();
+
+            if let Ok(mut db) = db.try_lock() {
+                db.discard_transaction(&tx);
+                return;
+            }
+
+            spawn(async move { db.lock().await.discard_transaction(&tx) });
+        }
+    }
+}
+
+/// Source of the units of work of the use-cases: the database handle shared by their stores, or
+/// a `DetachedDb` when the stores are mocked.
+pub trait Transactional: Send + Sync {
+    /// Starts a new unit of work (see `UnitOfWork::begin`).
+    ///
+    /// # Returns
+    /// A `UnitOfWork` instance.
+    fn begin(&self) -> BoxFuture<'static, ApiResult<UnitOfWork>>;
+}
+
+impl Transactional for SharedDb {
+    fn begin(&self) -> BoxFuture<'static, ApiResult<UnitOfWork>> {
+        let db = self.clone();
+
+        Box::pin(async move { UnitOfWork::begin(&db).await })
+    }
+}
+
+/// Handle without database whose units of work do nothing (used by the unit tests of the
+/// use-cases, their stores being mocked).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DetachedDb;
+
+impl Transactional for DetachedDb {
+    fn begin(&self) -> BoxFuture<'static, ApiResult<UnitOfWork>> {
+        Box::pin(async { Ok(UnitOfWork::detached()) })
+    }
+}
+
+/// Runs a task in the background (used when the rollback can't be scheduled synchronously).
+///
+/// # Arguments
+/// * `task` - Task to run.
+fn spawn<F>(task: F)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(task);
+        }
+        Err(e) => event!(Level::ERROR, "Cannot roll back the unit of work: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+
+    use crate::domain::db::Db;
+
+    async fn setup() -> Result<SharedDb, Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let pool = PgPoolOptions::new()
+            .connect(&std::env::var("DATABASE_URL_TEST")?)
+            .await?;
+
+        Ok(Db::new(pool).into_shared())
+    }
+
+    fn table_name() -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+
+        format!("unit_of_work_{nanos}")
+    }
+
+    async fn execute(db: &SharedDb, sql: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let db = db.lock().await.clone();
+        sqlx::query(sql).execute(db).await?;
+        Ok(())
+    }
+
+    async fn table_exists(db: &SharedDb, table: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let db = db.lock().await.clone();
+
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(table)
+            .fetch_one(db)
+            .await?;
+
+        Ok(exists)
+    }
+
+    #[tokio::test]
+    async fn test_commit_and_rollback() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup().await?;
+        let table = table_name();
+
+        // Rolled back
+        let uow = UnitOfWork::begin(&db).await?;
+        execute(&db, &format!("CREATE TABLE {table} (value INT)")).await?;
+        uow.rollback().await?;
+
+        assert!(!table_exists(&db, &table).await?);
+
+        // Committed
+        let uow = UnitOfWork::begin(&db).await?;
+        execute(&db, &format!("CREATE TABLE {table} (value INT)")).await?;
+        uow.commit().await?;
+
+        assert!(table_exists(&db, &table).await?);
+
+        execute(&db, &format!("DROP TABLE {table}")).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drop() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup().await?;
+        let table = table_name();
+
+        {
+            let _uow = UnitOfWork::begin(&db).await?;
+            execute(&db, &format!("CREATE TABLE {table} (value INT)")).await?;
+        }
+
+        assert!(db.lock().await.transaction().is_none());
+        assert!(!table_exists(&db, &table).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_detached() -> Result<(), Box<dyn std::error::Error>> {
+        DetachedDb.begin().await?.commit().await?;
+        DetachedDb.begin().await?.rollback().await?;
+
+        // Dropped without being committed
+        let _uow = DetachedDb.begin().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nested() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup().await?;
+
+        let uow = UnitOfWork::begin(&db).await?;
+        execute(&db, "CREATE TEMPORARY TABLE nested (value INT)").await?;
+        execute(&db, "INSERT INTO nested VALUES (1)").await?;
+
+        // Rolled back
+        let inner = UnitOfWork::begin(&db).await?;
+        execute(&db, "INSERT INTO nested VALUES (2)").await?;
+        inner.rollback().await?;
+
+        // Dropped, with a unit of work nested in it
+        {
+            let _inner = UnitOfWork::begin(&db).await?;
+            execute(&db, "INSERT INTO nested VALUES (3)").await?;
+
+            let inner = UnitOfWork::begin(&db).await?;
+            execute(&db, "INSERT INTO nested VALUES (4)").await?;
+            inner.commit().await?;
+        }
+
+        // Committed
+        let inner = UnitOfWork::begin(&db).await?;
+        execute(&db, "INSERT INTO nested VALUES (5)").await?;
+        inner.commit().await?;
+
+        let values: Vec<i32> = sqlx::query_scalar("SELECT value FROM nested ORDER BY value")
+            .fetch_all(db.lock().await.clone())
+            .await?;
+
+        assert_eq!(values, vec![1, 5]);
+
+        uow.rollback().await?;
+
+        assert!(db.lock().await.transaction().is_none());
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_after_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup().await?;
+        let done = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let schedule = |value: i32| {
+            let done = done.clone();
+            let db = db.clone();
+
+            async move {
+                let task = Box::pin(async move { done.lock().unwrap().push(value) });
+                db.lock().await.clone().after_commit(task).await;
+            }
+        };
+
+        // Outside of a transaction: run right away
+        schedule(1).await;
+        assert_eq!(*done.lock().unwrap(), vec![1]);
+
+        // Rolled back: discarded
+        let uow = UnitOfWork::begin(&db).await?;
+        schedule(2).await;
+        uow.rollback().await?;
+
+        let uow = UnitOfWork::begin(&db).await?;
+        schedule(3).await;
+
+        // Savepoint rolled back (then dropped): discarded
+        let inner = UnitOfWork::begin(&db).await?;
+        schedule(4).await;
+        inner.rollback().await?;
+
+        {
+            let _inner = UnitOfWork::begin(&db).await?;
+            schedule(5).await;
+        }
+
+        // Savepoint released: run with the transaction
+        let inner = UnitOfWork::begin(&db).await?;
+        schedule(6).await;
+        inner.commit().await?;
+
+        assert_eq!(*done.lock().unwrap(), vec![1]);
+
+        uow.commit().await?;
+        assert_eq!(*done.lock().unwrap(), vec![1, 3, 6]);
+
+        Ok(())
+    }
+}
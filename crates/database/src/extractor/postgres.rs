@@ -17,9 +17,14 @@ where
 {
     type Rejection = (StatusCode, String);
 
-    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Use the handle of the request (transaction, read-your-writes), if any
+        if let Some(db) = parts.extensions.get::<Self>() {
+            return Ok(db.clone());
+        }
+
         let state = AppState::from_ref(state);
 
-        Ok(Self::new(state.pool))
+        Ok(Self::new(state.pool).with_replicas(state.replicas))
     }
 }
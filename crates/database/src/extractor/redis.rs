@@ -1,32 +1,27 @@
-//! Extractor used to obtain the connection instance to the Redis database.
+//! Extractor used to obtain the cache stored in the Redis database.
 
 use async_trait::async_trait;
 use axum::extract::{FromRef, FromRequestParts};
 use axum::http::request::Parts;
 use axum::http::StatusCode;
-use bb8::PooledConnection;
-use bb8_redis::RedisConnectionManager;
 
 use common_state::AppState;
 
-/// Redis connection extractor.
+use crate::domain::cache::Cache;
+
+/// Cache extractor.
 ///
 /// Examples:
 ///
 /// ```ignore
-/// async fn using_connection_extractor(
-///     RedisConnection(mut conn): RedisConnection
-/// ) -> impl IntoResponse {
+/// async fn using_cache_extractor(db: Db, cache: Cache) -> impl IntoResponse {
+///     let db = db.into_shared();
+///     let store = CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache);
 ///     // ...
 /// }
 /// ```
-///
-/// TODO: to be removed as soon as Redis is used
-#[allow(dead_code)]
-struct RedisConnection(PooledConnection<'static, RedisConnectionManager>);
-
 #[async_trait]
-impl<S> FromRequestParts<S> for RedisConnection
+impl<S> FromRequestParts<S> for Cache
 where
     AppState: FromRef<S>,
     S: Send + Sync,
@@ -36,12 +31,6 @@ where
     async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let state = AppState::from_ref(state);
 
-        let conn = state
-            .redis
-            .get_owned()
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-        Ok(Self(conn))
+        Ok(Self::new(state.redis, state.config.cache))
     }
 }
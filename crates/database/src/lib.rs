@@ -1,19 +1,27 @@
 //! The `database`'s crate gathers of database related utilities such as:
 //!
 //! - Initialization of the connection pool to the database.
-//! - Migrations.
+//! - Migrations and seed data.
 //! - Extractors used to access database in endpoints.
+//! - Read-through cache stored in Redis.
+//! - Transactions (units of work and per-request transactions).
+//! - Routing of the read-only statements to the read replicas.
 
 #![forbid(unsafe_code)]
 
 // Modules
 mod domain;
 mod extractor;
+mod middleware;
 mod prelude;
 
 // Exports
-pub use domain::db::{initialize, Db, SharedDb};
+pub use domain::cache::{cache_stats, Cache, CacheStats};
+pub use domain::db::{connect, initialize, Db, SharedDb};
 pub use domain::error::Error;
+pub use domain::migration::{migrate_down, migrate_status, migrate_up, seed, MigrationStatus};
+pub use domain::unit_of_work::{DetachedDb, Transactional, UnitOfWork};
+pub use middleware::transaction::transaction;
 
 // Re-exports
-pub use common_state::RedisPool;
+pub use common_state::{RedisPool, Replicas};
@@ -0,0 +1,3 @@
+//! List of middlewares related to databases.
+
+pub(crate) mod transaction;
@@ -0,0 +1,264 @@
+//! Middleware running each request in its own transaction.
+
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::domain::db::Db;
+use crate::prelude::*;
+
+/// Macro used to create a middleware that runs a request in a transaction (the `Db` extracted
+/// by the handler uses it).
+///
+/// ```ignore
+/// Router::new().route(
+///     "/",
+///     put(upsert_user).route_layer(transaction_layer!(state)),
+/// )
+/// ```
+#[macro_export]
+macro_rules! transaction_layer {
+    ($state: expr) => {
+        axum::middleware::from_fn_with_state($state, $crate::transaction)
+    };
+}
+
+/// Opens a transaction for the request. It's committed if the response is successful (2xx) and
+/// rolled back otherwise. The units of work started by the use-cases become savepoints.
+///
+/// The responses whose body is streamed must not use it: the transaction would still be in use
+/// when committed.
+///
+/// # Arguments
+/// * `db`: Database handle.
+/// * `request`: HTTP request.
+/// * `next`: Next middleware in the chain.
+///
+/// # Returns
+/// Result containing the next response, or an error if the transaction can't be started or
+/// ended.
+pub async fn transaction(
+    mut db: Db,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    db.start_transaction().await.map_err(internal_error)?;
+
+    request.extensions_mut().insert(db.clone());
+
+    let response = next.run(request).await;
+
+    if response.status().is_success() {
+        db.commit_transaction().await.map_err(internal_error)?;
+    } else {
+        db.rollback_transaction().await.map_err(internal_error)?;
+    }
+
+    Ok(response)
+}
+
+/// Converts an error into an internal server error response.
+///
+/// # Arguments
+/// * `error`: Error.
+///
+/// # Returns
+/// The status code and message of the response.
+fn internal_error(error: Error) -> (StatusCode, String) {
+    event!(Level::ERROR, "Request transaction failed: {error}");
+
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::extract::Path;
+    use axum::routing::post;
+    use axum::Router;
+    use bb8_redis::{bb8, RedisConnectionManager};
+    use sqlx::postgres::PgPoolOptions;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use tower::util::ServiceExt;
+
+    use common_state::{AppState, Replicas};
+    use configuration::Config;
+
+    use super::*;
+
+    use crate::domain::db::SharedDb;
+    use crate::domain::unit_of_work::UnitOfWork;
+
+    async fn setup() -> Result<(Router, SharedDb), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let pool = PgPoolOptions::new()
+            .connect(&std::env::var("DATABASE_URL_TEST")?)
+            .await?;
+
+        // Redis is not used by these routes
+        let redis = bb8::Pool::builder()
+            .build_unchecked(RedisConnectionManager::new("redis://127.0.0.1:1")?);
+
+        let state = AppState::new(Config::new()?, pool.clone(), Replicas::default(), redis);
+
+        let router = Router::new()
+            .route("/commit/:table", post(create_table))
+            .route("/reject/:table", post(create_table_and_reject))
+            .route("/fail/:table", post(create_table_and_fail))
+            .route("/nested/:status/:table", post(create_table_nested))
+            .route_layer(crate::transaction_layer!(state.clone()))
+            .with_state(state);
+
+        Ok((router, Db::new(pool).into_shared()))
+    }
+
+    fn table_name() -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+
+        format!("transaction_{nanos}")
+    }
+
+    async fn execute(db: &SharedDb, sql: &str) -> ApiResult<()> {
+        let db = db.lock().await.clone();
+        sqlx::query(sql).execute(db).await?;
+        Ok(())
+    }
+
+    async fn create_table(db: Db, Path(table): Path<String>) -> StatusCode {
+        let db = db.into_shared();
+
+        match execute(&db, &format!("CREATE TABLE {table} (value INT)")).await {
+            Ok(()) => StatusCode::CREATED,
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    async fn create_table_and_reject(db: Db, path: Path<String>) -> StatusCode {
+        create_table(db, path).await;
+
+        StatusCode::BAD_REQUEST
+    }
+
+    async fn create_table_and_fail(db: Db, path: Path<String>) -> StatusCode {
+        create_table(db, path).await;
+
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    async fn create_table_nested(db: Db, Path((status, table)): Path<(u16, String)>) -> StatusCode {
+        let db = db.into_shared();
+
+        let res: ApiResult<()> = async {
+            execute(&db, &format!("CREATE TABLE {table} (value INT)")).await?;
+
+            // Savepoint released: kept unless the request fails
+            let uow = UnitOfWork::begin(&db).await?;
+            execute(&db, &format!("INSERT INTO {table} VALUES (1)")).await?;
+            uow.commit().await?;
+
+            // Savepoint rolled back: the table and the value above are kept
+            let uow = UnitOfWork::begin(&db).await?;
+            execute(&db, &format!("INSERT INTO {table} VALUES (2)")).await?;
+            uow.rollback().await
+        }
+        .await;
+
+        match res {
+            Ok(()) => StatusCode::from_u16(status).unwrap_or(StatusCode::OK),
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    async fn send(router: &Router, uri: &str) -> Result<StatusCode, Box<dyn std::error::Error>> {
+        let request = Request::post(uri).body(Body::empty())?;
+
+        Ok(router.clone().oneshot(request).await?.status())
+    }
+
+    async fn values(
+        db: &SharedDb,
+        table: &str,
+    ) -> Result<Option<Vec<i32>>, Box<dyn std::error::Error>> {
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(table)
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+        if !exists {
+            return Ok(None);
+        }
+
+        let values = sqlx::query_scalar(&format!("SELECT value FROM {table} ORDER BY value"))
+            .fetch_all(db.lock().await.clone())
+            .await?;
+
+        Ok(Some(values))
+    }
+
+    #[tokio::test]
+    async fn test_commit_on_success() -> Result<(), Box<dyn std::error::Error>> {
+        let (router, db) = setup().await?;
+        let table = table_name();
+
+        assert_eq!(
+            send(&router, &format!("/commit/{table}")).await?,
+            StatusCode::CREATED
+        );
+        assert_eq!(values(&db, &table).await?, Some(vec![]));
+
+        execute(&db, &format!("DROP TABLE {table}")).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rollback_on_error() -> Result<(), Box<dyn std::error::Error>> {
+        let (router, db) = setup().await?;
+
+        let table = table_name();
+        assert_eq!(
+            send(&router, &format!("/reject/{table}")).await?,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(values(&db, &table).await?, None);
+
+        let table = table_name();
+        assert_eq!(
+            send(&router, &format!("/fail/{table}")).await?,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(values(&db, &table).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nested_units_of_work() -> Result<(), Box<dyn std::error::Error>> {
+        let (router, db) = setup().await?;
+
+        // The units of work are savepoints of the request transaction
+        let table = table_name();
+        assert_eq!(
+            send(&router, &format!("/nested/200/{table}")).await?,
+            StatusCode::OK
+        );
+        assert_eq!(values(&db, &table).await?, Some(vec![1]));
+
+        execute(&db, &format!("DROP TABLE {table}")).await?;
+
+        // Even the committed ones are rolled back with the request
+        let table = table_name();
+        assert_eq!(
+            send(&router, &format!("/nested/500/{table}")).await?,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(values(&db, &table).await?, None);
+
+        Ok(())
+    }
+}
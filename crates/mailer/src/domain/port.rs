@@ -24,4 +24,88 @@ pub trait MailerProvider: Send + Sync {
         token: &Uuid,
         redirect_url: &str,
     ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Send an email to a user containing a link that allows to login without password.
+    ///
+    /// # Arguments
+    /// * `email`: Email address of the user to send the link to.
+    /// * `token`: One-time token of the link.
+    /// * `redirect_url`: URL to redirect the user to for login.
+    ///
+    /// # Returns
+    /// An error or no result.
+    fn send_magic_link(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Send an email to a user containing a link that allows to choose a new password.
+    ///
+    /// # Arguments
+    /// * `email`: Email address of the user to send the link to.
+    /// * `token`: One-time token of the link.
+    /// * `redirect_url`: URL to redirect the user to for password reset.
+    ///
+    /// # Returns
+    /// An error or no result.
+    fn send_password_reset(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Send an email to an invited user containing a link that allows to choose a password and
+    /// activate the account.
+    ///
+    /// # Arguments
+    /// * `email`: Email address of the invited user.
+    /// * `token`: One-time token of the invitation.
+    /// * `redirect_url`: URL to redirect the user to for accepting the invitation.
+    ///
+    /// # Returns
+    /// An error or no result.
+    fn send_invitation(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Send an email to the new address of a user in order to confirm the change of its email.
+    ///
+    /// # Arguments
+    /// * `email`: New email address of the user.
+    /// * `token`: One-time token of the change.
+    /// * `redirect_url`: URL to redirect the user to for confirming the change.
+    ///
+    /// # Returns
+    /// An error or no result.
+    fn send_email_change_confirmation(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Send an email to the previous address of a user to notify the change of its email. It
+    /// contains a link that allows to revert the change.
+    ///
+    /// # Arguments
+    /// * `email`: Previous email address of the user.
+    /// * `new_email`: New email address of the user.
+    /// * `token`: One-time token used to revert the change.
+    /// * `redirect_url`: URL to redirect the user to for reverting the change.
+    ///
+    /// # Returns
+    /// An error or no result.
+    fn send_email_change_notification(
+        &self,
+        email: &str,
+        new_email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>>;
 }
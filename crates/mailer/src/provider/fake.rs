@@ -29,4 +29,64 @@ impl MailerProvider for FakeMailer {
 
         Box::pin(async move { Ok(()) })
     }
+
+    fn send_magic_link(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        println!("Sending login link to {email} with url {redirect_url}?token={token}");
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn send_password_reset(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        println!("Sending password reset link to {email} with url {redirect_url}?token={token}");
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn send_invitation(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        println!("Sending invitation to {email} with url {redirect_url}?token={token}");
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn send_email_change_confirmation(
+        &self,
+        email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        println!(
+            "Sending email change confirmation to {email} with url {redirect_url}?token={token}"
+        );
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn send_email_change_notification(
+        &self,
+        email: &str,
+        new_email: &str,
+        token: &Uuid,
+        redirect_url: &str,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        println!(
+            "Sending email change notification (to {new_email}) to {email} with revert url {redirect_url}?token={token}"
+        );
+
+        Box::pin(async move { Ok(()) })
+    }
 }
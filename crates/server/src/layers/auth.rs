@@ -4,19 +4,28 @@
 
 use tower_sessions::{Expiry, MemoryStore, SessionManagerLayer};
 
-use configuration::Config;
+use common_state::RedisPool;
+use configuration::{Config, SessionStoreKind};
+
+use crate::layers::session_store::{AppSessionStore, RedisSessionStore};
 
 /// Gets the Axum layer used to enable authentication in the HTTP server.
 ///
 /// # Arguments
 /// * `config` - Application configuration.
+/// * `redis` - Redis database handle (used if sessions are stored in Redis).
 ///
 /// # Returns
 /// The authentication layer.
-pub fn authentication_session_layer(config: &Config) -> SessionManagerLayer<MemoryStore> {
+pub fn authentication_session_layer(
+    config: &Config,
+    redis: &RedisPool,
+) -> SessionManagerLayer<AppSessionStore> {
     // Session storage backend
-    // TODO: use Redis to store the values
-    let session_store = MemoryStore::default();
+    let session_store = match config.sessions.store {
+        SessionStoreKind::Memory => AppSessionStore::Memory(MemoryStore::default()),
+        SessionStoreKind::Redis => AppSessionStore::Redis(RedisSessionStore::new(redis.clone())),
+    };
 
     // Session layer
     SessionManagerLayer::new(session_store).with_expiry(Expiry::OnInactivity(
@@ -1,6 +1,6 @@
 //! This file contains all structures and functions related to CORS.
 
-use axum::http::header::{HeaderName, HeaderValue};
+use axum::http::header::{HeaderName, HeaderValue, ETAG, LINK};
 use axum::http::Method;
 use std::str::FromStr;
 use tower_http::cors::CorsLayer;
@@ -55,5 +55,6 @@ pub fn build(config: &Config) -> CorsLayer {
         .allow_credentials(true)
         .allow_methods(methods)
         .allow_headers(headers)
+        .expose_headers([ETAG, LINK])
         .allow_origin(allow_origins)
 }
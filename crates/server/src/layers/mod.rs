@@ -2,5 +2,6 @@
 
 pub(crate) mod auth;
 pub(crate) mod cors;
+pub(crate) mod session_store;
 pub(crate) mod timeout;
 pub(crate) mod tracing;
@@ -0,0 +1,257 @@
+//! This file contains the session stores that can be used by the
+//! authentication layer to persist the user sessions.
+
+use async_trait::async_trait;
+use bb8_redis::redis;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store::{Error as StoreError, Result as StoreResult};
+use tower_sessions::{MemoryStore, SessionStore};
+
+use common_state::RedisPool;
+
+/// Prefix of the Redis keys used to store the sessions.
+const REDIS_KEY_PREFIX: &str = "session:";
+
+/// Session store backed by a Redis database.
+#[derive(Clone, Debug)]
+pub(crate) struct RedisSessionStore {
+    /// Redis database handle.
+    pool: RedisPool,
+}
+
+impl RedisSessionStore {
+    /// Creates a new Redis session store.
+    ///
+    /// # Arguments
+    /// * `pool` - Redis database handle.
+    ///
+    /// # Returns
+    /// New instance of RedisSessionStore.
+    pub(crate) fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+
+    /// Builds the Redis key of a session.
+    ///
+    /// # Arguments
+    /// * `id` - Session identifier.
+    ///
+    /// # Returns
+    /// The Redis key.
+    fn key(id: &Id) -> String {
+        format!("{REDIS_KEY_PREFIX}{id}")
+    }
+
+    /// Writes a session record in Redis. The key expires at the same time as
+    /// the session.
+    ///
+    /// # Arguments
+    /// * `record` - Session record to be written.
+    /// * `only_if_absent` - Do not overwrite an existing session.
+    ///
+    /// # Returns
+    /// `true` if the record has been written, `false` otherwise.
+    async fn write(&self, record: &Record, only_if_absent: bool) -> StoreResult<bool> {
+        let value = serde_json::to_string(record).map_err(|e| StoreError::Encode(e.to_string()))?;
+
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+
+        let mut cmd = redis::cmd("SET");
+
+        cmd.arg(Self::key(&record.id))
+            .arg(value)
+            .arg("EXAT")
+            .arg(record.expiry_date.unix_timestamp());
+
+        if only_if_absent {
+            cmd.arg("NX");
+        }
+
+        let written: Option<String> = cmd
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+
+        Ok(written.is_some())
+    }
+}
+
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn create(&self, record: &mut Record) -> StoreResult<()> {
+        // Generate new identifiers until there's no collision
+        while !self.write(record, true).await? {
+            record.id = Id::default();
+        }
+
+        Ok(())
+    }
+
+    async fn save(&self, record: &Record) -> StoreResult<()> {
+        self.write(record, false).await.map(|_| ())
+    }
+
+    async fn load(&self, id: &Id) -> StoreResult<Option<Record>> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+
+        let value: Option<String> = redis::cmd("GET")
+            .arg(Self::key(id))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+
+        value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|e| StoreError::Decode(e.to_string()))
+    }
+
+    async fn delete(&self, id: &Id) -> StoreResult<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| StoreError::Backend(e.to_string()))?;
+
+        redis::cmd("DEL")
+            .arg(Self::key(id))
+            .query_async::<()>(&mut *conn)
+            .await
+            .map_err(|e| StoreError::Backend(e.to_string()))
+    }
+}
+
+/// Session store selected from the configuration.
+#[derive(Clone, Debug)]
+pub(crate) enum AppSessionStore {
+    /// Sessions are kept in the memory of the server.
+    Memory(MemoryStore),
+
+    /// Sessions are persisted in Redis.
+    Redis(RedisSessionStore),
+}
+
+#[async_trait]
+impl SessionStore for AppSessionStore {
+    async fn create(&self, record: &mut Record) -> StoreResult<()> {
+        match self {
+            Self::Memory(store) => store.create(record).await,
+            Self::Redis(store) => store.create(record).await,
+        }
+    }
+
+    async fn save(&self, record: &Record) -> StoreResult<()> {
+        match self {
+            Self::Memory(store) => store.save(record).await,
+            Self::Redis(store) => store.save(record).await,
+        }
+    }
+
+    async fn load(&self, id: &Id) -> StoreResult<Option<Record>> {
+        match self {
+            Self::Memory(store) => store.load(id).await,
+            Self::Redis(store) => store.load(id).await,
+        }
+    }
+
+    async fn delete(&self, id: &Id) -> StoreResult<()> {
+        match self {
+            Self::Memory(store) => store.delete(id).await,
+            Self::Redis(store) => store.delete(id).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bb8_redis::{bb8, RedisConnectionManager};
+    use std::collections::HashMap;
+    use time::{Duration, OffsetDateTime};
+
+    use super::*;
+
+    async fn setup_store() -> Result<RedisSessionStore, Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let manager = RedisConnectionManager::new(std::env::var("REDIS_URL_TEST")?)?;
+        let pool = bb8::Pool::builder().build(manager).await?;
+
+        Ok(RedisSessionStore::new(pool))
+    }
+
+    fn record(id: Id) -> Record {
+        Record {
+            id,
+            data: HashMap::from([(
+                "user".to_string(),
+                serde_json::json!(Id::default().to_string()),
+            )]),
+            expiry_date: OffsetDateTime::now_utc() + Duration::minutes(5),
+        }
+    }
+
+    async fn ttl(store: &RedisSessionStore, id: &Id) -> Result<i64, Box<dyn std::error::Error>> {
+        let mut conn = store.pool.get().await?;
+
+        Ok(redis::cmd("TTL")
+            .arg(RedisSessionStore::key(id))
+            .query_async(&mut *conn)
+            .await?)
+    }
+
+    #[tokio::test]
+    async fn test_redis_session_store() -> Result<(), Box<dyn std::error::Error>> {
+        let store = setup_store().await?;
+
+        let mut created = record(Id::default());
+        store.create(&mut created).await?;
+
+        assert_eq!(store.load(&created.id).await?, Some(created.clone()));
+
+        // The key expires with the session
+        let seconds = ttl(&store, &created.id).await?;
+        assert!(seconds > 0 && seconds <= 5 * 60);
+
+        let mut saved = record(created.id);
+        saved.expiry_date += Duration::minutes(5);
+        store.save(&saved).await?;
+
+        assert_eq!(store.load(&created.id).await?, Some(saved.clone()));
+        assert!(ttl(&store, &created.id).await? > 5 * 60);
+
+        store.delete(&created.id).await?;
+        assert_eq!(store.load(&created.id).await?, None);
+        assert_eq!(store.load(&Id::default()).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redis_session_store_collision() -> Result<(), Box<dyn std::error::Error>> {
+        let store = setup_store().await?;
+
+        let mut first = record(Id::default());
+        store.create(&mut first).await?;
+
+        // Another session can't overwrite an existing one
+        let mut second = record(first.id);
+        store.create(&mut second).await?;
+
+        assert_ne!(second.id, first.id);
+        assert_eq!(store.load(&first.id).await?, Some(first.clone()));
+        assert_eq!(store.load(&second.id).await?, Some(second.clone()));
+
+        store.delete(&first.id).await?;
+        store.delete(&second.id).await?;
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use axum::extract::Request;
 use axum::http::header::HeaderValue;
 use axum::http::{header, HeaderName};
 use std::sync::Arc;
+use tower::util::MapRequestLayer;
 use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
 use tower_http::request_id::{
     MakeRequestId, PropagateRequestIdLayer, RequestId, SetRequestIdLayer,
@@ -15,6 +16,9 @@ use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 
 use crate::prelude::*;
 
+/// Header containing the request-id.
+const X_REQUEST_ID: &str = "x-request-id";
+
 /// Empty structure used to represent the request identifier.
 #[derive(Clone, Default)]
 pub struct Id;
@@ -52,23 +56,45 @@ pub fn sensitive_headers_layers() -> (
 
 impl MakeRequestId for Id {
     fn make_request_id<T>(&mut self, _request: &Request<T>) -> Option<RequestId> {
-        let uuid = Uuid::new_v4().into_bytes();
+        let uuid = Uuid::new_v4().to_string();
 
-        match HeaderValue::from_bytes(&uuid) {
+        match HeaderValue::from_str(&uuid) {
             Ok(header) => Some(RequestId::new(header)),
             Err(_) => None,
         }
     }
 }
 
-/// Gets the request-id layer.
+/// Layer removing the request-id sent by the client.
+pub type RemoveRequestIdLayer = MapRequestLayer<fn(Request) -> Request>;
+
+/// Removes the request-id sent by the client (the identifiers recorded in the audit trail are
+/// always generated by the server).
+///
+/// # Arguments
+/// * `request`: HTTP request.
+///
+/// # Returns
+/// The request without request-id header.
+fn remove_request_id(mut request: Request) -> Request {
+    request.headers_mut().remove(X_REQUEST_ID);
+    request
+}
+
+/// Gets the request-id layers.
 ///
 /// # Returns
-/// Request ID layer.
-pub fn request_id_layers() -> (SetRequestIdLayer<Id>, PropagateRequestIdLayer) {
-    let x_request_id = HeaderName::from_static("x-request-id");
+/// Layers removing the request-id sent by the client, setting a new one and propagating it to
+/// the response.
+pub fn request_id_layers() -> (
+    RemoveRequestIdLayer,
+    SetRequestIdLayer<Id>,
+    PropagateRequestIdLayer,
+) {
+    let x_request_id = HeaderName::from_static(X_REQUEST_ID);
 
     (
+        MapRequestLayer::new(remove_request_id),
         SetRequestIdLayer::new(x_request_id.clone(), Id),
         PropagateRequestIdLayer::new(x_request_id),
     )
@@ -13,6 +13,7 @@ pub(crate) mod routes;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Router;
+use std::net::SocketAddr;
 use tokio::signal;
 
 use common_state::AppState;
@@ -53,10 +54,13 @@ pub async fn start(config: Option<Config>) -> ApiResult<()> {
         listener.local_addr().map_err(Error::Socket)?
     );
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .map_err(Error::Axum)?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .map_err(Error::Axum)?;
 
     Ok(())
 }
@@ -87,7 +91,8 @@ pub async fn app(
     });
 
     // Create Postgresql pool connection
-    let (pg_pool, redis_pool) = database::initialize(db_env_variable, redis_env_variable).await?;
+    let (pg_pool, replicas, redis_pool) =
+        database::initialize(&config.database, db_env_variable, redis_env_variable).await?;
     event!(Level::INFO, "🗃  Database initialized");
 
     // CORS layer
@@ -107,7 +112,7 @@ pub async fn app(
     event!(Level::INFO, "🔻 Compression enabled");
 
     // Authentication layer
-    let authentication = layers::auth::authentication_session_layer(config);
+    let authentication = layers::auth::authentication_session_layer(config, &redis_pool);
 
     event!(Level::INFO, "👤 Authentication enabled");
 
@@ -116,13 +121,14 @@ pub async fn app(
         layers::tracing::sensitive_headers_layers();
 
     // Request ID layers
-    let (request_id_layer, propagate_request_id_layer) = layers::tracing::request_id_layers();
+    let (remove_request_id_layer, request_id_layer, propagate_request_id_layer) =
+        layers::tracing::request_id_layers();
 
     // Tracing
     let tracing_layer = layers::tracing::tracing_layer();
 
     // State shared between handlers
-    let state = AppState::new(config.clone(), pg_pool, redis_pool);
+    let state = AppState::new(config.clone(), pg_pool, replicas, redis_pool);
 
     event!(Level::INFO, "📦 State configured");
 
@@ -139,10 +145,11 @@ pub async fn app(
         .layer(timeout)
         .layer(compression_layer)
         .layer(authentication)
+        .layer(propagate_request_id_layer)
         .layer(request_id_layer)
+        .layer(remove_request_id_layer)
         .layer(sensitive_request_layer)
         .layer(tracing_layer)
-        .layer(propagate_request_id_layer)
         .layer(sensitive_response_layer);
 
     Ok(router)
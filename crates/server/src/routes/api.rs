@@ -10,5 +10,18 @@ use common_state::AppState;
 /// An Axum router.
 pub fn router() -> Router<AppState> {
     // List all crates that provide APIs
-    Router::new().nest("/users", user::router())
+    Router::new()
+        .nest("/users", user::router())
+        .merge(auth::api_router())
+}
+
+/// Builds a router for the APIs that can be called without authentication.
+///
+/// # Arguments
+/// * `state`: Application state (used by the rate limiting middleware).
+///
+/// # Returns
+/// An Axum router.
+pub fn public_router(state: &AppState) -> Router<AppState> {
+    Router::new().nest("/users", user::public_router(state))
 }
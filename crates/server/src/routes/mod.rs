@@ -26,14 +26,19 @@ use configuration::Environment;
 pub fn build(config: &Config, state: AppState) -> ApiResult<Router<AppState>> {
     let mut router = Router::new();
 
+    let auth_router = auth::router(state.clone());
+    let public_router = api::public_router(&state);
+
     router = router
         // All APIs of this application
         .nest("/api", api::router())
         // Before this layer, all endpoints needs to be called by an authenticated user.
         // After this layer, authentication is not required (login for example).
         .route_layer(require_authentication!(state))
+        // Public APIs (self-registration for example)
+        .nest("/api", public_router)
         // Special endpoints for authentication
-        .merge(auth::router());
+        .merge(auth_router);
 
     #[cfg(feature = "k8s")]
     {
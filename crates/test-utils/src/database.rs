@@ -1,10 +1,12 @@
 //! Utilities used to initialiaze a connection to the database for testing purpose.
 
+use bb8_redis::{bb8, RedisConnectionManager};
 use sqlx::migrate::MigrateDatabase;
 use sqlx::postgres::PgPoolOptions;
 use std::error::Error;
 
-use database::{Db, SharedDb};
+use configuration::CacheSettings;
+use database::{Cache, Db, SharedDb};
 
 /// Initialize the database use in the application.
 ///
@@ -37,3 +39,41 @@ pub async fn initialize_database(db_env_variable: &str) -> Result<SharedDb, Box<
 
     Ok(Db::new(pool).into_shared())
 }
+
+/// Initialize a cache stored in the Redis database used by the tests.
+///
+/// # Returns
+/// Cache handle or an error.
+pub async fn setup_test_cache() -> Result<Cache, Box<dyn Error>> {
+    dotenvy::dotenv()?;
+
+    let manager = RedisConnectionManager::new(std::env::var("REDIS_URL_TEST")?)?;
+    let pool = bb8::Pool::builder().build(manager).await?;
+
+    Ok(Cache::new(pool, test_cache_settings()))
+}
+
+/// Initialize a cache that points to a Redis database that can't be reached: the lookups always
+/// miss and fall back to the stores.
+///
+/// # Returns
+/// Cache handle or an error.
+pub fn setup_unreachable_test_cache() -> Result<Cache, Box<dyn Error>> {
+    let manager = RedisConnectionManager::new("redis://127.0.0.1:1")?;
+    let pool = bb8::Pool::builder().build_unchecked(manager);
+
+    Ok(Cache::new(pool, test_cache_settings()))
+}
+
+/// Settings of the caches used by the tests.
+///
+/// # Returns
+/// The cache settings.
+fn test_cache_settings() -> CacheSettings {
+    CacheSettings {
+        enabled: true,
+        auth_user_ttl_seconds: 60,
+        user_ttl_seconds: 60,
+        timeout_ms: 100,
+    }
+}
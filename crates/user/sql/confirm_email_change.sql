@@ -0,0 +1,12 @@
+-- $1: ID of the change
+
+WITH confirmed_change AS (
+    UPDATE user_email_changes
+    SET confirmed_at = NOW()
+    WHERE id = $1
+    RETURNING user_id, new_email
+)
+UPDATE users u
+SET email = c.new_email
+FROM confirmed_change c
+WHERE u.id = c.user_id;
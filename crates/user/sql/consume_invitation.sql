@@ -0,0 +1,17 @@
+-- $1: Token of the invitation to consume
+
+WITH consumed_invitation AS (
+    DELETE FROM user_invitations
+    WHERE token = $1
+    RETURNING *
+)
+SELECT
+    i.id,
+    i.user_id,
+    u.email,
+    i.invited_by,
+    i.token,
+    i.created_at,
+    i.expires_at
+FROM consumed_invitation i
+INNER JOIN users u ON u.id = i.user_id;
@@ -0,0 +1,27 @@
+-- $1: First name (optional, pattern if not exact)
+-- $2: Last name (optional, pattern if not exact)
+-- $3: Email (optional, pattern if not exact)
+-- $4: Role (optional)
+-- $5: Exact matching of the text filters
+-- $6: Search query (optional, lowercase)
+-- $7: Search pattern (optional, lowercase)
+-- $8: Include the deleted users
+
+SELECT COUNT(*) AS "count!"
+FROM users u
+WHERE
+    ($1::VARCHAR IS NULL OR (
+        CASE WHEN $5::BOOLEAN THEN u.first_name = $1::VARCHAR
+        ELSE u.first_name ILIKE $1::VARCHAR END
+    )) AND
+    ($2::VARCHAR IS NULL OR (
+        CASE WHEN $5::BOOLEAN THEN u.last_name = $2::VARCHAR
+        ELSE u.last_name ILIKE $2::VARCHAR END
+    )) AND
+    ($3::VARCHAR IS NULL OR (
+        CASE WHEN $5::BOOLEAN THEN u.email = $3::VARCHAR
+        ELSE u.email ILIKE $3::VARCHAR END
+    )) AND
+    ($4::user_role IS NULL OR u.role = $4::user_role) AND
+    ($6::TEXT IS NULL OR $6::TEXT <% u.search_text OR u.search_text LIKE $7::TEXT) AND
+    ($8::BOOLEAN OR u.deleted_at IS NULL);
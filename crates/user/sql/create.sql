@@ -15,7 +15,8 @@ WITH created_user AS (
         role AS "role!: _",
         password,
         created_at,
-        updated_at
+        updated_at,
+        deleted_at
 )
 SELECT
     u.*,
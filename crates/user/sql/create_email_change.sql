@@ -0,0 +1,25 @@
+-- $1: User ID
+-- $2: Current email
+-- $3: New email
+-- $4: Expires at
+
+INSERT INTO user_email_changes (user_id, old_email, new_email, expires_at)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (user_id) DO UPDATE
+SET
+    id = uuid_generate_v4(),
+    old_email = EXCLUDED.old_email,
+    new_email = EXCLUDED.new_email,
+    revert_token = uuid_generate_v4(),
+    confirmed_at = NULL,
+    created_at = NOW(),
+    expires_at = EXCLUDED.expires_at
+RETURNING
+    id,
+    user_id,
+    old_email,
+    new_email,
+    revert_token,
+    confirmed_at,
+    created_at,
+    expires_at;
@@ -0,0 +1,19 @@
+-- $1: ID of the invited user
+-- $2: ID of the user who sends the invitation
+-- $3: Expires at
+
+WITH created_invitation AS (
+    INSERT INTO user_invitations (user_id, invited_by, expires_at)
+    VALUES ($1, $2, $3)
+    RETURNING *
+)
+SELECT
+    i.id,
+    i.user_id,
+    u.email,
+    i.invited_by,
+    i.token,
+    i.created_at,
+    i.expires_at
+FROM created_invitation i
+INNER JOIN users u ON u.id = i.user_id;
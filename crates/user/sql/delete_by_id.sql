@@ -1,3 +1,3 @@
 -- $1: ID of the user to delete
 
-DELETE FROM users WHERE id = $1;
+UPDATE users SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL;
@@ -0,0 +1,35 @@
+-- $1: ID of the user to erase
+-- $2: Anonymous email replacing the email of the user
+-- $3: Password replacing the password of the user (nobody knows it)
+
+-- The user is kept (it may be referenced) but all its personal data is removed
+WITH
+    confirmations AS (DELETE FROM user_confirmations WHERE user_id = $1),
+    sessions AS (DELETE FROM user_sessions WHERE user_id = $1),
+    magic_links AS (DELETE FROM user_magic_links WHERE user_id = $1),
+    password_resets AS (DELETE FROM user_password_resets WHERE user_id = $1),
+    totp AS (DELETE FROM user_totp WHERE user_id = $1),
+    recovery_codes AS (DELETE FROM user_recovery_codes WHERE user_id = $1),
+    identities AS (DELETE FROM user_identities WHERE user_id = $1),
+    refresh_tokens AS (DELETE FROM user_refresh_tokens WHERE user_id = $1),
+    api_keys AS (DELETE FROM user_api_keys WHERE user_id = $1),
+    invitations AS (DELETE FROM user_invitations WHERE user_id = $1),
+    email_changes AS (DELETE FROM user_email_changes WHERE user_id = $1)
+UPDATE users
+SET
+    first_name = NULL,
+    last_name = NULL,
+    email = $2,
+    password = $3
+WHERE id = $1
+RETURNING
+    id,
+    first_name,
+    last_name,
+    email,
+    role AS "role!: _",
+    password,
+    created_at,
+    updated_at,
+    deleted_at,
+    NULL AS "pending_confirmation: _";
@@ -1,3 +1,3 @@
 -- $1: ID of the user to check
 
-SELECT 1 AS exists FROM users WHERE id = $1 LIMIT 1;
+SELECT 1 AS exists FROM users WHERE id = $1 AND deleted_at IS NULL LIMIT 1;
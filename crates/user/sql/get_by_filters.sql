@@ -1,8 +1,66 @@
--- $1: First name (optional)
--- $2: Last name (optional)
--- $3: Email (optional)
+-- $1: First name (optional, pattern if not exact)
+-- $2: Last name (optional, pattern if not exact)
+-- $3: Email (optional, pattern if not exact)
 -- $4: Role (optional)
+-- $5: Exact matching of the text filters
+-- $6: Search query (optional, lowercase)
+-- $7: Search pattern (optional, lowercase)
+-- $8: Include the deleted users
+-- $9: Sort field
+-- $10: Descending order
+-- $11: Cursor text key (optional)
+-- $12: Cursor time key (optional)
+-- $13: Cursor ID (optional)
+-- $14: Limit (optional)
+-- $15: Offset
 
+WITH filtered_users AS (
+    SELECT
+        u.*,
+        CASE $9::VARCHAR
+            WHEN 'first_name' THEN COALESCE(u.first_name, '')
+            WHEN 'last_name' THEN COALESCE(u.last_name, '')
+            WHEN 'email' THEN u.email
+            WHEN 'role' THEN u.role::VARCHAR
+            ELSE ''
+        END AS text_key,
+        CASE $9::VARCHAR
+            WHEN 'created_at' THEN u.created_at
+            WHEN 'updated_at' THEN u.updated_at
+            ELSE 'epoch'::TIMESTAMPTZ
+        END AS time_key,
+        CASE $9::VARCHAR
+            WHEN 'relevance' THEN COALESCE(WORD_SIMILARITY($6::TEXT, u.search_text), 0)
+            ELSE 0
+        END AS rank_key
+    FROM users u
+    WHERE
+        ($1::VARCHAR IS NULL OR (
+            CASE WHEN $5::BOOLEAN THEN u.first_name = $1::VARCHAR
+            ELSE u.first_name ILIKE $1::VARCHAR END
+        )) AND
+        ($2::VARCHAR IS NULL OR (
+            CASE WHEN $5::BOOLEAN THEN u.last_name = $2::VARCHAR
+            ELSE u.last_name ILIKE $2::VARCHAR END
+        )) AND
+        ($3::VARCHAR IS NULL OR (
+            CASE WHEN $5::BOOLEAN THEN u.email = $3::VARCHAR
+            ELSE u.email ILIKE $3::VARCHAR END
+        )) AND
+        ($4::user_role IS NULL OR u.role = $4::user_role) AND
+        ($6::TEXT IS NULL OR $6::TEXT <% u.search_text OR u.search_text LIKE $7::TEXT) AND
+        ($8::BOOLEAN OR u.deleted_at IS NULL)
+),
+-- The rank is not part of the cursor: it's computed from the user it points to
+cursor_user AS (
+    SELECT
+        CASE $9::VARCHAR
+            WHEN 'relevance' THEN COALESCE(WORD_SIMILARITY($6::TEXT, c.search_text), 0)
+            ELSE 0
+        END AS rank_key
+    FROM users c
+    WHERE c.id = $13::UUID
+)
 SELECT
     u.id AS "id!: _",
     u.first_name AS "first_name!: _",
@@ -12,11 +70,38 @@ SELECT
     u.password AS "password!: _",
     u.created_at AS "created_at!: _",
     u.updated_at AS "updated_at!: _",
+    u.deleted_at,
     TO_JSONB(uc) AS "pending_confirmation?: _"
-FROM users u
+FROM filtered_users u
 LEFT JOIN user_confirmations uc ON uc.user_id = u.id
 WHERE
-    ($1::VARCHAR IS NULL OR u.first_name = $1::varchar) AND
-    ($2::VARCHAR IS NULL OR u.last_name = $2::varchar) AND
-    ($3::VARCHAR IS NULL OR u.email = $3::varchar) AND
-    ($4::user_role IS NULL OR u.role = $4::user_role);
+    $13::UUID IS NULL OR
+    (
+        NOT $10::BOOLEAN AND
+        (u.text_key, u.time_key, u.rank_key, u.id) > (
+            $11::VARCHAR,
+            $12::TIMESTAMPTZ,
+            COALESCE((SELECT rank_key FROM cursor_user), 0),
+            $13::UUID
+        )
+    ) OR
+    (
+        $10::BOOLEAN AND
+        (u.text_key, u.time_key, u.rank_key, u.id) < (
+            $11::VARCHAR,
+            $12::TIMESTAMPTZ,
+            COALESCE((SELECT rank_key FROM cursor_user), 0),
+            $13::UUID
+        )
+    )
+ORDER BY
+    CASE WHEN NOT $10::BOOLEAN THEN u.text_key END ASC,
+    CASE WHEN NOT $10::BOOLEAN THEN u.time_key END ASC,
+    CASE WHEN NOT $10::BOOLEAN THEN u.rank_key END ASC,
+    CASE WHEN NOT $10::BOOLEAN THEN u.id END ASC,
+    CASE WHEN $10::BOOLEAN THEN u.text_key END DESC,
+    CASE WHEN $10::BOOLEAN THEN u.time_key END DESC,
+    CASE WHEN $10::BOOLEAN THEN u.rank_key END DESC,
+    CASE WHEN $10::BOOLEAN THEN u.id END DESC
+LIMIT $14::BIGINT
+OFFSET $15::BIGINT;
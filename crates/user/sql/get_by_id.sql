@@ -9,8 +9,9 @@ SELECT
     u.password,
     u.created_at,
     u.updated_at,
+    u.deleted_at,
     TO_JSONB(uc) AS "pending_confirmation: _"
 FROM users u
 LEFT JOIN user_confirmations uc ON uc.user_id = u.id
-WHERE u.id = $1
+WHERE u.id = $1 AND u.deleted_at IS NULL
 LIMIT 1;
@@ -0,0 +1,13 @@
+-- $1: ID of the change
+
+SELECT
+    id,
+    user_id,
+    old_email,
+    new_email,
+    revert_token,
+    confirmed_at,
+    created_at,
+    expires_at
+FROM user_email_changes
+WHERE id = $1;
@@ -0,0 +1,14 @@
+-- $1: ID of the user
+
+SELECT
+    id,
+    user_id,
+    old_email,
+    new_email,
+    revert_token,
+    confirmed_at,
+    created_at,
+    expires_at
+FROM user_email_changes
+WHERE user_id = $1
+ORDER BY created_at;
@@ -0,0 +1,13 @@
+-- $1: ID of the invitation to find
+
+SELECT
+    i.id,
+    i.user_id,
+    u.email,
+    i.invited_by,
+    i.token,
+    i.created_at,
+    i.expires_at
+FROM user_invitations i
+INNER JOIN users u ON u.id = i.user_id
+WHERE i.id = $1;
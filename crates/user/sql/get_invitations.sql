@@ -0,0 +1,11 @@
+SELECT
+    i.id,
+    i.user_id,
+    u.email,
+    i.invited_by,
+    i.token,
+    i.created_at,
+    i.expires_at
+FROM user_invitations i
+INNER JOIN users u ON u.id = i.user_id
+ORDER BY i.created_at;
@@ -0,0 +1,3 @@
+-- $1: ID of the user
+
+SELECT password FROM users WHERE id = $1 AND deleted_at IS NULL LIMIT 1;
@@ -0,0 +1,3 @@
+-- $1: ID of the user to purge
+
+DELETE FROM users WHERE id = $1;
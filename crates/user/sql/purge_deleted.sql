@@ -0,0 +1,3 @@
+-- $1: Date before which the deleted users are purged
+
+DELETE FROM users WHERE deleted_at < $1;
@@ -0,0 +1,21 @@
+-- $1: ID of the invitation to renew
+-- $2: Expires at
+
+WITH renewed_invitation AS (
+    UPDATE user_invitations
+    SET
+        token = uuid_generate_v4(),
+        expires_at = $2
+    WHERE id = $1
+    RETURNING *
+)
+SELECT
+    i.id,
+    i.user_id,
+    u.email,
+    i.invited_by,
+    i.token,
+    i.created_at,
+    i.expires_at
+FROM renewed_invitation i
+INNER JOIN users u ON u.id = i.user_id;
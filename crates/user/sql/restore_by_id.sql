@@ -0,0 +1,22 @@
+-- $1: ID of the user to restore
+
+WITH restored_user AS (
+    UPDATE users
+    SET deleted_at = NULL
+    WHERE id = $1 AND deleted_at IS NOT NULL
+    RETURNING
+        id,
+        first_name,
+        last_name,
+        email,
+        role AS "role!: _",
+        password,
+        created_at,
+        updated_at,
+        deleted_at
+)
+SELECT
+    u.*,
+    TO_JSONB(uc) AS "pending_confirmation: _"
+FROM restored_user u
+LEFT JOIN user_confirmations uc ON uc.user_id = u.id;
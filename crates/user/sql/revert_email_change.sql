@@ -0,0 +1,11 @@
+-- $1: ID of the change
+
+WITH reverted_change AS (
+    DELETE FROM user_email_changes
+    WHERE id = $1
+    RETURNING user_id, old_email, confirmed_at
+)
+UPDATE users u
+SET email = c.old_email
+FROM reverted_change c
+WHERE u.id = c.user_id AND c.confirmed_at IS NOT NULL;
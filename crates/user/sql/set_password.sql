@@ -1,4 +1,7 @@
 -- $1: User ID
 -- $2: Password
+-- $3: Expected date of the last update (NULL: not checked)
 
-UPDATE users SET password = $2 WHERE id = $1;
+UPDATE users
+SET password = $2
+WHERE id = $1 AND deleted_at IS NULL AND ($3::timestamptz IS NULL OR updated_at = $3);
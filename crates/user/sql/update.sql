@@ -4,6 +4,7 @@
 -- $4: Email
 -- $5: Role
 -- $6: Password
+-- $7: Expected date of the last update (NULL: not checked)
 
 WITH updated_user AS (
     UPDATE users
@@ -13,7 +14,7 @@ WITH updated_user AS (
         email = $4,
         role = $5,
         password = $6
-    WHERE id = $1
+    WHERE id = $1 AND deleted_at IS NULL AND ($7::timestamptz IS NULL OR updated_at = $7)
     RETURNING
         id,
         first_name,
@@ -22,7 +23,8 @@ WITH updated_user AS (
         role AS "role!: _",
         password,
         created_at,
-        updated_at
+        updated_at,
+        deleted_at
 )
 SELECT
     u.*,
@@ -0,0 +1,82 @@
+//! HTTP endpoints for confirming or reverting the email changes (with the links received by
+//! email, so no login is needed).
+
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::post;
+use axum::Router;
+
+use auth::{Auth, CachedAuthStore, SQLxAuditStore, SQLxAuthStore};
+use common_core::UseCase;
+use common_state::AppState;
+use common_web::extractor::FormOrJson;
+use database::{Cache, Db};
+
+use crate::application::*;
+use crate::domain::email_change::EmailChangeToken;
+use crate::infrastructure::cache::CachedEmailChangeStore;
+use crate::infrastructure::email_change::SQLxEmailChangeStore;
+use crate::prelude::*;
+
+/// Builds an Axum router with the endpoints that don't require authentication.
+///
+/// # Returns
+/// An Axum router.
+pub fn public_router() -> Router<AppState> {
+    Router::new()
+        .route("/confirm", post(confirm_email_change))
+        .route("/revert", post(revert_email_change))
+}
+
+/// Handler used to confirm a new email (link sent to the new address).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn confirm_email_change(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<EmailChangeToken>,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = ConfirmEmailChangeStores {
+        email_change: CachedEmailChangeStore::new(
+            SQLxEmailChangeStore::new(db.clone()),
+            &db,
+            &cache,
+        ),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    ConfirmEmailChange::new(stores, db)
+        .handle(request.token)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Handler used to revert an email change (link sent to the previous address).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn revert_email_change(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<EmailChangeToken>,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = RevertEmailChangeStores {
+        email_change: CachedEmailChangeStore::new(
+            SQLxEmailChangeStore::new(db.clone()),
+            &db,
+            &cache,
+        ),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    RevertEmailChange::new(stores).handle(request.token).await?;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,164 @@
+//! HTTP endpoints for inviting users (by an admin user).
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use validator::Validate;
+
+use auth::{
+    Auth, Authorized, CachedAuthStore, SQLxAuditStore, SQLxAuthStore, UsersCreate, UsersDelete,
+    UsersRead,
+};
+use common_core::UseCase;
+use common_state::AppState;
+use common_web::extractor::FormOrJson;
+use database::{Cache, Db};
+use mailer::FakeMailer;
+
+use crate::application::*;
+use crate::domain::invitation::{AcceptInvitationRequest, InviteUserRequest};
+use crate::infrastructure::cache::CachedUserStore;
+use crate::infrastructure::invitation::SQLxInvitationStore;
+use crate::infrastructure::user::SQLxUserStore;
+use crate::prelude::*;
+
+/// Builds an Axum router.
+///
+/// # Returns
+/// An Axum router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/:invitation_id", delete(revoke_invitation))
+        .route("/", get(list_invitations))
+        .route("/", post(invite_user))
+        .route("/:invitation_id/resend", post(resend_invitation))
+}
+
+/// Builds an Axum router with the endpoints that don't require authentication.
+///
+/// # Returns
+/// An Axum router.
+pub fn public_router() -> Router<AppState> {
+    Router::new().route("/accept", post(accept_invitation))
+}
+
+/// Handler used to revoke a pending invitation.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn revoke_invitation(
+    auth: Authorized<UsersDelete>,
+    Path(invitation_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = RevokeInvitationStores {
+        invitation: SQLxInvitationStore::new(db.clone()),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    RevokeInvitation::new(stores).handle(invitation_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Handler used to list the pending invitations.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn list_invitations(
+    _: Authorized<UsersRead>,
+    db: Db,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = ListInvitationsStores {
+        invitation: SQLxInvitationStore::new(db),
+    };
+
+    let invitations = ListInvitations::new(stores).handle(()).await?;
+
+    Ok(Json(invitations))
+}
+
+/// Handler used to invite a user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn invite_user(
+    auth: Authorized<UsersCreate>,
+    db: Db,
+    cache: Cache,
+    State(state): State<AppState>,
+    FormOrJson(request): FormOrJson<InviteUserRequest>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let invited_by = auth.try_user()?.id;
+
+    let db = db.into_shared();
+
+    let stores = InviteUserStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        invitation: SQLxInvitationStore::new(db.clone()),
+        mailer: FakeMailer::new(),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let invitation = InviteUser::new(state.config, stores, db)
+        .handle((Some(invited_by), request))
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(invitation)))
+}
+
+/// Handler used to send again an invitation (the previous link can't be used anymore).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn resend_invitation(
+    auth: Authorized<UsersCreate>,
+    Path(invitation_id): Path<Uuid>,
+    db: Db,
+    State(state): State<AppState>,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = ResendInvitationStores {
+        invitation: SQLxInvitationStore::new(db.clone()),
+        mailer: FakeMailer::new(),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let invitation = ResendInvitation::new(state.config, stores)
+        .handle(invitation_id)
+        .await?;
+
+    Ok(Json(invitation))
+}
+
+/// Handler used by an invited user to choose its password (no login is needed).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn accept_invitation(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    FormOrJson(request): FormOrJson<AcceptInvitationRequest>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = AcceptInvitationStores {
+        invitation: SQLxInvitationStore::new(db.clone()),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    AcceptInvitation::new(stores, db).handle(request).await?;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,28 @@
+//! Jobs run periodically by the worker.
+
+use auth::{AuditContext, SQLxAuditStore};
+use common_core::UseCase;
+use configuration::Config;
+use database::SharedDb;
+
+use crate::application::*;
+use crate::infrastructure::user::SQLxUserStore;
+use crate::prelude::*;
+
+/// Permanently deletes the users deleted for longer than the retention period.
+///
+/// # Arguments
+/// * `config`: Application configuration.
+/// * `db`: Database handle.
+///
+/// # Returns
+/// The number of users purged.
+pub async fn purge_deleted_users(config: Config, db: SharedDb) -> ApiResult<u64> {
+    let stores = PurgeDeletedUsersStores {
+        user: SQLxUserStore::new(db.clone()),
+        // Performed by the system, outside of any request
+        audit: SQLxAuditStore::new(&db, AuditContext::default()),
+    };
+
+    PurgeDeletedUsers::new(config, stores).handle(()).await
+}
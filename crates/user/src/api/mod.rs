@@ -1,3 +1,8 @@
 //! List of HTTP endpoints for managing users.
 
+pub(crate) mod email_change;
+pub(crate) mod invitation;
+pub(crate) mod jobs;
+pub(crate) mod personal_data;
+pub(crate) mod transfer;
 pub(crate) mod user;
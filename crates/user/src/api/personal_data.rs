@@ -0,0 +1,97 @@
+//! HTTP endpoints for exporting and erasing the personal data of a user.
+
+use axum::extract::{Path, Query};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::Router;
+
+use auth::{Auth, CachedAuthStore, Permission, SQLxAuditStore, SQLxAuthStore, UsersDelete};
+use common_core::UseCase;
+use common_state::AppState;
+use database::{Cache, Db};
+
+use crate::application::*;
+use crate::domain::personal_data::PersonalDataOptions;
+use crate::infrastructure::cache::{CachedEmailChangeStore, CachedUserStore};
+use crate::infrastructure::email_change::SQLxEmailChangeStore;
+use crate::infrastructure::user::SQLxUserStore;
+use crate::prelude::*;
+
+/// Builds an Axum router.
+///
+/// # Returns
+/// An Axum router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/current/export", get(export_personal_data))
+        .route("/:user_id/erase", post(erase_user))
+}
+
+/// Handler used to download all the personal data of the current user.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn export_personal_data(
+    auth: Auth,
+    Query(options): Query<PersonalDataOptions>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
+    let user_id = auth.try_user()?.id;
+
+    let db = db.into_shared();
+
+    let stores = ExportPersonalDataStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        email_change: CachedEmailChangeStore::new(
+            SQLxEmailChangeStore::new(db.clone()),
+            &db,
+            &cache,
+        ),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let data = ExportPersonalData::new(stores).handle(user_id).await?;
+
+    let format = options.format;
+
+    let headers = [
+        (CONTENT_TYPE, format.content_type().to_string()),
+        (
+            CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"personal_data.{}\"",
+                format.extension()
+            ),
+        ),
+    ];
+
+    Ok((headers, data.encode(format)?))
+}
+
+/// Handler used to erase the personal data of a user (by the user itself or by an admin user).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn erase_user(
+    auth: Auth,
+    Path(user_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    auth.require_permission_or_self(UsersDelete::NAME, &user_id)?;
+
+    let db = db.into_shared();
+
+    let stores = EraseUserStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    EraseUser::new(stores, db).handle(user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
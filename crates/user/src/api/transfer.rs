@@ -0,0 +1,107 @@
+//! HTTP endpoints for importing and exporting users in bulk (by an admin user).
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Query, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::StreamExt;
+use tracing::{event, Level};
+
+use auth::{Authorized, CachedAuthStore, SQLxAuditStore, SQLxAuthStore, UsersCreate, UsersRead};
+use common_core::UseCase;
+use common_state::AppState;
+use database::{Cache, Db};
+use mailer::FakeMailer;
+
+use crate::application::*;
+use crate::domain::transfer::{ExportOptions, ImportOptions, TransferFormat};
+use crate::domain::user::UserFilters;
+use crate::infrastructure::cache::CachedUserStore;
+use crate::infrastructure::user::SQLxUserStore;
+use crate::prelude::*;
+
+/// Builds an Axum router.
+///
+/// # Returns
+/// An Axum router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/export", get(export_users))
+        .route("/import", post(import_users))
+}
+
+/// Handler used to import users from a CSV (`text/csv`) or JSON Lines (`application/x-ndjson`)
+/// file. Each row is validated like a user creation and the result of each row is returned.
+#[instrument(skip(body))]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn import_users(
+    auth: Authorized<UsersCreate>,
+    Query(options): Query<ImportOptions>,
+    db: Db,
+    cache: Cache,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<impl IntoResponse> {
+    let format = headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(TransferFormat::from_content_type)
+        .ok_or(Error::UnsupportedMediaType)?;
+
+    let rows = format.parse(&body);
+
+    let db = db.into_shared();
+
+    let stores = ImportUsersStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        mailer: FakeMailer::new(),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let report = ImportUsers::new(state.config, stores, db)
+        .handle((rows, options.dry_run))
+        .await?;
+
+    Ok(Json(report))
+}
+
+/// Handler used to export the users that match some filters (the body is streamed).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn export_users(
+    _: Authorized<UsersRead>,
+    Query(filters): Query<UserFilters>,
+    Query(options): Query<ExportOptions>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = ExportUsersStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+    };
+
+    let users = ExportUsers::new(stores).handle(filters).await?;
+
+    let format = options.format;
+
+    let body = users.enumerate().map(move |(index, user)| {
+        user.and_then(|user| format.encode(&user.into(), index == 0))
+            .inspect_err(|e| event!(Level::ERROR, "Export of users failed: {e}"))
+    });
+
+    let headers = [
+        (CONTENT_TYPE, format.content_type().to_string()),
+        (
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"users.{}\"", format.extension()),
+        ),
+    ];
+
+    Ok((headers, Body::from_stream(body)))
+}
@@ -1,23 +1,33 @@
 //! HTTP endpoints for user management (mostly by an admin user).
 
-use axum::extract::{Path, Query, State};
+use axum::extract::{OriginalUri, Path, Query, State};
+use axum::http::header::LINK;
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::response::{AppendHeaders, IntoResponse, Response};
 use axum::routing::{delete, get, patch, post, put};
 use axum::{Json, Router};
 use validator::Validate;
 
-use auth::{Auth, SQLxAuthStore};
+use auth::{
+    rate_limit_layer, Auth, Authorized, CachedAuthStore, Permission, SQLxAuditStore, SQLxAuthStore,
+    UsersCreate, UsersDelete, UsersRead, UsersUpdate,
+};
 use common_core::UseCase;
 use common_state::AppState;
+use common_web::conditional::Preconditions;
 use common_web::extractor::FormOrJson;
-use database::Db;
+use common_web::pagination::PageRequest;
+use database::{Cache, Db};
 use mailer::FakeMailer;
 
+use crate::api::{email_change, invitation, personal_data, transfer};
 use crate::application::*;
 use crate::domain::user::{
-    CreateUserRequest, PasswordUpdateRequest, UpdateUserRequest, UpsertUserRequest, UserFilters,
+    CreateUserRequest, PasswordUpdateRequest, RegisterUserRequest, UpdateUserRequest,
+    UpsertUserRequest, User, UserFilters,
 };
+use crate::infrastructure::cache::{CachedEmailChangeStore, CachedUserStore};
+use crate::infrastructure::email_change::SQLxEmailChangeStore;
 use crate::infrastructure::user::SQLxUserStore;
 use crate::prelude::*;
 
@@ -33,26 +43,46 @@ pub fn router() -> Router<AppState> {
         .route("/", get(get_users_by_filters))
         .route("/:user_id", patch(update_user))
         .route("/:user_id/password", patch(set_user_password))
+        .route("/:user_id/restore", post(restore_user_by_id))
         .route("/", post(create_user))
         .route("/", put(upsert_user))
+        .nest("/invitations", invitation::router())
+        .merge(transfer::router())
+        .merge(personal_data::router())
+}
+
+/// Builds an Axum router with the endpoints that don't require authentication.
+///
+/// # Arguments
+/// * `state`: Application state (used by the rate limiting middleware).
+///
+/// # Returns
+/// An Axum router.
+pub fn public_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/profile",
+            post(register_user).route_layer(rate_limit_layer!(state, requests_only)),
+        )
+        .nest("/email", email_change::public_router())
+        .nest("/invitations", invitation::public_router())
 }
 
 /// Handler used to delete a user giving its ID.
 #[instrument]
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn delete_user_by_id(
-    auth: Auth,
+    auth: Authorized<UsersDelete>,
     Path(user_id): Path<Uuid>,
     db: Db,
+    cache: Cache,
 ) -> ApiResult<impl IntoResponse> {
-    if !auth.try_user()?.is_admin() {
-        return Err(Error::Forbidden);
-    }
-
     let db = db.into_shared();
 
     let stores = DeleteUserByIdStores {
-        user: SQLxUserStore::new(db),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
     DeleteUserById::new(stores).handle(user_id).await?;
@@ -60,91 +90,163 @@ pub(crate) async fn delete_user_by_id(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Handler used to restore a deleted user giving its ID.
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn restore_user_by_id(
+    auth: Authorized<UsersDelete>,
+    Path(user_id): Path<Uuid>,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
+    let db = db.into_shared();
+
+    let stores = RestoreUserByIdStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let user = RestoreUserById::new(stores).handle(user_id).await?;
+
+    Ok(Json(user))
+}
+
+/// Builds the response of a user with its entity tag (or `304 Not Modified` if the client
+/// already has this version).
+///
+/// # Arguments
+/// * `user`: User to return.
+/// * `preconditions`: Preconditions of the request.
+///
+/// # Returns
+/// The response.
+fn user_response(user: User, preconditions: &Preconditions) -> Response {
+    let etag = user.etag();
+
+    if !preconditions.if_none_match(&etag) {
+        return (StatusCode::NOT_MODIFIED, etag, ()).into_response();
+    }
+
+    (etag, Json(user)).into_response()
+}
+
 /// Handler used to get information about the currently logged user.
 #[instrument]
 #[axum::debug_handler(state = AppState)]
-pub(crate) async fn get_current_user(auth: Auth, db: Db) -> ApiResult<impl IntoResponse> {
+pub(crate) async fn get_current_user(
+    auth: Auth,
+    preconditions: Preconditions,
+    db: Db,
+    cache: Cache,
+) -> ApiResult<impl IntoResponse> {
     let db = db.into_shared();
 
     let stores = GetUserByIdStores {
-        user: SQLxUserStore::new(db),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
     };
 
     let user = GetUserById::new(stores).handle(auth.try_user()?.id).await?;
 
-    Ok(Json(user))
+    Ok(user_response(user, &preconditions))
 }
 
 /// Handler used to get a specify user by providing its ID.
 #[instrument]
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn get_user_by_id(
-    auth: Auth,
+    _: Authorized<UsersRead>,
     Path(user_id): Path<Uuid>,
+    preconditions: Preconditions,
     db: Db,
+    cache: Cache,
 ) -> ApiResult<impl IntoResponse> {
-    if !auth.try_user()?.is_admin() {
-        return Err(Error::Forbidden);
-    }
-
     let db = db.into_shared();
 
     let stores = GetUserByIdStores {
-        user: SQLxUserStore::new(db),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
     };
 
     let user = GetUserById::new(stores).handle(user_id).await?;
 
-    Ok(Json(user))
+    Ok(user_response(user, &preconditions))
 }
 
 /// Handler used to get a list of users that match some filters.
 #[instrument]
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn get_users_by_filters(
-    auth: Auth,
+    _: Authorized<UsersRead>,
+    OriginalUri(uri): OriginalUri,
     Query(filters): Query<UserFilters>,
+    Query(page): Query<PageRequest>,
     db: Db,
+    cache: Cache,
 ) -> ApiResult<impl IntoResponse> {
-    if !auth.try_user()?.is_admin() {
-        return Err(Error::Forbidden);
-    }
-
     let db = db.into_shared();
 
     let stores = GetUsersByFiltersStores {
-        user: SQLxUserStore::new(db),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
     };
 
-    let users = GetUsersByFilters::new(stores).handle(filters).await?;
+    let users = GetUsersByFilters::new(stores)
+        .handle((filters, page.clone()))
+        .await?;
+
+    let links = users.links(&uri, &page).map(|links| (LINK, links));
 
-    Ok(Json(users))
+    Ok((AppendHeaders(links), Json(users)))
 }
 
 /// Handler used to create a new user.
 #[instrument]
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn create_user(
-    auth: Auth,
+    auth: Authorized<UsersCreate>,
     db: Db,
+    cache: Cache,
     State(state): State<AppState>,
     FormOrJson(request): FormOrJson<CreateUserRequest>,
 ) -> ApiResult<impl IntoResponse> {
-    if !auth.try_user()?.is_admin() {
-        return Err(Error::Forbidden);
-    }
-
     request.validate()?;
 
     let db = db.into_shared();
 
     let stores = CreateUserStores {
-        user: SQLxUserStore::new(db.clone()),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
         mailer: FakeMailer::new(),
-        auth: SQLxAuthStore::new(&db),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
-    let user = CreateUser::new(state.config, stores)
+    let user = CreateUser::new(state.config, stores, db)
+        .handle(request)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+/// Handler used by a user to register itself (the email must be confirmed before login).
+#[instrument]
+#[axum::debug_handler(state = AppState)]
+pub(crate) async fn register_user(
+    auth: Auth,
+    db: Db,
+    cache: Cache,
+    State(state): State<AppState>,
+    FormOrJson(request): FormOrJson<RegisterUserRequest>,
+) -> ApiResult<impl IntoResponse> {
+    request.validate()?;
+
+    let db = db.into_shared();
+
+    let stores = RegisterUserStores {
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        mailer: FakeMailer::new(),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
+    };
+
+    let user = RegisterUser::new(state.config, stores, db)
         .handle(request)
         .await?;
 
@@ -156,18 +258,16 @@ pub(crate) async fn create_user(
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn upsert_user(
     auth: Auth,
+    preconditions: Preconditions,
     db: Db,
+    cache: Cache,
     State(state): State<AppState>,
     FormOrJson(request): FormOrJson<UpsertUserRequest>,
 ) -> ApiResult<impl IntoResponse> {
-    let user = auth.try_user()?;
-
     let rc = match request.user_id {
         Some(user_id) => {
             // Update of existing user
-            if !user.is_admin() && !user.is(&user_id) {
-                return Err(Error::Forbidden);
-            }
+            auth.require_permission_or_self(UsersUpdate::NAME, &user_id)?;
 
             request.validate()?;
 
@@ -176,9 +276,7 @@ pub(crate) async fn upsert_user(
 
         None => {
             // Creation
-            if !user.is_admin() {
-                return Err(Error::Forbidden);
-            }
+            auth.require_permission(UsersCreate::NAME)?;
 
             request.validate()?;
 
@@ -189,16 +287,22 @@ pub(crate) async fn upsert_user(
     let db = db.into_shared();
 
     let stores = UpsertUserStores {
-        user: SQLxUserStore::new(db.clone()),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
         mailer: FakeMailer::new(),
-        auth: SQLxAuthStore::new(&db),
+        auth: CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache),
+        email_change: CachedEmailChangeStore::new(
+            SQLxEmailChangeStore::new(db.clone()),
+            &db,
+            &cache,
+        ),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
-    let user = UpsertUser::new(state.config, stores)
-        .handle(request)
+    let user = UpsertUser::new(state.config, stores, db)
+        .handle((request, preconditions))
         .await?;
 
-    Ok((rc, Json(user)))
+    Ok((rc, user.etag(), Json(user)))
 }
 
 // TODO: move to profile (even admin should not be able to update a user directly.
@@ -207,27 +311,35 @@ pub(crate) async fn upsert_user(
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn update_user(
     auth: Auth,
+    preconditions: Preconditions,
     db: Db,
+    cache: Cache,
+    State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
     FormOrJson(request): FormOrJson<UpdateUserRequest>,
 ) -> ApiResult<impl IntoResponse> {
-    let user = auth.try_user()?;
-
-    if !user.is_admin() && !user.is(&user_id) {
-        return Err(Error::Forbidden);
-    }
+    auth.require_permission_or_self(UsersUpdate::NAME, &user_id)?;
 
     request.validate()?;
 
     let db = db.into_shared();
 
     let stores = UpdateUserStores {
-        user: SQLxUserStore::new(db),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        email_change: CachedEmailChangeStore::new(
+            SQLxEmailChangeStore::new(db.clone()),
+            &db,
+            &cache,
+        ),
+        mailer: FakeMailer::new(),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
-    let user = UpdateUser::new(stores).handle((user_id, request)).await?;
+    let user = UpdateUser::new(state.config, stores)
+        .handle((user_id, request, preconditions))
+        .await?;
 
-    Ok(Json(user))
+    Ok((user.etag(), Json(user)))
 }
 
 // TODO: move to profile (even admin should not be able to update a user directly.
@@ -236,10 +348,14 @@ pub(crate) async fn update_user(
 #[axum::debug_handler(state = AppState)]
 pub(crate) async fn set_user_password(
     auth: Auth,
+    preconditions: Preconditions,
     db: Db,
+    cache: Cache,
     Path(user_id): Path<Uuid>,
     FormOrJson(request): FormOrJson<PasswordUpdateRequest>,
 ) -> ApiResult<impl IntoResponse> {
+    auth.require_login()?;
+
     if !auth.try_user()?.is(&user_id) {
         return Err(Error::Forbidden);
     }
@@ -249,12 +365,79 @@ pub(crate) async fn set_user_password(
     let db = db.into_shared();
 
     let stores = SetUserPasswordStores {
-        user: SQLxUserStore::new(db),
+        user: CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache),
+        audit: SQLxAuditStore::new(&db, auth.audit_context()),
     };
 
     SetUserPassword::new(stores)
-        .handle((user_id, request))
+        .handle((user_id, request, preconditions))
         .await?;
 
     Ok(StatusCode::OK)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::Request;
+    use bb8_redis::{bb8, RedisConnectionManager};
+    use sqlx::postgres::PgPoolOptions;
+    use tower::util::ServiceExt;
+    use tower_sessions::{MemoryStore, SessionManagerLayer};
+
+    use common_state::Replicas;
+    use configuration::Config;
+    use test_utils::rand::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_register_user_rate_limit() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let pool = PgPoolOptions::new()
+            .connect(&std::env::var("DATABASE_URL_TEST")?)
+            .await?;
+
+        let redis = bb8::Pool::builder()
+            .build(RedisConnectionManager::new(std::env::var(
+                "REDIS_URL_TEST",
+            )?)?)
+            .await?;
+
+        // The requests are counted by email (the client IP address is unknown here)
+        let mut config = Config::new()?;
+        config.auth.rate_limit.enabled = true;
+        config.auth.rate_limit.max_requests_per_ip = u32::MAX;
+        config.auth.rate_limit.max_requests_per_email = 2;
+
+        let state = AppState::new(config, pool, Replicas::default(), redis);
+
+        let router = public_router(&state)
+            .layer(SessionManagerLayer::new(MemoryStore::default()))
+            .with_state(state);
+
+        // The registration itself is refused (invalid request), only the counters matter
+        let body = serde_json::json!({ "email": random_email() }).to_string();
+
+        let send = || {
+            let request = Request::post("/profile")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body.clone()))
+                .unwrap();
+
+            router.clone().oneshot(request)
+        };
+
+        for _ in 0..2 {
+            let response = send().await?;
+            assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        let response = send().await?;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        Ok(())
+    }
+}
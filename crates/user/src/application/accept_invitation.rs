@@ -0,0 +1,238 @@
+//! Use-case for accepting an invitation (the password is chosen and the email is confirmed).
+
+use tracing::{event, Level};
+
+use auth::{AuditAction, AuditRecord, AuditStore, AuthStore, Expiring};
+use common_core::UseCase;
+use database::Transactional;
+
+use crate::domain::invitation::AcceptInvitationRequest;
+use crate::domain::port::InvitationStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct AcceptInvitationStores<A, B, C>
+where
+    A: InvitationStore,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Invitation store.
+    pub invitation: A,
+
+    /// Auth store.
+    pub auth: B,
+
+    /// Audit store.
+    pub audit: C,
+}
+
+/// Invitation acceptance use-case structure.
+pub(crate) struct AcceptInvitation<A, B, C, T>
+where
+    A: InvitationStore,
+    B: AuthStore,
+    C: AuditStore,
+    T: Transactional,
+{
+    /// List of stores used.
+    stores: AcceptInvitationStores<A, B, C>,
+
+    /// Database handle.
+    db: T,
+}
+
+impl<A, B, C, T> AcceptInvitation<A, B, C, T>
+where
+    A: InvitationStore,
+    B: AuthStore,
+    C: AuditStore,
+    T: Transactional,
+{
+    /// Creates a new `AcceptInvitation` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
+    ///
+    /// # Returns
+    /// An `AcceptInvitation` instance.
+    pub fn new(stores: AcceptInvitationStores<A, B, C>, db: T) -> Self {
+        Self { stores, db }
+    }
+}
+
+impl<A, B, C, T> UseCase for AcceptInvitation<A, B, C, T>
+where
+    A: InvitationStore,
+    B: AuthStore,
+    C: AuditStore,
+    T: Transactional,
+{
+    type Args = AcceptInvitationRequest;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
+        // The invitation is only consumed if the password is set
+        let uow = self.db.begin().await?;
+
+        // The invitation is deleted right away so that it can't be used twice
+        let invitation = self
+            .stores
+            .invitation
+            .consume(request.token)
+            .await?
+            .ok_or(Error::InvitationNotFound)?;
+
+        if invitation.is_expired() {
+            return Err(Error::InvitationExpired);
+        }
+
+        self.stores
+            .auth
+            .set_password(&invitation.user_id, &request.password.hashed()?)
+            .await?;
+
+        // The link has been received by email so the email is confirmed
+        self.stores
+            .auth
+            .delete_user_confirmation_by_user_id(&invitation.user_id)
+            .await?;
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::InvitationAccept, Some(invitation.user_id))
+                    .actor(invitation.user_id),
+            )
+            .await?;
+
+        uow.commit().await?;
+
+        event!(
+            Level::INFO,
+            "Invitation accepted by user {}",
+            invitation.user_id
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use auth::MockAuthStore;
+    use database::DetachedDb;
+    use security::password::{set_checks, Checks};
+    use test_utils::rand::*;
+
+    use crate::domain::invitation::Invitation;
+    use crate::domain::port::MockInvitationStore;
+    use crate::tests::utils::mock_audit_store;
+
+    fn mock_store(invitation: Option<Invitation>) -> MockInvitationStore {
+        let mut invitation_store = MockInvitationStore::new();
+
+        invitation_store
+            .expect_consume()
+            .times(1)
+            .returning(move |_| {
+                let invitation = invitation.clone();
+                Box::pin(async move { Ok(invitation) })
+            });
+
+        invitation_store
+    }
+
+    fn request() -> AcceptInvitationRequest {
+        AcceptInvitationRequest {
+            token: random_id(),
+            password: random_password(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_accept_invitation_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        set_checks(Checks::default());
+
+        let invitation = Invitation {
+            id: random_id(),
+            user_id: random_id(),
+            expires_at: Utc::now() + Duration::hours(1),
+            ..Default::default()
+        };
+
+        let user_id = invitation.user_id;
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_set_password()
+            .withf(move |id, _| *id == user_id)
+            .times(1)
+            .returning(|_, _| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_delete_user_confirmation_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = AcceptInvitationStores {
+            invitation: mock_store(Some(invitation)),
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::InvitationAccept]),
+        };
+
+        AcceptInvitation::new(stores, DetachedDb)
+            .handle(request())
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_accept_invitation_errors() -> Result<(), Box<dyn std::error::Error>> {
+        // Unknown token
+        let mut auth_store = MockAuthStore::new();
+        auth_store.expect_set_password().never();
+
+        let stores = AcceptInvitationStores {
+            invitation: mock_store(None),
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = AcceptInvitation::new(stores, DetachedDb)
+            .handle(request())
+            .await;
+        assert!(matches!(res, Err(Error::InvitationNotFound)));
+
+        // Expired invitation
+        let invitation = Invitation {
+            expires_at: Utc::now() - Duration::hours(1),
+            ..Default::default()
+        };
+
+        let mut auth_store = MockAuthStore::new();
+        auth_store.expect_set_password().never();
+
+        let stores = AcceptInvitationStores {
+            invitation: mock_store(Some(invitation)),
+            auth: auth_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = AcceptInvitation::new(stores, DetachedDb)
+            .handle(request())
+            .await;
+        assert!(matches!(res, Err(Error::InvitationExpired)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! Staging of the email changes requested when updating a user.
+
+use chrono::Duration;
+
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore};
+use configuration::Config;
+use mailer::MailerProvider;
+
+use crate::domain::email_change::EmailChange;
+use crate::domain::port::{EmailChangeStore, UserStore};
+use crate::domain::user::{User, UserFilters};
+use crate::prelude::*;
+
+/// Stages a change of email: the email of the user is changed only when the new address is
+/// confirmed. The previous address is notified and can revert the change.
+///
+/// # Arguments
+/// * `config`: Application configuration.
+/// * `users`: User store.
+/// * `email_changes`: Email change store.
+/// * `mailer`: Mailer provider.
+/// * `audit`: Audit store.
+/// * `user`: User (with its current email).
+/// * `new_email`: Email requested.
+///
+/// # Returns
+/// The staged change.
+pub(crate) async fn stage_email_change<A, B, C, D>(
+    config: &Config,
+    users: &A,
+    email_changes: &B,
+    mailer: &C,
+    audit: &D,
+    user: &User,
+    new_email: String,
+) -> ApiResult<EmailChange>
+where
+    A: UserStore,
+    B: EmailChangeStore,
+    C: MailerProvider,
+    D: AuditStore,
+{
+    // Soft-deleted users keep their email until they are purged
+    let existing = users
+        .get_by_filters(UserFilters {
+            email: Some(new_email.clone()),
+            include_deleted: true,
+            ..Default::default()
+        })
+        .await?;
+
+    if !existing.is_empty() {
+        return Err(Error::EmailAlreadyUsed);
+    }
+
+    let timeout = Duration::hours(config.auth.email_change_timeout_hours.into());
+
+    let change = email_changes
+        .create(user.id, user.email.clone(), new_email, timeout)
+        .await?;
+
+    let redirect_url = std::env::var("FRONTEND_URL")?;
+
+    mailer
+        .send_email_change_confirmation(&change.new_email, &change.id, &redirect_url)
+        .await?;
+
+    mailer
+        .send_email_change_notification(
+            &change.old_email,
+            &change.new_email,
+            &change.revert_token,
+            &redirect_url,
+        )
+        .await?;
+
+    audit
+        .record(
+            AuditRecord::new(AuditAction::EmailChangeRequest, Some(user.id)).changes(
+                AuditChanges::default().field("email", &change.old_email, &change.new_email),
+            ),
+        )
+        .await?;
+
+    Ok(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use mailer::MockMailerProvider;
+    use test_utils::rand::*;
+
+    use crate::domain::port::{MockEmailChangeStore, MockUserStore};
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_stage_email_change_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let user = User {
+            id: random_id(),
+            email: random_email(),
+            ..Default::default()
+        };
+
+        let new_email = random_email();
+
+        let mut users = MockUserStore::new();
+        let mut email_changes = MockEmailChangeStore::new();
+        let mut mailer = MockMailerProvider::new();
+
+        users
+            .expect_get_by_filters()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(vec![]) }));
+
+        email_changes
+            .expect_create()
+            .times(1)
+            .returning(|user_id, old_email, new_email, _| {
+                Box::pin(async move {
+                    Ok(EmailChange {
+                        id: random_id(),
+                        user_id,
+                        old_email,
+                        new_email,
+                        revert_token: random_id(),
+                        ..Default::default()
+                    })
+                })
+            });
+
+        mailer
+            .expect_send_email_change_confirmation()
+            .withf({
+                let new_email = new_email.clone();
+                move |email, _, _| email == new_email
+            })
+            .times(1)
+            .returning(|_, _, _| Box::pin(async move { Ok(()) }));
+
+        mailer
+            .expect_send_email_change_notification()
+            .withf({
+                let old_email = user.email.clone();
+                move |email, _, _, _| email == old_email
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async move { Ok(()) }));
+
+        let change = stage_email_change(
+            &Config::new()?,
+            &users,
+            &email_changes,
+            &mailer,
+            &mock_audit_store(&[AuditAction::EmailChangeRequest]),
+            &user,
+            new_email.clone(),
+        )
+        .await?;
+        assert_eq!(change.old_email, user.email);
+        assert_eq!(change.new_email, new_email);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stage_email_change_already_used() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let mut users = MockUserStore::new();
+        let mut email_changes = MockEmailChangeStore::new();
+
+        users
+            .expect_get_by_filters()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(vec![User::default()]) }));
+
+        email_changes.expect_create().never();
+
+        let res = stage_email_change(
+            &Config::new()?,
+            &users,
+            &email_changes,
+            &MockMailerProvider::new(),
+            &mock_audit_store(&[]),
+            &User::default(),
+            random_email(),
+        )
+        .await;
+        assert!(matches!(res, Err(Error::EmailAlreadyUsed)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stage_email_change_deleted_user() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let new_email = random_email();
+
+        let mut users = MockUserStore::new();
+        let mut email_changes = MockEmailChangeStore::new();
+
+        users
+            .expect_get_by_filters()
+            .withf({
+                let new_email = new_email.clone();
+                move |filters| {
+                    filters.include_deleted && filters.email.as_deref() == Some(&new_email)
+                }
+            })
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Ok(vec![User {
+                        deleted_at: Some(Utc::now()),
+                        ..Default::default()
+                    }])
+                })
+            });
+
+        email_changes.expect_create().never();
+
+        let res = stage_email_change(
+            &Config::new()?,
+            &users,
+            &email_changes,
+            &MockMailerProvider::new(),
+            &mock_audit_store(&[]),
+            &User::default(),
+            new_email,
+        )
+        .await;
+        assert!(matches!(res, Err(Error::EmailAlreadyUsed)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! Use-case for confirming the new email of a user (with the link received at this address).
+
+use tracing::{event, Level};
+
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore, Expiring};
+use common_core::UseCase;
+use database::Transactional;
+
+use crate::domain::port::EmailChangeStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ConfirmEmailChangeStores<A, B>
+where
+    A: EmailChangeStore,
+    B: AuditStore,
+{
+    /// Email change store.
+    pub email_change: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Email change confirmation use-case structure.
+pub(crate) struct ConfirmEmailChange<A, B, T>
+where
+    A: EmailChangeStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    /// List of stores used.
+    stores: ConfirmEmailChangeStores<A, B>,
+
+    /// Database handle.
+    db: T,
+}
+
+impl<A, B, T> ConfirmEmailChange<A, B, T>
+where
+    A: EmailChangeStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    /// Creates a new `ConfirmEmailChange` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
+    ///
+    /// # Returns
+    /// A `ConfirmEmailChange` instance.
+    pub fn new(stores: ConfirmEmailChangeStores<A, B>, db: T) -> Self {
+        Self { stores, db }
+    }
+}
+
+impl<A, B, T> UseCase for ConfirmEmailChange<A, B, T>
+where
+    A: EmailChangeStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    type Args = Uuid;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, token: Self::Args) -> Result<Self::Output, Self::Error> {
+        let change = self
+            .stores
+            .email_change
+            .get_by_id(token)
+            .await?
+            .filter(|change| !change.is_confirmed())
+            .ok_or(Error::EmailChangeNotFound)?;
+
+        if change.is_expired() {
+            return Err(Error::EmailChangeExpired);
+        }
+
+        // The email is only changed if the change is audited
+        let uow = self.db.begin().await?;
+
+        self.stores.email_change.confirm(change.id).await?;
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::EmailChangeConfirm, Some(change.user_id))
+                    .actor(change.user_id)
+                    .changes(AuditChanges::default().field(
+                        "email",
+                        &change.old_email,
+                        &change.new_email,
+                    )),
+            )
+            .await?;
+
+        uow.commit().await?;
+
+        event!(Level::INFO, "Email changed for user {}", change.user_id);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use database::DetachedDb;
+    use test_utils::rand::*;
+
+    use crate::domain::email_change::EmailChange;
+    use crate::domain::port::MockEmailChangeStore;
+    use crate::tests::utils::mock_audit_store;
+
+    fn mock_store(change: Option<EmailChange>, confirmed: bool) -> MockEmailChangeStore {
+        let mut store = MockEmailChangeStore::new();
+
+        store.expect_get_by_id().times(1).returning(move |_| {
+            let change = change.clone();
+            Box::pin(async move { Ok(change) })
+        });
+
+        store
+            .expect_confirm()
+            .times(usize::from(confirmed))
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        store
+    }
+
+    #[tokio::test]
+    async fn test_confirm_email_change_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let change = EmailChange {
+            id: random_id(),
+            expires_at: Utc::now() + Duration::hours(1),
+            ..Default::default()
+        };
+
+        let stores = ConfirmEmailChangeStores {
+            email_change: mock_store(Some(change.clone()), true),
+            audit: mock_audit_store(&[AuditAction::EmailChangeConfirm]),
+        };
+
+        ConfirmEmailChange::new(stores, DetachedDb)
+            .handle(change.id)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirm_email_change_errors() -> Result<(), Box<dyn std::error::Error>> {
+        // Unknown token
+        let stores = ConfirmEmailChangeStores {
+            email_change: mock_store(None, false),
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = ConfirmEmailChange::new(stores, DetachedDb)
+            .handle(random_id())
+            .await;
+        assert!(matches!(res, Err(Error::EmailChangeNotFound)));
+
+        // Already confirmed
+        let change = EmailChange {
+            confirmed_at: Some(Utc::now()),
+            expires_at: Utc::now() + Duration::hours(1),
+            ..Default::default()
+        };
+
+        let stores = ConfirmEmailChangeStores {
+            email_change: mock_store(Some(change), false),
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = ConfirmEmailChange::new(stores, DetachedDb)
+            .handle(random_id())
+            .await;
+        assert!(matches!(res, Err(Error::EmailChangeNotFound)));
+
+        // Expired
+        let change = EmailChange {
+            expires_at: Utc::now() - Duration::hours(1),
+            ..Default::default()
+        };
+
+        let stores = ConfirmEmailChangeStores {
+            email_change: mock_store(Some(change), false),
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = ConfirmEmailChange::new(stores, DetachedDb)
+            .handle(random_id())
+            .await;
+        assert!(matches!(res, Err(Error::EmailChangeExpired)));
+
+        Ok(())
+    }
+}
@@ -2,9 +2,10 @@
 
 use chrono::Duration;
 
-use auth::AuthStore;
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore, AuthStore};
 use common_core::UseCase;
 use configuration::Config;
+use database::Transactional;
 use mailer::MailerProvider;
 
 use crate::domain::port::UserStore;
@@ -12,11 +13,12 @@ use crate::domain::user::{CreateUserRequest, User, UserData};
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct CreateUserStores<A, B, C>
+pub(crate) struct CreateUserStores<A, B, C, D>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: AuditStore,
 {
     /// User store.
     pub user: A,
@@ -26,52 +28,75 @@ where
 
     /// Auth store.
     pub auth: C,
+
+    /// Audit store.
+    pub audit: D,
 }
 
 /// User creation use-case structure.
-pub(crate) struct CreateUser<A, B, C>
+pub(crate) struct CreateUser<A, B, C, D, T>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
 {
     /// Application configuration.
     config: Config,
 
     /// List of stores used.
-    stores: CreateUserStores<A, B, C>,
+    stores: CreateUserStores<A, B, C, D>,
+
+    /// Database handle.
+    db: T,
 }
 
-impl<A, B, C> CreateUser<A, B, C>
+impl<A, B, C, D, T> CreateUser<A, B, C, D, T>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
 {
     /// Creates a new `CreateUser` use-case instance.
     ///
     /// # Arguments
     /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
     ///
     /// # Returns
     /// A `CreateUser` instance.
-    pub fn new(config: Config, stores: CreateUserStores<A, B, C>) -> Self {
-        Self { config, stores }
+    pub fn new(config: Config, stores: CreateUserStores<A, B, C, D>, db: T) -> Self {
+        Self { config, stores, db }
+    }
+
+    /// Gets the stores used by this use-case.
+    ///
+    /// # Returns
+    /// The list of stores.
+    pub fn stores(&self) -> &CreateUserStores<A, B, C, D> {
+        &self.stores
     }
 }
 
-impl<A, B, C> UseCase for CreateUser<A, B, C>
+impl<A, B, C, D, T> UseCase for CreateUser<A, B, C, D, T>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
 {
     type Args = CreateUserRequest;
     type Output = User;
     type Error = Error;
 
-    // TODO: to be done in the same transaction
     async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
+        // The user is not created if any of the steps fails
+        let uow = self.db.begin().await?;
+
         // User creation
         let data = UserData {
             password: request.password.hashed()?,
@@ -100,6 +125,16 @@ where
             .send_email_confirmation(&user.email, &confirmation.id, &redirect_url)
             .await?;
 
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::UserCreate, Some(user.id))
+                    .changes(AuditChanges::diff(None, Some(&user))),
+            )
+            .await?;
+
+        uow.commit().await?;
+
         Ok(user)
     }
 }
@@ -110,12 +145,14 @@ mod tests {
 
     use auth::{AuthUserConfirmation, MockAuthStore};
     use configuration::Config;
+    use database::DetachedDb;
     use mailer::MockMailerProvider;
     use security::password::{set_checks, Checks};
     use test_utils::rand::*;
 
     use crate::domain::port::MockUserStore;
     use crate::domain::user::UserRole;
+    use crate::tests::utils::mock_audit_store;
 
     #[tokio::test]
     async fn test_create_user_nominal() -> Result<(), Box<dyn std::error::Error>> {
@@ -154,9 +191,10 @@ mod tests {
             user: user_store,
             mailer,
             auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::UserCreate]),
         };
 
-        let res = CreateUser::new(config, stores)
+        let res = CreateUser::new(config, stores, DetachedDb)
             .handle(CreateUserRequest {
                 first_name: random_string(),
                 last_name: random_string(),
@@ -1,31 +1,44 @@
-//! Use-case for deleting a user.
+//! Use-case for deleting a user (it can be restored until purged).
 
+use auth::{AuditAction, AuditRecord, AuditStore, AuthStore};
 use common_core::UseCase;
 
 use crate::domain::port::UserStore;
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct DeleteUserByIdStores<A>
+pub(crate) struct DeleteUserByIdStores<A, B, C>
 where
     A: UserStore,
+    B: AuthStore,
+    C: AuditStore,
 {
     /// User store.
     pub user: A,
+
+    /// Auth store.
+    pub auth: B,
+
+    /// Audit store.
+    pub audit: C,
 }
 
 /// User deletion use-case structure.
-pub(crate) struct DeleteUserById<A>
+pub(crate) struct DeleteUserById<A, B, C>
 where
     A: UserStore,
+    B: AuthStore,
+    C: AuditStore,
 {
     /// List of stores used.
-    stores: DeleteUserByIdStores<A>,
+    stores: DeleteUserByIdStores<A, B, C>,
 }
 
-impl<A> DeleteUserById<A>
+impl<A, B, C> DeleteUserById<A, B, C>
 where
     A: UserStore,
+    B: AuthStore,
+    C: AuditStore,
 {
     /// Creates a new `DeleteUserById` use-case instance.
     ///
@@ -34,14 +47,16 @@ where
     ///
     /// # Returns
     /// A `DeleteUserById` instance.
-    pub fn new(stores: DeleteUserByIdStores<A>) -> Self {
+    pub fn new(stores: DeleteUserByIdStores<A, B, C>) -> Self {
         Self { stores }
     }
 }
 
-impl<A> UseCase for DeleteUserById<A>
+impl<A, B, C> UseCase for DeleteUserById<A, B, C>
 where
     A: UserStore,
+    B: AuthStore,
+    C: AuditStore,
 {
     type Args = Uuid;
     type Output = ();
@@ -58,7 +73,25 @@ where
             return Err(Error::NotFound);
         }
 
-        self.stores.user.delete_by_id(user_id).await
+        self.stores.user.delete_by_id(user_id).await?;
+
+        // The sessions are not removed by the deletion anymore
+        self.stores
+            .auth
+            .delete_sessions_by_user_id(&user_id)
+            .await?;
+
+        self.stores
+            .auth
+            .revoke_refresh_tokens_by_user_id(&user_id)
+            .await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::UserDelete, Some(user_id)))
+            .await?;
+
+        Ok(())
     }
 }
 
@@ -66,9 +99,11 @@ where
 mod tests {
     use super::*;
 
+    use auth::MockAuthStore;
     use test_utils::rand::random_id;
 
     use crate::domain::port::MockUserStore;
+    use crate::tests::utils::mock_audit_store;
 
     #[tokio::test]
     async fn test_delete_user_by_id_not_found() {
@@ -81,7 +116,11 @@ mod tests {
             Box::pin(async move { Ok(false) })
         });
 
-        let stores = DeleteUserByIdStores { user: user_store };
+        let stores = DeleteUserByIdStores {
+            user: user_store,
+            auth: MockAuthStore::new(),
+            audit: mock_audit_store(&[]),
+        };
 
         let res = DeleteUserById::new(stores).handle(user_id).await;
         assert!(matches!(res, Err(Error::NotFound)));
@@ -106,7 +145,25 @@ mod tests {
                 Box::pin(async move { Ok(()) })
             });
 
-        let stores = DeleteUserByIdStores { user: user_store };
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_delete_sessions_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_revoke_refresh_tokens_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = DeleteUserByIdStores {
+            user: user_store,
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::UserDelete]),
+        };
 
         let res = DeleteUserById::new(stores).handle(user_id).await;
         assert!(res.is_ok());
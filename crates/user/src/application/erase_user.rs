@@ -0,0 +1,159 @@
+//! Use-case for erasing the personal data of a user (right to erasure).
+
+use auth::{AuditAction, AuditRecord, AuditStore};
+use common_core::UseCase;
+use database::Transactional;
+use security::password::Password;
+
+use crate::domain::personal_data::erased_email;
+use crate::domain::port::UserStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct EraseUserStores<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// User store.
+    pub user: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// User erasure use-case structure.
+pub(crate) struct EraseUser<A, B, T>
+where
+    A: UserStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    /// List of stores used.
+    stores: EraseUserStores<A, B>,
+
+    /// Database handle.
+    db: T,
+}
+
+impl<A, B, T> EraseUser<A, B, T>
+where
+    A: UserStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    /// Creates a new `EraseUser` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
+    ///
+    /// # Returns
+    /// A `EraseUser` instance.
+    pub fn new(stores: EraseUserStores<A, B>, db: T) -> Self {
+        Self { stores, db }
+    }
+}
+
+impl<A, B, T> UseCase for EraseUser<A, B, T>
+where
+    A: UserStore,
+    B: AuditStore,
+    T: Transactional,
+{
+    type Args = Uuid;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, user_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        // The row is kept (anonymised) so that the references to the user remain valid, and the
+        // password is replaced by one nobody knows
+        let password = Password::from(Uuid::new_v4().to_string()).hashed()?;
+
+        // The audit trail must not keep any personal data of an erased user
+        let uow = self.db.begin().await?;
+
+        self.stores
+            .user
+            .erase_by_id(user_id, erased_email(&user_id), password)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        self.stores.audit.anonymize_by_user_id(&user_id).await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::UserErase, Some(user_id)))
+            .await?;
+
+        uow.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use database::DetachedDb;
+    use test_utils::rand::random_id;
+
+    use crate::domain::port::MockUserStore;
+    use crate::domain::user::User;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_erase_user_not_found() -> Result<(), Box<dyn std::error::Error>> {
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_erase_by_id()
+            .times(1)
+            .returning(|_, _, _| Box::pin(async move { Ok(None) }));
+
+        let stores = EraseUserStores {
+            user: user_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = EraseUser::new(stores, DetachedDb).handle(random_id()).await;
+        assert!(matches!(res, Err(Error::NotFound)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_erase_user_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut user_store = MockUserStore::new();
+
+        let user_id = random_id();
+
+        user_store
+            .expect_erase_by_id()
+            .times(1)
+            .returning(move |id, email, password| {
+                assert_eq!(id, user_id);
+                assert_eq!(email, erased_email(&user_id));
+                assert!(password.as_str().starts_with("$argon2"));
+                Box::pin(async move { Ok(Some(User::default())) })
+            });
+
+        let mut audit_store = mock_audit_store(&[AuditAction::UserErase]);
+
+        audit_store
+            .expect_anonymize_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = EraseUserStores {
+            user: user_store,
+            audit: audit_store,
+        };
+
+        EraseUser::new(stores, DetachedDb).handle(user_id).await?;
+
+        Ok(())
+    }
+}
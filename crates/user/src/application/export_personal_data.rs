@@ -0,0 +1,188 @@
+//! Use-case for exporting all the personal data of a user.
+
+use auth::{AuditStore, AuthStore};
+use common_core::UseCase;
+
+use crate::domain::personal_data::PersonalData;
+use crate::domain::port::{EmailChangeStore, UserStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ExportPersonalDataStores<A, B, C, D>
+where
+    A: UserStore,
+    B: AuthStore,
+    C: EmailChangeStore,
+    D: AuditStore,
+{
+    /// User store.
+    pub user: A,
+
+    /// Auth store.
+    pub auth: B,
+
+    /// Email change store.
+    pub email_change: C,
+
+    /// Audit store.
+    pub audit: D,
+}
+
+/// Personal data export use-case structure.
+pub(crate) struct ExportPersonalData<A, B, C, D>
+where
+    A: UserStore,
+    B: AuthStore,
+    C: EmailChangeStore,
+    D: AuditStore,
+{
+    /// List of stores used.
+    stores: ExportPersonalDataStores<A, B, C, D>,
+}
+
+impl<A, B, C, D> ExportPersonalData<A, B, C, D>
+where
+    A: UserStore,
+    B: AuthStore,
+    C: EmailChangeStore,
+    D: AuditStore,
+{
+    /// Creates a new `ExportPersonalData` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ExportPersonalData` instance.
+    pub fn new(stores: ExportPersonalDataStores<A, B, C, D>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B, C, D> UseCase for ExportPersonalData<A, B, C, D>
+where
+    A: UserStore,
+    B: AuthStore,
+    C: EmailChangeStore,
+    D: AuditStore,
+{
+    type Args = Uuid;
+    type Output = PersonalData;
+    type Error = Error;
+
+    async fn handle(&self, user_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        if !self
+            .stores
+            .user
+            .exists(user_id)
+            .await
+            .map_err(|_| Error::NotFound)?
+        {
+            return Err(Error::NotFound);
+        }
+
+        let user = self.stores.user.get_by_id(user_id).await?;
+        let sessions = self.stores.auth.get_sessions_by_user_id(&user_id).await?;
+        let email_changes = self.stores.email_change.get_by_user_id(user_id).await?;
+        let audit = self.stores.audit.get_by_user_id(&user_id).await?;
+
+        Ok(PersonalData::new(user, sessions, email_changes, audit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use auth::{AuthSession, MockAuditStore, MockAuthStore};
+    use test_utils::rand::{random_email, random_id};
+
+    use crate::domain::email_change::EmailChange;
+    use crate::domain::port::{MockEmailChangeStore, MockUserStore};
+    use crate::domain::user::User;
+
+    #[tokio::test]
+    async fn test_export_personal_data_not_found() {
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_exists()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(false) }));
+
+        let stores = ExportPersonalDataStores {
+            user: user_store,
+            auth: MockAuthStore::new(),
+            email_change: MockEmailChangeStore::new(),
+            audit: MockAuditStore::new(),
+        };
+
+        let res = ExportPersonalData::new(stores).handle(random_id()).await;
+        assert!(matches!(res, Err(Error::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_export_personal_data_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let user = User {
+            id: random_id(),
+            email: random_email(),
+            ..Default::default()
+        };
+
+        let user_id = user.id;
+
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_exists()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(true) }));
+
+        user_store.expect_get_by_id().times(1).returning(move |id| {
+            assert_eq!(id, user_id);
+            let user = user.clone();
+            Box::pin(async move { Ok(user) })
+        });
+
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_get_sessions_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(vec![AuthSession::default()]) }));
+
+        let mut email_change_store = MockEmailChangeStore::new();
+
+        email_change_store
+            .expect_get_by_user_id()
+            .times(1)
+            .returning(move |id| {
+                assert_eq!(id, user_id);
+                Box::pin(async move { Ok(vec![EmailChange::default()]) })
+            });
+
+        let mut audit_store = MockAuditStore::new();
+
+        audit_store
+            .expect_get_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(vec![]) }));
+
+        let stores = ExportPersonalDataStores {
+            user: user_store,
+            auth: auth_store,
+            email_change: email_change_store,
+            audit: audit_store,
+        };
+
+        let data = ExportPersonalData::new(stores).handle(user_id).await?;
+        assert_eq!(data.profile.id, user_id);
+        assert_eq!(data.sessions.len(), 1);
+        assert_eq!(data.confirmations.email_changes.len(), 1);
+        assert!(data.audit.is_empty());
+
+        Ok(())
+    }
+}
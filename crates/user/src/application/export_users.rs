@@ -0,0 +1,92 @@
+//! Use-case for exporting users in bulk.
+
+use futures::stream::BoxStream;
+
+use common_core::UseCase;
+
+use crate::domain::port::UserStore;
+use crate::domain::user::{User, UserFilters};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ExportUsersStores<A>
+where
+    A: UserStore,
+{
+    /// User store.
+    pub user: A,
+}
+
+/// Users export use-case structure.
+pub(crate) struct ExportUsers<A>
+where
+    A: UserStore,
+{
+    /// List of stores used.
+    stores: ExportUsersStores<A>,
+}
+
+impl<A> ExportUsers<A>
+where
+    A: UserStore,
+{
+    /// Creates a new `ExportUsers` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ExportUsers` instance.
+    pub fn new(stores: ExportUsersStores<A>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A> UseCase for ExportUsers<A>
+where
+    A: UserStore,
+{
+    type Args = UserFilters;
+    type Output = BoxStream<'static, ApiResult<User>>;
+    type Error = Error;
+
+    async fn handle(&self, filters: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(self.stores.user.stream_by_filters(filters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::StreamExt;
+
+    use crate::domain::port::MockUserStore;
+    use crate::domain::user::UserRole;
+
+    #[tokio::test]
+    async fn test_export_users_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_stream_by_filters()
+            .times(1)
+            .returning(move |filters| {
+                assert_eq!(filters.role, Some(UserRole::Admin));
+                futures::stream::iter(vec![Ok(User::default()), Ok(User::default())]).boxed()
+            });
+
+        let stores = ExportUsersStores { user: user_store };
+
+        let users = ExportUsers::new(stores)
+            .handle(UserFilters {
+                role: Some(UserRole::Admin),
+                ..Default::default()
+            })
+            .await?;
+
+        assert_eq!(users.count().await, 2);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Use-case for getting users by filters.
 
 use common_core::UseCase;
+use common_web::pagination::{Page, PageRequest};
 
 use crate::domain::port::UserStore;
 use crate::domain::user::{User, UserFilters};
@@ -44,12 +45,14 @@ impl<A> UseCase for GetUsersByFilters<A>
 where
     A: UserStore,
 {
-    type Args = UserFilters;
-    type Output = Vec<User>;
+    type Args = (UserFilters, PageRequest);
+    type Output = Page<User>;
     type Error = Error;
 
-    async fn handle(&self, filters: Self::Args) -> Result<Self::Output, Self::Error> {
-        self.stores.user.get_by_filters(filters).await
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (filters, page) = args;
+
+        self.stores.user.get_page_by_filters(filters, page).await
     }
 }
 
@@ -57,8 +60,10 @@ where
 mod tests {
     use super::*;
 
+    use common_web::pagination::{Sort, SortOrder};
+
     use crate::domain::port::MockUserStore;
-    use crate::domain::user::UserRole;
+    use crate::domain::user::{TextMatch, UserRole, UserSortField};
     use test_utils::rand::random_string;
 
     #[tokio::test]
@@ -70,19 +75,32 @@ mod tests {
             last_name: Some(random_string()),
             email: Some(random_string()),
             role: Some(UserRole::Guest),
+            text_match: Some(TextMatch::Prefix),
+            q: Some(random_string()),
+            include_deleted: true,
+            sort: Some(Sort {
+                field: UserSortField::Email,
+                order: SortOrder::Desc,
+            }),
+        };
+
+        let page = PageRequest {
+            limit: Some(10),
+            ..Default::default()
         };
 
         user_store
-            .expect_get_by_filters()
+            .expect_get_page_by_filters()
             .times(1)
-            .returning(move |filters| {
+            .returning(move |filters, page| {
                 assert_eq!(filters, filters);
-                Box::pin(async move { Ok(vec![]) })
+                assert_eq!(page, page);
+                Box::pin(async move { Ok(Page::default()) })
             });
 
         let stores = GetUsersByFiltersStores { user: user_store };
 
-        let res = GetUsersByFilters::new(stores).handle(filters).await;
+        let res = GetUsersByFilters::new(stores).handle((filters, page)).await;
         assert!(res.is_ok());
     }
 }
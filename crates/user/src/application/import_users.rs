@@ -0,0 +1,341 @@
+//! Use-case for importing users in bulk.
+
+use std::collections::HashSet;
+
+use tracing::{event, Level};
+use validator::Validate;
+
+use auth::{AuditStore, AuthStore};
+use common_core::UseCase;
+use configuration::Config;
+use database::Transactional;
+use mailer::MailerProvider;
+
+use crate::application::{CreateUser, CreateUserStores};
+use crate::domain::port::UserStore;
+use crate::domain::transfer::{ImportReport, ImportRow, ImportRowReport, ImportStatus};
+use crate::domain::user::UserFilters;
+use crate::prelude::*;
+
+/// Stores used by this use-case (same as the creation).
+pub(crate) type ImportUsersStores<A, B, C, D> = CreateUserStores<A, B, C, D>;
+
+/// Users import use-case structure.
+pub(crate) struct ImportUsers<A, B, C, D, T>
+where
+    A: UserStore,
+    B: MailerProvider,
+    C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
+{
+    /// Creation of the users (each one in its own unit of work).
+    creation: CreateUser<A, B, C, D, T>,
+}
+
+impl<A, B, C, D, T> ImportUsers<A, B, C, D, T>
+where
+    A: UserStore,
+    B: MailerProvider,
+    C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
+{
+    /// Creates a new `ImportUsers` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
+    ///
+    /// # Returns
+    /// A `ImportUsers` instance.
+    pub fn new(config: Config, stores: ImportUsersStores<A, B, C, D>, db: T) -> Self {
+        Self {
+            creation: CreateUser::new(config, stores, db),
+        }
+    }
+
+    /// Checks if an email is already used (deleted users included as they keep their email
+    /// until purged).
+    ///
+    /// # Arguments
+    /// * `email`: Email to check.
+    ///
+    /// # Returns
+    /// `true` if used, `false` otherwise.
+    async fn is_email_used(&self, email: &str) -> ApiResult<bool> {
+        let existing = self
+            .creation
+            .stores()
+            .user
+            .get_by_filters(UserFilters {
+                email: Some(email.to_string()),
+                include_deleted: true,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(!existing.is_empty())
+    }
+
+    /// Imports a row.
+    ///
+    /// # Arguments
+    /// * `row`: Row to import.
+    /// * `dry_run`: Whether the user is only checked (not created).
+    /// * `seen`: Emails of the previous rows (lowercase).
+    ///
+    /// # Returns
+    /// The result of the row.
+    async fn import(
+        &self,
+        row: ImportRow,
+        dry_run: bool,
+        seen: &mut HashSet<String>,
+    ) -> ImportRowReport {
+        let mut report = ImportRowReport {
+            line: row.line,
+            email: None,
+            status: ImportStatus::Failed,
+            reason: None,
+            user_id: None,
+        };
+
+        let request = match row.request {
+            Ok(request) => request,
+            Err(reason) => {
+                report.reason = Some(reason);
+                return report;
+            }
+        };
+
+        report.email = Some(request.email.clone());
+
+        if let Err(e) = request.validate() {
+            report.reason = Some(e.to_string());
+            return report;
+        }
+
+        if !seen.insert(request.email.to_lowercase()) {
+            report.status = ImportStatus::Skipped;
+            report.reason = Some("Email duplicated in the file".to_string());
+            return report;
+        }
+
+        match self.is_email_used(&request.email).await {
+            Ok(false) => (),
+            Ok(true) => {
+                report.status = ImportStatus::Skipped;
+                report.reason = Some(Error::EmailAlreadyUsed.to_string());
+                return report;
+            }
+            Err(e) => {
+                report.reason = Some(e.to_string());
+                return report;
+            }
+        }
+
+        if dry_run {
+            report.status = ImportStatus::Created;
+            return report;
+        }
+
+        match self.creation.handle(request).await {
+            Ok(user) => {
+                report.status = ImportStatus::Created;
+                report.user_id = Some(user.id);
+            }
+            Err(e) => {
+                event!(Level::WARN, "Import of line {} failed: {e}", row.line);
+                report.reason = Some(e.to_string());
+            }
+        }
+
+        report
+    }
+}
+
+impl<A, B, C, D, T> UseCase for ImportUsers<A, B, C, D, T>
+where
+    A: UserStore,
+    B: MailerProvider,
+    C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
+{
+    type Args = (Vec<ImportRow>, bool);
+    type Output = ImportReport;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (rows, dry_run) = args;
+
+        let mut report = ImportReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        let mut seen = HashSet::new();
+
+        for row in rows {
+            report.push(self.import(row, dry_run, &mut seen).await);
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use auth::{AuditAction, AuthUserConfirmation, MockAuthStore};
+    use database::DetachedDb;
+    use mailer::MockMailerProvider;
+    use security::password::{set_checks, Checks};
+    use test_utils::rand::*;
+
+    use crate::domain::port::MockUserStore;
+    use crate::domain::user::{CreateUserRequest, User, UserRole};
+    use crate::tests::utils::mock_audit_store;
+
+    fn row(line: u64, email: &str) -> ImportRow {
+        ImportRow {
+            line,
+            request: Ok(CreateUserRequest {
+                first_name: random_string(),
+                last_name: random_string(),
+                email: email.to_string(),
+                role: UserRole::Normal,
+                password: random_password(),
+            }),
+        }
+    }
+
+    fn rows() -> (String, Vec<ImportRow>) {
+        let used = random_email();
+
+        let rows = vec![
+            row(2, "john@doe.com"),
+            row(3, &used),
+            row(4, "JOHN@doe.com"),
+            row(5, "invalid"),
+            ImportRow {
+                line: 6,
+                request: Err("missing field `email`".to_string()),
+            },
+        ];
+
+        (used, rows)
+    }
+
+    fn user_store(used: String, creations: usize) -> MockUserStore {
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_get_by_filters()
+            .times(2)
+            .returning(move |filters| {
+                let found = filters.email == Some(used.clone());
+                assert!(filters.include_deleted);
+
+                Box::pin(async move { Ok(if found { vec![User::default()] } else { vec![] }) })
+            });
+
+        user_store
+            .expect_create()
+            .times(creations)
+            .returning(move |data| {
+                Box::pin(async move {
+                    Ok(User {
+                        id: random_id(),
+                        email: data.email,
+                        ..Default::default()
+                    })
+                })
+            });
+
+        user_store
+    }
+
+    fn statuses(report: &ImportReport) -> Vec<ImportStatus> {
+        report.rows.iter().map(|row| row.status).collect()
+    }
+
+    #[tokio::test]
+    async fn test_import_users_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        set_checks(Checks::default());
+
+        let (used, rows) = rows();
+
+        let mut mailer = MockMailerProvider::new();
+        let mut auth_store = MockAuthStore::new();
+
+        auth_store
+            .expect_create_user_confirmation()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthUserConfirmation::default()) }));
+
+        mailer
+            .expect_send_email_confirmation()
+            .times(1)
+            .returning(move |_, _, _| Box::pin(async move { Ok(()) }));
+
+        let stores = ImportUsersStores {
+            user: user_store(used, 1),
+            mailer,
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::UserCreate]),
+        };
+
+        let report = ImportUsers::new(Config::new()?, stores, DetachedDb)
+            .handle((rows, false))
+            .await?;
+
+        assert!(!report.dry_run);
+        assert_eq!((report.created, report.skipped, report.failed), (1, 2, 2));
+        assert_eq!(
+            statuses(&report),
+            vec![
+                ImportStatus::Created,
+                ImportStatus::Skipped,
+                ImportStatus::Skipped,
+                ImportStatus::Failed,
+                ImportStatus::Failed,
+            ]
+        );
+        assert!(report.rows[0].user_id.is_some());
+        assert_eq!(report.rows[4].email, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_users_dry_run() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        set_checks(Checks::default());
+
+        let (used, rows) = rows();
+
+        let stores = ImportUsersStores {
+            user: user_store(used, 0),
+            mailer: MockMailerProvider::new(),
+            auth: MockAuthStore::new(),
+            audit: mock_audit_store(&[]),
+        };
+
+        let report = ImportUsers::new(Config::new()?, stores, DetachedDb)
+            .handle((rows, true))
+            .await?;
+
+        assert!(report.dry_run);
+        assert_eq!((report.created, report.skipped, report.failed), (1, 2, 2));
+        assert_eq!(report.rows[0].user_id, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! Use-case for inviting a user (the password is chosen by the user when accepting).
+
+use chrono::Duration;
+
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore, AuthStore};
+use common_core::UseCase;
+use configuration::Config;
+use database::Transactional;
+use mailer::MailerProvider;
+use security::password::Password;
+
+use crate::domain::invitation::{Invitation, InviteUserRequest};
+use crate::domain::port::{InvitationStore, UserStore};
+use crate::domain::user::UserData;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct InviteUserStores<A, B, C, D, E>
+where
+    A: UserStore,
+    B: InvitationStore,
+    C: MailerProvider,
+    D: AuthStore,
+    E: AuditStore,
+{
+    /// User store.
+    pub user: A,
+
+    /// Invitation store.
+    pub invitation: B,
+
+    /// Mailer provider.
+    pub mailer: C,
+
+    /// Auth store.
+    pub auth: D,
+
+    /// Audit store.
+    pub audit: E,
+}
+
+/// User invitation use-case structure.
+pub(crate) struct InviteUser<A, B, C, D, E, T>
+where
+    A: UserStore,
+    B: InvitationStore,
+    C: MailerProvider,
+    D: AuthStore,
+    E: AuditStore,
+    T: Transactional,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: InviteUserStores<A, B, C, D, E>,
+
+    /// Database handle.
+    db: T,
+}
+
+impl<A, B, C, D, E, T> InviteUser<A, B, C, D, E, T>
+where
+    A: UserStore,
+    B: InvitationStore,
+    C: MailerProvider,
+    D: AuthStore,
+    E: AuditStore,
+    T: Transactional,
+{
+    /// Creates a new `InviteUser` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
+    ///
+    /// # Returns
+    /// An `InviteUser` instance.
+    pub fn new(config: Config, stores: InviteUserStores<A, B, C, D, E>, db: T) -> Self {
+        Self { config, stores, db }
+    }
+}
+
+impl<A, B, C, D, E, T> UseCase for InviteUser<A, B, C, D, E, T>
+where
+    A: UserStore,
+    B: InvitationStore,
+    C: MailerProvider,
+    D: AuthStore,
+    E: AuditStore,
+    T: Transactional,
+{
+    type Args = (Option<Uuid>, InviteUserRequest);
+    type Output = Invitation;
+    type Error = Error;
+
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (invited_by, request) = args;
+
+        // The user is not created if the invitation can't be sent
+        let uow = self.db.begin().await?;
+
+        // The user can't login until the invitation is accepted
+        let password = Password::from(Uuid::new_v4().to_string()).hashed()?;
+
+        let user = self
+            .stores
+            .user
+            .create(UserData {
+                first_name: Some(request.first_name),
+                last_name: Some(request.last_name),
+                email: request.email,
+                role: request.role,
+                password,
+            })
+            .await?;
+
+        let timeout = Duration::hours(self.config.auth.invitation_timeout_hours.into());
+
+        // The email is confirmed when accepting the invitation
+        self.stores
+            .auth
+            .create_user_confirmation(&user.id, &timeout)
+            .await?;
+
+        let invitation = self
+            .stores
+            .invitation
+            .create(user.id, invited_by, timeout)
+            .await?;
+
+        let redirect_url = std::env::var("FRONTEND_URL")?;
+
+        self.stores
+            .mailer
+            .send_invitation(&invitation.email, &invitation.token, &redirect_url)
+            .await?;
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::InvitationCreate, Some(user.id))
+                    .changes(AuditChanges::diff(None, Some(&user))),
+            )
+            .await?;
+
+        uow.commit().await?;
+
+        Ok(invitation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use auth::{AuthUserConfirmation, MockAuthStore};
+    use database::DetachedDb;
+    use mailer::MockMailerProvider;
+    use test_utils::rand::*;
+
+    use crate::domain::port::{MockInvitationStore, MockUserStore};
+    use crate::domain::user::{User, UserRole};
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_invite_user_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let email = random_email();
+        let invited_by = random_id();
+
+        let mut user_store = MockUserStore::new();
+        let mut invitation_store = MockInvitationStore::new();
+        let mut mailer = MockMailerProvider::new();
+        let mut auth_store = MockAuthStore::new();
+
+        user_store
+            .expect_create()
+            .withf(|data| data.role == UserRole::Normal && !data.password.as_str().is_empty())
+            .times(1)
+            .returning(move |data| {
+                Box::pin(async move {
+                    Ok(User {
+                        id: random_id(),
+                        email: data.email,
+                        ..Default::default()
+                    })
+                })
+            });
+
+        auth_store
+            .expect_create_user_confirmation()
+            .times(1)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthUserConfirmation::default()) }));
+
+        invitation_store
+            .expect_create()
+            .withf(move |_, by, _| *by == Some(invited_by))
+            .times(1)
+            .returning({
+                let email = email.clone();
+                move |user_id, invited_by, _| {
+                    let email = email.clone();
+                    Box::pin(async move {
+                        Ok(Invitation {
+                            id: random_id(),
+                            user_id,
+                            email,
+                            invited_by,
+                            token: random_id(),
+                            ..Default::default()
+                        })
+                    })
+                }
+            });
+
+        mailer
+            .expect_send_invitation()
+            .withf({
+                let email = email.clone();
+                move |to, _, _| to == email
+            })
+            .times(1)
+            .returning(move |_, _, _| Box::pin(async move { Ok(()) }));
+
+        let stores = InviteUserStores {
+            user: user_store,
+            invitation: invitation_store,
+            mailer,
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::InvitationCreate]),
+        };
+
+        let invitation = InviteUser::new(Config::new()?, stores, DetachedDb)
+            .handle((
+                Some(invited_by),
+                InviteUserRequest {
+                    first_name: random_string(),
+                    last_name: random_string(),
+                    email: email.clone(),
+                    role: UserRole::Normal,
+                },
+            ))
+            .await?;
+        assert_eq!(invitation.email, email);
+
+        Ok(())
+    }
+}
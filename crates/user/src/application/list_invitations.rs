@@ -0,0 +1,54 @@
+//! Use-case for listing the pending invitations.
+
+use common_core::UseCase;
+
+use crate::domain::invitation::Invitation;
+use crate::domain::port::InvitationStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ListInvitationsStores<A>
+where
+    A: InvitationStore,
+{
+    /// Invitation store.
+    pub invitation: A,
+}
+
+/// Invitations listing use-case structure.
+pub(crate) struct ListInvitations<A>
+where
+    A: InvitationStore,
+{
+    /// List of stores used.
+    stores: ListInvitationsStores<A>,
+}
+
+impl<A> ListInvitations<A>
+where
+    A: InvitationStore,
+{
+    /// Creates a new `ListInvitations` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ListInvitations` instance.
+    pub fn new(stores: ListInvitationsStores<A>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A> UseCase for ListInvitations<A>
+where
+    A: InvitationStore,
+{
+    type Args = ();
+    type Output = Vec<Invitation>;
+    type Error = Error;
+
+    async fn handle(&self, _: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.stores.invitation.get_all().await
+    }
+}
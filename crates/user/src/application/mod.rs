@@ -1,17 +1,47 @@
 //! List of use-cases used by the api layer.
 
+mod accept_invitation;
+mod change_email;
+mod confirm_email_change;
 mod create_user;
 mod delete_user_by_id;
+mod erase_user;
+mod export_personal_data;
+mod export_users;
 mod get_user_by_id;
 mod get_users_by_filters;
+mod import_users;
+mod invite_user;
+mod list_invitations;
+mod purge_deleted_users;
+mod register_user;
+mod resend_invitation;
+mod restore_user_by_id;
+mod revert_email_change;
+mod revoke_invitation;
 mod set_user_password;
 mod update_user;
 mod upsert_user;
 
+pub(crate) use accept_invitation::{AcceptInvitation, AcceptInvitationStores};
+pub(crate) use change_email::stage_email_change;
+pub(crate) use confirm_email_change::{ConfirmEmailChange, ConfirmEmailChangeStores};
 pub(crate) use create_user::{CreateUser, CreateUserStores};
 pub(crate) use delete_user_by_id::{DeleteUserById, DeleteUserByIdStores};
+pub(crate) use erase_user::{EraseUser, EraseUserStores};
+pub(crate) use export_personal_data::{ExportPersonalData, ExportPersonalDataStores};
+pub(crate) use export_users::{ExportUsers, ExportUsersStores};
 pub(crate) use get_user_by_id::{GetUserById, GetUserByIdStores};
 pub(crate) use get_users_by_filters::{GetUsersByFilters, GetUsersByFiltersStores};
+pub(crate) use import_users::{ImportUsers, ImportUsersStores};
+pub(crate) use invite_user::{InviteUser, InviteUserStores};
+pub(crate) use list_invitations::{ListInvitations, ListInvitationsStores};
+pub(crate) use purge_deleted_users::{PurgeDeletedUsers, PurgeDeletedUsersStores};
+pub(crate) use register_user::{RegisterUser, RegisterUserStores};
+pub(crate) use resend_invitation::{ResendInvitation, ResendInvitationStores};
+pub(crate) use restore_user_by_id::{RestoreUserById, RestoreUserByIdStores};
+pub(crate) use revert_email_change::{RevertEmailChange, RevertEmailChangeStores};
+pub(crate) use revoke_invitation::{RevokeInvitation, RevokeInvitationStores};
 pub(crate) use set_user_password::{SetUserPassword, SetUserPasswordStores};
 pub(crate) use update_user::{UpdateUser, UpdateUserStores};
 pub(crate) use upsert_user::{UpsertUser, UpsertUserStores};
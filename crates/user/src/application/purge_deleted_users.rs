@@ -0,0 +1,121 @@
+//! Use-case for permanently deleting the users deleted for longer than the retention period.
+
+use chrono::{Duration, Utc};
+use tracing::{event, Level};
+
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore};
+use common_core::UseCase;
+use configuration::Config;
+
+use crate::domain::port::UserStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct PurgeDeletedUsersStores<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// User store.
+    pub user: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// Deleted users purge use-case structure.
+pub(crate) struct PurgeDeletedUsers<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: PurgeDeletedUsersStores<A, B>,
+}
+
+impl<A, B> PurgeDeletedUsers<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// Creates a new `PurgeDeletedUsers` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `PurgeDeletedUsers` instance.
+    pub fn new(config: Config, stores: PurgeDeletedUsersStores<A, B>) -> Self {
+        Self { config, stores }
+    }
+}
+
+impl<A, B> UseCase for PurgeDeletedUsers<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    type Args = ();
+    type Output = u64;
+    type Error = Error;
+
+    async fn handle(&self, _: Self::Args) -> Result<Self::Output, Self::Error> {
+        let retention = Duration::days(self.config.users.deleted_retention_days.into());
+
+        let purged = self
+            .stores
+            .user
+            .purge_deleted(Utc::now() - retention)
+            .await?;
+
+        event!(Level::INFO, "{purged} deleted user(s) purged");
+
+        if purged > 0 {
+            self.stores
+                .audit
+                .record(
+                    AuditRecord::new(AuditAction::UserPurge, None)
+                        .changes(AuditChanges::default().field("purged", 0, purged)),
+                )
+                .await?;
+        }
+
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::port::MockUserStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_purge_deleted_users_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let config = Config::new()?;
+        let retention = Duration::days(config.users.deleted_retention_days.into());
+
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_purge_deleted()
+            .withf(move |before| *before <= Utc::now() - retention)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(2) }));
+
+        let stores = PurgeDeletedUsersStores {
+            user: user_store,
+            audit: mock_audit_store(&[AuditAction::UserPurge]),
+        };
+
+        let purged = PurgeDeletedUsers::new(config, stores).handle(()).await?;
+        assert_eq!(purged, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+//! Use-case for registering a user (i.e. creation by the user itself).
+
+use tracing::{event, Level};
+
+use auth::{AuditStore, AuthStore};
+use common_core::UseCase;
+use configuration::Config;
+use database::Transactional;
+use mailer::MailerProvider;
+
+use crate::application::{CreateUser, CreateUserStores};
+use crate::domain::port::UserStore;
+use crate::domain::user::{RegisterUserRequest, User, UserRole};
+use crate::prelude::*;
+
+/// Stores used by this use-case (same as the creation).
+pub(crate) type RegisterUserStores<A, B, C, D> = CreateUserStores<A, B, C, D>;
+
+/// User registration use-case structure.
+pub(crate) struct RegisterUser<A, B, C, D, T>
+where
+    A: UserStore,
+    B: MailerProvider,
+    C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// Creation use-case (also sends the email confirmation).
+    create_user: CreateUser<A, B, C, D, T>,
+}
+
+impl<A, B, C, D, T> RegisterUser<A, B, C, D, T>
+where
+    A: UserStore,
+    B: MailerProvider,
+    C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
+{
+    /// Creates a new `RegisterUser` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
+    ///
+    /// # Returns
+    /// A `RegisterUser` instance.
+    pub fn new(config: Config, stores: RegisterUserStores<A, B, C, D>, db: T) -> Self {
+        Self {
+            create_user: CreateUser::new(config.clone(), stores, db),
+            config,
+        }
+    }
+}
+
+impl<A, B, C, D, T> UseCase for RegisterUser<A, B, C, D, T>
+where
+    A: UserStore,
+    B: MailerProvider,
+    C: AuthStore,
+    D: AuditStore,
+    T: Transactional,
+{
+    type Args = RegisterUserRequest;
+    type Output = User;
+    type Error = Error;
+
+    async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
+        let settings = &self.config.auth.registration;
+
+        if !settings.enabled {
+            return Err(Error::RegistrationDisabled);
+        }
+
+        // A user can't choose its role
+        if request
+            .role
+            .as_ref()
+            .is_some_and(|role| *role != UserRole::default())
+        {
+            event!(
+                Level::WARN,
+                "Registration of {} refused: role {:?} requested",
+                request.email,
+                request.role
+            );
+            return Err(Error::Forbidden);
+        }
+
+        if !settings.is_email_allowed(&request.email) {
+            event!(
+                Level::WARN,
+                "Registration of {} refused: domain not allowed",
+                request.email
+            );
+            return Err(Error::EmailDomainNotAllowed);
+        }
+
+        self.create_user.handle(request.into()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use auth::{AuditAction, AuthUserConfirmation, MockAuditStore, MockAuthStore};
+    use database::DetachedDb;
+    use mailer::MockMailerProvider;
+    use security::password::{set_checks, Checks};
+    use test_utils::rand::*;
+
+    use crate::domain::port::MockUserStore;
+    use crate::tests::utils::mock_audit_store;
+
+    fn request(email: String, role: Option<UserRole>) -> RegisterUserRequest {
+        RegisterUserRequest {
+            first_name: random_string(),
+            last_name: random_string(),
+            email,
+            role,
+            password: random_password(),
+        }
+    }
+
+    fn stores(
+        times: usize,
+    ) -> RegisterUserStores<MockUserStore, MockMailerProvider, MockAuthStore, MockAuditStore> {
+        let mut user_store = MockUserStore::new();
+        let mut mailer = MockMailerProvider::new();
+        let mut auth_store = MockAuthStore::new();
+
+        user_store
+            .expect_create()
+            .withf(|data| data.role == UserRole::Guest)
+            .times(times)
+            .returning(move |_| Box::pin(async move { Ok(User::default()) }));
+
+        auth_store
+            .expect_create_user_confirmation()
+            .times(times)
+            .returning(move |_, _| Box::pin(async move { Ok(AuthUserConfirmation::default()) }));
+
+        mailer
+            .expect_send_email_confirmation()
+            .times(times)
+            .returning(move |_, _, _| Box::pin(async move { Ok(()) }));
+
+        RegisterUserStores {
+            user: user_store,
+            mailer,
+            auth: auth_store,
+            audit: mock_audit_store(&vec![AuditAction::UserCreate; times]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_user_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        set_checks(Checks::default());
+
+        let config = Config::new()?;
+
+        let res = RegisterUser::new(config.clone(), stores(1), DetachedDb)
+            .handle(request(random_email(), None))
+            .await;
+        assert!(res.is_ok());
+
+        let res = RegisterUser::new(config, stores(1), DetachedDb)
+            .handle(request(random_email(), Some(UserRole::Guest)))
+            .await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_user_role() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let config = Config::new()?;
+
+        for role in [UserRole::Admin, UserRole::Normal] {
+            let res = RegisterUser::new(config.clone(), stores(0), DetachedDb)
+                .handle(request(random_email(), Some(role)))
+                .await;
+            assert!(matches!(res, Err(Error::Forbidden)));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_user_domains() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        set_checks(Checks::default());
+
+        let mut config = Config::new()?;
+        config.auth.registration.allowed_domains = vec!["allowed.com".to_string()];
+        config.auth.registration.denied_domains = vec!["denied.com".to_string()];
+
+        let res = RegisterUser::new(config.clone(), stores(1), DetachedDb)
+            .handle(request("john@allowed.com".to_string(), None))
+            .await;
+        assert!(res.is_ok());
+
+        for email in ["john@denied.com", "john@other.com"] {
+            let res = RegisterUser::new(config.clone(), stores(0), DetachedDb)
+                .handle(request(email.to_string(), None))
+                .await;
+            assert!(matches!(res, Err(Error::EmailDomainNotAllowed)));
+        }
+
+        // Disabled registration
+        config.auth.registration.enabled = false;
+
+        let res = RegisterUser::new(config, stores(0), DetachedDb)
+            .handle(request("john@allowed.com".to_string(), None))
+            .await;
+        assert!(matches!(res, Err(Error::RegistrationDisabled)));
+
+        Ok(())
+    }
+}
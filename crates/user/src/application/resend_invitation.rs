@@ -0,0 +1,182 @@
+//! Use-case for sending again an invitation (with a new token).
+
+use chrono::Duration;
+
+use auth::{AuditAction, AuditRecord, AuditStore};
+use common_core::UseCase;
+use configuration::Config;
+use mailer::MailerProvider;
+
+use crate::domain::invitation::Invitation;
+use crate::domain::port::InvitationStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct ResendInvitationStores<A, B, C>
+where
+    A: InvitationStore,
+    B: MailerProvider,
+    C: AuditStore,
+{
+    /// Invitation store.
+    pub invitation: A,
+
+    /// Mailer provider.
+    pub mailer: B,
+
+    /// Audit store.
+    pub audit: C,
+}
+
+/// Invitation sending use-case structure.
+pub(crate) struct ResendInvitation<A, B, C>
+where
+    A: InvitationStore,
+    B: MailerProvider,
+    C: AuditStore,
+{
+    /// Application configuration.
+    config: Config,
+
+    /// List of stores used.
+    stores: ResendInvitationStores<A, B, C>,
+}
+
+impl<A, B, C> ResendInvitation<A, B, C>
+where
+    A: InvitationStore,
+    B: MailerProvider,
+    C: AuditStore,
+{
+    /// Creates a new `ResendInvitation` use-case instance.
+    ///
+    /// # Arguments
+    /// * `config`: Application configuration.
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `ResendInvitation` instance.
+    pub fn new(config: Config, stores: ResendInvitationStores<A, B, C>) -> Self {
+        Self { config, stores }
+    }
+}
+
+impl<A, B, C> UseCase for ResendInvitation<A, B, C>
+where
+    A: InvitationStore,
+    B: MailerProvider,
+    C: AuditStore,
+{
+    type Args = Uuid;
+    type Output = Invitation;
+    type Error = Error;
+
+    async fn handle(&self, invitation_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        let timeout = Duration::hours(self.config.auth.invitation_timeout_hours.into());
+
+        // The previous link can't be used anymore
+        let invitation = self
+            .stores
+            .invitation
+            .renew(invitation_id, timeout)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        let redirect_url = std::env::var("FRONTEND_URL")?;
+
+        self.stores
+            .mailer
+            .send_invitation(&invitation.email, &invitation.token, &redirect_url)
+            .await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(
+                AuditAction::InvitationResend,
+                Some(invitation.user_id),
+            ))
+            .await?;
+
+        Ok(invitation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mailer::MockMailerProvider;
+    use test_utils::rand::*;
+
+    use crate::domain::port::MockInvitationStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_resend_invitation_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let invitation_id = random_id();
+
+        let mut invitation_store = MockInvitationStore::new();
+        let mut mailer = MockMailerProvider::new();
+
+        invitation_store
+            .expect_renew()
+            .withf(move |id, _| *id == invitation_id)
+            .times(1)
+            .returning(|id, _| {
+                Box::pin(async move {
+                    Ok(Some(Invitation {
+                        id,
+                        ..Default::default()
+                    }))
+                })
+            });
+
+        mailer
+            .expect_send_invitation()
+            .times(1)
+            .returning(|_, _, _| Box::pin(async move { Ok(()) }));
+
+        let stores = ResendInvitationStores {
+            invitation: invitation_store,
+            mailer,
+            audit: mock_audit_store(&[AuditAction::InvitationResend]),
+        };
+
+        let invitation = ResendInvitation::new(Config::new()?, stores)
+            .handle(invitation_id)
+            .await?;
+        assert_eq!(invitation.id, invitation_id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resend_invitation_not_found() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        let mut invitation_store = MockInvitationStore::new();
+        let mut mailer = MockMailerProvider::new();
+
+        invitation_store
+            .expect_renew()
+            .times(1)
+            .returning(|_, _| Box::pin(async move { Ok(None) }));
+
+        mailer.expect_send_invitation().never();
+
+        let stores = ResendInvitationStores {
+            invitation: invitation_store,
+            mailer,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = ResendInvitation::new(Config::new()?, stores)
+            .handle(random_id())
+            .await;
+        assert!(matches!(res, Err(Error::NotFound)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! Use-case for restoring a deleted user (before it's purged).
+
+use auth::{AuditAction, AuditRecord, AuditStore};
+use common_core::UseCase;
+
+use crate::domain::port::UserStore;
+use crate::domain::user::User;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RestoreUserByIdStores<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// User store.
+    pub user: A,
+
+    /// Audit store.
+    pub audit: B,
+}
+
+/// User restoration use-case structure.
+pub(crate) struct RestoreUserById<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// List of stores used.
+    stores: RestoreUserByIdStores<A, B>,
+}
+
+impl<A, B> RestoreUserById<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    /// Creates a new `RestoreUserById` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RestoreUserById` instance.
+    pub fn new(stores: RestoreUserByIdStores<A, B>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B> UseCase for RestoreUserById<A, B>
+where
+    A: UserStore,
+    B: AuditStore,
+{
+    type Args = Uuid;
+    type Output = User;
+    type Error = Error;
+
+    async fn handle(&self, user_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        let user = self
+            .stores
+            .user
+            .restore_by_id(user_id)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::UserRestore, Some(user_id)))
+            .await?;
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::rand::random_id;
+
+    use crate::domain::port::MockUserStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_restore_user_by_id_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let mut user_store = MockUserStore::new();
+
+        let user_id = random_id();
+
+        user_store
+            .expect_restore_by_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|id| {
+                Box::pin(async move {
+                    Ok(Some(User {
+                        id,
+                        ..Default::default()
+                    }))
+                })
+            });
+
+        let stores = RestoreUserByIdStores {
+            user: user_store,
+            audit: mock_audit_store(&[AuditAction::UserRestore]),
+        };
+
+        let user = RestoreUserById::new(stores).handle(user_id).await?;
+        assert_eq!(user.id, user_id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_user_by_id_not_deleted() {
+        let mut user_store = MockUserStore::new();
+
+        user_store
+            .expect_restore_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(None) }));
+
+        let stores = RestoreUserByIdStores {
+            user: user_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = RestoreUserById::new(stores).handle(random_id()).await;
+        assert!(matches!(res, Err(Error::NotFound)));
+    }
+}
@@ -0,0 +1,213 @@
+//! Use-case for reverting an email change (with the link received at the previous address).
+
+use tracing::{event, Level};
+
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore, AuthStore, Expiring};
+use common_core::UseCase;
+
+use crate::domain::port::EmailChangeStore;
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RevertEmailChangeStores<A, B, C>
+where
+    A: EmailChangeStore,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Email change store.
+    pub email_change: A,
+
+    /// Auth store.
+    pub auth: B,
+
+    /// Audit store.
+    pub audit: C,
+}
+
+/// Email change revert use-case structure.
+pub(crate) struct RevertEmailChange<A, B, C>
+where
+    A: EmailChangeStore,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// List of stores used.
+    stores: RevertEmailChangeStores<A, B, C>,
+}
+
+impl<A, B, C> RevertEmailChange<A, B, C>
+where
+    A: EmailChangeStore,
+    B: AuthStore,
+    C: AuditStore,
+{
+    /// Creates a new `RevertEmailChange` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RevertEmailChange` instance.
+    pub fn new(stores: RevertEmailChangeStores<A, B, C>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B, C> UseCase for RevertEmailChange<A, B, C>
+where
+    A: EmailChangeStore,
+    B: AuthStore,
+    C: AuditStore,
+{
+    type Args = Uuid;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, token: Self::Args) -> Result<Self::Output, Self::Error> {
+        let change = self
+            .stores
+            .email_change
+            .get_by_revert_token(token)
+            .await?
+            .ok_or(Error::EmailChangeNotFound)?;
+
+        if change.is_expired() {
+            return Err(Error::EmailChangeExpired);
+        }
+
+        self.stores.email_change.revert(change.id).await?;
+
+        // The change wasn't requested by the owner of the account: whoever requested it must be
+        // logged out
+        self.stores
+            .auth
+            .delete_sessions_by_user_id(&change.user_id)
+            .await?;
+
+        self.stores
+            .auth
+            .revoke_refresh_tokens_by_user_id(&change.user_id)
+            .await?;
+
+        // Performed by the owner of the previous address
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::EmailChangeRevert, Some(change.user_id))
+                    .actor(change.user_id)
+                    .changes(AuditChanges::default().field(
+                        "email",
+                        &change.new_email,
+                        &change.old_email,
+                    )),
+            )
+            .await?;
+
+        event!(
+            Level::WARN,
+            "Email change reverted for user {}",
+            change.user_id
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+
+    use auth::MockAuthStore;
+    use test_utils::rand::*;
+
+    use crate::domain::email_change::EmailChange;
+    use crate::domain::port::MockEmailChangeStore;
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_revert_email_change_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let change = EmailChange {
+            id: random_id(),
+            user_id: random_id(),
+            revert_token: random_id(),
+            confirmed_at: Some(Utc::now()),
+            expires_at: Utc::now() + Duration::hours(1),
+            ..Default::default()
+        };
+
+        let change_id = change.id;
+        let user_id = change.user_id;
+
+        let mut email_change_store = MockEmailChangeStore::new();
+        let mut auth_store = MockAuthStore::new();
+
+        email_change_store
+            .expect_get_by_revert_token()
+            .times(1)
+            .returning(move |_| {
+                let change = change.clone();
+                Box::pin(async move { Ok(Some(change)) })
+            });
+
+        email_change_store
+            .expect_revert()
+            .withf(move |id| *id == change_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_delete_sessions_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        auth_store
+            .expect_revoke_refresh_tokens_by_user_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = RevertEmailChangeStores {
+            email_change: email_change_store,
+            auth: auth_store,
+            audit: mock_audit_store(&[AuditAction::EmailChangeRevert]),
+        };
+
+        RevertEmailChange::new(stores).handle(random_id()).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revert_email_change_expired() -> Result<(), Box<dyn std::error::Error>> {
+        let mut email_change_store = MockEmailChangeStore::new();
+
+        email_change_store
+            .expect_get_by_revert_token()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Ok(Some(EmailChange {
+                        expires_at: Utc::now() - Duration::hours(1),
+                        ..Default::default()
+                    }))
+                })
+            });
+
+        email_change_store.expect_revert().never();
+
+        let stores = RevertEmailChangeStores {
+            email_change: email_change_store,
+            auth: MockAuthStore::new(),
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = RevertEmailChange::new(stores).handle(random_id()).await;
+        assert!(matches!(res, Err(Error::EmailChangeExpired)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+//! Use-case for revoking a pending invitation (the invited user is deleted).
+
+use auth::{AuditAction, AuditRecord, AuditStore};
+use common_core::UseCase;
+
+use crate::domain::port::{InvitationStore, UserStore};
+use crate::prelude::*;
+
+/// Stores used by this use-case.
+pub(crate) struct RevokeInvitationStores<A, B, C>
+where
+    A: InvitationStore,
+    B: UserStore,
+    C: AuditStore,
+{
+    /// Invitation store.
+    pub invitation: A,
+
+    /// User store.
+    pub user: B,
+
+    /// Audit store.
+    pub audit: C,
+}
+
+/// Invitation revocation use-case structure.
+pub(crate) struct RevokeInvitation<A, B, C>
+where
+    A: InvitationStore,
+    B: UserStore,
+    C: AuditStore,
+{
+    /// List of stores used.
+    stores: RevokeInvitationStores<A, B, C>,
+}
+
+impl<A, B, C> RevokeInvitation<A, B, C>
+where
+    A: InvitationStore,
+    B: UserStore,
+    C: AuditStore,
+{
+    /// Creates a new `RevokeInvitation` use-case instance.
+    ///
+    /// # Arguments
+    /// * `stores`: List of stores used by this use-case.
+    ///
+    /// # Returns
+    /// A `RevokeInvitation` instance.
+    pub fn new(stores: RevokeInvitationStores<A, B, C>) -> Self {
+        Self { stores }
+    }
+}
+
+impl<A, B, C> UseCase for RevokeInvitation<A, B, C>
+where
+    A: InvitationStore,
+    B: UserStore,
+    C: AuditStore,
+{
+    type Args = Uuid;
+    type Output = ();
+    type Error = Error;
+
+    async fn handle(&self, invitation_id: Self::Args) -> Result<Self::Output, Self::Error> {
+        let invitation = self
+            .stores
+            .invitation
+            .get_by_id(invitation_id)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        // The account has never been used: the invitation is deleted with it
+        self.stores.user.purge_by_id(invitation.user_id).await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(
+                AuditAction::InvitationRevoke,
+                Some(invitation.user_id),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::rand::*;
+
+    use crate::domain::invitation::Invitation;
+    use crate::domain::port::{MockInvitationStore, MockUserStore};
+    use crate::tests::utils::mock_audit_store;
+
+    #[tokio::test]
+    async fn test_revoke_invitation_nominal() -> Result<(), Box<dyn std::error::Error>> {
+        let user_id = random_id();
+
+        let mut invitation_store = MockInvitationStore::new();
+        let mut user_store = MockUserStore::new();
+
+        invitation_store
+            .expect_get_by_id()
+            .times(1)
+            .returning(move |id| {
+                Box::pin(async move {
+                    Ok(Some(Invitation {
+                        id,
+                        user_id,
+                        ..Default::default()
+                    }))
+                })
+            });
+
+        user_store
+            .expect_purge_by_id()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let stores = RevokeInvitationStores {
+            invitation: invitation_store,
+            user: user_store,
+            audit: mock_audit_store(&[AuditAction::InvitationRevoke]),
+        };
+
+        RevokeInvitation::new(stores).handle(random_id()).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_invitation_not_found() -> Result<(), Box<dyn std::error::Error>> {
+        let mut invitation_store = MockInvitationStore::new();
+        let mut user_store = MockUserStore::new();
+
+        invitation_store
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(None) }));
+
+        user_store.expect_purge_by_id().never();
+
+        let stores = RevokeInvitationStores {
+            invitation: invitation_store,
+            user: user_store,
+            audit: mock_audit_store(&[]),
+        };
+
+        let res = RevokeInvitation::new(stores).handle(random_id()).await;
+        assert!(matches!(res, Err(Error::NotFound)));
+
+        Ok(())
+    }
+}
@@ -1,32 +1,40 @@
 //! Use-case for setting a user's password.
 
+use auth::{AuditAction, AuditRecord, AuditStore};
 use common_core::UseCase;
+use common_web::conditional::Preconditions;
 
 use crate::domain::port::UserStore;
 use crate::domain::user::PasswordUpdateRequest;
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct SetUserPasswordStores<A>
+pub(crate) struct SetUserPasswordStores<A, B>
 where
     A: UserStore,
+    B: AuditStore,
 {
     /// User store.
     pub user: A,
+
+    /// Audit store.
+    pub audit: B,
 }
 
 /// Password update use-case structure.
-pub(crate) struct SetUserPassword<A>
+pub(crate) struct SetUserPassword<A, B>
 where
     A: UserStore,
+    B: AuditStore,
 {
     /// List of stores used.
-    stores: SetUserPasswordStores<A>,
+    stores: SetUserPasswordStores<A, B>,
 }
 
-impl<A> SetUserPassword<A>
+impl<A, B> SetUserPassword<A, B>
 where
     A: UserStore,
+    B: AuditStore,
 {
     /// Creates a new `SetUserPassword` use-case instance.
     ///
@@ -35,31 +43,46 @@ where
     ///
     /// # Returns
     /// A `SetUserPassword` instance.
-    pub fn new(stores: SetUserPasswordStores<A>) -> Self {
+    pub fn new(stores: SetUserPasswordStores<A, B>) -> Self {
         Self { stores }
     }
 }
 
-impl<A> UseCase for SetUserPassword<A>
+impl<A, B> UseCase for SetUserPassword<A, B>
 where
     A: UserStore,
+    B: AuditStore,
 {
-    type Args = (Uuid, PasswordUpdateRequest);
+    type Args = (Uuid, PasswordUpdateRequest, Preconditions);
     type Output = ();
     type Error = Error;
 
     async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        let (user_id, request) = args;
+        let (user_id, request, preconditions) = args;
 
         let user = self.stores.user.get_by_id(user_id).await?;
 
-        if !request.current.matches(&user.password).await? {
+        if !preconditions.if_match(Some(&user.etag())) {
+            return Err(Error::PreconditionFailed);
+        }
+
+        // Checked again by the update (the user can be modified concurrently until then)
+        let version = preconditions.if_match.as_ref().map(|_| user.updated_at);
+
+        let password = self.stores.user.get_password(user_id).await?;
+
+        if !request.current.matches(&password).await? {
             return Err(Error::InvalidPassword);
         }
 
         self.stores
             .user
-            .set_user_password(user_id, request.new.hashed()?)
+            .set_user_password(user_id, request.new.hashed()?, version)
+            .await?;
+
+        self.stores
+            .audit
+            .record(AuditRecord::new(AuditAction::PasswordChange, Some(user_id)))
             .await?;
 
         Ok(())
@@ -70,11 +93,12 @@ where
 mod tests {
     use super::*;
 
-    use security::password::{set_checks, Checks};
+    use security::password::{set_checks, Checks, Password};
     use test_utils::rand::*;
 
     use crate::domain::port::MockUserStore;
     use crate::domain::user::User;
+    use crate::tests::utils::mock_audit_store;
 
     #[tokio::test]
     async fn test_set_user_password_validation() {
@@ -82,16 +106,20 @@ mod tests {
 
         let mut user_store = MockUserStore::new();
 
-        user_store.expect_get_by_id().times(1).returning(move |_| {
-            Box::pin(async move {
-                Ok(User {
-                    password: random_password().hashed()?,
-                    ..Default::default()
-                })
-            })
-        });
+        user_store
+            .expect_get_by_id()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(User::default()) }));
 
-        let stores = SetUserPasswordStores { user: user_store };
+        user_store
+            .expect_get_password()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(random_password().hashed()?) }));
+
+        let stores = SetUserPasswordStores {
+            user: user_store,
+            audit: mock_audit_store(&[]),
+        };
 
         let user_id = random_id();
 
@@ -103,6 +131,7 @@ mod tests {
                     current: random_password(),
                     ..Default::default()
                 },
+                Preconditions::default(),
             ))
             .await;
         assert!(matches!(res, Err(Error::InvalidPassword)));
@@ -119,27 +148,84 @@ mod tests {
         let current = password.clone();
         let new = random_password();
 
-        user_store.expect_get_by_id().times(1).returning(move |_| {
-            let password = password.clone();
+        user_store
+            .expect_get_by_id()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(User::default()) }));
 
-            Box::pin(async move {
-                Ok(User {
-                    password: password.hashed()?,
-                    ..Default::default()
-                })
-            })
-        });
+        user_store
+            .expect_get_password()
+            .times(1)
+            .returning(move |_| {
+                let password = password.clone();
+                Box::pin(async move { Ok(password.hashed()?) })
+            });
 
         user_store
             .expect_set_user_password()
             .times(1)
-            .returning(move |_, _| Box::pin(async move { Ok(()) }));
+            .returning(move |_, _, _| Box::pin(async move { Ok(()) }));
 
-        let stores = SetUserPasswordStores { user: user_store };
+        let stores = SetUserPasswordStores {
+            user: user_store,
+            audit: mock_audit_store(&[AuditAction::PasswordChange]),
+        };
 
         let res = SetUserPassword::new(stores)
-            .handle((user_id, PasswordUpdateRequest { current, new }))
+            .handle((
+                user_id,
+                PasswordUpdateRequest { current, new },
+                Preconditions::default(),
+            ))
             .await;
         assert!(res.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_set_user_password_precondition_failed() {
+        let mut user_store = MockUserStore::new();
+
+        let user = User::default();
+        let etag = user.etag();
+
+        user_store
+            .expect_get_by_id()
+            .times(2)
+            .returning(move |_| Box::pin(async move { Ok(User::default()) }));
+
+        // Only read once the precondition passes
+        user_store
+            .expect_get_password()
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(Password::default()) }));
+
+        user_store.expect_set_user_password().never();
+
+        let stores = SetUserPasswordStores {
+            user: user_store,
+            audit: mock_audit_store(&[]),
+        };
+        let use_case = SetUserPassword::new(stores);
+
+        let preconditions = Preconditions {
+            if_match: Some(vec!["\"outdated\"".to_string()]),
+            ..Default::default()
+        };
+
+        let res = use_case
+            .handle((random_id(), PasswordUpdateRequest::default(), preconditions))
+            .await;
+        assert!(matches!(res, Err(Error::PreconditionFailed)));
+
+        // Same version: the precondition passes (the current password is then checked)
+        let preconditions = Preconditions {
+            if_match: Some(vec![etag.to_string()]),
+            ..Default::default()
+        };
+
+        let res = use_case
+            .handle((random_id(), PasswordUpdateRequest::default(), preconditions))
+            .await;
+        assert!(!matches!(res, Err(Error::PreconditionFailed)));
+    }
 }
@@ -1,57 +1,128 @@
 //! Use-case for updating a user.
 
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore};
 use common_core::UseCase;
+use common_web::conditional::Preconditions;
+use configuration::Config;
+use mailer::MailerProvider;
 
-use crate::domain::port::UserStore;
-use crate::domain::user::{UpdateUserRequest, User};
+use crate::application::stage_email_change;
+use crate::domain::port::{EmailChangeStore, UserStore};
+use crate::domain::user::{UpdateUserRequest, User, UserData};
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct UpdateUserStores<A>
+pub(crate) struct UpdateUserStores<A, B, C, D>
 where
     A: UserStore,
+    B: EmailChangeStore,
+    C: MailerProvider,
+    D: AuditStore,
 {
     /// User store.
     pub user: A,
+
+    /// Email change store.
+    pub email_change: B,
+
+    /// Mailer provider.
+    pub mailer: C,
+
+    /// Audit store.
+    pub audit: D,
 }
 
 /// User update use-case structure.
-pub(crate) struct UpdateUser<A>
+pub(crate) struct UpdateUser<A, B, C, D>
 where
     A: UserStore,
+    B: EmailChangeStore,
+    C: MailerProvider,
+    D: AuditStore,
 {
+    /// Application configuration.
+    config: Config,
+
     /// List of stores used.
-    stores: UpdateUserStores<A>,
+    stores: UpdateUserStores<A, B, C, D>,
 }
 
-impl<A> UpdateUser<A>
+impl<A, B, C, D> UpdateUser<A, B, C, D>
 where
     A: UserStore,
+    B: EmailChangeStore,
+    C: MailerProvider,
+    D: AuditStore,
 {
     /// Creates a new `UpdateUser` use-case instance.
     ///
     /// # Arguments
+    /// * `config`: Application configuration.
     /// * `stores`: List of stores used by this use-case.
     ///
     /// # Returns
     /// A `UpdateUser` instance.
-    pub fn new(stores: UpdateUserStores<A>) -> Self {
-        Self { stores }
+    pub fn new(config: Config, stores: UpdateUserStores<A, B, C, D>) -> Self {
+        Self { config, stores }
     }
 }
 
-impl<A> UseCase for UpdateUser<A>
+impl<A, B, C, D> UseCase for UpdateUser<A, B, C, D>
 where
     A: UserStore,
+    B: EmailChangeStore,
+    C: MailerProvider,
+    D: AuditStore,
 {
-    type Args = (Uuid, UpdateUserRequest);
+    type Args = (Uuid, UpdateUserRequest, Preconditions);
     type Output = User;
     type Error = Error;
 
     async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        let (user_id, request) = args;
+        let (user_id, request, preconditions) = args;
+
+        let user = self
+            .stores
+            .user
+            .get_by_id(user_id)
+            .await
+            .map_err(|_| Error::NotFound)?;
+
+        // The user must not have been modified since the version known by the client
+        if !preconditions.if_match(Some(&user.etag())) {
+            return Err(Error::PreconditionFailed);
+        }
+
+        // Checked again by the update (the user can be modified concurrently until then)
+        let version = preconditions.if_match.as_ref().map(|_| user.updated_at);
+
+        let mut data: UserData = request.into();
+
+        // The new email must be confirmed before being used
+        if !data.email.eq_ignore_ascii_case(&user.email) {
+            stage_email_change(
+                &self.config,
+                &self.stores.user,
+                &self.stores.email_change,
+                &self.stores.mailer,
+                &self.stores.audit,
+                &user,
+                std::mem::replace(&mut data.email, user.email.clone()),
+            )
+            .await?;
+        }
 
-        self.stores.user.update(user_id, request.into()).await
+        let updated = self.stores.user.update(user_id, data, version).await?;
+
+        self.stores
+            .audit
+            .record(
+                AuditRecord::new(AuditAction::UserUpdate, Some(user_id))
+                    .changes(AuditChanges::diff(Some(&user), Some(&updated))),
+            )
+            .await?;
+
+        Ok(updated)
     }
 }
 
@@ -59,38 +130,179 @@ where
 mod tests {
     use super::*;
 
+    use mailer::MockMailerProvider;
     use security::password::{set_checks, Checks};
     use test_utils::rand::{random_email, random_id, random_string};
 
-    use crate::domain::port::MockUserStore;
+    use crate::domain::email_change::EmailChange;
+    use crate::domain::port::{MockEmailChangeStore, MockUserStore};
     use crate::domain::user::UserRole;
+    use crate::tests::utils::mock_audit_store;
+
+    fn mock_user_store(email: String) -> MockUserStore {
+        let mut user_store = MockUserStore::new();
+
+        user_store.expect_get_by_id().times(1).returning(move |id| {
+            let email = email.clone();
+            Box::pin(async move {
+                Ok(User {
+                    id,
+                    email,
+                    ..Default::default()
+                })
+            })
+        });
+
+        user_store
+    }
 
     #[tokio::test]
-    async fn test_upsert_user_update_nominal() {
+    async fn test_upsert_user_update_nominal() -> Result<(), Box<dyn std::error::Error>> {
         set_checks(Checks::default());
 
-        let mut user_store = MockUserStore::new();
+        let email = random_email();
+
+        let mut user_store = mock_user_store(email.clone());
 
         user_store
             .expect_update()
             .times(1)
-            .returning(move |_, _| Box::pin(async move { Ok(User::default()) }));
+            .returning(move |_, _, _| Box::pin(async move { Ok(User::default()) }));
+
+        let mut email_change_store = MockEmailChangeStore::new();
+        email_change_store.expect_create().never();
 
-        let stores = UpdateUserStores { user: user_store };
+        let stores = UpdateUserStores {
+            user: user_store,
+            email_change: email_change_store,
+            mailer: MockMailerProvider::new(),
+            audit: mock_audit_store(&[AuditAction::UserUpdate]),
+        };
 
         let user_id = random_id();
 
-        let res = UpdateUser::new(stores)
+        let res = UpdateUser::new(Config::new()?, stores)
             .handle((
                 user_id,
                 UpdateUserRequest {
                     first_name: random_string(),
                     last_name: random_string(),
-                    email: random_email(),
+                    email,
                     role: UserRole::Admin,
                 },
+                Preconditions::default(),
             ))
             .await;
         assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_user_email() -> Result<(), Box<dyn std::error::Error>> {
+        dotenvy::dotenv()?;
+
+        set_checks(Checks::default());
+
+        let email = random_email();
+        let new_email = random_email();
+
+        let mut user_store = mock_user_store(email.clone());
+
+        user_store
+            .expect_get_by_filters()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(vec![]) }));
+
+        // The email is not changed until confirmed
+        user_store
+            .expect_update()
+            .withf({
+                let email = email.clone();
+                move |_, data, _| data.email == email
+            })
+            .times(1)
+            .returning(move |_, _, _| Box::pin(async move { Ok(User::default()) }));
+
+        let mut email_change_store = MockEmailChangeStore::new();
+
+        email_change_store
+            .expect_create()
+            .withf({
+                let new_email = new_email.clone();
+                move |_, _, email, _| *email == new_email
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async move { Ok(EmailChange::default()) }));
+
+        let mut mailer = MockMailerProvider::new();
+
+        mailer
+            .expect_send_email_change_confirmation()
+            .times(1)
+            .returning(|_, _, _| Box::pin(async move { Ok(()) }));
+
+        mailer
+            .expect_send_email_change_notification()
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async move { Ok(()) }));
+
+        let stores = UpdateUserStores {
+            user: user_store,
+            email_change: email_change_store,
+            mailer,
+            audit: mock_audit_store(&[AuditAction::EmailChangeRequest, AuditAction::UserUpdate]),
+        };
+
+        UpdateUser::new(Config::new()?, stores)
+            .handle((
+                random_id(),
+                UpdateUserRequest {
+                    first_name: random_string(),
+                    last_name: random_string(),
+                    email: new_email,
+                    role: UserRole::Admin,
+                },
+                Preconditions::default(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_user_precondition_failed() -> Result<(), Box<dyn std::error::Error>> {
+        let email = random_email();
+
+        let mut user_store = mock_user_store(email.clone());
+        user_store.expect_update().never();
+
+        let stores = UpdateUserStores {
+            user: user_store,
+            email_change: MockEmailChangeStore::new(),
+            mailer: MockMailerProvider::new(),
+            audit: mock_audit_store(&[]),
+        };
+
+        let preconditions = Preconditions {
+            if_match: Some(vec!["\"outdated\"".to_string()]),
+            ..Default::default()
+        };
+
+        let res = UpdateUser::new(Config::new()?, stores)
+            .handle((
+                random_id(),
+                UpdateUserRequest {
+                    first_name: random_string(),
+                    last_name: random_string(),
+                    email,
+                    role: UserRole::Admin,
+                },
+                preconditions,
+            ))
+            .await;
+        assert!(matches!(res, Err(Error::PreconditionFailed)));
+
+        Ok(())
     }
 }
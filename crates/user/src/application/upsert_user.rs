@@ -2,21 +2,26 @@
 
 use chrono::Duration;
 
-use auth::AuthStore;
+use auth::{AuditAction, AuditChanges, AuditRecord, AuditStore, AuthStore};
 use common_core::UseCase;
+use common_web::conditional::Preconditions;
 use configuration::Config;
+use database::Transactional;
 use mailer::MailerProvider;
 
-use crate::domain::port::UserStore;
+use crate::application::stage_email_change;
+use crate::domain::port::{EmailChangeStore, UserStore};
 use crate::domain::user::{UpsertUserRequest, User, UserData};
 use crate::prelude::*;
 
 /// Stores used by this use-case.
-pub(crate) struct UpsertUserStores<A, B, C>
+pub(crate) struct UpsertUserStores<A, B, C, D, E>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: EmailChangeStore,
+    E: AuditStore,
 {
     /// User store.
     pub user: A,
@@ -26,56 +31,126 @@ where
 
     /// Auth store.
     pub auth: C,
+
+    /// Email change store.
+    pub email_change: D,
+
+    /// Audit store.
+    pub audit: E,
 }
 
 /// User creation/update use-case structure.
-pub(crate) struct UpsertUser<A, B, C>
+pub(crate) struct UpsertUser<A, B, C, D, E, T>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: EmailChangeStore,
+    E: AuditStore,
+    T: Transactional,
 {
     /// Application configuration.
     config: Config,
 
     /// List of stores used.
-    stores: UpsertUserStores<A, B, C>,
+    stores: UpsertUserStores<A, B, C, D, E>,
+
+    /// Database handle.
+    db: T,
 }
 
-impl<A, B, C> UpsertUser<A, B, C>
+impl<A, B, C, D, E, T> UpsertUser<A, B, C, D, E, T>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: EmailChangeStore,
+    E: AuditStore,
+    T: Transactional,
 {
     /// Creates a new `UpsertUser` use-case instance.
     ///
     /// # Arguments
     /// * `stores`: List of stores used by this use-case.
+    /// * `db`: Database handle (shared by the stores).
     ///
     /// # Returns
     /// A `UpsertUser` instance.
-    pub fn new(config: Config, stores: UpsertUserStores<A, B, C>) -> Self {
-        Self { config, stores }
+    pub fn new(config: Config, stores: UpsertUserStores<A, B, C, D, E>, db: T) -> Self {
+        Self { config, stores, db }
     }
 }
 
-impl<A, B, C> UseCase for UpsertUser<A, B, C>
+impl<A, B, C, D, E, T> UseCase for UpsertUser<A, B, C, D, E, T>
 where
     A: UserStore,
     B: MailerProvider,
     C: AuthStore,
+    D: EmailChangeStore,
+    E: AuditStore,
+    T: Transactional,
 {
-    type Args = UpsertUserRequest;
+    type Args = (UpsertUserRequest, Preconditions);
     type Output = User;
     type Error = Error;
 
-    async fn handle(&self, request: Self::Args) -> Result<Self::Output, Self::Error> {
-        match request.user_id {
-            Some(user_id) => self.stores.user.update(user_id, request.into()).await,
+    async fn handle(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (request, preconditions) = args;
+
+        // Nothing is created nor updated if any of the steps fails
+        let uow = self.db.begin().await?;
+
+        let user = match request.user_id {
+            Some(user_id) => {
+                let user = self
+                    .stores
+                    .user
+                    .get_by_id(user_id)
+                    .await
+                    .map_err(|_| Error::NotFound)?;
+
+                if !preconditions.if_match(Some(&user.etag())) {
+                    return Err(Error::PreconditionFailed);
+                }
+
+                // Checked again by the update (the user can be modified concurrently until then)
+                let version = preconditions.if_match.as_ref().map(|_| user.updated_at);
+
+                let mut data: UserData = request.into();
+
+                // The new email must be confirmed before being used
+                if !data.email.eq_ignore_ascii_case(&user.email) {
+                    stage_email_change(
+                        &self.config,
+                        &self.stores.user,
+                        &self.stores.email_change,
+                        &self.stores.mailer,
+                        &self.stores.audit,
+                        &user,
+                        std::mem::replace(&mut data.email, user.email.clone()),
+                    )
+                    .await?;
+                }
+
+                let updated = self.stores.user.update(user_id, data, version).await?;
+
+                self.stores
+                    .audit
+                    .record(
+                        AuditRecord::new(AuditAction::UserUpdate, Some(user_id))
+                            .changes(AuditChanges::diff(Some(&user), Some(&updated))),
+                    )
+                    .await?;
+
+                updated
+            }
 
             None => {
-                // User creation
+                // User creation (no current version can match)
+                if !preconditions.if_match(None) {
+                    return Err(Error::PreconditionFailed);
+                }
+
                 let password = request.password.as_ref().ok_or(Error::MissingPassword)?;
 
                 let data = UserData {
@@ -105,9 +180,21 @@ where
                     .send_email_confirmation(&user.email, &confirmation.id, &redirect_url)
                     .await?;
 
-                Ok(user)
+                self.stores
+                    .audit
+                    .record(
+                        AuditRecord::new(AuditAction::UserCreate, Some(user.id))
+                            .changes(AuditChanges::diff(None, Some(&user))),
+                    )
+                    .await?;
+
+                user
             }
-        }
+        };
+
+        uow.commit().await?;
+
+        Ok(user)
     }
 }
 
@@ -117,12 +204,14 @@ mod tests {
 
     use auth::{AuthUserConfirmation, MockAuthStore};
     use configuration::Config;
+    use database::DetachedDb;
     use mailer::MockMailerProvider;
     use security::password::{set_checks, Checks, Password};
     use test_utils::rand::{random_email, random_id, random_string};
 
-    use crate::domain::port::MockUserStore;
+    use crate::domain::port::{MockEmailChangeStore, MockUserStore};
     use crate::domain::user::{UpdateUserRequest, UserRole};
+    use crate::tests::utils::mock_audit_store;
 
     #[tokio::test]
     async fn test_upsert_user_create_nominal() -> Result<(), Box<dyn std::error::Error>> {
@@ -155,19 +244,24 @@ mod tests {
             user: user_store,
             mailer,
             auth: auth_store,
+            email_change: MockEmailChangeStore::new(),
+            audit: mock_audit_store(&[AuditAction::UserCreate]),
         };
 
-        let res = UpsertUser::new(config, stores)
-            .handle(UpsertUserRequest {
-                password: Some(Password::default()),
-                user: UpdateUserRequest {
-                    first_name: random_string(),
-                    last_name: random_string(),
-                    email: random_email(),
-                    role: UserRole::Admin,
+        let res = UpsertUser::new(config, stores, DetachedDb)
+            .handle((
+                UpsertUserRequest {
+                    password: Some(Password::default()),
+                    user: UpdateUserRequest {
+                        first_name: random_string(),
+                        last_name: random_string(),
+                        email: random_email(),
+                        role: UserRole::Admin,
+                    },
+                    ..Default::default()
                 },
-                ..Default::default()
-            })
+                Preconditions::default(),
+            ))
             .await;
         assert!(res.is_ok());
 
@@ -178,14 +272,30 @@ mod tests {
     async fn test_upsert_user_update_nominal() -> Result<(), Box<dyn std::error::Error>> {
         set_checks(Checks::default());
 
+        let email = random_email();
+
         let mut user_store = MockUserStore::new();
         let mailer = MockMailerProvider::new();
         let auth_store = MockAuthStore::new();
 
+        user_store.expect_get_by_id().times(1).returning({
+            let email = email.clone();
+            move |id| {
+                let email = email.clone();
+                Box::pin(async move {
+                    Ok(User {
+                        id,
+                        email,
+                        ..Default::default()
+                    })
+                })
+            }
+        });
+
         user_store
             .expect_update()
             .times(1)
-            .returning(move |_, _| Box::pin(async move { Ok(User::default()) }));
+            .returning(move |_, _, _| Box::pin(async move { Ok(User::default()) }));
 
         let config = Config::new()?;
 
@@ -193,24 +303,86 @@ mod tests {
             user: user_store,
             mailer,
             auth: auth_store,
+            email_change: MockEmailChangeStore::new(),
+            audit: mock_audit_store(&[AuditAction::UserUpdate]),
         };
 
         let user_id = random_id();
 
-        let res = UpsertUser::new(config, stores)
-            .handle(UpsertUserRequest {
-                user_id: Some(user_id),
-                password: Some(Password::default()),
-                user: UpdateUserRequest {
-                    first_name: random_string(),
-                    last_name: random_string(),
-                    email: random_email(),
-                    role: UserRole::Admin,
+        let res = UpsertUser::new(config, stores, DetachedDb)
+            .handle((
+                UpsertUserRequest {
+                    user_id: Some(user_id),
+                    password: Some(Password::default()),
+                    user: UpdateUserRequest {
+                        first_name: random_string(),
+                        last_name: random_string(),
+                        email,
+                        role: UserRole::Admin,
+                    },
                 },
-            })
+                Preconditions::default(),
+            ))
             .await;
         assert!(res.is_ok());
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_upsert_user_precondition_failed() -> Result<(), Box<dyn std::error::Error>> {
+        let mut user_store = MockUserStore::new();
+
+        user_store.expect_get_by_id().times(1).returning(move |id| {
+            Box::pin(async move {
+                Ok(User {
+                    id,
+                    ..Default::default()
+                })
+            })
+        });
+
+        user_store.expect_update().never();
+        user_store.expect_create().never();
+
+        let stores = UpsertUserStores {
+            user: user_store,
+            mailer: MockMailerProvider::new(),
+            auth: MockAuthStore::new(),
+            email_change: MockEmailChangeStore::new(),
+            audit: mock_audit_store(&[]),
+        };
+
+        let use_case = UpsertUser::new(Config::new()?, stores, DetachedDb);
+
+        let preconditions = Preconditions {
+            if_match: Some(vec!["*".to_string()]),
+            ..Default::default()
+        };
+
+        // Creation: there's no current version
+        let res = use_case
+            .handle((UpsertUserRequest::default(), preconditions))
+            .await;
+        assert!(matches!(res, Err(Error::PreconditionFailed)));
+
+        // Update of an outdated version
+        let preconditions = Preconditions {
+            if_match: Some(vec!["\"outdated\"".to_string()]),
+            ..Default::default()
+        };
+
+        let res = use_case
+            .handle((
+                UpsertUserRequest {
+                    user_id: Some(random_id()),
+                    ..Default::default()
+                },
+                preconditions,
+            ))
+            .await;
+        assert!(matches!(res, Err(Error::PreconditionFailed)));
+
+        Ok(())
+    }
 }
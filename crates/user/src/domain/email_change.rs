@@ -0,0 +1,58 @@
+//! Email change data structures.
+
+use chrono::{DateTime, Utc};
+
+use auth::Expiring;
+
+use crate::prelude::*;
+
+/// Mirrors the `user_email_changes`'s table. The ID is used as token to confirm the change.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct EmailChange {
+    /// Unique record identifier (used as confirmation token).
+    #[debug(skip)]
+    pub id: Uuid,
+
+    /// ID of the user.
+    pub user_id: Uuid,
+
+    /// Email of the user before the change.
+    pub old_email: String,
+
+    /// Email requested by the user.
+    pub new_email: String,
+
+    /// Token sent to the previous email to revert the change.
+    #[debug(skip)]
+    pub revert_token: Uuid,
+
+    /// Date of confirmation of the new email (the email of the user is changed).
+    pub confirmed_at: Option<DateTime<Utc>>,
+
+    /// Date of record's creation.
+    pub created_at: DateTime<Utc>,
+
+    /// Date after which the change can't be confirmed nor reverted anymore.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl EmailChange {
+    /// Checks if the new email has been confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+impl Expiring for EmailChange {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+/// Structure used by HTTP endpoints to confirm or revert an email change.
+#[derive(Clone, Default, Deserialize, Serialize, derive_more::Debug)]
+pub struct EmailChangeToken {
+    /// Token received by email.
+    #[debug(skip)]
+    pub token: Uuid,
+}
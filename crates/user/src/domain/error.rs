@@ -16,6 +16,30 @@ pub enum Error {
     #[error(transparent)]
     Auth(#[from] auth::Error),
 
+    /// CSV error.
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    /// Database error.
+    #[error(transparent)]
+    Database(#[from] database::Error),
+
+    /// Email already used by another user.
+    #[error("Email already used")]
+    EmailAlreadyUsed,
+
+    /// Email change expired.
+    #[error("Email change expired")]
+    EmailChangeExpired,
+
+    /// Email change not found (or already confirmed).
+    #[error("Email change not found")]
+    EmailChangeNotFound,
+
+    /// Email domain not allowed to register.
+    #[error("Email domain not allowed")]
+    EmailDomainNotAllowed,
+
     /// Generic environment variable error.
     #[error(transparent)]
     Env(#[from] std::env::VarError),
@@ -24,10 +48,30 @@ pub enum Error {
     #[error("Forbidden")]
     Forbidden,
 
+    /// Invitation expired.
+    #[error("Invitation expired")]
+    InvitationExpired,
+
+    /// Invitation not found (or already accepted).
+    #[error("Invitation not found")]
+    InvitationNotFound,
+
+    /// Generic I/O error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// JSON error.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
     /// Generic mailer variable error.
     #[error(transparent)]
     Mailer(#[from] mailer::Error),
 
+    /// Invalid pagination cursor.
+    #[error("Invalid cursor")]
+    InvalidCursor,
+
     /// Invalid password
     #[error("Invalid password")]
     InvalidPassword,
@@ -40,6 +84,15 @@ pub enum Error {
     #[error("NotFound")]
     NotFound,
 
+    /// Precondition of a conditional request failed (e.g. the user has been modified since the
+    /// version known by the client).
+    #[error("Precondition failed")]
+    PreconditionFailed,
+
+    /// Self-registration disabled.
+    #[error("Registration disabled")]
+    RegistrationDisabled,
+
     /// Security error.
     #[error(transparent)]
     Security(#[from] security::Error),
@@ -48,9 +101,17 @@ pub enum Error {
     #[error(transparent)]
     SQLx(#[from] sqlx::Error),
 
+    /// Content type not supported.
+    #[error("Unsupported media type")]
+    UnsupportedMediaType,
+
     /// Validation error.
     #[error(transparent)]
     Validation(#[from] validator::ValidationErrors),
+
+    /// ZIP archive error.
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
 }
 
 impl axum::response::IntoResponse for Error {
@@ -58,10 +119,31 @@ impl axum::response::IntoResponse for Error {
         let message = self.to_string();
 
         let (rc, code) = match self {
-            Self::Forbidden | Self::InvalidPassword => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+            Self::Auth(e) => return e.into_response(),
+
+            Self::EmailAlreadyUsed => (StatusCode::CONFLICT, "CONFLICT"),
+
+            Self::EmailChangeExpired
+            | Self::EmailDomainNotAllowed
+            | Self::Forbidden
+            | Self::InvalidPassword
+            | Self::InvitationExpired
+            | Self::RegistrationDisabled => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+
+            Self::EmailChangeNotFound | Self::InvitationNotFound => {
+                (StatusCode::UNAUTHORIZED, "UNAUTHORIZED")
+            }
+
+            Self::InvalidCursor => (StatusCode::BAD_REQUEST, "BAD_REQUEST"),
 
             Self::NotFound => (StatusCode::NOT_FOUND, "NOT_FOUND"),
 
+            Self::PreconditionFailed => (StatusCode::PRECONDITION_FAILED, "PRECONDITION_FAILED"),
+
+            Self::UnsupportedMediaType => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, "UNSUPPORTED_MEDIA_TYPE")
+            }
+
             Self::Validation(_) | Self::MissingPassword => {
                 (StatusCode::UNPROCESSABLE_ENTITY, "UNPROCESSABLE_ENTITY")
             }
@@ -0,0 +1,76 @@
+//! Invitation data structures.
+
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use auth::Expiring;
+use security::password::Password;
+
+use crate::domain::user::UserRole;
+use crate::prelude::*;
+
+/// Mirrors the `user_invitations`'s table (with the email of the invited user).
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize, derive_more::Debug)]
+pub struct Invitation {
+    /// Unique record identifier.
+    pub id: Uuid,
+
+    /// ID of the invited user.
+    pub user_id: Uuid,
+
+    /// Email of the invited user.
+    pub email: String,
+
+    /// ID of the user who sent the invitation (if not deleted since).
+    pub invited_by: Option<Uuid>,
+
+    /// One-time token sent by email (never returned by the endpoints).
+    #[serde(skip)]
+    #[debug(skip)]
+    pub token: Uuid,
+
+    /// Date of record's creation.
+    pub created_at: DateTime<Utc>,
+
+    /// Date of expiration of the invitation.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Expiring for Invitation {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+/// Structure used by HTTP endpoint to invite a user. No password is provided: it will be chosen
+/// by the user when accepting the invitation.
+#[derive(Clone, Default, Debug, Deserialize, Serialize, Validate)]
+pub struct InviteUserRequest {
+    /// See `User::first_name`.
+    #[validate(length(min = 1))]
+    pub first_name: String,
+
+    /// See `User::last_name`.
+    #[validate(length(min = 1))]
+    pub last_name: String,
+
+    /// See `User::email`.
+    #[validate(email)]
+    pub email: String,
+
+    /// See `User::role`.
+    #[serde(default)]
+    pub role: UserRole,
+}
+
+/// Structure used by HTTP endpoint to accept an invitation.
+#[derive(Clone, Default, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct AcceptInvitationRequest {
+    /// Token received by email.
+    pub token: Uuid,
+
+    /// Password chosen by the user.
+    #[debug(skip)]
+    #[validate(nested)]
+    pub password: Password,
+}
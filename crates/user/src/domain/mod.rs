@@ -1,5 +1,9 @@
 //! List of entities and traits used in this crate.
 
+pub(crate) mod email_change;
 pub(crate) mod error;
+pub(crate) mod invitation;
+pub(crate) mod personal_data;
 pub(crate) mod port;
+pub(crate) mod transfer;
 pub(crate) mod user;
@@ -0,0 +1,285 @@
+//! Personal data export and erasure data structures.
+
+use std::io::{Cursor, Write};
+
+use chrono::{DateTime, Utc};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use auth::{AuditEntry, AuthSession};
+
+use crate::domain::email_change::EmailChange;
+use crate::domain::transfer::ExportedUser;
+use crate::domain::user::User;
+use crate::prelude::*;
+
+/// Domain of the anonymous emails given to the erased users (reserved, it can't be delivered).
+pub const ERASED_EMAIL_DOMAIN: &str = "erased.invalid";
+
+/// Builds the anonymous email of an erased user (unique as the emails must be).
+///
+/// # Arguments
+/// * `user_id`: ID of the user.
+///
+/// # Returns
+/// The anonymous email.
+pub fn erased_email(user_id: &Uuid) -> String {
+    format!("{user_id}@{ERASED_EMAIL_DOMAIN}")
+}
+
+/// List of formats supported by the export of the personal data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PersonalDataFormat {
+    /// A single JSON document.
+    #[default]
+    Json,
+
+    /// A ZIP archive with one JSON document per section.
+    Zip,
+}
+
+impl PersonalDataFormat {
+    /// Gets the content type of the format.
+    ///
+    /// # Returns
+    /// The value of the `Content-Type` header.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Zip => "application/zip",
+        }
+    }
+
+    /// Gets the file extension of the format.
+    ///
+    /// # Returns
+    /// The extension (without the dot).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// Query parameters of the export endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PersonalDataOptions {
+    /// Format of the export (JSON by default).
+    #[serde(default)]
+    pub format: PersonalDataFormat,
+}
+
+/// Email change as exported (the tokens are never exported).
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ExportedEmailChange {
+    /// See `EmailChange::old_email`.
+    pub old_email: String,
+
+    /// See `EmailChange::new_email`.
+    pub new_email: String,
+
+    /// See `EmailChange::confirmed_at`.
+    pub confirmed_at: Option<DateTime<Utc>>,
+
+    /// See `EmailChange::created_at`.
+    pub created_at: DateTime<Utc>,
+
+    /// See `EmailChange::expires_at`.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<EmailChange> for ExportedEmailChange {
+    fn from(change: EmailChange) -> Self {
+        Self {
+            old_email: change.old_email,
+            new_email: change.new_email,
+            confirmed_at: change.confirmed_at,
+            created_at: change.created_at,
+            expires_at: change.expires_at,
+        }
+    }
+}
+
+/// Confirmations of the user as exported.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ExportedConfirmations {
+    /// Expiration of the pending email confirmation (or None if the email is confirmed).
+    pub email_confirmation_expires_at: Option<DateTime<Utc>>,
+
+    /// Changes of email requested by the user.
+    pub email_changes: Vec<ExportedEmailChange>,
+}
+
+/// All the personal data stored about a user.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct PersonalData {
+    /// Date of the export.
+    pub exported_at: DateTime<Utc>,
+
+    /// Profile of the user.
+    pub profile: ExportedUser,
+
+    /// Sessions of the user.
+    pub sessions: Vec<AuthSession>,
+
+    /// Confirmations of the user.
+    pub confirmations: ExportedConfirmations,
+
+    /// Entries of the audit trail concerning the user.
+    pub audit: Vec<AuditEntry>,
+}
+
+impl PersonalData {
+    /// Gathers the personal data of a user.
+    ///
+    /// # Arguments
+    /// * `user`: User.
+    /// * `sessions`: Sessions of the user.
+    /// * `email_changes`: Changes of email of the user.
+    /// * `audit`: Entries of the audit trail.
+    ///
+    /// # Returns
+    /// A `PersonalData` instance.
+    pub fn new(
+        user: User,
+        sessions: Vec<AuthSession>,
+        email_changes: Vec<EmailChange>,
+        audit: Vec<AuditEntry>,
+    ) -> Self {
+        let confirmations = ExportedConfirmations {
+            email_confirmation_expires_at: user
+                .pending_confirmation
+                .as_ref()
+                .map(|confirmation| confirmation.expires_at),
+            email_changes: email_changes.into_iter().map(Into::into).collect(),
+        };
+
+        Self {
+            exported_at: Utc::now(),
+            profile: user.into(),
+            sessions,
+            confirmations,
+            audit,
+        }
+    }
+
+    /// Encodes the personal data to be downloaded.
+    ///
+    /// # Arguments
+    /// * `format`: Format of the export.
+    ///
+    /// # Returns
+    /// The content of the file.
+    pub fn encode(&self, format: PersonalDataFormat) -> ApiResult<Vec<u8>> {
+        match format {
+            PersonalDataFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+
+            PersonalDataFormat::Zip => {
+                let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+                let sections: [(&str, serde_json::Value); 4] = [
+                    ("profile", serde_json::to_value(&self.profile)?),
+                    ("sessions", serde_json::to_value(&self.sessions)?),
+                    ("confirmations", serde_json::to_value(&self.confirmations)?),
+                    ("audit", serde_json::to_value(&self.audit)?),
+                ];
+
+                for (name, section) in sections {
+                    zip.start_file(format!("{name}.json"), SimpleFileOptions::default())?;
+                    zip.write_all(&serde_json::to_vec_pretty(&section)?)?;
+                }
+
+                Ok(zip.finish()?.into_inner())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use zip::ZipArchive;
+
+    use auth::AuthUserConfirmation;
+    use test_utils::rand::*;
+
+    use super::*;
+
+    fn personal_data() -> PersonalData {
+        let user = User {
+            id: random_id(),
+            email: random_email(),
+            pending_confirmation: Some(AuthUserConfirmation::default()),
+            ..Default::default()
+        };
+
+        let change = EmailChange {
+            revert_token: random_id(),
+            new_email: random_email(),
+            ..Default::default()
+        };
+
+        PersonalData::new(user, vec![AuthSession::default()], vec![change], vec![])
+    }
+
+    #[test]
+    fn test_encode_json() -> Result<(), Box<dyn std::error::Error>> {
+        let data = personal_data();
+
+        let encoded = data.encode(PersonalDataFormat::Json)?;
+        let decoded: PersonalData = serde_json::from_slice(&encoded)?;
+        assert_eq!(decoded, data);
+
+        // Neither the password nor the tokens are exported
+        let value: serde_json::Value = serde_json::from_slice(&encoded)?;
+        assert!(value["profile"].get("password").is_none());
+        assert!(value["confirmations"]["email_changes"][0]
+            .get("revert_token")
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_zip() -> Result<(), Box<dyn std::error::Error>> {
+        let data = personal_data();
+
+        let encoded = data.encode(PersonalDataFormat::Zip)?;
+        let mut archive = ZipArchive::new(Cursor::new(encoded))?;
+
+        let mut names = archive.file_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "audit.json",
+                "confirmations.json",
+                "profile.json",
+                "sessions.json"
+            ]
+        );
+
+        let mut profile = String::new();
+        archive
+            .by_name("profile.json")?
+            .read_to_string(&mut profile)?;
+        assert_eq!(
+            serde_json::from_str::<ExportedUser>(&profile)?,
+            data.profile
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_erased_email() {
+        let user_id = random_id();
+        assert_eq!(
+            erased_email(&user_id),
+            format!("{user_id}@{ERASED_EMAIL_DOMAIN}")
+        );
+    }
+}
@@ -1,9 +1,14 @@
 //! User store trait.
 
+use chrono::{DateTime, Duration, Utc};
 use futures::future::BoxFuture;
+use futures::stream::BoxStream;
 
+use common_web::pagination::{Page, PageRequest};
 use security::password::Password;
 
+use crate::domain::email_change::EmailChange;
+use crate::domain::invitation::Invitation;
 use crate::domain::user::{User, UserData, UserFilters};
 use crate::prelude::*;
 
@@ -20,7 +25,7 @@ pub trait UserStore: Send + Sync {
     /// failed.
     fn exists(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<bool>>;
 
-    /// Delete a user from the database.
+    /// Delete a user (it's only marked as deleted until purged).
     ///
     /// # Arguments
     /// * `user_id` - The ID of the user to delete.
@@ -29,6 +34,52 @@ pub trait UserStore: Send + Sync {
     /// A `ApiResult` indicating if the deletion was successful or an error if it failed.
     fn delete_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<()>>;
 
+    /// Restore a deleted user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user to restore.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the restored user (or None if not deleted) or an error if it
+    /// failed.
+    fn restore_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Option<User>>>;
+
+    /// Permanently delete a user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user to purge.
+    ///
+    /// # Returns
+    /// A `ApiResult` indicating if the deletion was successful or an error if it failed.
+    fn purge_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Erase the personal data of a user: the user is anonymized (but not deleted so that the
+    /// references to it stay valid) and all its authentication data is deleted.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user to erase.
+    /// * `email` - The anonymous email replacing the email of the user.
+    /// * `password` - The password replacing the password of the user.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the anonymized user (or None if not found) or an error if it
+    /// failed.
+    fn erase_by_id(
+        &self,
+        user_id: Uuid,
+        email: String,
+        password: Password,
+    ) -> BoxFuture<'static, ApiResult<Option<User>>>;
+
+    /// Permanently delete the users deleted before a date.
+    ///
+    /// # Arguments
+    /// * `before` - Date before which the deleted users are purged.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the number of users purged or an error if it failed.
+    fn purge_deleted(&self, before: DateTime<Utc>) -> BoxFuture<'static, ApiResult<u64>>;
+
     /// Get a user by its ID.
     ///
     /// # Arguments
@@ -38,6 +89,15 @@ pub trait UserStore: Send + Sync {
     /// A `ApiResult` containing the user if found or an error if it failed.
     fn get_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<User>>;
 
+    /// Get the password of a user (hashed), where it must be verified.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the password if found or an error if it failed.
+    fn get_password(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Password>>;
+
     /// Find users using filters.
     ///
     /// # Arguments
@@ -47,6 +107,30 @@ pub trait UserStore: Send + Sync {
     /// A `ApiResult` containing a vector of users that match the filters or an error if it failed.
     fn get_by_filters(&self, filters: UserFilters) -> BoxFuture<'static, ApiResult<Vec<User>>>;
 
+    /// Stream the users found using filters (the pagination is not applied).
+    ///
+    /// # Arguments
+    /// * `filters` - The filters to apply when searching for users.
+    ///
+    /// # Returns
+    /// A stream of the users that match the filters (or of an error if it failed).
+    fn stream_by_filters(&self, filters: UserFilters) -> BoxStream<'static, ApiResult<User>>;
+
+    /// Find a page of users using filters.
+    ///
+    /// # Arguments
+    /// * `filters` - The filters to apply when searching for users.
+    /// * `page` - The page requested.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the page of users that match the filters or an error if it
+    /// failed.
+    fn get_page_by_filters(
+        &self,
+        filters: UserFilters,
+        page: PageRequest,
+    ) -> BoxFuture<'static, ApiResult<Page<User>>>;
+
     /// Create a new user in the database.
     ///
     /// # Arguments
@@ -61,22 +145,161 @@ pub trait UserStore: Send + Sync {
     /// # Arguments
     /// * `user_id` - The ID of the user to update.
     /// * `data` - The new data of the user.
+    /// * `version` - Date of the last update of the user known by the caller, if it must match.
     ///
     /// # Returns
-    /// A `ApiResult` containing the updated user or an error if the update failed.
-    fn update(&self, user_id: Uuid, data: UserData) -> BoxFuture<'static, ApiResult<User>>;
+    /// A `ApiResult` containing the updated user or an error if the update failed
+    /// (`Error::PreconditionFailed` if the user has been updated since `version`).
+    fn update(
+        &self,
+        user_id: Uuid,
+        data: UserData,
+        version: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'static, ApiResult<User>>;
 
     /// Update the password of an existing user in the database.
     ///
     /// # Arguments
     /// * `user_id` - The ID of the user to update.
     /// * `password` - The new password of the user.
+    /// * `version` - Date of the last update of the user known by the caller, if it must match.
     ///
     /// # Returns
-    /// A `ApiResult` indicating if the update was successful or an error if it failed.
+    /// A `ApiResult` indicating if the update was successful or an error if it failed
+    /// (`Error::PreconditionFailed` if the user has been updated since `version`).
     fn set_user_password(
         &self,
         user_id: Uuid,
         password: Password,
+        version: Option<DateTime<Utc>>,
     ) -> BoxFuture<'static, ApiResult<()>>;
 }
+
+/// Invitation store APIs.
+#[cfg_attr(any(test, feature = "mock"), mockall::automock)]
+pub trait InvitationStore: Send + Sync {
+    /// Create an invitation for a user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the invited user.
+    /// * `invited_by` - The ID of the user who sends the invitation.
+    /// * `timeout` - Duration after which the invitation expires.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the created invitation or an error if the creation failed.
+    fn create(
+        &self,
+        user_id: Uuid,
+        invited_by: Option<Uuid>,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<Invitation>>;
+
+    /// Get all pending invitations.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the invitations or an error if it failed.
+    fn get_all(&self) -> BoxFuture<'static, ApiResult<Vec<Invitation>>>;
+
+    /// Get an invitation by its ID.
+    ///
+    /// # Arguments
+    /// * `invitation_id` - The ID of the invitation to get.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the invitation (if found) or an error if it failed.
+    fn get_by_id(&self, invitation_id: Uuid) -> BoxFuture<'static, ApiResult<Option<Invitation>>>;
+
+    /// Renew the token and the expiration of an invitation.
+    ///
+    /// # Arguments
+    /// * `invitation_id` - The ID of the invitation to renew.
+    /// * `timeout` - Duration after which the invitation expires.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the renewed invitation (if found) or an error if it failed.
+    fn renew(
+        &self,
+        invitation_id: Uuid,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<Option<Invitation>>>;
+
+    /// Delete an invitation giving its token (so that it can't be used twice).
+    ///
+    /// # Arguments
+    /// * `token` - The token of the invitation.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the deleted invitation (if found) or an error if it failed.
+    fn consume(&self, token: Uuid) -> BoxFuture<'static, ApiResult<Option<Invitation>>>;
+}
+
+/// Email change store APIs.
+#[cfg_attr(any(test, feature = "mock"), mockall::automock)]
+pub trait EmailChangeStore: Send + Sync {
+    /// Stage a change of email (replaces any pending change of the user).
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user.
+    /// * `old_email` - The current email of the user.
+    /// * `new_email` - The email requested.
+    /// * `timeout` - Duration after which the change expires.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the staged change or an error if the creation failed.
+    fn create(
+        &self,
+        user_id: Uuid,
+        old_email: String,
+        new_email: String,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<EmailChange>>;
+
+    /// Get a change by its ID (i.e. its confirmation token).
+    ///
+    /// # Arguments
+    /// * `change_id` - The ID of the change.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the change (if found) or an error if it failed.
+    fn get_by_id(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<Option<EmailChange>>>;
+
+    /// Get a change by its revert token.
+    ///
+    /// # Arguments
+    /// * `token` - The revert token of the change.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the change (if found) or an error if it failed.
+    fn get_by_revert_token(
+        &self,
+        token: Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<EmailChange>>>;
+
+    /// Get all changes of a user (oldest first).
+    ///
+    /// # Arguments
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    /// A `ApiResult` containing the list of changes or an error if it failed.
+    fn get_by_user_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Vec<EmailChange>>>;
+
+    /// Confirm a change: the email of the user is replaced by the new one.
+    ///
+    /// # Arguments
+    /// * `change_id` - The ID of the change.
+    ///
+    /// # Returns
+    /// A `ApiResult` indicating if the confirmation was successful or an error if it failed.
+    fn confirm(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<()>>;
+
+    /// Revert a change: it's deleted and the previous email of the user is restored (if already
+    /// confirmed).
+    ///
+    /// # Arguments
+    /// * `change_id` - The ID of the change.
+    ///
+    /// # Returns
+    /// A `ApiResult` indicating if the revert was successful or an error if it failed.
+    fn revert(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<()>>;
+}
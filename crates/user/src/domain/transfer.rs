@@ -0,0 +1,420 @@
+//! Bulk import and export data structures.
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::user::{CreateUserRequest, User, UserRole};
+use crate::prelude::*;
+
+/// List of formats supported by the import and the export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferFormat {
+    /// Comma-separated values with a header line.
+    #[default]
+    Csv,
+
+    /// JSON Lines (one JSON object per line).
+    Jsonl,
+}
+
+impl TransferFormat {
+    /// Gets the format of a content type.
+    ///
+    /// # Arguments
+    /// * `content_type`: Value of the `Content-Type` header (parameters are ignored).
+    ///
+    /// # Returns
+    /// The format or `None` if not supported.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+
+        match essence.to_ascii_lowercase().as_str() {
+            "text/csv" => Some(Self::Csv),
+            "application/jsonl" | "application/x-ndjson" | "application/x-jsonlines" => {
+                Some(Self::Jsonl)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the content type of the format.
+    ///
+    /// # Returns
+    /// The value of the `Content-Type` header.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    /// Gets the file extension of the format.
+    ///
+    /// # Returns
+    /// The extension (without the dot).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+
+    /// Parses the rows of a file to import. A row that can't be parsed is kept with the reason of
+    /// the failure so that it appears in the report.
+    ///
+    /// # Arguments
+    /// * `body`: Content of the file.
+    ///
+    /// # Returns
+    /// The list of rows.
+    pub fn parse(&self, body: &[u8]) -> Vec<ImportRow> {
+        match self {
+            Self::Csv => parse_csv(body),
+            Self::Jsonl => parse_jsonl(body),
+        }
+    }
+
+    /// Encodes a user to be exported.
+    ///
+    /// # Arguments
+    /// * `user`: User to export.
+    /// * `first`: Whether it's the first user exported (the CSV header is written before it).
+    ///
+    /// # Returns
+    /// The encoded row (with its line terminator).
+    pub fn encode(&self, user: &ExportedUser, first: bool) -> ApiResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+
+        match self {
+            Self::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(first)
+                    .from_writer(&mut buffer);
+
+                // The free text can't be run as a formula by the spreadsheets
+                let user = ExportedUser {
+                    first_name: escape_formula(&user.first_name),
+                    last_name: escape_formula(&user.last_name),
+                    email: escape_formula(&user.email),
+                    ..user.clone()
+                };
+
+                writer.serialize(user)?;
+                writer.flush().map_err(csv::Error::from)?;
+            }
+
+            Self::Jsonl => {
+                serde_json::to_writer(&mut buffer, user)?;
+                buffer.push(b'\n');
+            }
+        }
+
+        Ok(buffer)
+    }
+}
+
+/// Escapes a CSV cell that a spreadsheet would interpret as a formula (prefixed with a quote).
+///
+/// # Arguments
+/// * `value`: Value of the cell.
+///
+/// # Returns
+/// The escaped value.
+fn escape_formula(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parses a CSV file (the first line gives the name of the columns).
+///
+/// # Arguments
+/// * `body`: Content of the file.
+///
+/// # Returns
+/// The list of rows.
+fn parse_csv(body: &[u8]) -> Vec<ImportRow> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body);
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            return vec![ImportRow {
+                line: 1,
+                request: Err(e.to_string()),
+            }]
+        }
+    };
+
+    reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => ImportRow {
+                line: record.position().map(|p| p.line()).unwrap_or_default(),
+                request: record
+                    .deserialize(Some(&headers))
+                    .map_err(|e| e.to_string()),
+            },
+
+            Err(e) => ImportRow {
+                line: e.position().map(|p| p.line()).unwrap_or_default(),
+                request: Err(e.to_string()),
+            },
+        })
+        .collect()
+}
+
+/// Parses a JSON Lines file (the empty lines are ignored).
+///
+/// # Arguments
+/// * `body`: Content of the file.
+///
+/// # Returns
+/// The list of rows.
+fn parse_jsonl(body: &[u8]) -> Vec<ImportRow> {
+    body.split(|c| *c == b'\n')
+        .zip(1..)
+        .filter(|(line, _)| !line.trim_ascii().is_empty())
+        .map(|(line, number)| ImportRow {
+            line: number,
+            request: serde_json::from_slice(line).map_err(|e| e.to_string()),
+        })
+        .collect()
+}
+
+/// Row of a file to import.
+#[derive(Clone, Debug)]
+pub struct ImportRow {
+    /// Line number in the file.
+    pub line: u64,
+
+    /// Request parsed (or the reason of the failure).
+    pub request: Result<CreateUserRequest, String>,
+}
+
+/// List of statuses of an imported row.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// User created (or would be created if dry-run).
+    Created,
+
+    /// User not created because its email is already used.
+    Skipped,
+
+    /// Row invalid or user creation failed.
+    Failed,
+}
+
+/// Result of the import of a row.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ImportRowReport {
+    /// Line number in the file.
+    pub line: u64,
+
+    /// Email of the user (if the row could be parsed).
+    pub email: Option<String>,
+
+    /// Status of the import.
+    pub status: ImportStatus,
+
+    /// Why the row has been skipped or has failed (or None).
+    pub reason: Option<String>,
+
+    /// ID of the user created (or None).
+    pub user_id: Option<Uuid>,
+}
+
+/// Report returned after an import.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ImportReport {
+    /// Whether nothing has been written.
+    pub dry_run: bool,
+
+    /// Number of rows created.
+    pub created: u64,
+
+    /// Number of rows skipped.
+    pub skipped: u64,
+
+    /// Number of rows failed.
+    pub failed: u64,
+
+    /// Result of each row.
+    pub rows: Vec<ImportRowReport>,
+}
+
+impl ImportReport {
+    /// Adds the result of a row to the report.
+    ///
+    /// # Arguments
+    /// * `row`: Result of the row.
+    pub fn push(&mut self, row: ImportRowReport) {
+        match row.status {
+            ImportStatus::Created => self.created += 1,
+            ImportStatus::Skipped => self.skipped += 1,
+            ImportStatus::Failed => self.failed += 1,
+        }
+
+        self.rows.push(row);
+    }
+}
+
+/// Query parameters of the import endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ImportOptions {
+    /// Whether the rows are only checked (nothing is written).
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Query parameters of the export endpoint (in addition to the `UserFilters`).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ExportOptions {
+    /// Format of the export (CSV by default).
+    #[serde(default)]
+    pub format: TransferFormat,
+}
+
+/// User as exported (the password is never exported).
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ExportedUser {
+    /// See `User::id`.
+    pub id: Uuid,
+
+    /// See `User::first_name`.
+    pub first_name: String,
+
+    /// See `User::last_name`.
+    pub last_name: String,
+
+    /// See `User::email`.
+    pub email: String,
+
+    /// See `User::role`.
+    pub role: UserRole,
+
+    /// Whether the email has been confirmed.
+    pub email_confirmed: bool,
+
+    /// See `User::created_at`.
+    pub created_at: DateTime<Utc>,
+
+    /// See `User::updated_at`.
+    pub updated_at: DateTime<Utc>,
+
+    /// See `User::deleted_at`.
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl From<User> for ExportedUser {
+    fn from(user: User) -> Self {
+        Self {
+            email_confirmed: user.is_email_confirmed(),
+            id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            email: user.email,
+            role: user.role,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(
+            TransferFormat::from_content_type("text/csv; charset=utf-8"),
+            Some(TransferFormat::Csv)
+        );
+        assert_eq!(
+            TransferFormat::from_content_type("application/x-ndjson"),
+            Some(TransferFormat::Jsonl)
+        );
+        assert_eq!(TransferFormat::from_content_type("application/json"), None);
+    }
+
+    #[test]
+    fn test_parse_csv() -> Result<(), Box<dyn std::error::Error>> {
+        let body = b"first_name,last_name,email,role,password\n\
+                     John, Doe ,john@doe.com,admin,Secret1!\n\
+                     Jane,Doe,jane@doe.com,unknown,Secret1!\n";
+
+        let rows = TransferFormat::Csv.parse(body);
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(rows[0].line, 2);
+        let request = rows[0].request.clone()?;
+        assert_eq!(request.last_name, "Doe");
+        assert_eq!(request.role, UserRole::Admin);
+        assert_eq!(request.password.as_str(), "Secret1!");
+
+        assert_eq!(rows[1].line, 3);
+        assert!(rows[1].request.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_jsonl() {
+        let body = b"{\"first_name\":\"John\",\"last_name\":\"Doe\",\"email\":\"john@doe.com\",\
+                     \"role\":\"normal\",\"password\":\"Secret1!\"}\n\
+                     \n\
+                     {\"first_name\":\"Jane\"}\n";
+
+        let rows = TransferFormat::Jsonl.parse(body);
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(rows[0].line, 1);
+        assert!(rows[0].request.is_ok());
+
+        assert_eq!(rows[1].line, 3);
+        assert!(rows[1].request.is_err());
+    }
+
+    #[test]
+    fn test_encode() -> Result<(), Box<dyn std::error::Error>> {
+        let user = ExportedUser {
+            email: "john@doe.com".to_string(),
+            role: UserRole::Normal,
+            ..Default::default()
+        };
+
+        let first = String::from_utf8(TransferFormat::Csv.encode(&user, true)?)?;
+        let next = String::from_utf8(TransferFormat::Csv.encode(&user, false)?)?;
+
+        assert!(first.starts_with("id,first_name,last_name,email,role,email_confirmed,"));
+        assert_eq!(first.lines().count(), 2);
+        assert_eq!(next.lines().count(), 1);
+        assert!(next.contains(",john@doe.com,normal,false,"));
+
+        let formula = ExportedUser {
+            first_name: "=HYPERLINK(\"http://evil.com\")".to_string(),
+            last_name: "-2+3".to_string(),
+            email: "@SUM(A1)".to_string(),
+            ..user.clone()
+        };
+
+        let csv = String::from_utf8(TransferFormat::Csv.encode(&formula, false)?)?;
+        assert!(csv.contains(",\"'=HYPERLINK(\"\"http://evil.com\"\")\",'-2+3,'@SUM(A1),"));
+
+        let json = TransferFormat::Jsonl.encode(&formula, false)?;
+        assert_eq!(serde_json::from_slice::<ExportedUser>(&json)?, formula);
+
+        let json = TransferFormat::Jsonl.encode(&user, true)?;
+        assert!(json.ends_with(b"\n"));
+        assert_eq!(serde_json::from_slice::<ExportedUser>(&json)?, user);
+
+        Ok(())
+    }
+}
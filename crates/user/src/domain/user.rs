@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use validator::Validate;
 
 use auth::AuthUserConfirmation;
+use common_web::conditional::ETag;
+use common_web::pagination::{Sort, SortOrder};
 use security::password::Password;
 
 use crate::prelude::*;
@@ -23,6 +25,48 @@ pub enum UserRole {
     Guest,
 }
 
+/// List of fields that can be used to sort the users.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortField {
+    /// Sort by first name.
+    FirstName,
+
+    /// Sort by last name.
+    LastName,
+
+    /// Sort by email.
+    Email,
+
+    /// Sort by role.
+    Role,
+
+    /// Sort by creation date.
+    #[default]
+    CreatedAt,
+
+    /// Sort by last update date.
+    UpdatedAt,
+
+    /// Sort by relevance of the search (see `UserFilters::q`).
+    Relevance,
+}
+
+/// How the text filters (first name, last name and email) are matched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextMatch {
+    /// Exact value.
+    #[default]
+    Exact,
+
+    /// Case-insensitive prefix.
+    Prefix,
+
+    /// Case-insensitive substring.
+    Contains,
+}
+
 /// Structure that list all filters available for querying database.
 #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct UserFilters {
@@ -37,6 +81,38 @@ pub struct UserFilters {
 
     /// Role of the user (or None).
     pub role: Option<UserRole>,
+
+    /// How the text filters are matched (or None for exact matching).
+    #[serde(rename = "match")]
+    pub text_match: Option<TextMatch>,
+
+    /// Fuzzy search across the names and the email (or None).
+    pub q: Option<String>,
+
+    /// Whether the deleted users are listed too.
+    #[serde(default)]
+    pub include_deleted: bool,
+
+    /// Sort of the users (or None to sort by relevance when searching, by creation date
+    /// otherwise).
+    pub sort: Option<Sort<UserSortField>>,
+}
+
+impl UserFilters {
+    /// Gets the sort to apply.
+    ///
+    /// # Returns
+    /// The sort requested or the default one.
+    pub fn sort(&self) -> Sort<UserSortField> {
+        match (&self.sort, &self.q) {
+            (Some(sort), _) => sort.clone(),
+            (None, Some(_)) => Sort {
+                field: UserSortField::Relevance,
+                order: SortOrder::Desc,
+            },
+            (None, None) => Sort::default(),
+        }
+    }
 }
 
 /// Mirrors the `users`'s' table.
@@ -67,6 +143,9 @@ pub struct User {
     /// Date of record's last update.
     pub updated_at: DateTime<Utc>,
 
+    /// Date of deletion (the user is purged after a retention period).
+    pub deleted_at: Option<DateTime<Utc>>,
+
     /// User confirmation information.
     pub pending_confirmation: Option<AuthUserConfirmation>,
 }
@@ -76,6 +155,28 @@ impl User {
     pub fn is_email_confirmed(&self) -> bool {
         self.pending_confirmation.is_none()
     }
+
+    /// Checks if the user has been deleted.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Gets the entity tag of the user, used for the conditional requests. It changes at each
+    /// update of the user (and when its pending confirmation changes).
+    ///
+    /// # Returns
+    /// The entity tag.
+    pub fn etag(&self) -> ETag {
+        let version = self.updated_at.timestamp_micros();
+
+        match &self.pending_confirmation {
+            Some(confirmation) => ETag::new(format!(
+                "{version:x}-{:x}",
+                confirmation.expires_at.timestamp_micros()
+            )),
+            None => ETag::new(format!("{version:x}")),
+        }
+    }
 }
 
 /// Data structure passed to database queries when inserting or updating entries.
@@ -145,6 +246,45 @@ impl From<CreateUserRequest> for UserData {
     }
 }
 
+/// Structure used by HTTP endpoint to register a new user (without being logged in).
+/// This structure is not expected to be used directly in queries. It must be converted first to a
+/// `CreateUserRequest`.
+#[derive(Clone, Default, Deserialize, Serialize, Validate, derive_more::Debug)]
+pub struct RegisterUserRequest {
+    /// See `User::first_name`.
+    #[validate(length(min = 1))]
+    pub first_name: String,
+
+    /// See `User::last_name`.
+    #[validate(length(min = 1))]
+    pub last_name: String,
+
+    /// See `User::email`.
+    #[validate(email)]
+    pub email: String,
+
+    /// See `User::role`. Only accepted to be able to refuse any other role than the default one.
+    pub role: Option<UserRole>,
+
+    /// See `User::password`.
+    #[debug(skip)]
+    #[validate(nested)]
+    pub password: Password,
+}
+
+impl From<RegisterUserRequest> for CreateUserRequest {
+    fn from(request: RegisterUserRequest) -> Self {
+        // The role of the request is ignored: a registered user always gets the default one
+        Self {
+            first_name: request.first_name,
+            last_name: request.last_name,
+            email: request.email,
+            role: UserRole::default(),
+            password: request.password,
+        }
+    }
+}
+
 /// Structure used by HTTP endpoint to query an update in the database.
 /// This structure is not expected to be used directly in queries. It must be converted first to a
 /// `UserData`.
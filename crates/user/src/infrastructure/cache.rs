@@ -0,0 +1,516 @@
+//! Read-through cache (stored in Redis) of the `UserStore` trait.
+
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use std::sync::Arc;
+
+use auth::{evict_user, user_cache_key};
+use common_web::pagination::{Page, PageRequest};
+use database::{Cache, SharedDb};
+use security::password::Password;
+
+use crate::domain::email_change::EmailChange;
+use crate::domain::port::{EmailChangeStore, UserStore};
+use crate::domain::user::{User, UserData, UserFilters};
+use crate::prelude::*;
+
+/// Name of the cache of the users profiles.
+const USER_CACHE: &str = "user";
+
+/// Runs a call that changes a user then removes the entries cached for the user (once the change
+/// is committed, see `evict_user`).
+///
+/// # Arguments
+/// * `db`: Database handle of the decorated store.
+/// * `cache`: Cache handle.
+/// * `user_id`: ID of the user changed.
+/// * `call`: Call to the decorated store.
+///
+/// # Returns
+/// The result of the call.
+fn evicting<T: Send + 'static>(
+    db: &SharedDb,
+    cache: &Cache,
+    user_id: Uuid,
+    call: BoxFuture<'static, ApiResult<T>>,
+) -> BoxFuture<'static, ApiResult<T>> {
+    let db = db.clone();
+    let cache = cache.clone();
+
+    Box::pin(async move {
+        let output = call.await?;
+
+        evict_user(&db, &cache, &user_id).await;
+
+        Ok(output)
+    })
+}
+
+/// Decorator of a `UserStore` caching the lookups of the users by ID. The entries of a user are
+/// removed by the calls that change it.
+///
+/// Inside a transaction, the cache is bypassed: the users may have been changed by it.
+#[derive(Debug)]
+pub struct CachedUserStore<S: UserStore> {
+    /// Decorated store.
+    store: Arc<S>,
+
+    /// Database handle of the decorated store.
+    db: SharedDb,
+
+    /// Cache handle.
+    cache: Cache,
+}
+
+impl<S: UserStore> CachedUserStore<S> {
+    /// Creates a new instance of the cached user store.
+    ///
+    /// # Arguments
+    /// * `store`: Store to be decorated.
+    /// * `db`: Database handle of the decorated store.
+    /// * `cache`: Cache handle.
+    ///
+    /// # Returns
+    /// A new instance of `CachedUserStore`.
+    #[must_use]
+    pub fn new(store: S, db: &SharedDb, cache: &Cache) -> Self {
+        Self {
+            store: Arc::new(store),
+            db: db.clone(),
+            cache: cache.clone(),
+        }
+    }
+}
+
+impl<S: UserStore + 'static> UserStore for CachedUserStore<S> {
+    fn exists(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<bool>> {
+        self.store.exists(user_id)
+    }
+
+    fn delete_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        evicting(
+            &self.db,
+            &self.cache,
+            user_id,
+            self.store.delete_by_id(user_id),
+        )
+    }
+
+    fn restore_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Option<User>>> {
+        evicting(
+            &self.db,
+            &self.cache,
+            user_id,
+            self.store.restore_by_id(user_id),
+        )
+    }
+
+    fn purge_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        evicting(
+            &self.db,
+            &self.cache,
+            user_id,
+            self.store.purge_by_id(user_id),
+        )
+    }
+
+    fn erase_by_id(
+        &self,
+        user_id: Uuid,
+        email: String,
+        password: Password,
+    ) -> BoxFuture<'static, ApiResult<Option<User>>> {
+        evicting(
+            &self.db,
+            &self.cache,
+            user_id,
+            self.store.erase_by_id(user_id, email, password),
+        )
+    }
+
+    fn purge_deleted(&self, before: DateTime<Utc>) -> BoxFuture<'static, ApiResult<u64>> {
+        // The entries of the deleted users have been removed when they were deleted
+        self.store.purge_deleted(before)
+    }
+
+    fn get_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<User>> {
+        let store = self.store.clone();
+        let db = self.db.clone();
+        let cache = self.cache.clone();
+
+        Box::pin(async move {
+            let db = db.lock().await.clone();
+            let in_transaction = db.in_transaction();
+            let key = user_cache_key(&user_id, "profile");
+
+            if !in_transaction {
+                if let Some(user) = cache.get(USER_CACHE, &key).await {
+                    return Ok(user);
+                }
+
+                // A replica may not have caught up with the last change of the user yet
+                db.read_your_writes();
+            }
+
+            // The password is only read where it's verified (see `UserStore::get_password`)
+            let user = User {
+                password: Password::default(),
+                ..store.get_by_id(user_id).await?
+            };
+
+            if !in_transaction {
+                cache
+                    .set(&key, &user, cache.settings().user_ttl_seconds)
+                    .await;
+            }
+
+            Ok(user)
+        })
+    }
+
+    fn get_password(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Password>> {
+        self.store.get_password(user_id)
+    }
+
+    fn get_by_filters(&self, filters: UserFilters) -> BoxFuture<'static, ApiResult<Vec<User>>> {
+        self.store.get_by_filters(filters)
+    }
+
+    fn stream_by_filters(&self, filters: UserFilters) -> BoxStream<'static, ApiResult<User>> {
+        self.store.stream_by_filters(filters)
+    }
+
+    fn get_page_by_filters(
+        &self,
+        filters: UserFilters,
+        page: PageRequest,
+    ) -> BoxFuture<'static, ApiResult<Page<User>>> {
+        self.store.get_page_by_filters(filters, page)
+    }
+
+    fn create(&self, data: UserData) -> BoxFuture<'static, ApiResult<User>> {
+        self.store.create(data)
+    }
+
+    fn update(
+        &self,
+        user_id: Uuid,
+        data: UserData,
+        version: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'static, ApiResult<User>> {
+        evicting(
+            &self.db,
+            &self.cache,
+            user_id,
+            self.store.update(user_id, data, version),
+        )
+    }
+
+    fn set_user_password(
+        &self,
+        user_id: Uuid,
+        password: Password,
+        version: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        evicting(
+            &self.db,
+            &self.cache,
+            user_id,
+            self.store.set_user_password(user_id, password, version),
+        )
+    }
+}
+
+/// Decorator of an `EmailChangeStore` removing the entries cached for a user when a change of
+/// its email is confirmed or reverted.
+#[derive(Debug)]
+pub struct CachedEmailChangeStore<S: EmailChangeStore> {
+    /// Decorated store.
+    store: Arc<S>,
+
+    /// Database handle of the decorated store.
+    db: SharedDb,
+
+    /// Cache handle.
+    cache: Cache,
+}
+
+impl<S: EmailChangeStore> CachedEmailChangeStore<S> {
+    /// Creates a new instance of the cached email change store.
+    ///
+    /// # Arguments
+    /// * `store`: Store to be decorated.
+    /// * `db`: Database handle of the decorated store.
+    /// * `cache`: Cache handle.
+    ///
+    /// # Returns
+    /// A new instance of `CachedEmailChangeStore`.
+    #[must_use]
+    pub fn new(store: S, db: &SharedDb, cache: &Cache) -> Self {
+        Self {
+            store: Arc::new(store),
+            db: db.clone(),
+            cache: cache.clone(),
+        }
+    }
+
+    /// Runs a call that changes the email of a user then removes the entries cached for the
+    /// user.
+    ///
+    /// # Arguments
+    /// * `change_id`: ID of the change of email.
+    /// * `call`: Call to the decorated store.
+    ///
+    /// # Returns
+    /// The result of the call.
+    fn evicting(
+        &self,
+        change_id: Uuid,
+        call: BoxFuture<'static, ApiResult<()>>,
+    ) -> BoxFuture<'static, ApiResult<()>> {
+        // The change is needed to know which user is changed
+        let change = self.store.get_by_id(change_id);
+        let db = self.db.clone();
+        let cache = self.cache.clone();
+
+        Box::pin(async move {
+            let change = change.await?;
+
+            call.await?;
+
+            if let Some(change) = change {
+                evict_user(&db, &cache, &change.user_id).await;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<S: EmailChangeStore + 'static> EmailChangeStore for CachedEmailChangeStore<S> {
+    fn create(
+        &self,
+        user_id: Uuid,
+        old_email: String,
+        new_email: String,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<EmailChange>> {
+        self.store.create(user_id, old_email, new_email, timeout)
+    }
+
+    fn get_by_id(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<Option<EmailChange>>> {
+        self.store.get_by_id(change_id)
+    }
+
+    fn get_by_revert_token(
+        &self,
+        token: Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<EmailChange>>> {
+        self.store.get_by_revert_token(token)
+    }
+
+    fn get_by_user_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Vec<EmailChange>>> {
+        self.store.get_by_user_id(user_id)
+    }
+
+    fn confirm(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.evicting(change_id, self.store.confirm(change_id))
+    }
+
+    fn revert(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        self.evicting(change_id, self.store.revert(change_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use auth::{AuthStore, CachedAuthStore, SQLxAuthStore};
+    use database::{cache_stats, UnitOfWork};
+    use test_utils::database::{
+        setup_test_cache, setup_test_database, setup_unreachable_test_cache,
+    };
+    use test_utils::rand::*;
+
+    use crate::domain::port::{MockEmailChangeStore, MockUserStore};
+    use crate::domain::user::UserRole;
+    use crate::infrastructure::user::SQLxUserStore;
+    use crate::tests::utils::create_user;
+
+    use super::*;
+
+    /// Name of the cache of the authenticated users (see the `auth` crate).
+    const AUTH_USER_CACHE: &str = "auth_user";
+
+    /// Counts the hits of a cache.
+    fn hits(name: &str) -> u64 {
+        cache_stats().get(name).map_or(0, |stats| stats.hits)
+    }
+
+    /// Counts the misses of a cache.
+    fn misses(name: &str) -> u64 {
+        cache_stats().get(name).map_or(0, |stats| stats.misses)
+    }
+
+    /// Looks a user up in both caches (profile and authenticated user).
+    ///
+    /// # Returns
+    /// Whether each lookup is a hit. Only this test reaches Redis in this crate: the hits are
+    /// exact, unlike the misses that are also counted by the tests using an unreachable cache.
+    async fn lookup<A: UserStore, B: AuthStore>(
+        users: &A,
+        auth: &B,
+        user_id: Uuid,
+    ) -> Result<(bool, bool), Box<dyn std::error::Error>> {
+        let (user_hits, auth_hits) = (hits(USER_CACHE), hits(AUTH_USER_CACHE));
+
+        let user = users.get_by_id(user_id).await?;
+        let auth_user = auth.get_user_by_id(&user_id).await?;
+
+        // The password is never cached
+        assert!(user.password.as_str().is_empty());
+        assert!(auth_user.password.as_str().is_empty());
+
+        Ok((
+            hits(USER_CACHE) > user_hits,
+            hits(AUTH_USER_CACHE) > auth_hits,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_cached_lookups_and_evictions() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let cache = setup_test_cache().await?;
+
+        let users = CachedUserStore::new(SQLxUserStore::new(db.clone()), &db, &cache);
+        let auth = CachedAuthStore::new(SQLxAuthStore::new(&db), &db, &cache);
+
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        // Hit after the first lookup (a miss)
+        let (user_misses, auth_misses) = (misses(USER_CACHE), misses(AUTH_USER_CACHE));
+
+        assert_eq!(lookup(&users, &auth, user.id).await?, (false, false));
+        assert!(misses(USER_CACHE) > user_misses);
+        assert!(misses(AUTH_USER_CACHE) > auth_misses);
+
+        assert_eq!(lookup(&users, &auth, user.id).await?, (true, true));
+
+        // Update: evicted, the change is visible
+        let data = UserData {
+            first_name: Some("John".to_string()),
+            email: user.email.clone(),
+            role: UserRole::Guest,
+            ..Default::default()
+        };
+        users.update(user.id, data.clone(), None).await?;
+
+        assert_eq!(lookup(&users, &auth, user.id).await?, (false, false));
+        assert_eq!(lookup(&users, &auth, user.id).await?, (true, true));
+        assert_eq!(users.get_by_id(user.id).await?.first_name, "John");
+
+        // Password: evicted (the session fingerprint changes)
+        let fingerprint = auth.get_user_by_id(&user.id).await?.password_fingerprint;
+        users
+            .set_user_password(user.id, random_password().hashed()?, None)
+            .await?;
+
+        assert_eq!(lookup(&users, &auth, user.id).await?, (false, false));
+        assert_ne!(
+            auth.get_user_by_id(&user.id).await?.password_fingerprint,
+            fingerprint
+        );
+
+        // Inside a transaction: the cache is bypassed, and nothing is evicted if rolled back
+        let uow = UnitOfWork::begin(&db).await?;
+        users.update(user.id, data.clone(), None).await?;
+        assert_eq!(lookup(&users, &auth, user.id).await?, (false, false));
+        uow.rollback().await?;
+
+        assert_eq!(lookup(&users, &auth, user.id).await?, (true, true));
+
+        // Evicted once committed
+        let uow = UnitOfWork::begin(&db).await?;
+        users.update(user.id, data, None).await?;
+        uow.commit().await?;
+
+        assert_eq!(lookup(&users, &auth, user.id).await?, (false, false));
+        assert_eq!(lookup(&users, &auth, user.id).await?, (true, true));
+
+        // Delete: evicted, the user isn't found anymore
+        users.delete_by_id(user.id).await?;
+
+        assert!(users.get_by_id(user.id).await.is_err());
+        assert!(auth.get_user_by_id(&user.id).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_falls_back_to_store() -> Result<(), Box<dyn std::error::Error>> {
+        let user = User {
+            id: random_id(),
+            email: random_email(),
+            ..Default::default()
+        };
+
+        let mut store = MockUserStore::new();
+
+        let expected = user.clone();
+        store.expect_get_by_id().times(2).returning(move |_| {
+            let user = expected.clone();
+            Box::pin(async move { Ok(user) })
+        });
+
+        let db = setup_test_database().await?;
+        let store = CachedUserStore::new(store, &db, &setup_unreachable_test_cache()?);
+
+        // Redis can't be reached: both lookups are served by the store
+        assert_eq!(store.get_by_id(user.id).await?, user);
+        assert_eq!(store.get_by_id(user.id).await?, user);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_without_cache() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockUserStore::new();
+
+        store
+            .expect_update()
+            .times(1)
+            .returning(|_, _, _| Box::pin(async move { Ok(User::default()) }));
+
+        let db = setup_test_database().await?;
+        let store = CachedUserStore::new(store, &db, &setup_unreachable_test_cache()?);
+
+        // The change succeeds even if the entries can't be removed
+        let res = store.update(random_id(), UserData::default(), None).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirm_email_change() -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = MockEmailChangeStore::new();
+
+        store
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(Some(EmailChange::default())) }));
+
+        store
+            .expect_confirm()
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+
+        let db = setup_test_database().await?;
+        let store = CachedEmailChangeStore::new(store, &db, &setup_unreachable_test_cache()?);
+
+        let res = store.confirm(random_id()).await;
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+}
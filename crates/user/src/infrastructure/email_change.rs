@@ -0,0 +1,264 @@
+//! SQLx implementation of the EmailChangeStore trait.
+
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use sqlx::FromRow;
+
+use database::SharedDb;
+
+use crate::domain::email_change::EmailChange;
+use crate::domain::port::EmailChangeStore;
+use crate::prelude::*;
+
+/// Mirrors the `user_email_changes`'s table.
+#[derive(Clone, Default, FromRow, Deserialize, Serialize, derive_more::Debug)]
+pub(crate) struct DbEmailChange {
+    /// See `EmailChange::id`.
+    #[debug(skip)]
+    pub id: Uuid,
+
+    /// See `EmailChange::user_id`.
+    pub user_id: Uuid,
+
+    /// See `EmailChange::old_email`.
+    pub old_email: String,
+
+    /// See `EmailChange::new_email`.
+    pub new_email: String,
+
+    /// See `EmailChange::revert_token`.
+    #[debug(skip)]
+    pub revert_token: Uuid,
+
+    /// See `EmailChange::confirmed_at`.
+    pub confirmed_at: Option<DateTime<Utc>>,
+
+    /// See `EmailChange::created_at`.
+    pub created_at: DateTime<Utc>,
+
+    /// See `EmailChange::expires_at`.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<DbEmailChange> for EmailChange {
+    fn from(db_change: DbEmailChange) -> Self {
+        Self {
+            id: db_change.id,
+            user_id: db_change.user_id,
+            old_email: db_change.old_email,
+            new_email: db_change.new_email,
+            revert_token: db_change.revert_token,
+            confirmed_at: db_change.confirmed_at,
+            created_at: db_change.created_at,
+            expires_at: db_change.expires_at,
+        }
+    }
+}
+
+/// SQLx version of the EmailChangeStore trait.
+pub struct SQLxEmailChangeStore {
+    /// Database connection pool.
+    db: SharedDb,
+}
+
+impl SQLxEmailChangeStore {
+    /// Creates a new SQLxEmailChangeStore instance.
+    ///
+    /// # Arguments
+    /// * `db`: Database handle.
+    ///
+    /// # Returns
+    /// A new instance of SQLxEmailChangeStore.
+    #[must_use]
+    pub fn new(db: SharedDb) -> Self {
+        Self { db }
+    }
+}
+
+impl EmailChangeStore for SQLxEmailChangeStore {
+    fn create(
+        &self,
+        user_id: Uuid,
+        old_email: String,
+        new_email: String,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<EmailChange>> {
+        let db = self.db.clone();
+        let expires_at = Utc::now() + timeout;
+
+        Box::pin(async move {
+            let change = sqlx::query_file_as!(
+                DbEmailChange,
+                "sql/create_email_change.sql",
+                user_id,
+                old_email,
+                new_email,
+                expires_at
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(change.into())
+        })
+    }
+
+    fn get_by_id(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<Option<EmailChange>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let change =
+                sqlx::query_file_as!(DbEmailChange, "sql/get_email_change_by_id.sql", change_id)
+                    .fetch_optional(db.lock().await.clone())
+                    .await?;
+
+            Ok(change.map(EmailChange::from))
+        })
+    }
+
+    fn get_by_revert_token(
+        &self,
+        token: Uuid,
+    ) -> BoxFuture<'static, ApiResult<Option<EmailChange>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let change = sqlx::query_file_as!(
+                DbEmailChange,
+                "sql/get_email_change_by_revert_token.sql",
+                token
+            )
+            .fetch_optional(db.lock().await.clone())
+            .await?;
+
+            Ok(change.map(EmailChange::from))
+        })
+    }
+
+    fn get_by_user_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Vec<EmailChange>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let changes = sqlx::query_file_as!(
+                DbEmailChange,
+                "sql/get_email_changes_by_user_id.sql",
+                user_id
+            )
+            .fetch_all(db.lock().await.clone())
+            .await?;
+
+            Ok(changes.into_iter().map(EmailChange::from).collect())
+        })
+    }
+
+    fn confirm(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/confirm_email_change.sql", change_id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn revert(&self, change_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/revert_email_change.sql", change_id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::database::setup_test_database;
+    use test_utils::rand::*;
+
+    use crate::domain::port::UserStore;
+    use crate::domain::user::UserRole;
+    use crate::infrastructure::user::SQLxUserStore;
+    use crate::tests::utils::create_user;
+
+    #[tokio::test]
+    async fn test_create_and_get() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxEmailChangeStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        let change = repo
+            .create(
+                user.id,
+                user.email.clone(),
+                random_email(),
+                Duration::hours(1),
+            )
+            .await?;
+        assert!(!change.is_confirmed());
+
+        assert_eq!(repo.get_by_id(change.id).await?, Some(change.clone()));
+        assert_eq!(
+            repo.get_by_revert_token(change.revert_token).await?,
+            Some(change.clone())
+        );
+        assert!(repo.get_by_id(random_id()).await?.is_none());
+        assert_eq!(repo.get_by_user_id(user.id).await?, vec![change.clone()]);
+
+        // A new request replaces the pending one
+        let replaced = repo
+            .create(
+                user.id,
+                user.email.clone(),
+                random_email(),
+                Duration::hours(1),
+            )
+            .await?;
+        assert_ne!(replaced.id, change.id);
+        assert!(repo.get_by_id(change.id).await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirm_and_revert() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxEmailChangeStore::new(db.clone());
+        let users = SQLxUserStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+        let new_email = random_email();
+
+        let change = repo
+            .create(
+                user.id,
+                user.email.clone(),
+                new_email.clone(),
+                Duration::hours(1),
+            )
+            .await?;
+
+        // The email is changed only once confirmed
+        assert_eq!(users.get_by_id(user.id).await?.email, user.email);
+
+        repo.confirm(change.id).await?;
+        assert_eq!(users.get_by_id(user.id).await?.email, new_email);
+
+        let confirmed = repo.get_by_id(change.id).await?.ok_or("Change not found")?;
+        assert!(confirmed.is_confirmed());
+
+        // The previous email is restored
+        repo.revert(change.id).await?;
+        assert_eq!(users.get_by_id(user.id).await?.email, user.email);
+        assert!(repo.get_by_id(change.id).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! SQLx implementation of the InvitationStore trait.
+
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use sqlx::FromRow;
+
+use database::SharedDb;
+
+use crate::domain::invitation::Invitation;
+use crate::domain::port::InvitationStore;
+use crate::prelude::*;
+
+/// Mirrors the `user_invitations`'s table (with the email of the invited user).
+#[derive(Clone, Default, FromRow, Deserialize, Serialize, derive_more::Debug)]
+pub(crate) struct DbInvitation {
+    /// See `Invitation::id`.
+    pub id: Uuid,
+
+    /// See `Invitation::user_id`.
+    pub user_id: Uuid,
+
+    /// See `Invitation::email`.
+    pub email: String,
+
+    /// See `Invitation::invited_by`.
+    pub invited_by: Option<Uuid>,
+
+    /// See `Invitation::token`.
+    #[debug(skip)]
+    pub token: Uuid,
+
+    /// See `Invitation::created_at`.
+    pub created_at: DateTime<Utc>,
+
+    /// See `Invitation::expires_at`.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<DbInvitation> for Invitation {
+    fn from(db_invitation: DbInvitation) -> Self {
+        Self {
+            id: db_invitation.id,
+            user_id: db_invitation.user_id,
+            email: db_invitation.email,
+            invited_by: db_invitation.invited_by,
+            token: db_invitation.token,
+            created_at: db_invitation.created_at,
+            expires_at: db_invitation.expires_at,
+        }
+    }
+}
+
+/// SQLx version of the InvitationStore trait.
+pub struct SQLxInvitationStore {
+    /// Database connection pool.
+    db: SharedDb,
+}
+
+impl SQLxInvitationStore {
+    /// Creates a new SQLxInvitationStore instance.
+    ///
+    /// # Arguments
+    /// * `db`: Database handle.
+    ///
+    /// # Returns
+    /// A new instance of SQLxInvitationStore.
+    #[must_use]
+    pub fn new(db: SharedDb) -> Self {
+        Self { db }
+    }
+}
+
+impl InvitationStore for SQLxInvitationStore {
+    fn create(
+        &self,
+        user_id: Uuid,
+        invited_by: Option<Uuid>,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<Invitation>> {
+        let db = self.db.clone();
+        let expires_at = Utc::now() + timeout;
+
+        Box::pin(async move {
+            let invitation = sqlx::query_file_as!(
+                DbInvitation,
+                "sql/create_invitation.sql",
+                user_id,
+                invited_by,
+                expires_at
+            )
+            .fetch_one(db.lock().await.clone())
+            .await?;
+
+            Ok(invitation.into())
+        })
+    }
+
+    fn get_all(&self) -> BoxFuture<'static, ApiResult<Vec<Invitation>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let invitations = sqlx::query_file_as!(DbInvitation, "sql/get_invitations.sql")
+                .fetch_all(db.lock().await.clone())
+                .await?;
+
+            Ok(invitations.into_iter().map(Invitation::from).collect())
+        })
+    }
+
+    fn get_by_id(&self, invitation_id: Uuid) -> BoxFuture<'static, ApiResult<Option<Invitation>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let invitation =
+                sqlx::query_file_as!(DbInvitation, "sql/get_invitation_by_id.sql", invitation_id)
+                    .fetch_optional(db.lock().await.clone())
+                    .await?;
+
+            Ok(invitation.map(Invitation::from))
+        })
+    }
+
+    fn renew(
+        &self,
+        invitation_id: Uuid,
+        timeout: Duration,
+    ) -> BoxFuture<'static, ApiResult<Option<Invitation>>> {
+        let db = self.db.clone();
+        let expires_at = Utc::now() + timeout;
+
+        Box::pin(async move {
+            let invitation = sqlx::query_file_as!(
+                DbInvitation,
+                "sql/renew_invitation.sql",
+                invitation_id,
+                expires_at
+            )
+            .fetch_optional(db.lock().await.clone())
+            .await?;
+
+            Ok(invitation.map(Invitation::from))
+        })
+    }
+
+    fn consume(&self, token: Uuid) -> BoxFuture<'static, ApiResult<Option<Invitation>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let invitation =
+                sqlx::query_file_as!(DbInvitation, "sql/consume_invitation.sql", token)
+                    .fetch_optional(db.lock().await.clone())
+                    .await?;
+
+            Ok(invitation.map(Invitation::from))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_utils::database::setup_test_database;
+    use test_utils::rand::*;
+
+    use crate::domain::user::UserRole;
+    use crate::tests::utils::create_user;
+
+    #[tokio::test]
+    async fn test_create_and_get() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxInvitationStore::new(db.clone());
+
+        let admin = create_user(UserRole::Admin, &db).await?;
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        let invitation = repo
+            .create(user.id, Some(admin.id), Duration::hours(1))
+            .await?;
+        assert_eq!(invitation.user_id, user.id);
+        assert_eq!(invitation.email, user.email);
+        assert_eq!(invitation.invited_by, Some(admin.id));
+
+        let fetched = repo.get_by_id(invitation.id).await?;
+        assert_eq!(fetched, Some(invitation.clone()));
+
+        assert!(repo.get_by_id(random_id()).await?.is_none());
+
+        let invitations = repo.get_all().await?;
+        assert!(invitations.contains(&invitation));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_renew() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxInvitationStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        let invitation = repo.create(user.id, None, Duration::hours(1)).await?;
+
+        let renewed = repo
+            .renew(invitation.id, Duration::hours(2))
+            .await?
+            .ok_or("Invitation not found")?;
+        assert_eq!(renewed.id, invitation.id);
+        assert_ne!(renewed.token, invitation.token);
+        assert!(renewed.expires_at > invitation.expires_at);
+
+        assert!(repo.renew(random_id(), Duration::hours(2)).await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_consume() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxInvitationStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        let invitation = repo.create(user.id, None, Duration::hours(1)).await?;
+
+        let consumed = repo.consume(invitation.token).await?;
+        assert_eq!(consumed, Some(invitation.clone()));
+
+        // Can't be used twice
+        assert!(repo.consume(invitation.token).await?.is_none());
+        assert!(repo.get_by_id(invitation.id).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
 //! Implementation of the traits declared in domain.
 
+pub(crate) mod cache;
+pub(crate) mod email_change;
+pub(crate) mod invitation;
 pub(crate) mod user;
@@ -2,15 +2,18 @@
 
 use chrono::{DateTime, Utc};
 use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
 use sqlx::types::Json;
 use sqlx::{FromRow, Type};
 
 use auth::AuthUserConfirmation;
+use common_web::pagination::{Cursor, Page, PageRequest, Sort};
 use database::SharedDb;
 use security::password::Password;
 
 use crate::domain::port::UserStore;
-use crate::domain::user::{User, UserData, UserFilters, UserRole};
+use crate::domain::user::{TextMatch, User, UserData, UserFilters, UserRole, UserSortField};
 use crate::prelude::*;
 
 /// List of users roles in the DB enum.
@@ -76,6 +79,9 @@ pub(crate) struct DbUser {
     /// See `User::updated_at`.
     pub updated_at: DateTime<Utc>,
 
+    /// See `User::deleted_at`.
+    pub deleted_at: Option<DateTime<Utc>>,
+
     /// See `User::pending_confirmation`.
     pub pending_confirmation: Option<Json<AuthUserConfirmation>>,
 }
@@ -91,11 +97,203 @@ impl From<DbUser> for User {
             password: Password::from(db_user.password),
             created_at: db_user.created_at,
             updated_at: db_user.updated_at,
+            deleted_at: db_user.deleted_at,
             pending_confirmation: db_user.pending_confirmation.map(|e| e.0),
         }
     }
 }
 
+/// Sort key of a user, used to build the pagination cursors.
+///
+/// Only one of the `text` or `time` fields is relevant depending on the sort field, the other one
+/// being set to a constant value (see `sql/get_by_filters.sql`). The relevance isn't stored: it's
+/// computed from the user pointed by the cursor.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub(crate) struct DbUserCursor {
+    /// Sort key of the text fields.
+    pub text: String,
+
+    /// Sort key of the date fields.
+    pub time: DateTime<Utc>,
+
+    /// ID of the user (to sort users with the same key).
+    pub id: Uuid,
+}
+
+impl DbUserCursor {
+    /// Builds the sort key of a user.
+    ///
+    /// # Arguments
+    /// * `user`: User.
+    /// * `field`: Sort field.
+    ///
+    /// # Returns
+    /// A `DbUserCursor` instance.
+    fn new(user: &User, field: UserSortField) -> Self {
+        let text = match field {
+            UserSortField::FirstName => user.first_name.clone(),
+            UserSortField::LastName => user.last_name.clone(),
+            UserSortField::Email => user.email.clone(),
+            UserSortField::Role => match user.role {
+                UserRole::Admin => "admin".to_string(),
+                UserRole::Normal => "normal".to_string(),
+                UserRole::Guest => "guest".to_string(),
+            },
+            UserSortField::CreatedAt | UserSortField::UpdatedAt | UserSortField::Relevance => {
+                String::new()
+            }
+        };
+
+        let time = match field {
+            UserSortField::CreatedAt => user.created_at,
+            UserSortField::UpdatedAt => user.updated_at,
+            _ => DateTime::UNIX_EPOCH,
+        };
+
+        Self {
+            text,
+            time,
+            id: user.id,
+        }
+    }
+}
+
+/// Gets the name of the column used to sort the users.
+///
+/// # Arguments
+/// * `field`: Sort field.
+///
+/// # Returns
+/// The name of the column.
+fn sort_column(field: UserSortField) -> &'static str {
+    match field {
+        UserSortField::FirstName => "first_name",
+        UserSortField::LastName => "last_name",
+        UserSortField::Email => "email",
+        UserSortField::Role => "role",
+        UserSortField::CreatedAt => "created_at",
+        UserSortField::UpdatedAt => "updated_at",
+        UserSortField::Relevance => "relevance",
+    }
+}
+
+/// Escapes the wildcards of a `LIKE` pattern.
+///
+/// # Arguments
+/// * `value`: Value to escape.
+///
+/// # Returns
+/// The escaped value.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Parameters of the filters in the SQL queries (see `sql/get_by_filters.sql`).
+struct DbUserFilters {
+    /// See `UserFilters::first_name` (pattern if not exact).
+    first_name: Option<String>,
+
+    /// See `UserFilters::last_name` (pattern if not exact).
+    last_name: Option<String>,
+
+    /// See `UserFilters::email` (pattern if not exact).
+    email: Option<String>,
+
+    /// See `UserFilters::role`.
+    role: Option<DbUserRole>,
+
+    /// Whether the text filters are exact or patterns.
+    exact: bool,
+
+    /// See `UserFilters::include_deleted`.
+    include_deleted: bool,
+
+    /// See `UserFilters::q` (lowercase).
+    q: Option<String>,
+
+    /// Pattern matching the search query as a substring.
+    q_pattern: Option<String>,
+
+    /// Sort to apply.
+    sort: Sort<UserSortField>,
+}
+
+impl From<&UserFilters> for DbUserFilters {
+    fn from(filters: &UserFilters) -> Self {
+        let text_match = filters.text_match.unwrap_or_default();
+
+        let pattern = |value: &Option<String>| {
+            value.as_ref().map(|value| match text_match {
+                TextMatch::Exact => value.clone(),
+                TextMatch::Prefix => format!("{}%", escape_like(value)),
+                TextMatch::Contains => format!("%{}%", escape_like(value)),
+            })
+        };
+
+        let q = filters.q.as_ref().map(|q| q.trim().to_lowercase());
+
+        Self {
+            first_name: pattern(&filters.first_name),
+            last_name: pattern(&filters.last_name),
+            email: pattern(&filters.email),
+            role: filters.role.clone().map(Into::into),
+            exact: text_match == TextMatch::Exact,
+            include_deleted: filters.include_deleted,
+            q_pattern: q.as_ref().map(|q| format!("%{}%", escape_like(q))),
+            q,
+            sort: filters.sort(),
+        }
+    }
+}
+
+/// Fetches the users matching some filters, sorted as requested.
+///
+/// # Arguments
+/// * `db`: Database handle.
+/// * `filters`: Filters (and sort) to apply.
+/// * `cursor`: Sort key of the last user of the previous page (or None).
+/// * `limit`: Maximum number of users to fetch (or None).
+/// * `offset`: Number of users to skip.
+///
+/// # Returns
+/// The list of users found.
+async fn fetch_by_filters(
+    db: &SharedDb,
+    filters: &UserFilters,
+    cursor: Option<DbUserCursor>,
+    limit: Option<i64>,
+    offset: i64,
+) -> ApiResult<Vec<User>> {
+    let filters = DbUserFilters::from(filters);
+
+    let users = sqlx::query_file_as!(
+        DbUser,
+        "sql/get_by_filters.sql",
+        filters.first_name,
+        filters.last_name,
+        filters.email,
+        filters.role as Option<DbUserRole>,
+        filters.exact,
+        filters.q,
+        filters.q_pattern,
+        filters.include_deleted,
+        sort_column(filters.sort.field),
+        filters.sort.is_descending(),
+        cursor.as_ref().map(|cursor| cursor.text.clone()),
+        cursor.as_ref().map(|cursor| cursor.time),
+        cursor.as_ref().map(|cursor| cursor.id),
+        limit,
+        offset,
+    )
+    .fetch_all(db.lock().await.clone())
+    .await?;
+
+    Ok(users.into_iter().map(User::from).collect())
+}
+
 /// SQLx version of the UserStore trait.
 pub struct SQLxUserStore {
     /// Database connection pool.
@@ -142,6 +340,65 @@ impl UserStore for SQLxUserStore {
         })
     }
 
+    fn restore_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Option<User>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let user = sqlx::query_file_as!(DbUser, "sql/restore_by_id.sql", user_id)
+                .fetch_optional(db.lock().await.clone())
+                .await?;
+
+            Ok(user.map(User::from))
+        })
+    }
+
+    fn purge_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<()>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            sqlx::query_file!("sql/purge_by_id.sql", user_id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn erase_by_id(
+        &self,
+        user_id: Uuid,
+        email: String,
+        password: Password,
+    ) -> BoxFuture<'static, ApiResult<Option<User>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let user = sqlx::query_file_as!(
+                DbUser,
+                "sql/erase_by_id.sql",
+                user_id,
+                email,
+                password.as_str()
+            )
+            .fetch_optional(db.lock().await.clone())
+            .await?;
+
+            Ok(user.map(User::from))
+        })
+    }
+
+    fn purge_deleted(&self, before: DateTime<Utc>) -> BoxFuture<'static, ApiResult<u64>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let res = sqlx::query_file!("sql/purge_deleted.sql", before)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            Ok(res.rows_affected())
+        })
+    }
+
     fn get_by_id(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<User>> {
         let db = self.db.clone();
 
@@ -154,23 +411,112 @@ impl UserStore for SQLxUserStore {
         })
     }
 
-    fn get_by_filters(&self, filters: UserFilters) -> BoxFuture<'static, ApiResult<Vec<User>>> {
+    fn get_password(&self, user_id: Uuid) -> BoxFuture<'static, ApiResult<Password>> {
         let db = self.db.clone();
-        let role = filters.role.map(Into::into);
 
         Box::pin(async move {
-            let users = sqlx::query_file_as!(
+            let password = sqlx::query_file_scalar!("sql/get_password.sql", user_id)
+                .fetch_one(db.lock().await.clone())
+                .await?;
+
+            Ok(Password::from(password))
+        })
+    }
+
+    fn get_by_filters(&self, filters: UserFilters) -> BoxFuture<'static, ApiResult<Vec<User>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move { fetch_by_filters(&db, &filters, None, None, 0).await })
+    }
+
+    fn stream_by_filters(&self, filters: UserFilters) -> BoxStream<'static, ApiResult<User>> {
+        let db = self.db.clone();
+
+        Box::pin(async_stream::try_stream! {
+            let filters = DbUserFilters::from(&filters);
+            let db = db.lock().await.clone();
+
+            let mut users = sqlx::query_file_as!(
                 DbUser,
                 "sql/get_by_filters.sql",
                 filters.first_name,
                 filters.last_name,
                 filters.email,
-                role as Option<DbUserRole>,
+                filters.role as Option<DbUserRole>,
+                filters.exact,
+                filters.q,
+                filters.q_pattern,
+                filters.include_deleted,
+                sort_column(filters.sort.field),
+                filters.sort.is_descending(),
+                None::<String>,
+                None::<DateTime<Utc>>,
+                None::<Uuid>,
+                None::<i64>,
+                0_i64,
+            )
+            .fetch(db);
+
+            while let Some(user) = users.try_next().await? {
+                yield User::from(user);
+            }
+        })
+    }
+
+    fn get_page_by_filters(
+        &self,
+        filters: UserFilters,
+        page: PageRequest,
+    ) -> BoxFuture<'static, ApiResult<Page<User>>> {
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let cursor = page
+                .cursor
+                .as_ref()
+                .map(|cursor| cursor.decode::<DbUserCursor>().ok_or(Error::InvalidCursor))
+                .transpose()?;
+
+            let limit = i64::from(page.limit());
+
+            // Fetch one more user to know if there's a next page
+            let mut users =
+                fetch_by_filters(&db, &filters, cursor, Some(limit + 1), page.offset().into())
+                    .await?;
+
+            let next_cursor = if users.len() > page.limit() as usize {
+                users.truncate(page.limit() as usize);
+
+                let field = filters.sort().field;
+
+                users
+                    .last()
+                    .map(|user| Cursor::encode(&DbUserCursor::new(user, field)))
+            } else {
+                None
+            };
+
+            let filters = DbUserFilters::from(&filters);
+
+            let total = sqlx::query_file_scalar!(
+                "sql/count_by_filters.sql",
+                filters.first_name,
+                filters.last_name,
+                filters.email,
+                filters.role as Option<DbUserRole>,
+                filters.exact,
+                filters.q,
+                filters.q_pattern,
+                filters.include_deleted,
             )
-            .fetch_all(db.lock().await.clone())
+            .fetch_one(db.lock().await.clone())
             .await?;
 
-            Ok(users.into_iter().map(User::from).collect())
+            Ok(Page {
+                items: users,
+                total,
+                next_cursor,
+            })
         })
     }
 
@@ -195,7 +541,12 @@ impl UserStore for SQLxUserStore {
         })
     }
 
-    fn update(&self, user_id: Uuid, data: UserData) -> BoxFuture<'static, ApiResult<User>> {
+    fn update(
+        &self,
+        user_id: Uuid,
+        data: UserData,
+        version: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'static, ApiResult<User>> {
         let db = self.db.clone();
         let role: DbUserRole = data.role.into();
 
@@ -208,12 +559,18 @@ impl UserStore for SQLxUserStore {
                 data.last_name,
                 data.email,
                 role as DbUserRole,
-                data.password.as_str()
+                data.password.as_str(),
+                version
             )
-            .fetch_one(db.lock().await.clone())
+            .fetch_optional(db.lock().await.clone())
             .await?;
 
-            Ok(user.into())
+            // The user has been updated (or deleted) since the version known by the caller
+            match (user, version) {
+                (Some(user), _) => Ok(user.into()),
+                (None, Some(_)) => Err(Error::PreconditionFailed),
+                (None, None) => Err(sqlx::Error::RowNotFound.into()),
+            }
         })
     }
 
@@ -221,13 +578,19 @@ impl UserStore for SQLxUserStore {
         &self,
         user_id: Uuid,
         password: Password,
+        version: Option<DateTime<Utc>>,
     ) -> BoxFuture<'static, ApiResult<()>> {
         let db = self.db.clone();
 
         Box::pin(async move {
-            sqlx::query_file!("sql/set_password.sql", user_id, password.as_str())
-                .execute(db.lock().await.clone())
-                .await?;
+            let result =
+                sqlx::query_file!("sql/set_password.sql", user_id, password.as_str(), version)
+                    .execute(db.lock().await.clone())
+                    .await?;
+
+            if result.rows_affected() == 0 && version.is_some() {
+                return Err(Error::PreconditionFailed);
+            }
 
             Ok(())
         })
@@ -238,6 +601,7 @@ impl UserStore for SQLxUserStore {
 mod tests {
     use super::*;
 
+    use common_web::pagination::SortOrder;
     use test_utils::database::setup_test_database;
     use test_utils::rand::*;
 
@@ -270,6 +634,120 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_restore_by_id() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        // Not deleted
+        assert!(repo.restore_by_id(user.id).await?.is_none());
+
+        repo.delete_by_id(user.id).await?;
+        assert!(!repo.exists(user.id).await?);
+
+        // Listed only if requested
+        let filters = |include_deleted| UserFilters {
+            email: Some(user.email.clone()),
+            include_deleted,
+            ..Default::default()
+        };
+
+        assert!(repo.get_by_filters(filters(false)).await?.is_empty());
+
+        let users = repo.get_by_filters(filters(true)).await?;
+        assert!(users.first().is_some_and(User::is_deleted));
+
+        let restored = repo.restore_by_id(user.id).await?.ok_or("Not restored")?;
+        assert!(!restored.is_deleted());
+        assert!(repo.exists(user.id).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_purge() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+        let deleted = create_user(UserRole::Normal, &db).await?;
+        let purged = create_user(UserRole::Normal, &db).await?;
+
+        repo.delete_by_id(deleted.id).await?;
+        repo.delete_by_id(purged.id).await?;
+
+        sqlx::query("UPDATE users SET deleted_at = NOW() - INTERVAL '2 days' WHERE id = $1")
+            .bind(purged.id)
+            .execute(db.lock().await.clone())
+            .await?;
+
+        repo.purge_deleted(Utc::now() - chrono::Duration::days(1))
+            .await?;
+
+        let filters = |email: &str| UserFilters {
+            email: Some(email.to_string()),
+            include_deleted: true,
+            ..Default::default()
+        };
+
+        assert_eq!(repo.get_by_filters(filters(&user.email)).await?.len(), 1);
+        assert_eq!(repo.get_by_filters(filters(&deleted.email)).await?.len(), 1);
+        assert!(repo
+            .get_by_filters(filters(&purged.email))
+            .await?
+            .is_empty());
+
+        // Permanent deletion
+        repo.purge_by_id(user.id).await?;
+        assert!(repo.get_by_filters(filters(&user.email)).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_erase_by_id() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        let user = create_user(UserRole::Normal, &db).await?;
+
+        sqlx::query("INSERT INTO user_sessions (user_id) VALUES ($1)")
+            .bind(user.id)
+            .execute(db.lock().await.clone())
+            .await?;
+
+        let email = random_email();
+
+        let erased = repo
+            .erase_by_id(user.id, email.clone(), random_password())
+            .await?
+            .ok_or("Not erased")?;
+        assert_eq!(erased.id, user.id);
+        assert_eq!(erased.email, email);
+        assert!(erased.first_name.is_empty());
+        assert!(erased.last_name.is_empty());
+
+        // The user is kept but its authentication data is deleted
+        assert!(repo.exists(user.id).await?);
+
+        let sessions: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM user_sessions WHERE user_id = $1")
+                .bind(user.id)
+                .fetch_one(db.lock().await.clone())
+                .await?;
+        assert_eq!(sessions, 0);
+
+        // Unknown user
+        assert!(repo
+            .erase_by_id(random_id(), random_email(), random_password())
+            .await?
+            .is_none());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_by_id() -> Result<(), Box<dyn std::error::Error>> {
         let db = setup_test_database().await?;
@@ -339,6 +817,212 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_stream_by_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        let user = create_user(UserRole::Admin, &db).await?;
+        let deleted = create_user(UserRole::Admin, &db).await?;
+
+        repo.delete_by_id(deleted.id).await?;
+
+        let users: Vec<User> = repo
+            .stream_by_filters(UserFilters {
+                role: Some(UserRole::Admin),
+                ..Default::default()
+            })
+            .try_collect()
+            .await?;
+
+        assert!(users.iter().any(|u| u.id == user.id));
+        assert!(!users.iter().any(|u| u.id == deleted.id));
+        assert!(users.windows(2).all(|w| w[0].created_at <= w[1].created_at));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_page_by_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        // Users sharing the same last name to be isolated from the others
+        let last_name = random_string();
+        let mut users = vec![];
+
+        for _ in 0..3 {
+            let user = create_user(UserRole::Normal, &db).await?;
+
+            sqlx::query("UPDATE users SET last_name = $1 WHERE id = $2")
+                .bind(&last_name)
+                .bind(user.id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            users.push(user);
+        }
+
+        users.sort_by(|a, b| b.email.cmp(&a.email));
+
+        let filters = || UserFilters {
+            last_name: Some(last_name.clone()),
+            sort: Some(Sort {
+                field: UserSortField::Email,
+                order: SortOrder::Desc,
+            }),
+            ..Default::default()
+        };
+
+        // First page
+        let page = repo
+            .get_page_by_filters(
+                filters(),
+                PageRequest {
+                    limit: Some(2),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            page.items.iter().map(|u| u.id).collect::<Vec<_>>(),
+            vec![users[0].id, users[1].id]
+        );
+
+        // Next page with the cursor
+        let page = repo
+            .get_page_by_filters(
+                filters(),
+                PageRequest {
+                    limit: Some(2),
+                    cursor: page.next_cursor,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            page.items.iter().map(|u| u.id).collect::<Vec<_>>(),
+            vec![users[2].id]
+        );
+        assert!(page.next_cursor.is_none());
+
+        // Next page with the offset
+        let page = repo
+            .get_page_by_filters(
+                filters(),
+                PageRequest {
+                    limit: Some(2),
+                    offset: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(
+            page.items.iter().map(|u| u.id).collect::<Vec<_>>(),
+            vec![users[1].id, users[2].id]
+        );
+        assert!(page.next_cursor.is_none());
+
+        // Invalid cursor
+        let res = repo
+            .get_page_by_filters(
+                filters(),
+                PageRequest {
+                    cursor: Some(Cursor::encode(&"invalid")),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(res, Err(Error::InvalidCursor)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        // Users sharing the same last name to be isolated from the others
+        let last_name = random_string();
+        let mut users = vec![];
+
+        for first_name in ["Maxime", "Maximilian"] {
+            let user = create_user(UserRole::Normal, &db).await?;
+
+            sqlx::query("UPDATE users SET first_name = $1, last_name = $2 WHERE id = $3")
+                .bind(first_name)
+                .bind(&last_name)
+                .bind(user.id)
+                .execute(db.lock().await.clone())
+                .await?;
+
+            users.push(user);
+        }
+
+        let search = |filters: UserFilters| {
+            let repo = &repo;
+            async move {
+                let page = repo
+                    .get_page_by_filters(filters, PageRequest::default())
+                    .await?;
+
+                Ok::<_, Error>(page.items.into_iter().map(|u| u.id).collect::<Vec<_>>())
+            }
+        };
+
+        // Case-insensitive prefix
+        let found = search(UserFilters {
+            first_name: Some("MAXIMI".to_string()),
+            last_name: Some(last_name.clone()),
+            text_match: Some(TextMatch::Prefix),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(found, vec![users[1].id]);
+
+        // Case-insensitive substring
+        let found = search(UserFilters {
+            first_name: Some("xim".to_string()),
+            last_name: Some(last_name[4..12].to_uppercase()),
+            text_match: Some(TextMatch::Contains),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(found, vec![users[0].id, users[1].id]);
+
+        // Wildcards are escaped
+        let found = search(UserFilters {
+            first_name: Some("%".to_string()),
+            last_name: Some(last_name.clone()),
+            text_match: Some(TextMatch::Contains),
+            ..Default::default()
+        })
+        .await?;
+        assert!(found.is_empty());
+
+        // Search ranked by relevance
+        let found = search(UserFilters {
+            last_name: Some(last_name.clone()),
+            q: Some(format!("maximilian {last_name}")),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(found.first(), Some(&users[1].id));
+
+        // Search in the email
+        let found = search(UserFilters {
+            q: Some(users[0].email.to_uppercase()),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(found, vec![users[0].id]);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create() -> Result<(), Box<dyn std::error::Error>> {
         let db = setup_test_database().await?;
@@ -375,7 +1059,7 @@ mod tests {
             password: random_password(),
         };
 
-        let updated = repo.update(user.id, data.clone()).await?;
+        let updated = repo.update(user.id, data.clone(), None).await?;
         assert_eq!(updated.first_name, data.first_name.unwrap());
         assert_eq!(updated.last_name, data.last_name.unwrap());
         assert_eq!(updated.email, data.email);
@@ -385,6 +1069,48 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_update_concurrently() -> Result<(), Box<dyn std::error::Error>> {
+        let db = setup_test_database().await?;
+        let repo = SQLxUserStore::new(db.clone());
+
+        let user = create_user(UserRole::Admin, &db).await?;
+
+        let data = || UserData {
+            first_name: Some(random_string()),
+            last_name: Some(random_string()),
+            email: user.email.clone(),
+            role: UserRole::Normal,
+            password: random_password(),
+        };
+
+        // Another handle so that the updates run on distinct connections
+        let other = SQLxUserStore::new(setup_test_database().await?);
+
+        // Both updates are based on the same version: only one of them can be applied
+        let (first, second) = tokio::join!(
+            repo.update(user.id, data(), Some(user.updated_at)),
+            other.update(user.id, data(), Some(user.updated_at)),
+        );
+
+        let (updated, outdated) = match (first, second) {
+            (Ok(updated), outdated) | (outdated, Ok(updated)) => (updated, outdated),
+            _ => panic!("No update applied"),
+        };
+        assert!(matches!(outdated, Err(Error::PreconditionFailed)));
+        assert_eq!(repo.get_by_id(user.id).await?, updated);
+
+        let res = repo
+            .set_user_password(user.id, random_password(), Some(user.updated_at))
+            .await;
+        assert!(matches!(res, Err(Error::PreconditionFailed)));
+
+        repo.set_user_password(user.id, random_password(), Some(updated.updated_at))
+            .await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_set_password() -> Result<(), Box<dyn std::error::Error>> {
         let db = setup_test_database().await?;
@@ -393,11 +1119,14 @@ mod tests {
         let user = create_user(UserRole::Admin, &db).await?;
         let password = random_password();
 
-        repo.set_user_password(user.id, password.clone()).await?;
+        repo.set_user_password(user.id, password.clone(), None)
+            .await?;
 
         let fetched = repo.get_by_id(user.id).await?;
         assert_eq!(fetched.password, password);
 
+        assert_eq!(repo.get_password(user.id).await?, password);
+
         Ok(())
     }
 }
@@ -12,5 +12,7 @@ mod prelude;
 #[cfg(test)]
 mod tests;
 
-pub use api::user::router;
+pub use api::jobs::purge_deleted_users;
+pub use api::user::{public_router, router};
+pub use domain::error::Error;
 pub use domain::user::{User, UserRole};
@@ -1,5 +1,6 @@
 //! Utilities functions for handling users in the database.
 
+use auth::{AuditAction, MockAuditStore};
 use database::SharedDb;
 use test_utils::rand::{random_email, random_string};
 
@@ -39,6 +40,7 @@ pub async fn create_user(
             password,
             created_at,
             updated_at,
+            deleted_at,
             NULL AS \"pending_confirmation: _\"",
         first_name,
         last_name,
@@ -50,3 +52,29 @@ pub async fn create_user(
 
     Ok(user.into())
 }
+
+/// Creates an audit store expecting each of the given actions to be recorded once (and nothing
+/// else).
+///
+/// # Arguments
+/// * `actions` - The list of actions expected.
+///
+/// # Returns
+/// A `MockAuditStore` instance.
+pub fn mock_audit_store(actions: &[AuditAction]) -> MockAuditStore {
+    let mut audit_store = MockAuditStore::new();
+
+    for action in actions {
+        let action = *action;
+
+        audit_store
+            .expect_record()
+            .withf(move |record| record.action == action)
+            .times(1)
+            .returning(|_| Box::pin(async move { Ok(()) }));
+    }
+
+    audit_store.expect_record().never();
+
+    audit_store
+}
@@ -108,13 +108,7 @@ echo -e "${PURPLE} done (PID ${SERVER_PID})${NC}"
 
 echo -ne "${PURPLE}Seeding database...${NC}"
 
-psql \
-    -h 127.0.0.1 \
-    -d ${db_name} \
-    -U demo \
-    -p 5432 \
-    -q \
-    -f ./data/tests/seed.sql
+./target/${mode}/axum-skeleton seed --env testing > /dev/null
 
 echo -e "${PURPLE} done${NC}"
 